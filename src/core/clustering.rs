@@ -0,0 +1,129 @@
+//! Clustering of near-duplicate texts.
+//!
+//! Pairwise similarities only say which two texts are alike. This module
+//! merges them into clusters of texts connected by chains of similar pairs,
+//! so that three revisions of the same document end up in one group even when
+//! the first and last revision differ too much to match directly.
+
+use crate::core::similarity::{SimilarityMethod, pairwise_similarities};
+
+/// Disjoint-set forest over the indices `0..len`, with path halving and
+/// union by size.
+struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    /// Creates a forest where each index is its own set.
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    /// Returns the representative of the set containing `index`.
+    fn find(&mut self, mut index: usize) -> usize {
+        while self.parent[index] != index {
+            self.parent[index] = self.parent[self.parent[index]];
+            index = self.parent[index];
+        }
+        index
+    }
+
+    /// Merges the sets containing `a` and `b`.
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+    }
+}
+
+/// Groups texts into clusters of transitive near-duplicates.
+///
+/// Every pair of texts is compared with `pairwise_similarities`, and texts are
+/// merged into the same cluster whenever a pair reaches the threshold. Texts
+/// that match nothing form a cluster of their own.
+///
+/// # Arguments
+///
+/// * `texts` - The texts to cluster
+/// * `method` - The similarity method to use
+/// * `threshold` - The minimum similarity percentage (0-100) for two texts to
+///   be linked
+///
+/// # Returns
+///
+/// The clusters as lists of indices into `texts`. Each index appears in
+/// exactly one cluster, indices within a cluster are ascending, and clusters
+/// are ordered by their smallest index.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::clustering::cluster_texts;
+/// # use dms_toolkit_rs::core::similarity::SimilarityMethod;
+/// let texts = vec![
+///     "quarterly report".to_string(),
+///     "cafeteria menu".to_string(),
+///     "quarterly report".to_string(),
+/// ];
+/// let clusters = cluster_texts(&texts, SimilarityMethod::Jaccard, 90.0);
+/// assert_eq!(clusters, vec![vec![0, 2], vec![1]]);
+/// ```
+pub fn cluster_texts(
+    texts: &[String],
+    method: SimilarityMethod,
+    threshold: f64,
+) -> Vec<Vec<usize>> {
+    let mut sets = UnionFind::new(texts.len());
+    for (i, j, _) in pairwise_similarities(texts, method, threshold) {
+        sets.union(i, j);
+    }
+
+    // Indices are visited in ascending order, so each cluster is created at
+    // its smallest index and filled in ascending order.
+    let mut cluster_of_root = vec![usize::MAX; texts.len()];
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    for index in 0..texts.len() {
+        let root = sets.find(index);
+        if cluster_of_root[root] == usize::MAX {
+            cluster_of_root[root] = clusters.len();
+            clusters.push(Vec::new());
+        }
+        clusters[cluster_of_root[root]].push(index);
+    }
+
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cluster_texts_merges_chains() {
+        let texts: Vec<String> = [
+            "alpha beta gamma delta epsilon zeta",
+            "gamma delta epsilon zeta eta theta",
+            "unrelated words about the cafeteria lunch menu",
+            "epsilon zeta eta theta iota kappa",
+        ]
+        .iter()
+        .map(|text| text.to_string())
+        .collect();
+
+        // Neighbours in the chain share 4 of 8 words (50%), the ends only
+        // 2 of 10 (20%).
+        let clusters = cluster_texts(&texts, SimilarityMethod::Jaccard, 50.0);
+
+        assert_eq!(clusters, vec![vec![0, 1, 3], vec![2]]);
+    }
+}
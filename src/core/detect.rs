@@ -0,0 +1,145 @@
+//! File type detection from content.
+//!
+//! Uploads sometimes arrive with a generic or wrong MIME type (such as
+//! `application/octet-stream` for a PDF). When no handler accepts the declared
+//! MIME type, the content's leading "magic" bytes are inspected instead.
+
+use std::io::Cursor;
+use zip::ZipArchive;
+
+/// Detects a file's MIME type from its leading bytes.
+///
+/// # Recognized Formats
+///
+/// - PDF (`%PDF`)
+/// - DOCX, XLSX, PPTX, and EPUB (ZIP signature `PK\x03\x04`, told apart by
+///   their main package part: `word/document.xml`, `xl/workbook.xml`,
+///   `ppt/presentation.xml`, or `META-INF/container.xml`), ODS (by the
+///   `mimetype` entry that starts OpenDocument packages), and other ZIP
+///   archives
+/// - PNG, JPEG, GIF, BMP, TIFF, and WebP images
+/// - Gzip-compressed files (`\x1F\x8B`)
+///
+/// # Arguments
+///
+/// * `content` - The raw file content
+///
+/// # Returns
+///
+/// The detected MIME type, or `None` if the content matches no known signature.
+/// ZIP archives that are not DOCX, XLSX, PPTX, EPUB, or ODS are reported as
+/// `application/zip`; content with a ZIP signature that cannot be opened as
+/// an archive is reported as `None`.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::detect::sniff_mime;
+/// assert_eq!(sniff_mime(b"%PDF-1.7\n..."), Some("application/pdf".to_string()));
+/// assert_eq!(sniff_mime(b"plain text"), None);
+/// ```
+pub fn sniff_mime(content: &[u8]) -> Option<String> {
+    let mime_type = if content.starts_with(b"%PDF") {
+        "application/pdf"
+    } else if content.starts_with(b"PK\x03\x04") {
+        sniff_office_package(content)?
+    } else if content.starts_with(b"\x89PNG\r\n\x1a\n") {
+        "image/png"
+    } else if content.starts_with(b"\xFF\xD8\xFF") {
+        "image/jpeg"
+    } else if content.starts_with(b"GIF87a") || content.starts_with(b"GIF89a") {
+        "image/gif"
+    } else if content.starts_with(b"BM") && content.len() >= 14 {
+        "image/bmp"
+    } else if content.starts_with(b"II*\0") || content.starts_with(b"MM\0*") {
+        "image/tiff"
+    } else if content.len() >= 12 && &content[..4] == b"RIFF" && &content[8..12] == b"WEBP" {
+        "image/webp"
+    } else if content.starts_with(b"\x1F\x8B") {
+        "application/gzip"
+    } else {
+        return None;
+    };
+
+    Some(mime_type.to_string())
+}
+
+/// Identifies a ZIP archive as a DOCX, XLSX, PPTX, EPUB, or ODS package, or as
+/// a plain ZIP archive.
+fn sniff_office_package(content: &[u8]) -> Option<&'static str> {
+    let archive = ZipArchive::new(Cursor::new(content)).ok()?;
+    let has_part = |name: &str| archive.file_names().any(|part| part == name);
+
+    if has_part("word/document.xml") {
+        Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document")
+    } else if has_part("xl/workbook.xml") {
+        Some("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+    } else if has_part("ppt/presentation.xml") {
+        Some("application/vnd.openxmlformats-officedocument.presentationml.presentation")
+    } else if has_part("META-INF/container.xml") {
+        Some("application/epub+zip")
+    } else if content.get(30..).is_some_and(|rest| {
+        // OpenDocument packages start with an uncompressed `mimetype` entry
+        rest.starts_with(b"mimetypeapplication/vnd.oasis.opendocument.spreadsheet")
+    }) {
+        Some("application/vnd.oasis.opendocument.spreadsheet")
+    } else {
+        Some("application/zip")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::docx::tests::build_docx;
+    use crate::handlers::epub::tests::build_epub;
+    use crate::handlers::pptx::tests::build_pptx;
+    use crate::handlers::xlsx::tests::{build_ods, build_xlsx};
+    use crate::handlers::zip::tests::build_zip;
+
+    #[test]
+    fn test_sniff_mime() {
+        assert_eq!(sniff_mime(b"%PDF-1.5"), Some("application/pdf".to_string()));
+        assert_eq!(
+            sniff_mime(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            Some("image/png".to_string())
+        );
+        assert_eq!(
+            sniff_mime(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some("image/webp".to_string())
+        );
+        assert_eq!(sniff_mime(b"just some text"), None);
+        assert_eq!(sniff_mime(b""), None);
+    }
+
+    #[test]
+    fn test_sniff_office_packages() {
+        let docx = build_docx(&["Hello"], &[]);
+        let xlsx = build_xlsx(&[("Sheet1", &[&["Hello"]], &[])]);
+        let pptx = build_pptx(&[&["Hello"]]);
+        let epub = build_epub(&[("hello.xhtml", "<p>Hello</p>")]);
+        let ods = build_ods(&[("Sheet1", &[&["Hello"]])]);
+
+        assert_eq!(
+            sniff_mime(&docx).as_deref(),
+            Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document")
+        );
+        assert_eq!(
+            sniff_mime(&xlsx).as_deref(),
+            Some("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+        );
+        assert_eq!(
+            sniff_mime(&pptx).as_deref(),
+            Some("application/vnd.openxmlformats-officedocument.presentationml.presentation")
+        );
+        assert_eq!(sniff_mime(&epub).as_deref(), Some("application/epub+zip"));
+        assert_eq!(
+            sniff_mime(&ods).as_deref(),
+            Some("application/vnd.oasis.opendocument.spreadsheet")
+        );
+        assert_eq!(
+            sniff_mime(&build_zip(&[("notes.txt", b"Hello")])).as_deref(),
+            Some("application/zip")
+        );
+    }
+}
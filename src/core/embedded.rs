@@ -0,0 +1,120 @@
+//! Discovery of documents embedded inside Office packages.
+//!
+//! DOCX and XLSX files are ZIP packages. Documents inserted as objects (a PDF
+//! in a Word file, a spreadsheet in another spreadsheet) are stored as package
+//! parts under `word/embeddings/` and `xl/embeddings/`. This module lists those
+//! parts so they can be routed back through the handler set.
+
+use crate::core::mime::mime_from_filename;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+/// Maximum nesting depth for embedded document extraction.
+///
+/// A document embedded in a document embedded in a document is extracted up
+/// to this depth; deeper parts are ignored. This bounds the work done on
+/// maliciously or accidentally deep nesting.
+pub const MAX_EMBEDDED_DEPTH: usize = 3;
+
+/// A document stored as a part inside an Office package.
+pub struct EmbeddedPart {
+    /// File name of the part, without its package path (e.g., "report.pdf").
+    pub name: String,
+    /// MIME type inferred from the part's file extension.
+    pub mime_type: &'static str,
+    /// Raw bytes of the part.
+    pub content: Vec<u8>,
+}
+
+/// Returns the package directory holding embedded parts for a MIME type.
+fn embeddings_prefix(mime_type: &str) -> Option<&'static str> {
+    match mime_type {
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+        | "application/docx" => Some("word/embeddings/"),
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+        | "application/xlsx" => Some("xl/embeddings/"),
+        _ => None,
+    }
+}
+
+/// Lists the embedded documents of a DOCX or XLSX package.
+///
+/// Only parts whose extension maps to a known MIME type are returned. Legacy
+/// OLE objects (`oleObject*.bin`) wrap their payload in a compound file and
+/// are skipped, as are parts that cannot be read.
+///
+/// # Arguments
+///
+/// * `content` - The raw package bytes
+/// * `mime_type` - The MIME type of the package
+///
+/// # Returns
+///
+/// The embedded parts in package order, or an empty vector if the MIME type
+/// is not an Office package or the package cannot be opened.
+pub fn embedded_parts(content: &[u8], mime_type: &str) -> Vec<EmbeddedPart> {
+    let Some(prefix) = embeddings_prefix(mime_type) else {
+        return Vec::new();
+    };
+    let Ok(mut archive) = ZipArchive::new(Cursor::new(content)) else {
+        return Vec::new();
+    };
+
+    let mut parts = Vec::new();
+
+    for index in 0..archive.len() {
+        let Ok(mut entry) = archive.by_index(index) else {
+            continue;
+        };
+        if entry.is_dir() {
+            continue;
+        }
+
+        let Some(name) = entry.name().strip_prefix(prefix).map(str::to_string) else {
+            continue;
+        };
+        let Some(mime_type) = mime_from_filename(&name) else {
+            continue;
+        };
+
+        let mut content = Vec::new();
+        if entry.read_to_end(&mut content).is_ok() {
+            parts.push(EmbeddedPart {
+                name,
+                mime_type,
+                content,
+            });
+        }
+    }
+
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::docx::tests::build_docx;
+
+    #[test]
+    fn test_embedded_parts() {
+        let docx = build_docx(
+            &["Host paragraph"],
+            &[
+                ("notes.txt", b"embedded notes".to_vec()),
+                ("oleObject1.bin", vec![0xD0, 0xCF, 0x11, 0xE0]),
+            ],
+        );
+
+        let parts = embedded_parts(
+            &docx,
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        );
+
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].name, "notes.txt");
+        assert_eq!(parts[0].mime_type, "text/plain");
+        assert_eq!(parts[0].content, b"embedded notes");
+
+        assert!(embedded_parts(&docx, "application/pdf").is_empty());
+    }
+}
@@ -66,6 +66,34 @@ pub trait FileHandler: Send + Sync {
     /// ```
     fn can_handle(&self, mime_type: &str) -> bool;
 
+    /// Lists the MIME types this handler accepts.
+    ///
+    /// `can_handle()` decides for a given MIME type but cannot enumerate the
+    /// types it accepts, so this method lists them for callers that need to
+    /// know up front, such as `supported_mime_types`. A handler that accepts a
+    /// whole family of types may list a wildcard such as `text/*` alongside
+    /// its common concrete types.
+    ///
+    /// # Returns
+    ///
+    /// The accepted MIME types. Defaults to an empty list, for handlers that
+    /// do not advertise their types.
+    fn supported_mimes(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Returns a name identifying this handler, for diagnostics.
+    ///
+    /// `which_handler` reports it to show which handler a MIME type is routed
+    /// to. The built-in handlers return their type name, such as `PdfHandler`.
+    ///
+    /// # Returns
+    ///
+    /// The handler name. Defaults to the full path of the implementing type.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
     /// Extracts text content from the given file bytes.
     ///
     /// This method performs the actual text extraction from the raw file content.
@@ -115,4 +143,61 @@ pub trait FileHandler: Send + Sync {
         filename: &str,
         mime_type: &str,
     ) -> Result<String, String>;
+
+    /// Returns the estimated peak memory use per input byte during extraction.
+    ///
+    /// This is a rough, documented heuristic used for capacity planning, not a
+    /// measurement. The estimated peak memory for a file is its size multiplied
+    /// by this factor. Handlers that decompress or decode their input (images,
+    /// ZIP-based documents) should override it with a larger factor.
+    ///
+    /// # Returns
+    ///
+    /// The memory multiplier. Defaults to `1.0`, which fits handlers whose
+    /// working memory is roughly the size of their input.
+    fn memory_multiplier(&self) -> f64 {
+        1.0
+    }
+
+    /// Extracts text content together with the encoding it was decoded from.
+    ///
+    /// The processing pipeline calls this method instead of `extract_text()`,
+    /// so the detected encoding can be reported in `FileMetadata`. Handlers
+    /// that decode text themselves (such as `TextHandler`) override it.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw file content as a byte slice
+    /// * `filename` - The name of the file
+    /// * `mime_type` - The MIME type of the file (already verified by `can_handle()`)
+    ///
+    /// # Returns
+    ///
+    /// The extracted text and its encoding label, or the error from
+    /// `extract_text()`. Defaults to `extract_text()` with the "utf-8"
+    /// encoding and `lossy` unset.
+    fn extract_text_with_encoding(
+        &self,
+        content: &[u8],
+        filename: &str,
+        mime_type: &str,
+    ) -> Result<ExtractedText, String> {
+        self.extract_text(content, filename, mime_type)
+            .map(|text| ExtractedText {
+                text,
+                encoding: "utf-8".to_string(),
+                lossy: false,
+            })
+    }
+}
+
+/// Text extracted by a handler, with the encoding it was decoded from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedText {
+    /// The extracted text content.
+    pub text: String,
+    /// Lowercase label of the source encoding (e.g. "utf-8", "windows-1252").
+    pub encoding: String,
+    /// Whether invalid byte sequences were replaced with U+FFFD while decoding.
+    pub lossy: bool,
 }
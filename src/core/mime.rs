@@ -0,0 +1,91 @@
+//! MIME type helpers for routing files to handlers.
+//!
+//! Files nested inside containers (such as parts embedded in DOCX and XLSX
+//! packages) carry no MIME type of their own, so it is inferred from the
+//! file extension before routing them through the handler set. Declared MIME
+//! types are normalized first, since clients send them in varying forms.
+
+use std::borrow::Cow;
+
+/// Normalizes a declared MIME type before it is matched against handlers.
+///
+/// Handlers compare MIME types exactly, but clients send them uppercased
+/// (`IMAGE/PNG`), with parameters (`text/plain; charset=utf-8`), or as the
+/// nonstandard `image/jpg`. This function drops any parameters, trims
+/// whitespace, lowercases the type, and maps `image/jpg` to `image/jpeg`.
+/// MIME types already in normal form are borrowed unchanged.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::mime::normalize_mime_type;
+/// assert_eq!(normalize_mime_type("Text/Plain; charset=UTF-8"), "text/plain");
+/// assert_eq!(normalize_mime_type("IMAGE/JPG"), "image/jpeg");
+/// ```
+pub fn normalize_mime_type(mime_type: &str) -> Cow<'_, str> {
+    let essence = mime_type.split(';').next().unwrap_or_default().trim();
+
+    if essence.eq_ignore_ascii_case("image/jpg") {
+        return Cow::Borrowed("image/jpeg");
+    }
+    if essence.bytes().any(|b| b.is_ascii_uppercase()) {
+        return Cow::Owned(essence.to_ascii_lowercase());
+    }
+    Cow::Borrowed(essence)
+}
+
+/// Infers a MIME type from a filename's extension.
+///
+/// The lookup is case-insensitive and covers the formats supported by the
+/// built-in handlers.
+///
+/// # Arguments
+///
+/// * `filename` - The filename or path to inspect (e.g., "word/embeddings/report.xlsx")
+///
+/// # Returns
+///
+/// The MIME type for a known extension, or `None` if the extension is unknown.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::mime::mime_from_filename;
+/// assert_eq!(mime_from_filename("notes.TXT"), Some("text/plain"));
+/// assert_eq!(mime_from_filename("oleObject1.bin"), None);
+/// ```
+pub fn mime_from_filename(filename: &str) -> Option<&'static str> {
+    let (_, extension) = filename.rsplit_once('.')?;
+
+    let mime_type = match extension.to_ascii_lowercase().as_str() {
+        "txt" | "log" => "text/plain",
+        "csv" => "text/csv",
+        "tsv" => "text/tab-separated-values",
+        "json" => "application/json",
+        "jsonld" => "application/ld+json",
+        "yaml" | "yml" => "application/yaml",
+        "toml" => "application/toml",
+        "html" | "htm" => "text/html",
+        "xhtml" => "application/xhtml+xml",
+        "md" | "markdown" => "text/markdown",
+        "xml" => "application/xml",
+        "pdf" => "application/pdf",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "xls" => "application/vnd.ms-excel",
+        "ods" => "application/vnd.oasis.opendocument.spreadsheet",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "epub" => "application/epub+zip",
+        "eml" => "message/rfc822",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "tif" | "tiff" => "image/tiff",
+        "webp" => "image/webp",
+        "zip" => "application/zip",
+        _ => return None,
+    };
+
+    Some(mime_type)
+}
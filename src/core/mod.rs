@@ -1,2 +1,15 @@
+pub mod clustering;
+pub mod detect;
+pub mod embedded;
 pub mod handler;
+pub mod mime;
+pub mod preprocess;
+pub mod redact;
+pub mod reference_index;
+pub mod sampling;
 pub mod similarity;
+pub mod similarity_cache;
+pub mod stem;
+pub mod summary;
+pub mod thread_pool;
+pub mod tokenize;
@@ -0,0 +1,500 @@
+//! Text preprocessing applied before similarity comparison.
+//!
+//! Preprocessing only affects the text that is compared; the extracted
+//! `text_content` returned to callers is left untouched. Both the source text
+//! and the reference texts go through the same steps so they stay comparable.
+//! The one exception is `normalize_whitespace`, which `process_files` applies
+//! to the extracted text itself when its `normalize_whitespace` option is set.
+
+use crate::models::options::CompareOptions;
+use std::borrow::Cow;
+use unicode_normalization::{IsNormalized, UnicodeNormalization, is_nfc_quick};
+
+/// Zero-width characters removed by `normalize_whitespace`: zero-width space,
+/// non-joiner, and joiner, word joiner, and the zero-width no-break space
+/// (byte order mark).
+const ZERO_WIDTH_CHARS: &[char] = &['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}'];
+
+/// Log-level tokens stripped from the start of log lines.
+const LOG_LEVELS: &[&str] = &[
+    "TRACE", "DEBUG", "INFO", "NOTICE", "WARN", "WARNING", "ERROR", "FATAL", "CRITICAL",
+];
+
+/// Settings for the preprocessing steps applied before comparison.
+#[derive(Debug, Clone, Default)]
+pub struct PreprocessOptions {
+    /// Strip leading ISO timestamps and log-level tokens from each line.
+    pub clean_logs: bool,
+    /// Collapse runs of whitespace into a single space and trim.
+    pub collapse_whitespace: bool,
+    /// Rewrite formatted numbers into a canonical form.
+    pub canonicalize_numbers: bool,
+    /// Normalize the text to Unicode Normalization Form C (NFC).
+    pub normalize_unicode: bool,
+}
+
+impl PreprocessOptions {
+    /// Returns `true` if no preprocessing step is enabled.
+    pub fn is_noop(&self) -> bool {
+        !self.clean_logs
+            && !self.collapse_whitespace
+            && !self.canonicalize_numbers
+            && !self.normalize_unicode
+    }
+}
+
+impl From<&CompareOptions> for PreprocessOptions {
+    fn from(options: &CompareOptions) -> Self {
+        Self {
+            clean_logs: options.clean_logs.unwrap_or(false),
+            collapse_whitespace: options.collapse_whitespace.unwrap_or(false),
+            canonicalize_numbers: options.canonicalize_numbers.unwrap_or(false),
+            normalize_unicode: options.normalize_unicode.unwrap_or(true),
+        }
+    }
+}
+
+/// Applies the enabled preprocessing steps to a text.
+///
+/// # Arguments
+///
+/// * `text` - The text to preprocess
+/// * `options` - The preprocessing steps to apply
+///
+/// # Returns
+///
+/// The preprocessed text, borrowed unchanged when no step is enabled.
+pub fn preprocess_text<'a>(text: &'a str, options: &PreprocessOptions) -> Cow<'a, str> {
+    if options.is_noop() {
+        return Cow::Borrowed(text);
+    }
+
+    let mut text = Cow::Borrowed(text);
+
+    // Runs first so the other steps see one spelling of each character
+    if options.normalize_unicode
+        && let Cow::Owned(normalized) = normalize_unicode(&text)
+    {
+        text = Cow::Owned(normalized);
+    }
+
+    if options.clean_logs {
+        text = Cow::Owned(clean_log_text(&text));
+    }
+
+    // Runs after log cleaning, which works line by line
+    if options.collapse_whitespace {
+        text = Cow::Owned(collapse_whitespace(&text));
+    }
+
+    if options.canonicalize_numbers {
+        text = Cow::Owned(canonicalize_numbers(&text));
+    }
+
+    text
+}
+
+/// Normalizes a text to Unicode Normalization Form C (NFC).
+///
+/// OCR engines and word processors write accented characters either
+/// precomposed ("é" as U+00E9) or decomposed ("e" followed by the combining
+/// acute accent U+0301). NFC composes them, so visually identical texts
+/// consist of the same characters.
+///
+/// # Returns
+///
+/// The normalized text, borrowed unchanged when it is already in NFC (the
+/// common case, detected with a quick check).
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::preprocess::normalize_unicode;
+/// assert_eq!(normalize_unicode("cafe\u{301}"), "caf\u{e9}");
+/// ```
+pub fn normalize_unicode(text: &str) -> Cow<'_, str> {
+    match is_nfc_quick(text.chars()) {
+        IsNormalized::Yes => Cow::Borrowed(text),
+        _ => Cow::Owned(text.nfc().collect()),
+    }
+}
+
+/// Removes structural log prefixes from every line of a text.
+///
+/// Each line is stripped of a leading ISO 8601 timestamp (optionally wrapped
+/// in square brackets) followed by a log-level token such as `INFO` or
+/// `[ERROR]`. Lines without these prefixes are kept as they are.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::preprocess::clean_log_text;
+/// let line = "2024-05-01T12:30:00Z ERROR connection refused";
+/// assert_eq!(clean_log_text(line), "connection refused");
+/// ```
+pub fn clean_log_text(text: &str) -> String {
+    text.lines()
+        .map(strip_log_prefix)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Strips a leading timestamp and log level from a single line.
+fn strip_log_prefix(line: &str) -> &str {
+    let rest = strip_timestamp(line.trim_start()).trim_start();
+    strip_log_level(rest).trim_start()
+}
+
+/// Strips a leading ISO 8601 timestamp such as `2024-05-01T12:30:00.123+02:00`.
+///
+/// The date part is required; the time, fractional seconds, and zone are
+/// optional. The timestamp may be wrapped in square brackets.
+fn strip_timestamp(line: &str) -> &str {
+    let (inner, bracketed) = match line.strip_prefix('[') {
+        Some(inner) => (inner, true),
+        None => (line, false),
+    };
+    let bytes = inner.as_bytes();
+    let digits = |start: usize, count: usize| {
+        bytes.len() >= start + count && bytes[start..start + count].iter().all(u8::is_ascii_digit)
+    };
+    let byte_is = |index: usize, expected: &[u8]| {
+        bytes.get(index).is_some_and(|byte| expected.contains(byte))
+    };
+
+    // Date: YYYY-MM-DD
+    if !(digits(0, 4) && byte_is(4, b"-") && digits(5, 2) && byte_is(7, b"-") && digits(8, 2)) {
+        return line;
+    }
+    let mut end = 10;
+
+    // Time: HH:MM[:SS[.fff]]
+    if byte_is(end, b"T ") && digits(end + 1, 2) && byte_is(end + 3, b":") && digits(end + 4, 2) {
+        end += 6;
+        if byte_is(end, b":") && digits(end + 1, 2) {
+            end += 3;
+            if byte_is(end, b".,") && digits(end + 1, 1) {
+                end += 1;
+                while digits(end, 1) {
+                    end += 1;
+                }
+            }
+        }
+
+        // Zone: Z, +HH:MM, or +HHMM
+        if byte_is(end, b"Z") {
+            end += 1;
+        } else if byte_is(end, b"+-") && digits(end + 1, 2) {
+            if byte_is(end + 3, b":") && digits(end + 4, 2) {
+                end += 6;
+            } else if digits(end + 3, 2) {
+                end += 5;
+            }
+        }
+    }
+
+    if bracketed {
+        if !byte_is(end, b"]") {
+            return line;
+        }
+        end += 1;
+    }
+
+    &inner[end..]
+}
+
+/// Strips a leading log-level token such as `INFO`, `[WARN]`, or `ERROR:`.
+fn strip_log_level(line: &str) -> &str {
+    let (inner, bracketed) = match line.strip_prefix('[') {
+        Some(inner) => (inner, true),
+        None => (line, false),
+    };
+
+    for level in LOG_LEVELS {
+        let Some(mut rest) = inner.strip_prefix(level) else {
+            continue;
+        };
+
+        if bracketed {
+            match rest.strip_prefix(']') {
+                Some(after) => rest = after,
+                None => continue,
+            }
+        }
+
+        let rest = rest.strip_prefix(':').unwrap_or(rest);
+        if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+            return rest;
+        }
+    }
+
+    line
+}
+
+/// Collapses every run of whitespace into a single space and trims the ends.
+///
+/// Only spacing changes: case, punctuation, and all other characters are kept.
+/// Line breaks and tabs count as whitespace, so the result is a single line.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::preprocess::collapse_whitespace;
+/// assert_eq!(collapse_whitespace("  Hello,\t world.  Bye\n"), "Hello, world. Bye");
+/// ```
+pub fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Normalizes the whitespace of extracted text, keeping its lines.
+///
+/// Handlers lay out text differently (tabs between XLSX cells, PDF line
+/// breaks, CRLF line endings in text files), which skews comparisons across
+/// formats. This function removes zero-width characters, treats `\r\n` and
+/// `\r` as line breaks, collapses every run of spaces and tabs within a line
+/// into a single space, trims each line, and drops empty lines, so the result
+/// is single-spaced and `\n`-delimited.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::preprocess::normalize_whitespace;
+/// assert_eq!(normalize_whitespace("Name\tTotal\r\n\r\nAda  12"), "Name Total\nAda 12");
+/// ```
+pub fn normalize_whitespace(text: &str) -> String {
+    text.replace(ZERO_WIDTH_CHARS, "")
+        .split(['\n', '\r'])
+        .map(collapse_whitespace)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Rewrites formatted numbers into a canonical form.
+///
+/// The same amount written as "1,000.00", "1000", or "1.000,00" (European
+/// style) becomes "1000", so number-sensitive methods see identical tokens
+/// while the actual values stay comparable.
+///
+/// # Rules
+///
+/// - When both `,` and `.` appear, the last one is the decimal separator and
+///   the other is the thousands separator
+/// - A separator appearing more than once is a thousands separator
+/// - A single separator followed by exactly three digits, after an integer
+///   part of one to three digits not starting with `0`, is a thousands separator
+/// - Any other single separator is a decimal separator
+/// - Thousands separators are removed, the decimal separator becomes `.`, and
+///   trailing zeros in the fraction are dropped
+///
+/// Tokens whose thousands groups aren't three digits wide (such as version
+/// numbers like "1.2.3") are left unchanged.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::preprocess::canonicalize_numbers;
+/// assert_eq!(canonicalize_numbers("Total: 1.000,00 EUR"), "Total: 1000 EUR");
+/// ```
+pub fn canonicalize_numbers(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let run_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == ',' || c == '.'))
+            .unwrap_or(rest.len());
+        let run = &rest[..run_len];
+
+        // Trailing separators are sentence punctuation, not part of the number
+        let number = run.trim_end_matches([',', '.']);
+        match canonicalize_number(number) {
+            Some(canonical) => output.push_str(&canonical),
+            None => output.push_str(number),
+        }
+        output.push_str(&run[number.len()..]);
+
+        rest = &rest[run_len..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Canonicalizes a single number made of digits, `,` and `.`.
+///
+/// Returns `None` if the separators don't form a valid number.
+fn canonicalize_number(number: &str) -> Option<String> {
+    let last_comma = number.rfind(',');
+    let last_dot = number.rfind('.');
+
+    let decimal_separator = match (last_comma, last_dot) {
+        (None, None) => None,
+        (Some(comma), Some(dot)) => Some(if comma > dot { ',' } else { '.' }),
+        (Some(_), None) | (None, Some(_)) => {
+            let separator = if last_comma.is_some() { ',' } else { '.' };
+            let mut parts = number.split(separator);
+            let integer = parts.next().unwrap_or_default();
+            let fraction: Vec<&str> = parts.collect();
+
+            let is_grouping = fraction.len() > 1
+                || (fraction[0].len() == 3
+                    && (1..=3).contains(&integer.len())
+                    && !integer.starts_with('0'));
+            if is_grouping { None } else { Some(separator) }
+        }
+    };
+
+    let (integer, fraction) = match decimal_separator {
+        Some(separator) => {
+            let index = number.rfind(separator)?;
+            (&number[..index], &number[index + 1..])
+        }
+        None => (number, ""),
+    };
+
+    if fraction.contains([',', '.']) {
+        return None;
+    }
+
+    let thousands_separator = match decimal_separator {
+        Some(',') => '.',
+        Some(_) => ',',
+        None if integer.contains('.') => '.',
+        None => ',',
+    };
+    let mut groups = integer.split(thousands_separator);
+
+    let first = groups.next().unwrap_or_default();
+    if first.is_empty() || first.contains([',', '.']) {
+        return None;
+    }
+
+    let mut digits = first.to_string();
+    for group in groups {
+        if group.len() != 3 || !group.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.push_str(group);
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        Some(digits)
+    } else {
+        Some(format!("{}.{}", digits, fraction))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_log_text() {
+        let log = "2024-05-01T12:30:00Z ERROR connection refused\n\
+                   [2024-05-01 12:30:01,250] [INFO] retrying in 5s\n\
+                   2024-05-01T12:30:02.5+02:00 WARN: disk almost full\n\
+                   INFORMATION is not a level";
+
+        assert_eq!(
+            clean_log_text(log),
+            "connection refused\nretrying in 5s\ndisk almost full\nINFORMATION is not a level"
+        );
+    }
+
+    #[test]
+    fn test_preprocess_text_makes_repeated_errors_identical() {
+        let options = PreprocessOptions {
+            clean_logs: true,
+            ..Default::default()
+        };
+        let first = "2024-05-01T08:00:00Z ERROR failed to open socket";
+        let second = "2024-06-13T21:45:10Z ERROR failed to open socket";
+
+        assert_eq!(
+            preprocess_text(first, &options),
+            preprocess_text(second, &options)
+        );
+        assert!(matches!(
+            preprocess_text(first, &PreprocessOptions::default()),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_collapse_whitespace_ignores_spacing_differences() {
+        use crate::core::similarity::levenshtein_similarity;
+
+        let first = "The contract starts today.  Payment is due\tmonthly.";
+        let second = "The contract starts today. Payment is due monthly.\n";
+        let options = PreprocessOptions {
+            collapse_whitespace: true,
+            ..Default::default()
+        };
+
+        assert!(levenshtein_similarity(first, second, None) < 100.0);
+        assert_eq!(
+            levenshtein_similarity(
+                &preprocess_text(first, &options),
+                &preprocess_text(second, &options),
+                None
+            ),
+            100.0
+        );
+        assert_eq!(
+            collapse_whitespace(" Keep CASE, and punctuation! "),
+            "Keep CASE, and punctuation!"
+        );
+    }
+
+    #[test]
+    fn test_normalize_whitespace() {
+        let text = "  Invoice\t\t2024-001\r\nCustomer:   Ada\u{200B} Lovelace \r\n\r\n\nTotal\t 12.50\rPaid";
+
+        assert_eq!(
+            normalize_whitespace(text),
+            "Invoice 2024-001\nCustomer: Ada Lovelace\nTotal 12.50\nPaid"
+        );
+    }
+
+    #[test]
+    fn test_normalize_unicode_matches_decomposed_accents() {
+        use crate::core::similarity::{SimilarityMethod, calculate_similarity};
+
+        let composed = "caf\u{e9} cr\u{e8}me";
+        let decomposed = "cafe\u{301} cre\u{300}me";
+        let options = PreprocessOptions::from(&CompareOptions::default());
+        assert!(options.normalize_unicode);
+
+        assert!(calculate_similarity(composed, decomposed, SimilarityMethod::Levenshtein) < 100.0);
+        assert_eq!(
+            calculate_similarity(
+                &preprocess_text(composed, &options),
+                &preprocess_text(decomposed, &options),
+                SimilarityMethod::Levenshtein
+            ),
+            100.0
+        );
+        assert!(matches!(
+            preprocess_text(composed, &options),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_canonicalize_numbers() {
+        assert_eq!(
+            canonicalize_numbers("1,000.00 and 1000 and 1.000,00"),
+            "1000 and 1000 and 1000"
+        );
+        assert_eq!(canonicalize_numbers("1,234,567.891"), "1234567.891");
+        assert_eq!(canonicalize_numbers("12,5% of 3.14"), "12.5% of 3.14");
+        assert_eq!(canonicalize_numbers("costs 1,000."), "costs 1000.");
+        assert_eq!(canonicalize_numbers("0.500 kg"), "0.5 kg");
+        assert_eq!(canonicalize_numbers("version v1.2.3"), "version v1.2.3");
+    }
+}
@@ -0,0 +1,181 @@
+//! Redaction of sensitive patterns in extracted text.
+//!
+//! `process_files` can mask emails, identifiers, phone numbers, and similar
+//! personal data before the text leaves the library. The patterns are
+//! compiled once per processor into a `Redactor` and applied to the text of
+//! every file.
+//!
+//! Patterns use the syntax of the `regex` crate, which matches in time linear
+//! in the text for every pattern, so nested quantifiers and long runs are
+//! safe. Patterns whose compiled form exceeds the crate's size limit are
+//! rejected.
+
+use regex::{Captures, Regex};
+
+/// The mask that replaces redacted matches by default.
+pub const DEFAULT_REDACTION_MASK: &str = "[REDACTED]";
+
+/// A set of compiled redaction patterns and the mask replacing their matches.
+pub struct Redactor {
+    patterns: Vec<Regex>,
+    mask: String,
+}
+
+impl Redactor {
+    /// Compiles redaction patterns.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - The patterns to redact, in `regex` crate syntax
+    /// * `mask` - The text replacing each match
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Redactor)` - The compiled patterns
+    /// * `Err(String)` - Error message naming the first invalid pattern and
+    ///   what is wrong with it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dms_toolkit_rs::core::redact::Redactor;
+    /// let redactor = Redactor::new(&[r"\d{3}-\d{2}-\d{4}".to_string()], "[SSN]").unwrap();
+    /// assert_eq!(redactor.redact("SSN: 123-45-6789"), "SSN: [SSN]");
+    /// ```
+    pub fn new(patterns: &[String], mask: &str) -> Result<Self, String> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .map_err(|e| format!("Invalid redaction pattern \"{}\": {}", pattern, e))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            patterns,
+            mask: mask.to_string(),
+        })
+    }
+
+    /// Replaces every match of the patterns in `text` with the mask.
+    ///
+    /// Patterns are applied in order, each to the result of the previous one.
+    /// Matches are found from left to right without overlapping; a pattern
+    /// matching the empty string leaves the text unchanged where it does. The
+    /// mask is inserted as is, without expanding `$` group references.
+    pub fn redact(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for pattern in &self.patterns {
+            let mask = |caps: &Captures| {
+                if caps[0].is_empty() {
+                    ""
+                } else {
+                    self.mask.as_str()
+                }
+            };
+            text = pattern.replace_all(&text, mask).into_owned();
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redact(patterns: &[&str], text: &str) -> String {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        Redactor::new(&patterns, DEFAULT_REDACTION_MASK)
+            .unwrap()
+            .redact(text)
+    }
+
+    #[test]
+    fn test_redact_common_pii() {
+        let email = r"[\w.+-]+@[\w-]+(\.[\w-]+)+";
+        let ssn = r"\b\d{3}-\d{2}-\d{4}\b";
+        let phone = r"\(?\d{3}\)?[ .-]?\d{3}[ .-]\d{4}";
+
+        assert_eq!(
+            redact(
+                &[email, ssn, phone],
+                "Contact ada.lovelace+work@example.co.uk or (555) 123-4567.\nSSN 123-45-6789, ref 1234-56-7890."
+            ),
+            "Contact [REDACTED] or [REDACTED].\nSSN [REDACTED], ref 1234-56-7890."
+        );
+    }
+
+    #[test]
+    fn test_pattern_syntax() {
+        // Alternation, groups, anchors, and lazy quantifiers
+        assert_eq!(
+            redact(&["cat|dog"], "cat, dog, cow"),
+            "[REDACTED], [REDACTED], cow"
+        );
+        assert_eq!(redact(&["^id"], "id id"), "[REDACTED] id");
+        assert_eq!(redact(&["<.+?>"], "<a><b>"), "[REDACTED][REDACTED]");
+        assert_eq!(redact(&["<.+>"], "<a><b>"), "[REDACTED]");
+        assert_eq!(redact(&["(?:ab){2,}"], "ab abab"), "ab [REDACTED]");
+        assert_eq!(redact(&[r"[^\s]+\.pdf"], "see q1.pdf"), "see [REDACTED]");
+        // Empty matches leave the text unchanged
+        assert_eq!(redact(&["x*"], "abc"), "abc");
+        assert_eq!(redact(&[r"\bid\b"], "grid id"), "grid [REDACTED]");
+        assert_eq!(redact(&["a?b|ab"], "ab"), "[REDACTED]");
+    }
+
+    #[test]
+    fn test_mask_is_not_expanded() {
+        let redactor = Redactor::new(&[r"(\d+)".to_string()], "$1 ${x}").unwrap();
+        assert_eq!(redactor.redact("pin 1234"), "pin $1 ${x}");
+    }
+
+    #[test]
+    fn test_long_and_pathological_inputs() {
+        // Repeated groups over a long run, on a thread with a small stack
+        let run = "1 ".repeat(200_000);
+        let redacted = std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(move || redact(&[r"(?:\d )+"], &run))
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(redacted, DEFAULT_REDACTION_MASK);
+
+        // A long word without a match is scanned once, not once per offset
+        let blob = format!("{} tail", "QUJD".repeat(25_000));
+        let email = r"[\w.+-]+@[\w-]+\.\w+";
+        assert_eq!(redact(&[email], &blob), blob);
+        assert_eq!(
+            redact(&[email], &format!("{}@example.org", "a".repeat(100_000))),
+            DEFAULT_REDACTION_MASK
+        );
+
+        // Nested quantifiers that take exponential time when backtracking
+        let text = format!("{}b", "a".repeat(10_000));
+        assert_eq!(redact(&["(a+)+$"], &text), text);
+        assert_eq!(redact(&["(a*)*b"], &text), DEFAULT_REDACTION_MASK);
+    }
+
+    #[test]
+    fn test_invalid_patterns_are_rejected() {
+        for (pattern, reason) in [
+            ("(abc", "unclosed group"),
+            ("abc)", "unopened group"),
+            ("[a-", "unclosed character class"),
+            ("[z-a]", "invalid character class range"),
+            ("*a", "repetition operator missing expression"),
+            ("a{3,1}", "invalid repetition count range"),
+            ("(?:a{1000}){1000}", "exceeds size limit"),
+        ] {
+            let err = Redactor::new(&[pattern.to_string()], DEFAULT_REDACTION_MASK)
+                .err()
+                .unwrap();
+            assert!(
+                err.starts_with(&format!("Invalid redaction pattern \"{}\"", pattern))
+                    && err.contains(reason),
+                "{}",
+                err
+            );
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! Inverted trigram index over a reference corpus.
+//!
+//! `compare_with_documents` scores every reference for every source text,
+//! which is O(files × references). A `ReferenceIndex` maps each character
+//! trigram to the references containing it, so `compare_with_index` can count
+//! the trigrams a source shares with each reference in one pass over the
+//! source's trigrams and score only the references sharing enough of them.
+
+use crate::core::similarity::{SimilarityMethod, character_ngrams, document_idf, score_reference};
+use crate::core::similarity_cache;
+use crate::core::tokenize::TokenizerOptions;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// The n-gram size of the index.
+const INDEX_NGRAM_SIZE: usize = 3;
+
+/// An inverted trigram index over reference texts.
+///
+/// Trigrams are taken as `SimilarityMethod::Ngram(3)` takes them: from the
+/// lowercased text with whitespace collapsed. Build one with
+/// `build_reference_index` and reuse it for every source text.
+pub struct ReferenceIndex {
+    /// The indexed reference texts, in input order.
+    texts: Vec<String>,
+    /// The number of distinct trigrams of each reference.
+    trigram_counts: Vec<usize>,
+    /// The references containing each trigram, in ascending order.
+    postings: HashMap<String, Vec<u32>>,
+}
+
+impl ReferenceIndex {
+    /// Returns the indices of the references worth scoring against a source
+    /// text, in ascending order.
+    ///
+    /// For case-insensitive `Ngram(3)` the retrieval is exact: a reference
+    /// sharing `s` trigrams with the source scores at most
+    /// `s / max(source trigrams, reference trigrams)`, so references whose
+    /// bound is below the threshold are skipped without changing the result.
+    /// For every other method, candidates are the references sharing at least
+    /// one trigram with the source. References too short to have trigrams are
+    /// always candidates, and so is every reference when the source has no
+    /// trigrams or the threshold is 0.
+    fn candidates(
+        &self,
+        source: &str,
+        method: SimilarityMethod,
+        threshold: f64,
+        tokenizer: &TokenizerOptions,
+    ) -> Vec<usize> {
+        let source_trigrams =
+            character_ngrams(source, INDEX_NGRAM_SIZE, &TokenizerOptions::default());
+        if source_trigrams.is_empty() || threshold <= 0.0 {
+            return (0..self.texts.len()).collect();
+        }
+
+        let mut shared = vec![0usize; self.texts.len()];
+        for trigram in &source_trigrams {
+            if let Some(references) = self.postings.get(trigram) {
+                for &idx in references {
+                    shared[idx as usize] += 1;
+                }
+            }
+        }
+
+        let exact_bound = matches!(method, SimilarityMethod::Ngram(INDEX_NGRAM_SIZE))
+            && !tokenizer.case_sensitive;
+        (0..self.texts.len())
+            .filter(|&idx| {
+                let reference_trigrams = self.trigram_counts[idx];
+                if reference_trigrams == 0 {
+                    return true;
+                }
+                if exact_bound {
+                    let largest = reference_trigrams.max(source_trigrams.len());
+                    // The percentage is compared as `compare_with_documents` computes it
+                    shared[idx] as f64 / largest as f64 * 100.0 >= threshold
+                } else {
+                    shared[idx] > 0
+                }
+            })
+            .collect()
+    }
+}
+
+/// Builds a trigram index over reference texts.
+///
+/// The trigrams of the references are collected in parallel; building the
+/// index costs about as much as one brute-force comparison pass.
+///
+/// # Arguments
+///
+/// * `reference_texts` - The reference texts to index
+///
+/// # Returns
+///
+/// A `ReferenceIndex` holding a copy of the references and their trigrams.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::reference_index::build_reference_index;
+/// let references = vec!["annual report".to_string(), "cafeteria menu".to_string()];
+/// let index = build_reference_index(&references);
+/// ```
+pub fn build_reference_index(reference_texts: &[String]) -> ReferenceIndex {
+    let trigram_sets: Vec<_> = reference_texts
+        .par_iter()
+        .map(|text| character_ngrams(text, INDEX_NGRAM_SIZE, &TokenizerOptions::default()))
+        .collect();
+
+    let trigram_counts = trigram_sets.iter().map(|set| set.len()).collect();
+    let mut postings: HashMap<String, Vec<u32>> = HashMap::new();
+    for (idx, trigrams) in trigram_sets.into_iter().enumerate() {
+        for trigram in trigrams {
+            postings.entry(trigram).or_default().push(idx as u32);
+        }
+    }
+
+    ReferenceIndex {
+        texts: reference_texts.to_vec(),
+        trigram_counts,
+        postings,
+    }
+}
+
+/// Compares one text against indexed reference texts, scoring only the
+/// candidates retrieved from the index.
+///
+/// Candidates are scored exactly like `compare_with_documents` scores every
+/// reference. For `Ngram(3)`, the result is the same as a brute-force
+/// comparison; for other methods, references sharing no trigram with the
+/// source are skipped even if they would have reached the threshold, which
+/// only happens for very short texts or very low thresholds. With
+/// `case_sensitive` set, `Ngram(3)` candidates are retrieved like those of the
+/// other methods, since the index holds lowercased trigrams. `CosineTfIdf`
+/// weighs terms across all references, so it still builds an IDF table over
+/// the whole corpus for each call.
+///
+/// # Arguments
+///
+/// * `source_text` - The text to compare
+/// * `index` - The index over the reference texts
+/// * `method` - The similarity method to use
+/// * `threshold` - The minimum similarity percentage (0-100) required for a match
+/// * `tokenizer` - Tokenization settings (see `calculate_similarity_with`)
+/// * `parallel` - Score the candidates in parallel, as in
+///   `compare_with_documents`
+///
+/// # Returns
+///
+/// A vector of `(reference_index, similarity_percentage, method_used)` tuples
+/// for every match at or above the threshold, as returned by
+/// `compare_with_documents`, in no particular order.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::reference_index::{build_reference_index, compare_with_index};
+/// # use dms_toolkit_rs::core::similarity::SimilarityMethod;
+/// # use dms_toolkit_rs::core::tokenize::TokenizerOptions;
+/// let references = vec![
+///     "the quick brown fox".to_string(),
+///     "a completely different text".to_string(),
+/// ];
+/// let index = build_reference_index(&references);
+///
+/// let matches = compare_with_index(
+///     "the quick brown fox",
+///     &index,
+///     SimilarityMethod::Ngram(3),
+///     50.0,
+///     &TokenizerOptions::default(),
+///     true,
+/// );
+/// assert_eq!(matches, vec![(0, 100.0, "ngram")]);
+/// ```
+pub fn compare_with_index(
+    source_text: &str,
+    index: &ReferenceIndex,
+    method: SimilarityMethod,
+    threshold: f64,
+    tokenizer: &TokenizerOptions,
+    parallel: bool,
+) -> Vec<(usize, f64, &'static str)> {
+    let idf = document_idf(source_text, &index.texts, method);
+    let candidates = index.candidates(source_text, method, threshold, tokenizer);
+    // A minimum split length covering every candidate keeps Rayon from splitting the work
+    let min_len = if parallel { 1 } else { candidates.len().max(1) };
+    let source_hash = similarity_cache::hash_source(source_text);
+
+    candidates
+        .into_par_iter()
+        .with_min_len(min_len)
+        .filter_map(|idx| {
+            score_reference(
+                source_text,
+                source_hash,
+                &index.texts[idx],
+                method,
+                threshold,
+                tokenizer,
+                idf.as_ref(),
+            )
+            .map(|(similarity, method_used)| (idx, similarity, method_used))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::similarity::compare_with_documents;
+
+    #[test]
+    fn test_indexed_matches_equal_brute_force() {
+        let references: Vec<String> = [
+            "Invoice 2024-001 for consulting services",
+            "Invoice 2024-002 for consulting services",
+            "Quarterly report on revenue and growth",
+            "Cafeteria menu for the week of March 4",
+            "Service agreement between the parties",
+            "ok",
+            "",
+            "Invoice for consulting",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        let index = build_reference_index(&references);
+
+        for source in [
+            "invoice 2024-003 for consulting services",
+            "Quarterly revenue report",
+            "ok",
+        ] {
+            for threshold in [0.0, 20.0, 50.0, 90.0] {
+                for method in [SimilarityMethod::Ngram(3), SimilarityMethod::Jaccard] {
+                    let mut indexed = compare_with_index(
+                        source,
+                        &index,
+                        method,
+                        threshold,
+                        &TokenizerOptions::default(),
+                        true,
+                    );
+                    let mut brute = compare_with_documents(
+                        source,
+                        &references,
+                        method,
+                        threshold,
+                        &TokenizerOptions::default(),
+                        true,
+                    );
+                    indexed.sort_by_key(|m| m.0);
+                    brute.sort_by_key(|m| m.0);
+
+                    assert_eq!(
+                        indexed, brute,
+                        "{:?} at {} for {:?}",
+                        method, threshold, source
+                    );
+                }
+            }
+        }
+    }
+}
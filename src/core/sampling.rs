@@ -0,0 +1,114 @@
+//! Deterministic text sampling for approximate similarity estimates.
+//!
+//! Comparing full texts of very large documents is slow. In sampling mode the
+//! source and reference texts are reduced to a few evenly spaced windows (the
+//! beginning, middle, and end), and the similarity of the samples is used as
+//! an estimate of the similarity of the full texts. Whether a comparison is
+//! sampled is decided by the size of the source text; the reference texts are
+//! then sampled with the same fraction so that both sides stay proportional.
+
+/// Default fraction of each text kept by sampling.
+pub const DEFAULT_SAMPLE_FRACTION: f64 = 0.1;
+
+/// Source texts shorter than this (in characters) are always compared in full.
+pub const MIN_SAMPLE_CHARS: usize = 2_000;
+
+/// Number of windows taken from each sampled text.
+const SAMPLE_WINDOWS: usize = 3;
+
+/// Returns whether a text is large enough to be sampled in sampling mode.
+///
+/// Texts shorter than `MIN_SAMPLE_CHARS` are cheap to compare and are always
+/// compared in full.
+pub fn should_sample(text: &str) -> bool {
+    text.chars().nth(MIN_SAMPLE_CHARS - 1).is_some()
+}
+
+/// Reduces a text to evenly spaced windows covering `fraction` of it.
+///
+/// The text is split into three windows of equal size taken from its
+/// beginning, middle, and end, joined by newlines. Sampling is deterministic
+/// and proportional: the same text and fraction always produce the same
+/// sample, and two texts sampled with the same fraction keep their relative
+/// lengths, so similarity between samples approximates similarity between
+/// the full texts.
+///
+/// # Arguments
+///
+/// * `text` - The text to sample
+/// * `fraction` - Fraction of the text to keep, clamped to 0.0-1.0
+///
+/// # Returns
+///
+/// The sampled text, or the whole text if the windows would overlap.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::sampling::sample_text;
+/// let text = "a".repeat(10_000);
+/// let sample = sample_text(&text, 0.1);
+/// assert!(sample.len() < 1_100);
+/// ```
+pub fn sample_text(text: &str, fraction: f64) -> String {
+    let fraction = fraction.clamp(0.0, 1.0);
+    let char_count = text.chars().count();
+
+    let window_len = ((char_count as f64 * fraction) / SAMPLE_WINDOWS as f64).ceil() as usize;
+    let window_len = window_len.max(1);
+    if window_len * SAMPLE_WINDOWS >= char_count {
+        return text.to_string();
+    }
+    let last_start = char_count - window_len;
+
+    // Map character offsets to byte offsets so windows never split a character
+    let byte_offset = |char_index: usize| {
+        text.char_indices()
+            .nth(char_index)
+            .map_or(text.len(), |(offset, _)| offset)
+    };
+
+    let windows: Vec<&str> = (0..SAMPLE_WINDOWS)
+        .map(|window| {
+            let start = last_start * window / (SAMPLE_WINDOWS - 1);
+            &text[byte_offset(start)..byte_offset(start + window_len)]
+        })
+        .collect();
+
+    windows.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_text_takes_start_middle_and_end() {
+        let text = format!(
+            "{}{}{}",
+            "a".repeat(1_000),
+            "b".repeat(1_000),
+            "c".repeat(1_000)
+        );
+
+        let sample = sample_text(&text, 0.03);
+
+        assert_eq!(
+            sample,
+            format!("{}\n{}\n{}", "a".repeat(30), "b".repeat(30), "c".repeat(30))
+        );
+    }
+
+    #[test]
+    fn test_should_sample() {
+        assert!(!should_sample("short text"));
+        assert!(!should_sample(&"é".repeat(MIN_SAMPLE_CHARS - 1)));
+        assert!(should_sample(&"é".repeat(MIN_SAMPLE_CHARS)));
+    }
+
+    #[test]
+    fn test_sample_text_keeps_small_texts_whole() {
+        assert_eq!(sample_text("abc", 0.1), "abc");
+        assert_eq!(sample_text("abcdef", 1.0), "abcdef");
+    }
+}
@@ -4,14 +4,23 @@
 //! used by the `process_and_compare_files` function to match extracted
 //! text against reference documents.
 
+use crate::core::preprocess::collapse_whitespace;
+use crate::core::similarity_cache::{self, SourceHash};
+use crate::core::tokenize::{TokenizerOptions, tokenize};
 use rayon::prelude::*;
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+/// Default n-gram size used by the `Ngram` and `Hybrid` methods (trigrams).
+pub const DEFAULT_NGRAM_SIZE: usize = 3;
 
 /// Enumeration of available similarity calculation methods.
 ///
 /// Each method has different characteristics in terms of speed and accuracy,
 /// making them suitable for different use cases.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SimilarityMethod {
     /// Fast word-based similarity using Jaccard index.
     ///
@@ -20,11 +29,21 @@ pub enum SimilarityMethod {
     /// Very fast but may miss character-level similarities.
     Jaccard,
 
-    /// Character n-gram based similarity (uses 3-grams).
+    /// Character n-gram based similarity with the given n-gram size.
     ///
     /// Good for longer texts where word-based methods might miss character-level
     /// similarities. Breaks texts into character sequences and compares shared n-grams.
-    Ngram,
+    /// Trigrams (`DEFAULT_NGRAM_SIZE`) work well for most text; bigrams or 4-grams
+    /// can work better for CJK text and short product codes.
+    Ngram(usize),
+
+    /// Word n-gram (shingle) based similarity with the given n-gram size.
+    ///
+    /// Like `Ngram`, but the n-grams are runs of consecutive words rather than
+    /// characters, so a shared phrase counts as a match while the same words
+    /// in a different order do not. Word bigrams or trigrams suit articles and
+    /// other prose compared for reused passages.
+    WordNgram(usize),
 
     /// Edit distance based similarity using Levenshtein distance.
     ///
@@ -33,15 +52,253 @@ pub enum SimilarityMethod {
     /// long texts. Converts edit distance to similarity percentage.
     Levenshtein,
 
+    /// Edit distance based similarity using Damerau-Levenshtein distance.
+    ///
+    /// Like `Levenshtein`, but a swap of two adjacent characters counts as a
+    /// single edit instead of two. Better suited for OCR output and typing
+    /// errors, where adjacent characters are often transposed.
+    DamerauLevenshtein,
+
     /// Progressive filtering approach combining multiple methods.
     ///
     /// Balances speed and accuracy by:
     /// 1. Fast Jaccard check - if score < 20%, return immediately
-    /// 2. For small texts (< 1000 chars): Use Levenshtein with early termination
-    /// 3. For larger texts: Use N-gram similarity
+    /// 2. For small texts (< 1000 chars): Use Levenshtein similarity
+    /// 3. For larger texts: Use N-gram similarity with the given n-gram size
     ///
     /// This is the default method and recommended for most use cases.
-    Hybrid,
+    Hybrid(usize),
+
+    /// Cosine similarity over TF-IDF weighted term vectors.
+    ///
+    /// Weights each term by how rare it is across the comparison corpus, so
+    /// shared common words like "the" and "and" contribute almost nothing while
+    /// shared rare terms dominate the score. When used through
+    /// `compare_with_documents`, the corpus is the source plus all target texts.
+    CosineTfIdf,
+
+    /// Jaro-Winkler similarity for short strings.
+    ///
+    /// Designed for short strings such as names and titles. Treats nearby
+    /// transposed characters leniently and boosts strings sharing a common
+    /// prefix, so "MARTHA" and "MARHTA" score much higher than with Levenshtein.
+    JaroWinkler,
+
+    /// Weighted blend of Jaccard, n-gram, and Levenshtein similarity.
+    ///
+    /// Unlike `Hybrid`, which picks one method per comparison, every method
+    /// with a non-zero weight runs and the score is
+    /// `jaccard * J + ngram * N + levenshtein * L`. Build it with
+    /// `SimilarityMethod::weighted`, which validates the weights and scales
+    /// them to sum to 1.
+    Weighted {
+        /// Weight of the Jaccard score.
+        jaccard: f64,
+        /// Weight of the n-gram score.
+        ngram: f64,
+        /// Weight of the Levenshtein score.
+        levenshtein: f64,
+        /// N-gram size of the n-gram score.
+        ngram_size: usize,
+    },
+
+    /// Best n-gram similarity of the shorter text against any same-sized
+    /// window of the longer text, with the given n-gram size.
+    ///
+    /// Finds a short passage embedded in a much longer document, which
+    /// whole-document methods score low because most of the document does not
+    /// match. The length pre-filter is skipped for this method.
+    SlidingWindow(usize),
+
+    /// 100 if the target text occurs verbatim in the source text, 0 otherwise.
+    ///
+    /// Both texts are lowercased (unless `case_sensitive` is set) and their
+    /// whitespace collapsed first. Meant for short references such as IDs or
+    /// case numbers that must appear exactly, which fuzzy methods score low
+    /// in a long document. The length pre-filter is skipped for this method.
+    Substring,
+}
+
+impl SimilarityMethod {
+    /// Returns the method's name as accepted by `process_and_compare_files`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dms_toolkit_rs::core::similarity::SimilarityMethod;
+    /// assert_eq!(SimilarityMethod::DamerauLevenshtein.name(), "damerau");
+    /// ```
+    pub fn name(&self) -> &'static str {
+        match self {
+            SimilarityMethod::Jaccard => "jaccard",
+            SimilarityMethod::Ngram(_) => "ngram",
+            SimilarityMethod::WordNgram(_) => "word_ngram",
+            SimilarityMethod::Levenshtein => "levenshtein",
+            SimilarityMethod::DamerauLevenshtein => "damerau",
+            SimilarityMethod::Hybrid(_) => "hybrid",
+            SimilarityMethod::CosineTfIdf => "cosine",
+            SimilarityMethod::JaroWinkler => "jarowinkler",
+            SimilarityMethod::Weighted { .. } => "weighted",
+            SimilarityMethod::SlidingWindow(_) => "sliding_window",
+            SimilarityMethod::Substring => "substring",
+        }
+    }
+
+    /// Returns `true` if texts of very different lengths can still score high,
+    /// so the length pre-filter (`pre_filter_by_length`) must not reject them.
+    fn matches_across_lengths(&self) -> bool {
+        matches!(
+            self,
+            SimilarityMethod::SlidingWindow(_) | SimilarityMethod::Substring
+        )
+    }
+
+    /// Returns `true` if the score depends on which text is the source, so
+    /// comparisons without a designated source must score both directions.
+    fn is_directional(&self) -> bool {
+        matches!(self, SimilarityMethod::Substring)
+    }
+
+    /// Returns `true` if the score compares the words kept by `tokenize`, so
+    /// the stopword and length filters of `TokenizerOptions` can leave a text
+    /// with nothing to compare.
+    fn compares_words(&self) -> bool {
+        match self {
+            SimilarityMethod::Jaccard | SimilarityMethod::WordNgram(_) => true,
+            SimilarityMethod::Weighted { jaccard, .. } => *jaccard > 0.0,
+            _ => false,
+        }
+    }
+
+    /// Creates a `Weighted` method from relative weights.
+    ///
+    /// The weights are scaled to sum to 1, so `(1, 1, 2)` and
+    /// `(0.25, 0.25, 0.5)` blend the scores the same way.
+    ///
+    /// # Arguments
+    ///
+    /// * `jaccard` - Weight of the Jaccard score
+    /// * `ngram` - Weight of the n-gram score
+    /// * `levenshtein` - Weight of the Levenshtein score
+    /// * `ngram_size` - N-gram size of the n-gram score (0 is clamped to 1)
+    ///
+    /// # Returns
+    ///
+    /// The weighted method, or an error if a weight is negative or not a
+    /// number, or if every weight is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dms_toolkit_rs::core::similarity::SimilarityMethod;
+    /// let method = SimilarityMethod::weighted(1.0, 1.0, 2.0, 3).unwrap();
+    /// assert!(matches!(method, SimilarityMethod::Weighted { levenshtein, .. } if levenshtein == 0.5));
+    /// assert!(SimilarityMethod::weighted(-1.0, 1.0, 1.0, 3).is_err());
+    /// ```
+    pub fn weighted(
+        jaccard: f64,
+        ngram: f64,
+        levenshtein: f64,
+        ngram_size: usize,
+    ) -> Result<Self, String> {
+        let weights = [jaccard, ngram, levenshtein];
+        if weights
+            .iter()
+            .any(|weight| !weight.is_finite() || *weight < 0.0)
+        {
+            return Err("Similarity weights must be non-negative numbers".to_string());
+        }
+
+        let total: f64 = weights.iter().sum();
+        if total == 0.0 {
+            return Err("At least one similarity weight must be positive".to_string());
+        }
+
+        Ok(SimilarityMethod::Weighted {
+            jaccard: jaccard / total,
+            ngram: ngram / total,
+            levenshtein: levenshtein / total,
+            ngram_size: ngram_size.max(1),
+        })
+    }
+}
+
+impl Hash for SimilarityMethod {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match *self {
+            SimilarityMethod::Ngram(n)
+            | SimilarityMethod::WordNgram(n)
+            | SimilarityMethod::Hybrid(n)
+            | SimilarityMethod::SlidingWindow(n) => n.hash(state),
+            SimilarityMethod::Weighted {
+                jaccard,
+                ngram,
+                levenshtein,
+                ngram_size,
+            } => {
+                // Adding 0.0 turns -0.0 into 0.0, which compares equal to it
+                for weight in [jaccard, ngram, levenshtein] {
+                    (weight + 0.0).to_bits().hash(state);
+                }
+                ngram_size.hash(state);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Which dimension of a files-by-references comparison runs in parallel.
+///
+/// Comparing every file with every reference text can be parallelized over
+/// either dimension. Parallelizing over both nests Rayon iterators, which
+/// splits the work into many small tasks that compete for the same threads
+/// when both dimensions are large.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parallelism {
+    /// Compare files in parallel, each with its reference texts in sequence.
+    /// Best for many files.
+    ByFile,
+    /// Compare files in sequence, each with its reference texts in parallel.
+    /// Best for few files and many reference texts.
+    ByReference,
+}
+
+impl Parallelism {
+    /// Picks the dimension to parallelize for a batch.
+    ///
+    /// Files are compared in parallel when there are at least as many files
+    /// as worker threads, since every thread then has a file to work on;
+    /// smaller batches parallelize over the reference texts instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_count` - The number of files in the batch
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dms_toolkit_rs::core::similarity::Parallelism;
+    /// assert_eq!(Parallelism::auto(10_000), Parallelism::ByFile);
+    /// ```
+    pub fn auto(file_count: usize) -> Self {
+        if file_count >= rayon::current_num_threads() {
+            Parallelism::ByFile
+        } else {
+            Parallelism::ByReference
+        }
+    }
+}
+
+/// Which parts of a source text are compared with the reference texts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    /// Compare the whole text with each reference.
+    Document,
+    /// Compare each paragraph with each reference and keep the best-scoring
+    /// paragraph per reference, so a section shared with a reference is found
+    /// even when the rest of the document differs.
+    Paragraph,
 }
 
 /// Fast pre-filtering using length difference heuristic.
@@ -125,14 +382,38 @@ pub fn pre_filter_by_length(source: &str, target: &str, threshold: f64) -> bool
 /// // Returns a value between 0 and 100 based on shared words
 /// ```
 pub fn jaccard_similarity(source: &str, target: &str) -> f64 {
-    let source_words: HashSet<String> = source
-        .split_whitespace()
-        .map(|s| s.to_lowercase())
-        .collect();
-    let target_words: HashSet<String> = target
-        .split_whitespace()
-        .map(|s| s.to_lowercase())
-        .collect();
+    jaccard_similarity_with(source, target, &TokenizerOptions::default())
+}
+
+/// Calculates Jaccard similarity between two texts with configurable tokenization.
+///
+/// Works like `jaccard_similarity`, but splits the texts into words according
+/// to `options`. Removing stopwords keeps common words from dominating the
+/// score, and stemming lets different forms of a word ("running", "runs")
+/// count as the same word.
+///
+/// # Arguments
+///
+/// * `source` - The source text to compare
+/// * `target` - The target text to compare against
+/// * `options` - The tokenizer settings (see `TokenizerOptions`)
+///
+/// # Returns
+///
+/// Similarity percentage (0.0 to 100.0) of the two word sets.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::jaccard_similarity_with;
+/// # use dms_toolkit_rs::core::tokenize::TokenizerOptions;
+/// let options = TokenizerOptions { remove_stopwords: true, stem: true, ..Default::default() };
+/// let similarity = jaccard_similarity_with("the cats are running", "a cat runs", &options);
+/// assert_eq!(similarity, 100.0);
+/// ```
+pub fn jaccard_similarity_with(source: &str, target: &str, options: &TokenizerOptions) -> f64 {
+    let source_words: HashSet<String> = tokenize(source, options).collect();
+    let target_words: HashSet<String> = tokenize(target, options).collect();
 
     let intersection_size = source_words.intersection(&target_words).count();
     let union_size = source_words.union(&target_words).count();
@@ -153,7 +434,8 @@ pub fn jaccard_similarity(source: &str, target: &str) -> f64 {
 ///
 /// # Algorithm
 ///
-/// 1. Normalizes texts: converts to lowercase and normalizes whitespace
+/// 1. Normalizes texts: converts to lowercase and collapses every run of
+///    whitespace (spaces, tabs, line breaks) into a single space
 /// 2. Generates n-grams (character sequences of length n) for both texts
 /// 3. Creates sets of unique n-grams
 /// 4. Calculates: `intersection_size / union_size * 100`
@@ -162,7 +444,8 @@ pub fn jaccard_similarity(source: &str, target: &str) -> f64 {
 ///
 /// * `source` - The source text to compare
 /// * `target` - The target text to compare against
-/// * `n` - The n-gram size (typically 2-4, commonly 3 for trigrams)
+/// * `n` - The n-gram size (typically 2-4, commonly 3 for trigrams). A size of
+///   0 is clamped to 1.
 ///
 /// # Returns
 ///
@@ -190,32 +473,131 @@ pub fn jaccard_similarity(source: &str, target: &str) -> f64 {
 /// let similarity = ngram_similarity(text1, text2, 3); // Uses trigrams
 /// ```
 pub fn ngram_similarity(source: &str, target: &str, n: usize) -> f64 {
-    fn get_ngrams(text: &str, n: usize) -> HashSet<String> {
-        let cleaned: String = text
-            .to_lowercase()
-            .chars()
-            .filter(|c| !c.is_whitespace() || *c == ' ')
-            .collect();
+    ngram_similarity_with(source, target, n, &TokenizerOptions::default())
+}
 
-        let cleaned = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+/// Calculates n-gram similarity between two texts with configurable case handling.
+///
+/// Works like `ngram_similarity`, but only lowercases the texts when
+/// `options.case_sensitive` is unset. The other tokenizer settings are
+/// word-level and don't apply to character n-grams.
+///
+/// # Arguments
+///
+/// * `source` - The source text to compare
+/// * `target` - The target text to compare against
+/// * `n` - The n-gram size. A size of 0 is clamped to 1.
+/// * `options` - The tokenizer settings (see `TokenizerOptions`)
+///
+/// # Returns
+///
+/// Similarity percentage (0.0 to 100.0) of the two n-gram sets.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::ngram_similarity_with;
+/// # use dms_toolkit_rs::core::tokenize::TokenizerOptions;
+/// let options = TokenizerOptions { case_sensitive: true, ..Default::default() };
+/// assert!(ngram_similarity_with("SKU-KB", "SKU-Kb", 2, &options) < 100.0);
+/// ```
+pub fn ngram_similarity_with(
+    source: &str,
+    target: &str,
+    n: usize,
+    options: &TokenizerOptions,
+) -> f64 {
+    let n = n.max(1);
 
-        if cleaned.len() < n {
-            return HashSet::new();
-        }
+    let source_ngrams = character_ngrams(source, n, options);
+    let target_ngrams = character_ngrams(target, n, options);
 
-        cleaned
-            .chars()
-            .collect::<Vec<_>>()
-            .windows(n)
-            .map(|window| window.iter().collect::<String>())
-            .collect()
-    }
+    set_similarity(&source_ngrams, &target_ngrams)
+}
+
+/// Calculates word n-gram similarity between two texts.
+///
+/// Word n-gram similarity (shingling) breaks the texts into sequences of n
+/// consecutive words and compares the overlap of these sequences, so it
+/// rewards shared phrases rather than shared characters or isolated words.
+///
+/// # Algorithm
+///
+/// 1. Splits texts into lowercased words on runs of whitespace
+/// 2. Generates n-grams (word sequences of length n) for both texts
+/// 3. Creates sets of unique n-grams
+/// 4. Calculates: `intersection_size / union_size * 100`
+///
+/// # Arguments
+///
+/// * `source` - The source text to compare
+/// * `target` - The target text to compare against
+/// * `n` - The n-gram size in words (typically 2 or 3). A size of 0 is
+///   clamped to 1, which compares word sets like `jaccard_similarity`.
+///
+/// # Returns
+///
+/// Similarity percentage (0.0 to 100.0), where:
+/// - 100.0 means identical word n-gram sets
+/// - 0.0 means no shared word n-grams
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::word_ngram_similarity;
+/// let text1 = "the annual report was published";
+/// let text2 = "the annual report was delayed";
+/// let similarity = word_ngram_similarity(text1, text2, 2); // Uses word bigrams
+/// assert_eq!(similarity, 60.0);
+/// ```
+pub fn word_ngram_similarity(source: &str, target: &str, n: usize) -> f64 {
+    word_ngram_similarity_with(source, target, n, &TokenizerOptions::default())
+}
+
+/// Calculates word n-gram similarity between two texts with configurable
+/// tokenization.
+///
+/// Works like `word_ngram_similarity`, but splits the texts into words
+/// according to `options`, so stopwords can be dropped and words stemmed
+/// before the n-grams are built.
+///
+/// # Arguments
+///
+/// * `source` - The source text to compare
+/// * `target` - The target text to compare against
+/// * `n` - The n-gram size in words. A size of 0 is clamped to 1.
+/// * `options` - The tokenizer settings (see `TokenizerOptions`)
+///
+/// # Returns
+///
+/// Similarity percentage (0.0 to 100.0) of the two word n-gram sets.
+pub fn word_ngram_similarity_with(
+    source: &str,
+    target: &str,
+    n: usize,
+    options: &TokenizerOptions,
+) -> f64 {
+    let n = n.max(1);
+
+    let source_ngrams = word_ngrams(source, n, options);
+    let target_ngrams = word_ngrams(target, n, options);
+
+    set_similarity(&source_ngrams, &target_ngrams)
+}
+
+/// Returns the set of word n-grams of a text, each joined with single spaces.
+///
+/// Texts shorter than `n` words have no n-grams.
+fn word_ngrams(text: &str, n: usize, options: &TokenizerOptions) -> HashSet<String> {
+    let words: Vec<String> = tokenize(text, options).collect();
 
-    let source_ngrams = get_ngrams(source, n);
-    let target_ngrams = get_ngrams(target, n);
+    words.windows(n).map(|window| window.join(" ")).collect()
+}
 
-    let intersection_size = source_ngrams.intersection(&target_ngrams).count();
-    let union_size = source_ngrams.union(&target_ngrams).count();
+/// Returns the Jaccard index of two n-gram sets as a percentage.
+fn set_similarity(source: &HashSet<String>, target: &HashSet<String>) -> f64 {
+    let intersection_size = source.intersection(target).count();
+    let union_size = source.union(target).count();
 
     if union_size == 0 {
         return 0.0;
@@ -224,6 +606,275 @@ pub fn ngram_similarity(source: &str, target: &str, n: usize) -> f64 {
     (intersection_size as f64 / union_size as f64) * 100.0
 }
 
+/// Calculates the best n-gram similarity of the shorter text against any
+/// window of the longer text with as many words.
+///
+/// A window the size of the shorter text (in words) slides across the longer
+/// text, and each window is compared with `ngram_similarity_with`. A short
+/// reference quoted inside a long document therefore scores as high as if it
+/// were compared with the quoted passage alone.
+///
+/// # Algorithm
+///
+/// 1. If the longer text has no more words than the shorter one, the texts
+///    are compared in full
+/// 2. Otherwise every window is scored, one word further each time. The
+///    n-gram counts of the window are updated with the n-grams entering and
+///    leaving it rather than recomputed, so the scan takes time linear in the
+///    length of the longer text
+///
+/// # Arguments
+///
+/// * `source` - The source text to compare
+/// * `target` - The target text to compare against
+/// * `n` - The n-gram size (0 is treated as 1)
+/// * `options` - The tokenizer settings; only `case_sensitive` applies
+///
+/// # Returns
+///
+/// The highest window similarity percentage (0.0 to 100.0).
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::sliding_window_similarity_with;
+/// # use dms_toolkit_rs::core::tokenize::TokenizerOptions;
+/// let options = TokenizerOptions::default();
+/// let document = "intro text here then the quoted passage appears and more text follows";
+/// let similarity = sliding_window_similarity_with(document, "the quoted passage", 3, &options);
+/// assert_eq!(similarity, 100.0);
+/// ```
+pub fn sliding_window_similarity_with(
+    source: &str,
+    target: &str,
+    n: usize,
+    options: &TokenizerOptions,
+) -> f64 {
+    sliding_window_match_with(source, target, n, options).0
+}
+
+/// Calculates the sliding window similarity like
+/// `sliding_window_similarity_with`, and locates the best window in the source.
+///
+/// # Arguments
+///
+/// * `source` - The source text to compare, typically a long document
+/// * `target` - The target text to compare against, typically a short reference
+/// * `n` - The n-gram size (0 is treated as 1)
+/// * `options` - The tokenizer settings; only `case_sensitive` applies
+///
+/// # Returns
+///
+/// The highest window similarity percentage, and the byte range of the best
+/// window in `source`: from the start of its first word to the end of its
+/// last word. The range is `None` when the window slid over `target` instead
+/// (the source is the shorter text) or the texts were compared in full.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::sliding_window_match_with;
+/// # use dms_toolkit_rs::core::tokenize::TokenizerOptions;
+/// let options = TokenizerOptions::default();
+/// let document = "intro text here then the quoted passage appears and more text follows";
+/// let (similarity, passage) = sliding_window_match_with(document, "the quoted passage", 3, &options);
+/// assert_eq!(similarity, 100.0);
+/// assert_eq!(&document[passage.unwrap()], "the quoted passage");
+/// ```
+pub fn sliding_window_match_with(
+    source: &str,
+    target: &str,
+    n: usize,
+    options: &TokenizerOptions,
+) -> (f64, Option<Range<usize>>) {
+    let n = n.max(1);
+    let source_words: Vec<&str> = source.split_whitespace().collect();
+    let target_words: Vec<&str> = target.split_whitespace().collect();
+    let source_is_long = source_words.len() > target_words.len();
+    let (short, long_words, window) = if source_is_long {
+        (target, source_words, target_words.len())
+    } else {
+        (source, target_words, source_words.len())
+    };
+
+    if window == 0 || long_words.len() <= window {
+        return (ngram_similarity_with(source, target, n, options), None);
+    }
+
+    let short_ngrams = character_ngrams(short, n, options);
+    let (best_start, best_score) = best_window(&short_ngrams, &long_words, window, n, options);
+
+    // The words borrow from the source, so their addresses give their offsets
+    let passage = source_is_long.then(|| {
+        let first = long_words[best_start];
+        let last = long_words[best_start + window - 1];
+        let start = first.as_ptr() as usize - source.as_ptr() as usize;
+        let end = last.as_ptr() as usize - source.as_ptr() as usize + last.len();
+        start..end
+    });
+
+    (best_score, passage)
+}
+
+/// Checks whether the target text occurs in the source text.
+///
+/// Both texts are lowercased unless `options.case_sensitive` is set, and
+/// their whitespace is collapsed with `collapse_whitespace`, so a reference
+/// split across lines or table cells in the source is still found. Unlike the
+/// other methods, the comparison is directional: a source contained in the
+/// target does not match.
+///
+/// # Arguments
+///
+/// * `source` - The text to search, typically a long document
+/// * `target` - The text to search for, typically a short reference
+/// * `options` - The tokenizer settings; only `case_sensitive` applies
+///
+/// # Returns
+///
+/// 100.0 if the target is non-blank and occurs in the source, 0.0 otherwise.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::substring_similarity_with;
+/// # use dms_toolkit_rs::core::tokenize::TokenizerOptions;
+/// let options = TokenizerOptions::default();
+/// assert_eq!(substring_similarity_with("Case no. INV-2024-001\nfiled", "inv-2024-001", &options), 100.0);
+/// assert_eq!(substring_similarity_with("Case no. INV-2024-002", "inv-2024-001", &options), 0.0);
+/// ```
+pub fn substring_similarity_with(source: &str, target: &str, options: &TokenizerOptions) -> f64 {
+    let normalize = |text: &str| {
+        if options.case_sensitive {
+            collapse_whitespace(text)
+        } else {
+            collapse_whitespace(&text.to_lowercase())
+        }
+    };
+    let target = normalize(target);
+
+    if !target.is_empty() && normalize(source).contains(&target) {
+        100.0
+    } else {
+        0.0
+    }
+}
+
+/// Scores every window of `window` consecutive words against the n-grams of
+/// the shorter text, as `set_similarity` of the n-grams of the joined window.
+///
+/// The words are joined into one normalized text, where the n-grams of a
+/// window are those starting between its first character and `n` characters
+/// before its end. Moving to the next window adds the n-grams past the old
+/// end and drops those before the new start, and counts how many distinct
+/// n-grams of the window the shorter text shares.
+///
+/// # Returns
+///
+/// The first window start with the highest score, and the score.
+fn best_window(
+    short_ngrams: &HashSet<String>,
+    words: &[&str],
+    window: usize,
+    n: usize,
+    options: &TokenizerOptions,
+) -> (usize, f64) {
+    let mut text = String::new();
+    let mut word_chars = Vec::with_capacity(words.len());
+    let mut char_count = 0;
+    for word in words {
+        if !text.is_empty() {
+            text.push(' ');
+            char_count += 1;
+        }
+        let word = if options.case_sensitive {
+            Cow::Borrowed(*word)
+        } else {
+            Cow::Owned(word.to_lowercase())
+        };
+        let len = word.chars().count();
+        word_chars.push((char_count, char_count + len));
+        text.push_str(&word);
+        char_count += len;
+    }
+    let offsets: Vec<usize> = text
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain([text.len()])
+        .collect();
+    let ngram = |position: usize| &text[offsets[position]..offsets[position + n]];
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    let mut shared = 0;
+    let (mut low, mut high) = (0, 0);
+    let mut best = (0, 0.0);
+    for start in 0..=words.len() - window {
+        // The n-gram positions of the window, empty if it is shorter than n
+        let new_low = word_chars[start].0;
+        let new_high = (word_chars[start + window - 1].1 + 1)
+            .saturating_sub(n)
+            .max(new_low);
+
+        for position in high..new_high {
+            let count = counts.entry(ngram(position)).or_insert(0);
+            *count += 1;
+            if *count == 1 && short_ngrams.contains(ngram(position)) {
+                shared += 1;
+            }
+        }
+        for position in low..new_low {
+            let gram = ngram(position);
+            let count = counts.get_mut(gram).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(gram);
+                if short_ngrams.contains(gram) {
+                    shared -= 1;
+                }
+            }
+        }
+        (low, high) = (new_low, new_high);
+
+        let union = short_ngrams.len() + counts.len() - shared;
+        let score = if union == 0 {
+            0.0
+        } else {
+            shared as f64 / union as f64 * 100.0
+        };
+        if score > best.1 {
+            best = (start, score);
+        }
+    }
+
+    best
+}
+
+/// Returns the set of character n-grams of a text.
+///
+/// The text is lowercased unless `options.case_sensitive` is set, and its
+/// whitespace is collapsed with `collapse_whitespace`: every run of spaces,
+/// tabs, and line breaks becomes a single space. Word-based methods split on
+/// the same runs of whitespace, so both see "a\tb\nc" and "a b c" as the same
+/// text, and tab-separated spreadsheet output compares fairly with prose.
+/// Texts shorter than `n` characters have no n-grams.
+pub(crate) fn character_ngrams(
+    text: &str,
+    n: usize,
+    options: &TokenizerOptions,
+) -> HashSet<String> {
+    let text = if options.case_sensitive {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(text.to_lowercase())
+    };
+    let chars: Vec<char> = collapse_whitespace(&text).chars().collect();
+
+    chars
+        .windows(n)
+        .map(|window| window.iter().collect::<String>())
+        .collect()
+}
+
 /// Calculates Levenshtein distance (edit distance) between two strings.
 ///
 /// Levenshtein distance is the minimum number of single-character edits
@@ -319,10 +970,10 @@ pub fn levenshtein_distance(source: &str, target: &str, max_distance: Option<usi
         }
 
         // Early termination if this row exceeds max_distance
-        if let Some(max_dist) = max_distance {
-            if row_min > max_dist {
-                return max_dist + 1;
-            }
+        if let Some(max_dist) = max_distance
+            && row_min > max_dist
+        {
+            return max_dist + 1;
         }
 
         std::mem::swap(&mut previous, &mut current);
@@ -331,6 +982,143 @@ pub fn levenshtein_distance(source: &str, target: &str, max_distance: Option<usi
     previous[cols - 1]
 }
 
+/// One step of a Levenshtein alignment, from `levenshtein_alignment`.
+///
+/// Positions are character indices. Every operation carries the position in
+/// both strings where it happens: an insertion goes before
+/// `source[source_index]`, and a deletion happens before
+/// `target[target_index]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditOp {
+    /// `source[source_index]` and `target[target_index]` are both `ch`.
+    Match {
+        source_index: usize,
+        target_index: usize,
+        ch: char,
+    },
+    /// `target[target_index]`, `ch`, is inserted.
+    Insert {
+        source_index: usize,
+        target_index: usize,
+        ch: char,
+    },
+    /// `source[source_index]`, `ch`, is deleted.
+    Delete {
+        source_index: usize,
+        target_index: usize,
+        ch: char,
+    },
+    /// `source[source_index]`, `from`, is replaced by `target[target_index]`, `to`.
+    Substitute {
+        source_index: usize,
+        target_index: usize,
+        from: char,
+        to: char,
+    },
+}
+
+/// Calculates the edit operations turning one string into another.
+///
+/// Fills the full Levenshtein matrix and backtracks from its last cell, so
+/// the operations are one minimal edit script: the number of inserts,
+/// deletes, and substitutions equals `levenshtein_distance`. Where several
+/// scripts are minimal, backtracking prefers a match or substitution, then a
+/// deletion, then an insertion.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+///
+/// # Returns
+///
+/// The operations in order from the start of both strings, covering every
+/// character of each.
+///
+/// # Performance
+///
+/// Time and space complexity: O(m * n). Unlike `levenshtein_distance`, the
+/// whole matrix is kept for backtracking, so this suits short texts such as
+/// lines or fields rather than whole documents.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::{EditOp, levenshtein_alignment};
+/// let ops = levenshtein_alignment("cat", "cut");
+/// assert_eq!(
+///     ops[1],
+///     EditOp::Substitute { source_index: 1, target_index: 1, from: 'a', to: 'u' }
+/// );
+/// ```
+pub fn levenshtein_alignment(source: &str, target: &str) -> Vec<EditOp> {
+    let source_chars: Vec<char> = source.chars().collect();
+    let target_chars: Vec<char> = target.chars().collect();
+    let cols = target_chars.len() + 1;
+
+    // matrix[i * cols + j] is the distance between the first i source and j target chars
+    let mut matrix = vec![0usize; (source_chars.len() + 1) * cols];
+    for (j, cell) in matrix.iter_mut().take(cols).enumerate() {
+        *cell = j;
+    }
+    for i in 1..=source_chars.len() {
+        matrix[i * cols] = i;
+        for j in 1..cols {
+            let cost = usize::from(source_chars[i - 1] != target_chars[j - 1]);
+            matrix[i * cols + j] = (matrix[i * cols + j - 1] + 1)
+                .min(matrix[(i - 1) * cols + j] + 1)
+                .min(matrix[(i - 1) * cols + j - 1] + cost);
+        }
+    }
+
+    let (mut i, mut j) = (source_chars.len(), target_chars.len());
+    let mut ops = Vec::with_capacity(i.max(j));
+    while i > 0 || j > 0 {
+        let distance = matrix[i * cols + j];
+        if i > 0 && j > 0 {
+            let (from, to) = (source_chars[i - 1], target_chars[j - 1]);
+            let cost = usize::from(from != to);
+            if matrix[(i - 1) * cols + j - 1] + cost == distance {
+                i -= 1;
+                j -= 1;
+                ops.push(if cost == 0 {
+                    EditOp::Match {
+                        source_index: i,
+                        target_index: j,
+                        ch: from,
+                    }
+                } else {
+                    EditOp::Substitute {
+                        source_index: i,
+                        target_index: j,
+                        from,
+                        to,
+                    }
+                });
+                continue;
+            }
+        }
+        if i > 0 && matrix[(i - 1) * cols + j] + 1 == distance {
+            i -= 1;
+            ops.push(EditOp::Delete {
+                source_index: i,
+                target_index: j,
+                ch: source_chars[i],
+            });
+        } else {
+            j -= 1;
+            ops.push(EditOp::Insert {
+                source_index: i,
+                target_index: j,
+                ch: target_chars[j],
+            });
+        }
+    }
+
+    ops.reverse();
+    ops
+}
+
 /// Calculates Levenshtein similarity as a percentage.
 ///
 /// Converts Levenshtein distance into a similarity percentage by comparing
@@ -373,15 +1161,324 @@ pub fn levenshtein_similarity(source: &str, target: &str, max_distance: Option<u
 
     let distance = levenshtein_distance(source, target, max_distance);
 
-    if let Some(max_dist) = max_distance {
-        if distance > max_dist {
-            return 0.0;
-        }
+    if let Some(max_dist) = max_distance
+        && distance > max_dist
+    {
+        return 0.0;
+    }
+
+    ((max_length - distance) as f64 / max_length as f64) * 100.0
+}
+
+/// Calculates a Levenshtein distance in which edits to uncertain source
+/// characters cost less.
+///
+/// Meant for OCR output compared with a known-good reference: a character the
+/// OCR engine was unsure about is likely misread, so substituting or deleting
+/// it should not count as a full edit. Each edit of source character `i`
+/// costs `confidence[i]` instead of 1; inserting a target character always
+/// costs 1.
+///
+/// # Arguments
+///
+/// * `source` - The source string, typically OCR output
+/// * `confidence` - Per-character confidence of `source` (0.0 to 1.0), by
+///   character index. Values are clamped to that range; missing or non-finite
+///   entries count as 1.0, so an empty slice gives the plain Levenshtein
+///   distance.
+/// * `target` - The target string
+///
+/// # Returns
+///
+/// The weighted edit distance, between 0.0 and the plain Levenshtein distance.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::weighted_levenshtein_distance;
+/// // The misread "0" was recognized with a confidence of 0.25
+/// let confidence = [1.0, 1.0, 0.25, 1.0];
+/// assert_eq!(weighted_levenshtein_distance("ab0d", &confidence, "abcd"), 0.25);
+/// assert_eq!(weighted_levenshtein_distance("ab0d", &[], "abcd"), 1.0);
+/// ```
+pub fn weighted_levenshtein_distance(source: &str, confidence: &[f64], target: &str) -> f64 {
+    let source_chars: Vec<char> = source.chars().collect();
+    let target_chars: Vec<char> = target.chars().collect();
+    let costs: Vec<f64> = (0..source_chars.len())
+        .map(|i| {
+            confidence
+                .get(i)
+                .copied()
+                .filter(|c| c.is_finite())
+                .map_or(1.0, |c| c.clamp(0.0, 1.0))
+        })
+        .collect();
+
+    // Rows follow the source, so each row knows the cost of its character
+    let mut previous: Vec<f64> = (0..=target_chars.len()).map(|j| j as f64).collect();
+    let mut current: Vec<f64> = vec![0.0; target_chars.len() + 1];
+
+    for (i, &source_char) in source_chars.iter().enumerate() {
+        let cost = costs[i];
+        current[0] = previous[0] + cost;
+
+        for (j, &target_char) in target_chars.iter().enumerate() {
+            let substitution = if source_char == target_char {
+                0.0
+            } else {
+                cost
+            };
+            current[j + 1] = (current[j] + 1.0)
+                .min(previous[j + 1] + cost)
+                .min(previous[j] + substitution);
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[target_chars.len()]
+}
+
+/// Calculates confidence-weighted Levenshtein similarity as a percentage.
+///
+/// Converts `weighted_levenshtein_distance` into a similarity percentage the
+/// same way `levenshtein_similarity` does, so with full confidence both give
+/// the same score, and low-confidence mismatches lower it less.
+///
+/// # Formula
+///
+/// `similarity = ((max_length - distance) / max_length) * 100`
+///
+/// # Arguments
+///
+/// * `source` - The source string, typically OCR output
+/// * `confidence` - Per-character confidence of `source` (see
+///   `weighted_levenshtein_distance`)
+/// * `target` - The target string
+///
+/// # Returns
+///
+/// Similarity percentage (0.0 to 100.0), where 100.0 means identical strings
+/// or differences only in characters with zero confidence.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::weighted_levenshtein_similarity;
+/// let confidence = [1.0, 1.0, 0.0, 1.0];
+/// assert_eq!(weighted_levenshtein_similarity("ab0d", &confidence, "abcd"), 100.0);
+/// ```
+pub fn weighted_levenshtein_similarity(source: &str, confidence: &[f64], target: &str) -> f64 {
+    let max_length = source.chars().count().max(target.chars().count());
+    if max_length == 0 {
+        return 100.0;
+    }
+
+    let distance = weighted_levenshtein_distance(source, confidence, target);
+
+    ((max_length as f64 - distance) / max_length as f64 * 100.0).clamp(0.0, 100.0)
+}
+
+/// Calculates Damerau-Levenshtein distance between two strings.
+///
+/// Damerau-Levenshtein distance extends Levenshtein distance with adjacent
+/// transpositions: swapping two neighboring characters ("ca" to "ac") counts
+/// as one edit, where Levenshtein distance counts it as two substitutions.
+///
+/// # Algorithm
+///
+/// Computes the optimal string alignment variant with dynamic programming:
+/// - Keeps three rows (the transposition step looks two rows back)
+/// - Swaps shorter string as rows for memory efficiency
+/// - Supports early termination if distance exceeds `max_distance`
+///
+/// In this variant a substring is edited at most once, so a transposed pair
+/// cannot also receive an insertion between its characters. This rarely
+/// matters for typo and OCR errors.
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+/// * `max_distance` - Optional maximum distance threshold for early termination.
+///   If the distance exceeds this value, the function returns `max_distance + 1`
+///   immediately without completing the calculation.
+///
+/// # Returns
+///
+/// The Damerau-Levenshtein distance (number of edits), or `max_distance + 1`
+/// if the distance exceeds the threshold.
+///
+/// # Performance
+///
+/// Time complexity: O(m * n) where m and n are string lengths.
+/// Space complexity: O(min(m, n)) due to space optimization.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::{damerau_levenshtein_distance, levenshtein_distance};
+/// assert_eq!(damerau_levenshtein_distance("ca", "ac", None), 1);
+/// assert_eq!(levenshtein_distance("ca", "ac", None), 2);
+/// ```
+pub fn damerau_levenshtein_distance(
+    source: &str,
+    target: &str,
+    max_distance: Option<usize>,
+) -> usize {
+    let source_chars: Vec<char> = source.chars().collect();
+    let target_chars: Vec<char> = target.chars().collect();
+
+    if source_chars.is_empty() {
+        return target_chars.len();
+    }
+    if target_chars.is_empty() {
+        return source_chars.len();
+    }
+
+    // Use shorter string as rows for memory efficiency
+    let (s_chars, t_chars) = if source_chars.len() < target_chars.len() {
+        (&source_chars, &target_chars)
+    } else {
+        (&target_chars, &source_chars)
+    };
+    let rows = s_chars.len() + 1;
+    let cols = t_chars.len() + 1;
+
+    let mut before_previous: Vec<usize> = vec![0; cols];
+    let mut previous: Vec<usize> = (0..cols).collect();
+    let mut current: Vec<usize> = vec![0; cols];
+
+    for i in 1..rows {
+        current[0] = i;
+        let mut row_min = i;
+
+        for j in 1..cols {
+            let cost = if s_chars[i - 1] == t_chars[j - 1] {
+                0
+            } else {
+                1
+            };
+            current[j] = (current[j - 1] + 1)
+                .min(previous[j] + 1)
+                .min(previous[j - 1] + cost);
+
+            // Adjacent transposition
+            if i > 1
+                && j > 1
+                && s_chars[i - 1] == t_chars[j - 2]
+                && s_chars[i - 2] == t_chars[j - 1]
+            {
+                current[j] = current[j].min(before_previous[j - 2] + 1);
+            }
+
+            row_min = row_min.min(current[j]);
+        }
+
+        // Early termination if this row exceeds max_distance; row minimums
+        // never decrease, including through transpositions
+        if let Some(max_dist) = max_distance
+            && row_min > max_dist
+        {
+            return max_dist + 1;
+        }
+
+        std::mem::swap(&mut before_previous, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[cols - 1]
+}
+
+/// Calculates Damerau-Levenshtein similarity as a percentage.
+///
+/// Converts Damerau-Levenshtein distance into a similarity percentage by
+/// comparing the edit distance to the maximum possible distance (the number
+/// of characters in the longer string).
+///
+/// # Formula
+///
+/// `similarity = ((max_length - distance) / max_length) * 100`
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+/// * `max_distance` - Optional maximum distance threshold. If the distance
+///   exceeds this value, returns 0.0 immediately.
+///
+/// # Returns
+///
+/// Similarity percentage (0.0 to 100.0), where:
+/// - 100.0 means identical strings (distance = 0)
+/// - 0.0 means maximum distance or distance exceeds threshold
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::damerau_levenshtein_similarity;
+/// // One transposition in a ten-character string
+/// assert_eq!(damerau_levenshtein_similarity("1234567890", "1234567809", None), 90.0);
+/// ```
+pub fn damerau_levenshtein_similarity(
+    source: &str,
+    target: &str,
+    max_distance: Option<usize>,
+) -> f64 {
+    let max_length = source.chars().count().max(target.chars().count());
+    if max_length == 0 {
+        return 100.0;
+    }
+
+    let distance = damerau_levenshtein_distance(source, target, max_distance);
+
+    if let Some(max_dist) = max_distance
+        && distance > max_dist
+    {
+        return 0.0;
     }
 
     ((max_length - distance) as f64 / max_length as f64) * 100.0
 }
 
+/// Calculates Jaro-Winkler similarity as a percentage.
+///
+/// Jaro similarity counts characters that match within a window of half the
+/// longer string's length and penalizes transpositions among them. The Winkler
+/// extension then boosts the score of strings that share a common prefix.
+///
+/// # Algorithm
+///
+/// 1. Computes the Jaro similarity from matching characters and transpositions
+/// 2. Applies the standard prefix boost: scaling factor 0.1 for a shared prefix
+///    of up to 4 characters
+/// 3. Scales the result to a percentage
+///
+/// # Arguments
+///
+/// * `source` - The source string
+/// * `target` - The target string
+///
+/// # Returns
+///
+/// Similarity percentage (0.0 to 100.0), where 100.0 means identical strings.
+///
+/// # Use Cases
+///
+/// - Short strings such as vendor names, person names, and titles
+/// - Strings where adjacent characters are commonly swapped
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::jaro_winkler_similarity;
+/// let similarity = jaro_winkler_similarity("MARTHA", "MARHTA");
+/// assert!(similarity > 95.0);
+/// ```
+pub fn jaro_winkler_similarity(source: &str, target: &str) -> f64 {
+    strsim::jaro_winkler(source, target) * 100.0
+}
+
 /// Calculates hybrid similarity using progressive filtering.
 ///
 /// This method combines multiple similarity algorithms in a progressive
@@ -395,13 +1492,12 @@ pub fn levenshtein_similarity(source: &str, target: &str, max_distance: Option<u
 ///    (texts are too dissimilar).
 ///
 /// 2. **Small Text Handling** (< 1000 characters):
-///    - Uses Levenshtein distance with early termination
-///    - Calculates maximum allowed distance as 80% of max length
-///    - If distance exceeds threshold, returns 20.0 (low similarity)
-///    - Otherwise converts distance to similarity percentage
+///    - Computes the full Levenshtein distance
+///    - Converts the distance to a similarity percentage, which can fall
+///      below 20% even though the texts passed the Jaccard check
 ///
 /// 3. **Large Text Handling** (>= 1000 characters):
-///    - Uses N-gram similarity with 3-grams (trigrams)
+///    - Uses N-gram similarity with `ngram_size`-grams (trigrams by default)
 ///    - More efficient than Levenshtein for long texts
 ///    - Captures character-level similarities
 ///
@@ -409,6 +1505,7 @@ pub fn levenshtein_similarity(source: &str, target: &str, max_distance: Option<u
 ///
 /// * `source` - The source text to compare
 /// * `target` - The target text to compare against
+/// * `ngram_size` - The n-gram size used for large texts
 ///
 /// # Returns
 ///
@@ -427,103 +1524,535 @@ pub fn levenshtein_similarity(source: &str, target: &str, max_distance: Option<u
 /// # use dms_toolkit_rs::core::similarity::hybrid_similarity;
 /// let text1 = "The quick brown fox jumps over the lazy dog";
 /// let text2 = "The quick brown fox jumps over the lazy dog";
-/// let similarity = hybrid_similarity(text1, text2);
+/// let similarity = hybrid_similarity(text1, text2, 3);
 /// // Returns 100.0 for identical texts
 /// ```
-pub fn hybrid_similarity(source: &str, target: &str) -> f64 {
-    // Fast initial filter using Jaccard
-    let jaccard_score = jaccard_similarity(source, target);
+pub fn hybrid_similarity(source: &str, target: &str, ngram_size: usize) -> f64 {
+    hybrid_similarity_with(source, target, ngram_size, &TokenizerOptions::default())
+}
 
-    if jaccard_score < 20.0 {
-        return jaccard_score;
+/// Calculates hybrid similarity with configurable case handling.
+///
+/// Works like `hybrid_similarity`, but the Jaccard filter and the n-gram
+/// comparison for large texts respect `options.case_sensitive`. The
+/// Levenshtein comparison for small texts is always case-sensitive. Stopword
+/// removal and stemming are not applied.
+///
+/// # Arguments
+///
+/// * `source` - The source text to compare
+/// * `target` - The target text to compare against
+/// * `ngram_size` - The n-gram size used for large texts
+/// * `options` - The tokenizer settings (see `TokenizerOptions`)
+///
+/// # Returns
+///
+/// Similarity percentage (0.0 to 100.0)
+pub fn hybrid_similarity_with(
+    source: &str,
+    target: &str,
+    ngram_size: usize,
+    options: &TokenizerOptions,
+) -> f64 {
+    hybrid_similarity_with_method(source, target, ngram_size, options).0
+}
+
+/// Calculates hybrid similarity and reports which branch produced the score.
+///
+/// Scores from different branches are not directly comparable: an early
+/// Jaccard exit measures word overlap, while Levenshtein and n-gram scores
+/// measure character-level differences.
+///
+/// # Arguments
+///
+/// * `source` - The source text to compare
+/// * `target` - The target text to compare against
+/// * `ngram_size` - The n-gram size used for large texts
+/// * `options` - The tokenizer settings (see `hybrid_similarity_with`)
+///
+/// # Returns
+///
+/// The similarity percentage (0.0 to 100.0) together with the method that
+/// computed it: "jaccard", "levenshtein", or "ngram".
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::hybrid_similarity_with_method;
+/// # use dms_toolkit_rs::core::tokenize::TokenizerOptions;
+/// let (_, method) = hybrid_similarity_with_method(
+///     "invoice 1042",
+///     "invoice 1043",
+///     3,
+///     &TokenizerOptions::default(),
+/// );
+/// assert_eq!(method, "levenshtein");
+/// ```
+pub fn hybrid_similarity_with_method(
+    source: &str,
+    target: &str,
+    ngram_size: usize,
+    options: &TokenizerOptions,
+) -> (f64, &'static str) {
+    // Identical texts pass the Jaccard filter; report the branch that would score them
+    if identical_texts(source, target) {
+        let branch = if source.len() < 1000 {
+            "levenshtein"
+        } else {
+            "ngram"
+        };
+        return (100.0, branch);
     }
 
-    // For small texts, use Levenshtein with early termination
-    if source.len() < 1000 && target.len() < 1000 {
-        let max_length = source.len().max(target.len());
-        let max_allowed_distance = (max_length as f64 * 0.8) as usize;
+    let options = TokenizerOptions {
+        case_sensitive: options.case_sensitive,
+        ..Default::default()
+    };
 
-        let distance = levenshtein_distance(source, target, Some(max_allowed_distance));
+    // Fast initial filter using Jaccard
+    let jaccard_score = jaccard_similarity_with(source, target, &options);
 
-        if distance > max_allowed_distance {
-            return 20.0;
-        }
+    if jaccard_score < 20.0 {
+        return (jaccard_score, "jaccard");
+    }
 
-        return ((max_length - distance) as f64 / max_length as f64) * 100.0;
+    // For small texts, use Levenshtein. The distance is computed in full:
+    // cutting it off early would leave only an upper bound on the score.
+    if source.len() < 1000 && target.len() < 1000 {
+        return (levenshtein_similarity(source, target, None), "levenshtein");
     }
 
     // For larger texts, use N-gram
-    ngram_similarity(source, target, 3)
+    (
+        ngram_similarity_with(source, target, ngram_size, &options),
+        "ngram",
+    )
 }
 
-/// Calculates similarity between two texts using the specified method.
+/// Calculates a weighted blend of Jaccard, n-gram, and Levenshtein similarity.
 ///
-/// This is a dispatcher function that routes to the appropriate similarity
-/// algorithm based on the `SimilarityMethod` enum value.
+/// Each method's score is multiplied by its weight and the products are
+/// summed. Methods with a zero weight are skipped, so a blend without
+/// Levenshtein stays fast on long texts. The Jaccard and n-gram scores
+/// respect `options` as in `jaccard_similarity_with` and
+/// `ngram_similarity_with`; Levenshtein always compares the texts as written.
 ///
 /// # Arguments
 ///
 /// * `source` - The source text to compare
 /// * `target` - The target text to compare against
-/// * `method` - The similarity method to use (Jaccard, Ngram, Levenshtein, or Hybrid)
+/// * `jaccard` - Weight of the Jaccard score
+/// * `ngram` - Weight of the n-gram score
+/// * `levenshtein` - Weight of the Levenshtein score
+/// * `ngram_size` - The n-gram size of the n-gram score
+/// * `options` - The tokenizer settings (see `TokenizerOptions`)
 ///
 /// # Returns
 ///
-/// Similarity percentage (0.0 to 100.0) calculated using the specified method
+/// The blended similarity percentage. With weights summing to 1 (as built by
+/// `SimilarityMethod::weighted`), it lies between the lowest and the highest
+/// of the individual scores.
 ///
 /// # Example
 ///
 /// ```
-/// # use dms_toolkit_rs::core::similarity::{calculate_similarity, SimilarityMethod};
-/// let text1 = "hello world";
-/// let text2 = "hello there";
-///
-/// let jaccard = calculate_similarity(text1, text2, SimilarityMethod::Jaccard);
-/// let hybrid = calculate_similarity(text1, text2, SimilarityMethod::Hybrid);
+/// # use dms_toolkit_rs::core::similarity::weighted_similarity_with;
+/// # use dms_toolkit_rs::core::tokenize::TokenizerOptions;
+/// let options = TokenizerOptions::default();
+/// let similarity = weighted_similarity_with("hello world", "hello world", 0.5, 0.5, 0.0, 3, &options);
+/// assert_eq!(similarity, 100.0);
 /// ```
-pub fn calculate_similarity(source: &str, target: &str, method: SimilarityMethod) -> f64 {
-    match method {
-        SimilarityMethod::Jaccard => jaccard_similarity(source, target),
-        SimilarityMethod::Ngram => ngram_similarity(source, target, 3),
-        SimilarityMethod::Levenshtein => levenshtein_similarity(source, target, None),
-        SimilarityMethod::Hybrid => hybrid_similarity(source, target),
+pub fn weighted_similarity_with(
+    source: &str,
+    target: &str,
+    jaccard: f64,
+    ngram: f64,
+    levenshtein: f64,
+    ngram_size: usize,
+    options: &TokenizerOptions,
+) -> f64 {
+    let mut similarity = 0.0;
+    if jaccard > 0.0 {
+        similarity += jaccard * jaccard_similarity_with(source, target, options);
+    }
+    if ngram > 0.0 {
+        similarity += ngram * ngram_similarity_with(source, target, ngram_size, options);
+    }
+    if levenshtein > 0.0 {
+        similarity += levenshtein * levenshtein_similarity(source, target, None);
     }
+    similarity
 }
 
-/// Compares one text against multiple reference texts in parallel.
-///
-/// This function is the main entry point for similarity comparison. It takes
-/// a source text and compares it against multiple reference texts using the
-/// specified similarity method, returning only matches above the threshold.
-///
-/// # Processing Flow
-///
-/// 1. **Parallel Iteration**: Uses Rayon to process all reference texts in parallel
-/// 2. **Pre-filtering**: Applies length-based pre-filtering to quickly eliminate
-///    obviously dissimilar texts before expensive calculations
-/// 3. **Similarity Calculation**: Calculates similarity using the specified method
-/// 4. **Threshold Filtering**: Only includes matches with similarity >= threshold
-/// 5. **Result Collection**: Returns pairs of (reference_index, similarity_percentage)
+/// Inverse document frequency table used for TF-IDF weighting.
 ///
-/// # Arguments
+/// Records, for every term in a corpus, how many documents contain it. Terms
+/// that appear in many documents receive a low weight, while rare terms
+/// receive a high weight.
 ///
-/// * `source_text` - The text extracted from a file to compare
-/// * `target_texts` - A slice of reference text strings to compare against
-/// * `method` - The similarity method to use (Jaccard, Ngram, Levenshtein, or Hybrid)
-/// * `threshold` - The minimum similarity percentage (0-100) required for a match
+/// # Weighting
 ///
-/// # Returns
+/// `idf(term) = ln((document_count + 1) / document_frequency)`
 ///
-/// A vector of tuples `(usize, f64)` where:
-/// - `usize` is the index of the reference text in the input array
-/// - `f64` is the similarity percentage (0-100)
+/// A term present in every document gets a weight close to zero. The `+ 1`
+/// keeps the weight positive, so two documents still compare meaningfully
+/// when the corpus consists of only those two documents.
+pub struct IdfTable {
+    document_count: usize,
+    document_frequency: HashMap<String, usize>,
+}
+
+impl IdfTable {
+    /// Builds an IDF table from a collection of documents.
+    ///
+    /// # Arguments
+    ///
+    /// * `documents` - The corpus texts; each one counts as a single document
+    ///
+    /// # Returns
+    ///
+    /// An `IdfTable` holding the document frequency of every term in the corpus.
+    pub fn from_documents<'a>(documents: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut document_count = 0;
+        let mut document_frequency: HashMap<String, usize> = HashMap::new();
+
+        for document in documents {
+            document_count += 1;
+
+            let terms: HashSet<String> = tokenize_terms(document).collect();
+            for term in terms {
+                *document_frequency.entry(term).or_default() += 1;
+            }
+        }
+
+        Self {
+            document_count,
+            document_frequency,
+        }
+    }
+
+    /// Returns the inverse document frequency weight of a term.
+    ///
+    /// Terms not present in the corpus are treated as appearing in one document.
+    fn idf(&self, term: &str) -> f64 {
+        let frequency = self.document_frequency.get(term).copied().unwrap_or(1);
+        ((self.document_count + 1) as f64 / frequency as f64).ln()
+    }
+}
+
+/// Splits text into lowercase alphanumeric terms for TF-IDF weighting.
+fn tokenize_terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| term.to_lowercase())
+}
+
+/// Builds a TF-IDF weighted term vector for a text.
+fn tfidf_vector(text: &str, idf: &IdfTable) -> HashMap<String, f64> {
+    let mut frequencies: HashMap<String, usize> = HashMap::new();
+    for term in tokenize_terms(text) {
+        *frequencies.entry(term).or_default() += 1;
+    }
+
+    frequencies
+        .into_iter()
+        .map(|(term, frequency)| {
+            // Sublinear term frequency keeps repeated words from dominating
+            let weight = (1.0 + (frequency as f64).ln()) * idf.idf(&term);
+            (term, weight)
+        })
+        .collect()
+}
+
+/// Calculates cosine similarity between TF-IDF weighted term vectors.
+///
+/// Cosine TF-IDF similarity focuses on the terms that distinguish documents.
+/// Common words shared by most of the corpus carry almost no weight, so two
+/// documents that only share stopwords score near zero, while documents that
+/// share rare terms score high.
+///
+/// # Algorithm
+///
+/// 1. Tokenizes both texts into lowercase alphanumeric terms
+/// 2. Computes sublinear term frequencies (`1 + ln(tf)`) for each text
+/// 3. Multiplies each frequency by the term's IDF weight from `idf`
+/// 4. Calculates: `dot(source, target) / (|source| * |target|) * 100`
+///
+/// # Arguments
+///
+/// * `source` - The source text to compare
+/// * `target` - The target text to compare against
+/// * `idf` - The IDF table for the comparison corpus
+///
+/// # Returns
+///
+/// Similarity percentage (0.0 to 100.0), where:
+/// - 100.0 means identical weighted term distributions
+/// - 0.0 means no shared weighted terms
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::{cosine_tfidf_similarity, IdfTable};
+/// let corpus = ["quantum entanglement", "quantum computing", "gardening tips"];
+/// let idf = IdfTable::from_documents(corpus);
+/// let similarity = cosine_tfidf_similarity(corpus[0], corpus[1], &idf);
+/// ```
+pub fn cosine_tfidf_similarity(source: &str, target: &str, idf: &IdfTable) -> f64 {
+    let source_vector = tfidf_vector(source, idf);
+    let target_vector = tfidf_vector(target, idf);
+
+    let dot_product: f64 = source_vector
+        .iter()
+        .filter_map(|(term, weight)| target_vector.get(term).map(|other| weight * other))
+        .sum();
+
+    let source_norm = source_vector.values().map(|w| w * w).sum::<f64>().sqrt();
+    let target_norm = target_vector.values().map(|w| w * w).sum::<f64>().sqrt();
+
+    if source_norm == 0.0 || target_norm == 0.0 {
+        return 0.0;
+    }
+
+    (dot_product / (source_norm * target_norm) * 100.0).min(100.0)
+}
+
+/// Checks whether two texts are the same non-blank text.
+///
+/// Identical texts score 100 with every method, so the dispatchers return
+/// that score without running the method, which for Levenshtein on large
+/// texts saves a quadratic computation. Blank texts are left to the methods,
+/// which disagree on them (most score them 0).
+fn identical_texts(source: &str, target: &str) -> bool {
+    source == target && !source.trim().is_empty()
+}
+
+/// Checks whether two texts are the same text that scores 100 with `method`
+/// and `tokenizer`, like `identical_texts`.
+///
+/// Word-based methods score a text whose words are all filtered out (for
+/// example only stopwords with `remove_stopwords`) below 100, so for them the
+/// tokenizer must keep at least one word.
+fn identical_texts_with(
+    source: &str,
+    target: &str,
+    method: SimilarityMethod,
+    tokenizer: &TokenizerOptions,
+) -> bool {
+    identical_texts(source, target)
+        && (!method.compares_words() || tokenize(source, tokenizer).next().is_some())
+}
+
+/// Calculates similarity between two texts using the specified method.
+///
+/// This is a dispatcher function that routes to the appropriate similarity
+/// algorithm based on the `SimilarityMethod` enum value. Identical non-blank
+/// texts score 100 without running the method, including texts too short to
+/// form a single n-gram.
+///
+/// # Arguments
+///
+/// * `source` - The source text to compare
+/// * `target` - The target text to compare against
+/// * `method` - The similarity method to use (Jaccard, Ngram, Levenshtein,
+///   DamerauLevenshtein, Hybrid, CosineTfIdf, JaroWinkler, Weighted,
+///   SlidingWindow, Substring, or WordNgram)
+///
+/// # Returns
+///
+/// Similarity percentage (0.0 to 100.0) calculated using the specified method
+///
+/// # Note
+///
+/// For `CosineTfIdf`, the IDF corpus is just the two texts being compared.
+/// Use `compare_with_documents` to weight terms across a full reference set.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::{calculate_similarity, SimilarityMethod};
+/// let text1 = "hello world";
+/// let text2 = "hello there";
+///
+/// let jaccard = calculate_similarity(text1, text2, SimilarityMethod::Jaccard);
+/// let hybrid = calculate_similarity(text1, text2, SimilarityMethod::Hybrid(3));
+/// ```
+pub fn calculate_similarity(source: &str, target: &str, method: SimilarityMethod) -> f64 {
+    if identical_texts(source, target) {
+        return 100.0;
+    }
+
+    match method {
+        SimilarityMethod::Jaccard => jaccard_similarity(source, target),
+        SimilarityMethod::Ngram(n) => ngram_similarity(source, target, n),
+        SimilarityMethod::WordNgram(n) => word_ngram_similarity(source, target, n),
+        SimilarityMethod::Levenshtein => levenshtein_similarity(source, target, None),
+        SimilarityMethod::DamerauLevenshtein => {
+            damerau_levenshtein_similarity(source, target, None)
+        }
+        SimilarityMethod::Hybrid(ngram_size) => hybrid_similarity(source, target, ngram_size),
+        SimilarityMethod::CosineTfIdf => {
+            let idf = IdfTable::from_documents([source, target]);
+            cosine_tfidf_similarity(source, target, &idf)
+        }
+        SimilarityMethod::JaroWinkler => jaro_winkler_similarity(source, target),
+        SimilarityMethod::Weighted { .. } => {
+            calculate_similarity_with(source, target, method, &TokenizerOptions::default())
+        }
+        SimilarityMethod::SlidingWindow(n) => {
+            sliding_window_similarity_with(source, target, n, &TokenizerOptions::default())
+        }
+        SimilarityMethod::Substring => {
+            substring_similarity_with(source, target, &TokenizerOptions::default())
+        }
+    }
+}
+
+/// Calculates similarity between two texts with configurable tokenization.
+///
+/// Works like `calculate_similarity`, but passes `tokenizer` to the methods
+/// that tokenize or lowercase their input:
+///
+/// - `Jaccard` applies all tokenizer settings, including as part of `Weighted`,
+///   and so does `WordNgram`
+/// - `Ngram`, `Hybrid`, `SlidingWindow`, and `Substring` only apply
+///   `case_sensitive`
+/// - `Levenshtein`, `DamerauLevenshtein`, and `JaroWinkler` always compare the
+///   texts as they are, and `CosineTfIdf` always lowercases its terms
+///
+/// Identical texts score 100 without running the method, as in
+/// `calculate_similarity`, unless the method compares words and the
+/// tokenizer filters out every word of the text.
+///
+/// # Arguments
+///
+/// * `source` - The source text to compare
+/// * `target` - The target text to compare against
+/// * `method` - The similarity method to use
+/// * `tokenizer` - The tokenizer settings (see `TokenizerOptions`)
+///
+/// # Returns
+///
+/// Similarity percentage (0.0 to 100.0) calculated using the specified method
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::{calculate_similarity_with, SimilarityMethod};
+/// # use dms_toolkit_rs::core::tokenize::TokenizerOptions;
+/// let options = TokenizerOptions { case_sensitive: true, ..Default::default() };
+/// let similarity = calculate_similarity_with("1 KB", "1 Kb", SimilarityMethod::Jaccard, &options);
+/// assert!(similarity < 100.0);
+/// ```
+pub fn calculate_similarity_with(
+    source: &str,
+    target: &str,
+    method: SimilarityMethod,
+    tokenizer: &TokenizerOptions,
+) -> f64 {
+    if identical_texts_with(source, target, method, tokenizer) {
+        return 100.0;
+    }
+
+    match method {
+        SimilarityMethod::Jaccard => jaccard_similarity_with(source, target, tokenizer),
+        SimilarityMethod::Ngram(n) => ngram_similarity_with(source, target, n, tokenizer),
+        SimilarityMethod::WordNgram(n) => word_ngram_similarity_with(source, target, n, tokenizer),
+        SimilarityMethod::Hybrid(ngram_size) => {
+            hybrid_similarity_with(source, target, ngram_size, tokenizer)
+        }
+        SimilarityMethod::SlidingWindow(n) => {
+            sliding_window_similarity_with(source, target, n, tokenizer)
+        }
+        SimilarityMethod::Substring => substring_similarity_with(source, target, tokenizer),
+        SimilarityMethod::Weighted {
+            jaccard,
+            ngram,
+            levenshtein,
+            ngram_size,
+        } => weighted_similarity_with(
+            source,
+            target,
+            jaccard,
+            ngram,
+            levenshtein,
+            ngram_size,
+            tokenizer,
+        ),
+        _ => calculate_similarity(source, target, method),
+    }
+}
+
+/// Calculates similarity like `calculate_similarity_with` and reports the
+/// method that computed the score.
+///
+/// For `Hybrid`, this is the branch that produced the score (see
+/// `hybrid_similarity_with_method`); every other method reports its own name.
+/// Scores are looked up in and added to the similarity cache when it is
+/// enabled (see `similarity_cache::configure`), under `source_hash`, the
+/// `similarity_cache::hash_source` of the source text.
+fn calculate_similarity_with_method(
+    source: &str,
+    source_hash: Option<SourceHash>,
+    target: &str,
+    method: SimilarityMethod,
+    tokenizer: &TokenizerOptions,
+) -> (f64, &'static str) {
+    similarity_cache::get_or_compute(source_hash, target, method, tokenizer, || match method {
+        SimilarityMethod::Hybrid(ngram_size) => {
+            hybrid_similarity_with_method(source, target, ngram_size, tokenizer)
+        }
+        _ => (
+            calculate_similarity_with(source, target, method, tokenizer),
+            method.name(),
+        ),
+    })
+}
+
+/// Compares one text against multiple reference texts in parallel.
+///
+/// This function is the main entry point for similarity comparison. It takes
+/// a source text and compares it against multiple reference texts using the
+/// specified similarity method, returning only matches above the threshold.
+///
+/// # Processing Flow
+///
+/// 1. **Parallel Iteration**: Uses Rayon to process all reference texts in parallel
+/// 2. **Pre-filtering**: Applies length-based pre-filtering to quickly eliminate
+///    obviously dissimilar texts before expensive calculations
+/// 3. **Similarity Calculation**: Calculates similarity using the specified method.
+///    For `CosineTfIdf`, the IDF table is built once from the source and all
+///    target texts, so terms rare across the reference set dominate the score
+/// 4. **Threshold Filtering**: Only includes matches with similarity >= threshold
+/// 5. **Result Collection**: Returns (reference_index, similarity_percentage, method_used) tuples
+///
+/// # Arguments
+///
+/// * `source_text` - The text extracted from a file to compare
+/// * `target_texts` - A slice of reference text strings to compare against
+/// * `method` - The similarity method to use (Jaccard, Ngram, Levenshtein, or Hybrid)
+/// * `threshold` - The minimum similarity percentage (0-100) required for a match
+/// * `tokenizer` - Tokenization settings (see `calculate_similarity_with` for
+///   the methods each setting applies to)
+/// * `parallel` - Compare with the reference texts in parallel. Pass `false`
+///   when the caller already compares several source texts in parallel, so
+///   the two levels do not compete for the same threads
+///
+/// # Returns
+///
+/// A vector of tuples `(usize, f64, &str)` where:
+/// - `usize` is the index of the reference text in the input array
+/// - `f64` is the similarity percentage (0-100)
+/// - `&str` is the method that computed the score: the name of `method`, or
+///   for `Hybrid` the branch that ran ("jaccard", "levenshtein", or "ngram")
 ///
 /// Only matches with similarity >= threshold are included. Results are not
 /// guaranteed to be in any particular order due to parallel processing.
 ///
 /// # Performance
 ///
-/// - Parallel processing: All comparisons run simultaneously across CPU cores
+/// - Parallel processing: All comparisons run simultaneously across CPU cores,
+///   unless `parallel` is `false`
 /// - Pre-filtering: Quickly eliminates dissimilar texts before expensive calculations
 /// - Early termination: Some methods (like Levenshtein) support early termination
 ///
@@ -531,6 +2060,7 @@ pub fn calculate_similarity(source: &str, target: &str, method: SimilarityMethod
 ///
 /// ```
 /// # use dms_toolkit_rs::core::similarity::{compare_with_documents, SimilarityMethod};
+/// # use dms_toolkit_rs::core::tokenize::TokenizerOptions;
 /// let source = "The quick brown fox";
 /// let references = vec![
 ///     "The quick brown fox jumps".to_string(),
@@ -541,42 +2071,545 @@ pub fn calculate_similarity(source: &str, target: &str, method: SimilarityMethod
 /// let matches = compare_with_documents(
 ///     source,
 ///     &references,
-///     SimilarityMethod::Hybrid,
+///     SimilarityMethod::Hybrid(3),
 ///     50.0, // 50% threshold
+///     &TokenizerOptions::default(),
+///     true,
 /// );
 ///
-/// // matches contains (index, similarity) pairs for texts above 50% similarity
+/// // matches contains (index, similarity, method_used) for texts above 50% similarity
 /// ```
 pub fn compare_with_documents(
     source_text: &str,
     target_texts: &[String],
     method: SimilarityMethod,
     threshold: f64,
-) -> Vec<(usize, f64)> {
+    tokenizer: &TokenizerOptions,
+    parallel: bool,
+) -> Vec<(usize, f64, &'static str)> {
+    let idf = document_idf(source_text, target_texts, method);
+    document_matches(
+        source_text,
+        target_texts,
+        method,
+        threshold,
+        tokenizer,
+        idf.as_ref(),
+        parallel,
+    )
+    .collect()
+}
+
+/// Compares a text with reference texts, passing each match to a callback as
+/// soon as it is found.
+///
+/// Runs the same comparison as `compare_with_documents`, but instead of
+/// collecting the matches at the end of the scan, calls `on_match` from the
+/// worker thread that scored each one, so results can be shown progressively.
+///
+/// # Arguments
+///
+/// * `source_text` - The text extracted from a file to compare
+/// * `target_texts` - A slice of reference text strings to compare against
+/// * `method` - The similarity method to use
+/// * `threshold` - The minimum similarity percentage (0-100) required for a match
+/// * `tokenizer` - Tokenization settings (see `calculate_similarity_with`)
+/// * `parallel` - Compare with the reference texts in parallel
+/// * `on_match` - Called with the `(reference_index, similarity_percentage,
+///   method_used)` of each match at or above the threshold. With `parallel`
+///   it runs on several threads at once, hence the `Sync` bound. Returning
+///   an error stops the scan, for example when the matches can no longer be
+///   delivered
+///
+/// # Returns
+///
+/// The number of matches `on_match` accepted, or the first error it
+/// returned. Matches arrive in completion order, not by reference index or
+/// score.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::{compare_with_documents_streaming, SimilarityMethod};
+/// # use dms_toolkit_rs::core::tokenize::TokenizerOptions;
+/// let references = vec!["the quick brown fox".to_string()];
+///
+/// let count = compare_with_documents_streaming(
+///     "the quick brown fox",
+///     &references,
+///     SimilarityMethod::Jaccard,
+///     50.0,
+///     &TokenizerOptions::default(),
+///     true,
+///     |idx, similarity, _| {
+///         println!("reference {} matched at {}%", idx, similarity);
+///         Ok::<(), String>(())
+///     },
+/// );
+/// assert_eq!(count, Ok(1));
+/// ```
+pub fn compare_with_documents_streaming<E: Send>(
+    source_text: &str,
+    target_texts: &[String],
+    method: SimilarityMethod,
+    threshold: f64,
+    tokenizer: &TokenizerOptions,
+    parallel: bool,
+    on_match: impl Fn(usize, f64, &'static str) -> Result<(), E> + Sync,
+) -> Result<usize, E> {
+    let idf = document_idf(source_text, target_texts, method);
+    document_matches(
+        source_text,
+        target_texts,
+        method,
+        threshold,
+        tokenizer,
+        idf.as_ref(),
+        parallel,
+    )
+    .map(|(idx, similarity, method_used)| on_match(idx, similarity, method_used).map(|()| 1))
+    .try_reduce(|| 0, |a, b| Ok(a + b))
+}
+
+/// Finds the single reference text most similar to a source text.
+///
+/// Runs the same parallel comparison as `compare_with_documents`, but reduces
+/// the matches to the best one as they are produced instead of collecting
+/// them all.
+///
+/// # Arguments
+///
+/// * `source_text` - The text extracted from a file to compare
+/// * `target_texts` - A slice of reference text strings to compare against
+/// * `method` - The similarity method to use
+/// * `threshold` - The minimum similarity percentage (0-100) required for a match
+/// * `tokenizer` - Word tokenization settings for the `Jaccard` method
+/// * `parallel` - Compare with the reference texts in parallel
+///
+/// # Returns
+///
+/// The `(reference_index, similarity_percentage, method_used)` of the
+/// highest-scoring reference at or above the threshold, or `None` if no
+/// reference reaches it.
+/// Ties are broken by the lower reference index, as in `rank_matches`.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::{best_document_match, SimilarityMethod};
+/// # use dms_toolkit_rs::core::tokenize::TokenizerOptions;
+/// let references = vec![
+///     "the quick brown fox jumps".to_string(),
+///     "the quick brown fox".to_string(),
+/// ];
+///
+/// let best = best_document_match(
+///     "the quick brown fox",
+///     &references,
+///     SimilarityMethod::Jaccard,
+///     50.0,
+///     &TokenizerOptions::default(),
+///     true,
+/// );
+/// assert_eq!(best, Some((1, 100.0, "jaccard")));
+/// ```
+pub fn best_document_match(
+    source_text: &str,
+    target_texts: &[String],
+    method: SimilarityMethod,
+    threshold: f64,
+    tokenizer: &TokenizerOptions,
+    parallel: bool,
+) -> Option<(usize, f64, &'static str)> {
+    let idf = document_idf(source_text, target_texts, method);
+    document_matches(
+        source_text,
+        target_texts,
+        method,
+        threshold,
+        tokenizer,
+        idf.as_ref(),
+        parallel,
+    )
+    .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+}
+
+/// Builds the IDF table for comparing a source text with reference texts, if
+/// `method` needs one.
+///
+/// TF-IDF weighting needs corpus-wide term statistics, computed once up front
+/// from the source text and all reference texts.
+pub(crate) fn document_idf(
+    source_text: &str,
+    target_texts: &[String],
+    method: SimilarityMethod,
+) -> Option<IdfTable> {
+    match method {
+        SimilarityMethod::CosineTfIdf => Some(IdfTable::from_documents(
+            std::iter::once(source_text).chain(target_texts.iter().map(String::as_str)),
+        )),
+        _ => None,
+    }
+}
+
+/// Compares a source text with each reference text, yielding the
+/// `(reference_index, similarity_percentage, method_used)` of every match at
+/// or above the threshold.
+///
+/// Unless `parallel` is set, the references are compared in sequence on the
+/// calling thread.
+fn document_matches<'a>(
+    source_text: &'a str,
+    target_texts: &'a [String],
+    method: SimilarityMethod,
+    threshold: f64,
+    tokenizer: &'a TokenizerOptions,
+    idf: Option<&'a IdfTable>,
+    parallel: bool,
+) -> impl ParallelIterator<Item = (usize, f64, &'static str)> + 'a {
+    // A minimum split length covering every reference keeps Rayon from splitting the work
+    let min_len = if parallel {
+        1
+    } else {
+        target_texts.len().max(1)
+    };
+    let source_hash = similarity_cache::hash_source(source_text);
+
     target_texts
         .par_iter()
+        .with_min_len(min_len)
         .enumerate()
-        .filter_map(|(idx, target)| {
-            // Pre-filter by length
-            if !pre_filter_by_length(source_text, target, threshold) {
+        .filter_map(move |(idx, target)| {
+            score_reference(
+                source_text,
+                source_hash,
+                target,
+                method,
+                threshold,
+                tokenizer,
+                idf,
+            )
+            .map(|(similarity, method_used)| (idx, similarity, method_used))
+        })
+}
+
+/// Scores one reference text against a source text, with the length
+/// pre-filter and threshold check of `compare_with_documents`.
+///
+/// # Returns
+///
+/// The `(similarity_percentage, method_used)` of the comparison, or `None` if
+/// the reference was pre-filtered or scored below the threshold.
+pub(crate) fn score_reference(
+    source_text: &str,
+    source_hash: Option<SourceHash>,
+    target: &str,
+    method: SimilarityMethod,
+    threshold: f64,
+    tokenizer: &TokenizerOptions,
+    idf: Option<&IdfTable>,
+) -> Option<(f64, &'static str)> {
+    // Pre-filter by length
+    if !method.matches_across_lengths() && !pre_filter_by_length(source_text, target, threshold) {
+        return None;
+    }
+
+    let (similarity, method_used) = match idf {
+        Some(idf) if !identical_texts(source_text, target) => (
+            cosine_tfidf_similarity(source_text, target, idf),
+            method.name(),
+        ),
+        _ => calculate_similarity_with_method(source_text, source_hash, target, method, tokenizer),
+    };
+
+    (similarity >= threshold).then_some((similarity, method_used))
+}
+
+/// Compares every pair of texts in a collection in parallel.
+///
+/// Used to find near-duplicates within a batch: each unordered pair of texts
+/// is compared once, with the same length pre-filtering and threshold check
+/// as `compare_with_documents`. For `CosineTfIdf`, the IDF table is built
+/// once from all texts. A directional method (`Substring`) scores a pair in
+/// both directions and keeps the higher score, so the pairs found do not
+/// depend on the order of the texts.
+///
+/// # Arguments
+///
+/// * `texts` - The texts to compare with each other
+/// * `method` - The similarity method to use
+/// * `threshold` - The minimum similarity percentage (0-100) required for a pair
+///
+/// # Returns
+///
+/// A vector of `(i, j, similarity)` tuples with `i < j` for every pair at or
+/// above the threshold, ordered by `i` and then `j`. Empty texts are never
+/// paired.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::{pairwise_similarities, SimilarityMethod};
+/// let texts = vec![
+///     "quarterly report".to_string(),
+///     "cafeteria menu".to_string(),
+///     "quarterly report".to_string(),
+/// ];
+/// let pairs = pairwise_similarities(&texts, SimilarityMethod::Jaccard, 90.0);
+/// assert_eq!(pairs, vec![(0, 2, 100.0)]);
+/// ```
+pub fn pairwise_similarities(
+    texts: &[String],
+    method: SimilarityMethod,
+    threshold: f64,
+) -> Vec<(usize, usize, f64)> {
+    let idf = match method {
+        SimilarityMethod::CosineTfIdf => {
+            Some(IdfTable::from_documents(texts.iter().map(String::as_str)))
+        }
+        _ => None,
+    };
+
+    let pairs: Vec<(usize, usize)> = (0..texts.len())
+        .flat_map(|i| (i + 1..texts.len()).map(move |j| (i, j)))
+        .collect();
+
+    pairs
+        .into_par_iter()
+        .filter_map(|(i, j)| {
+            let (source, target) = (&texts[i], &texts[j]);
+            if source.is_empty()
+                || target.is_empty()
+                || (!method.matches_across_lengths()
+                    && !pre_filter_by_length(source, target, threshold))
+            {
                 return None;
             }
 
-            let similarity = calculate_similarity(source_text, target, method);
+            let similarity = match &idf {
+                Some(idf) => cosine_tfidf_similarity(source, target, idf),
+                None if method.is_directional() => calculate_similarity(source, target, method)
+                    .max(calculate_similarity(target, source, method)),
+                None => calculate_similarity(source, target, method),
+            };
 
-            if similarity >= threshold {
-                Some((idx, similarity))
-            } else {
-                None
+            (similarity >= threshold).then_some((i, j, similarity))
+        })
+        .collect()
+}
+
+/// Removes exact duplicates from a list of texts.
+///
+/// Comparing against the unique texts and expanding the matches with
+/// `expand_duplicate_matches` gives the same matches as comparing against
+/// every text, without scoring a duplicated text more than once.
+///
+/// # Arguments
+///
+/// * `texts` - The texts to deduplicate
+///
+/// # Returns
+///
+/// The unique texts in order of first occurrence, and for each unique text the
+/// ascending indices in `texts` where it occurs.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::dedupe_texts;
+/// let texts = vec!["a".to_string(), "b".to_string(), "a".to_string()];
+/// let (unique, origins) = dedupe_texts(&texts);
+/// assert_eq!(unique, vec!["a".to_string(), "b".to_string()]);
+/// assert_eq!(origins, vec![vec![0, 2], vec![1]]);
+/// ```
+pub fn dedupe_texts(texts: &[String]) -> (Vec<String>, Vec<Vec<usize>>) {
+    let mut positions: HashMap<&str, usize> = HashMap::with_capacity(texts.len());
+    let mut unique = Vec::new();
+    let mut origins: Vec<Vec<usize>> = Vec::new();
+
+    for (idx, text) in texts.iter().enumerate() {
+        match positions.get(text.as_str()) {
+            Some(&position) => origins[position].push(idx),
+            None => {
+                positions.insert(text, unique.len());
+                unique.push(text.clone());
+                origins.push(vec![idx]);
             }
+        }
+    }
+
+    (unique, origins)
+}
+
+/// Maps matches against deduplicated texts back to the original indices.
+///
+/// Each match against a unique text becomes one match per original
+/// occurrence of that text, with the same score.
+///
+/// # Arguments
+///
+/// * `matches` - The `(unique_index, similarity_percentage, method_used)` matches
+/// * `origins` - The original indices of each unique text, from `dedupe_texts`
+///
+/// # Returns
+///
+/// The matches with `reference_index` pointing into the original texts.
+pub fn expand_duplicate_matches(
+    matches: Vec<(usize, f64, &'static str)>,
+    origins: &[Vec<usize>],
+) -> Vec<(usize, f64, &'static str)> {
+    matches
+        .into_iter()
+        .flat_map(|(idx, similarity, method_used)| {
+            origins[idx]
+                .iter()
+                .map(move |&origin| (origin, similarity, method_used))
         })
         .collect()
 }
 
+/// Splits text into paragraphs at blank lines.
+///
+/// Lines containing only whitespace count as blank, so `\r\n` line endings
+/// and indented blank lines separate paragraphs too. Each paragraph is
+/// trimmed, and blank paragraphs are dropped.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::split_paragraphs;
+/// let text = "Terms\nof service\n\n  \nPrivacy policy\n";
+/// assert_eq!(split_paragraphs(text), vec!["Terms\nof service", "Privacy policy"]);
+/// ```
+pub fn split_paragraphs(text: &str) -> Vec<&str> {
+    let mut paragraphs = Vec::new();
+    let mut start = None;
+    let mut end = 0;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if let Some(start) = start.take() {
+                paragraphs.push(text[start..end].trim());
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + line.len();
+        }
+        offset += line.len();
+    }
+    if let Some(start) = start {
+        paragraphs.push(text[start..end].trim());
+    }
+
+    paragraphs
+}
+
+/// Sorts similarity matches by score and optionally keeps only the top results.
+///
+/// Matches are ordered by similarity percentage, highest first. Ties are
+/// broken by the lower reference index, so the same inputs always produce
+/// the same ordering regardless of the parallel scan order.
+///
+/// # Arguments
+///
+/// * `matches` - The `(reference_index, similarity_percentage)` pairs to sort
+/// * `max_results` - Optional maximum number of matches to keep
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::similarity::rank_matches;
+/// let mut matches = vec![(2, 40.0, "ngram"), (0, 90.0, "ngram"), (1, 90.0, "ngram")];
+/// rank_matches(&mut matches, Some(2));
+/// assert_eq!(matches, vec![(0, 90.0, "ngram"), (1, 90.0, "ngram")]);
+/// ```
+pub fn rank_matches(matches: &mut Vec<(usize, f64, &'static str)>, max_results: Option<usize>) {
+    matches.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    if let Some(max_results) = max_results {
+        matches.truncate(max_results);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_levenshtein_alignment() {
+        let ops = levenshtein_alignment("kitten", "sitting");
+
+        let matched = |source_index, target_index, ch| EditOp::Match {
+            source_index,
+            target_index,
+            ch,
+        };
+        assert_eq!(
+            ops,
+            vec![
+                EditOp::Substitute {
+                    source_index: 0,
+                    target_index: 0,
+                    from: 'k',
+                    to: 's'
+                },
+                matched(1, 1, 'i'),
+                matched(2, 2, 't'),
+                matched(3, 3, 't'),
+                EditOp::Substitute {
+                    source_index: 4,
+                    target_index: 4,
+                    from: 'e',
+                    to: 'i'
+                },
+                matched(5, 5, 'n'),
+                EditOp::Insert {
+                    source_index: 6,
+                    target_index: 6,
+                    ch: 'g'
+                },
+            ]
+        );
+        let edits = ops
+            .iter()
+            .filter(|op| !matches!(op, EditOp::Match { .. }))
+            .count();
+        assert_eq!(edits, levenshtein_distance("kitten", "sitting", None));
+
+        assert_eq!(
+            levenshtein_alignment("ab", ""),
+            vec![
+                EditOp::Delete {
+                    source_index: 0,
+                    target_index: 0,
+                    ch: 'a'
+                },
+                EditOp::Delete {
+                    source_index: 1,
+                    target_index: 0,
+                    ch: 'b'
+                },
+            ]
+        );
+        assert!(levenshtein_alignment("", "").is_empty());
+    }
+
+    #[test]
+    fn test_split_paragraphs() {
+        let text =
+            "\n\nFirst line\nsecond line\r\n\r\n \t \n\n  Second paragraph  \nThird?\n\nLast";
+        assert_eq!(
+            split_paragraphs(text),
+            vec![
+                "First line\nsecond line",
+                "Second paragraph  \nThird?",
+                "Last"
+            ]
+        );
+        assert!(split_paragraphs(" \n\n").is_empty());
+    }
+
     #[test]
     fn test_jaccard_similarity() {
         let text1 = "hello world";
@@ -585,6 +2618,201 @@ mod tests {
         assert!(score > 0.0 && score < 100.0);
     }
 
+    #[test]
+    fn test_jaccard_stemming_and_stopwords() {
+        let source = "the cats are running";
+        let target = "a cat runs";
+        let options = TokenizerOptions {
+            remove_stopwords: true,
+            stem: true,
+            ..Default::default()
+        };
+
+        let plain = jaccard_similarity(source, target);
+        let normalized = jaccard_similarity_with(source, target, &options);
+
+        assert_eq!(plain, 0.0);
+        assert_eq!(normalized, 100.0);
+
+        let references = vec![target.to_string()];
+        let matches = compare_with_documents(
+            source,
+            &references,
+            SimilarityMethod::Jaccard,
+            50.0,
+            &options,
+            true,
+        );
+        assert_eq!(matches, vec![(0, 100.0, "jaccard")]);
+    }
+
+    #[test]
+    fn test_min_token_len_drops_short_words() {
+        let source = "a b c d quarterly report";
+        let target = "a b c d annual summary";
+        let options = TokenizerOptions {
+            min_token_len: 2,
+            ..Default::default()
+        };
+
+        assert_eq!(jaccard_similarity(source, target), 50.0);
+        assert_eq!(jaccard_similarity_with(source, target, &options), 0.0);
+
+        // Combines with stemming; words are measured before they are stemmed
+        let options = TokenizerOptions {
+            min_token_len: 2,
+            stem: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            jaccard_similarity_with("x y reports, as", "z report as", &options),
+            100.0
+        );
+    }
+
+    #[test]
+    fn test_case_sensitive_comparison() {
+        let case_sensitive = TokenizerOptions {
+            case_sensitive: true,
+            ..Default::default()
+        };
+
+        for method in [SimilarityMethod::Jaccard, SimilarityMethod::Ngram(2)] {
+            let default = TokenizerOptions::default();
+            assert_eq!(
+                calculate_similarity_with("ABC", "abc", method, &default),
+                100.0
+            );
+            assert!(calculate_similarity_with("ABC", "abc", method, &case_sensitive) < 100.0);
+        }
+    }
+
+    #[test]
+    fn test_hybrid_reports_method_used() {
+        let references = vec!["invoice 1043 for ACME Corp".to_string()];
+
+        let matches = compare_with_documents(
+            "invoice 1042 for ACME Corp",
+            &references,
+            SimilarityMethod::Hybrid(DEFAULT_NGRAM_SIZE),
+            0.0,
+            &TokenizerOptions::default(),
+            true,
+        );
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].2, "levenshtein");
+
+        let matches = compare_with_documents(
+            "invoice 1042 for ACME Corp",
+            &references,
+            SimilarityMethod::Levenshtein,
+            0.0,
+            &TokenizerOptions::default(),
+            true,
+        );
+        assert_eq!(matches[0].2, "levenshtein");
+
+        let (_, method_used) = hybrid_similarity_with_method(
+            "alpha beta",
+            "gamma delta",
+            DEFAULT_NGRAM_SIZE,
+            &TokenizerOptions::default(),
+        );
+        assert_eq!(method_used, "jaccard");
+    }
+
+    #[test]
+    fn test_hybrid_reports_true_low_scores() {
+        // Passes the Jaccard check (1 of 3 words shared) but differs in
+        // 20 of 22 characters
+        let similarity = hybrid_similarity(
+            "a bbbbbbbbbbbbbbbbbbbb",
+            "a cccccccccccccccccccc",
+            DEFAULT_NGRAM_SIZE,
+        );
+
+        assert!(similarity < 20.0, "similarity was {}", similarity);
+        assert!((similarity - 200.0 / 22.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_identical_texts_skip_computation() {
+        let text = "Clause 7.2: the supplier shall deliver the goods. ".repeat(1000);
+        assert!(text.len() >= 50_000);
+        let copy = text.clone();
+
+        for method in [
+            SimilarityMethod::Levenshtein,
+            SimilarityMethod::DamerauLevenshtein,
+            SimilarityMethod::Hybrid(3),
+            SimilarityMethod::JaroWinkler,
+            SimilarityMethod::Ngram(3),
+        ] {
+            assert_eq!(calculate_similarity(&text, &copy, method), 100.0);
+        }
+
+        // N-grams longer than the text do not exist, so running the method
+        // scores 0; the 100 can only come from the fast path
+        let method = SimilarityMethod::Ngram(text.len() + 1);
+        assert_eq!(ngram_similarity(&text, &copy, text.len() + 1), 0.0);
+        assert_eq!(calculate_similarity(&text, &copy, method), 100.0);
+        assert_eq!(
+            calculate_similarity_with(&text, &copy, method, &TokenizerOptions::default()),
+            100.0
+        );
+
+        assert_eq!(
+            hybrid_similarity_with_method(&text, &copy, 3, &TokenizerOptions::default()),
+            (100.0, "ngram")
+        );
+        // Texts too short for an n-gram still match themselves
+        assert_eq!(
+            calculate_similarity("ab", "ab", SimilarityMethod::Ngram(3)),
+            100.0
+        );
+        assert_eq!(
+            calculate_similarity("", "", SimilarityMethod::Ngram(3)),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_identical_texts_without_words_are_scored() {
+        let stopwords = TokenizerOptions {
+            remove_stopwords: true,
+            ..Default::default()
+        };
+        let long_words = TokenizerOptions {
+            min_token_len: 3,
+            ..Default::default()
+        };
+        let weighted = SimilarityMethod::weighted(1.0, 1.0, 0.0, 3).unwrap();
+
+        // The tokenizer leaves no word to compare, as when the method runs
+        for (text, options) in [("the of and", &stopwords), ("a 12 by", &long_words)] {
+            for method in [SimilarityMethod::Jaccard, SimilarityMethod::WordNgram(1)] {
+                assert_eq!(calculate_similarity_with(text, text, method, options), 0.0);
+            }
+            assert_eq!(
+                calculate_similarity_with(text, text, weighted, options),
+                50.0
+            );
+            // Character-based methods ignore the filters
+            let method = SimilarityMethod::Ngram(3);
+            assert_eq!(
+                calculate_similarity_with(text, text, method, options),
+                100.0
+            );
+        }
+
+        // A kept word is enough for the fast path
+        let method = SimilarityMethod::Jaccard;
+        assert_eq!(
+            calculate_similarity_with("the contract", "the contract", method, &stopwords),
+            100.0
+        );
+    }
+
     #[test]
     fn test_levenshtein_distance() {
         assert_eq!(levenshtein_distance("kitten", "sitting", None), 3);
@@ -592,6 +2820,17 @@ mod tests {
         assert_eq!(levenshtein_distance("abc", "abc", None), 0);
     }
 
+    #[test]
+    fn test_damerau_levenshtein_counts_transposition_once() {
+        assert_eq!(damerau_levenshtein_distance("ca", "ac", None), 1);
+        assert_eq!(levenshtein_distance("ca", "ac", None), 2);
+
+        assert_eq!(damerau_levenshtein_distance("kitten", "sitting", None), 3);
+        assert_eq!(damerau_levenshtein_distance("", "abc", None), 3);
+        assert_eq!(damerau_levenshtein_distance("recieve", "receive", None), 1);
+        assert_eq!(damerau_levenshtein_distance("abcdef", "ghijkl", Some(2)), 3);
+    }
+
     #[test]
     fn test_pre_filter() {
         assert!(pre_filter_by_length("hello", "hello world", 30.0));
@@ -601,4 +2840,391 @@ mod tests {
             30.0
         ));
     }
+
+    #[test]
+    fn test_cosine_tfidf_ignores_shared_stopwords() {
+        let source = "the cat and the dog of the farm";
+        let references = vec![
+            "the car and the road of the city".to_string(),
+            "the sun and the moon of the sky".to_string(),
+            "the book and the pen of the desk".to_string(),
+            "the fish and the sea of the coast".to_string(),
+            "the tree and the leaf of the forest".to_string(),
+        ];
+
+        let matches = compare_with_documents(
+            source,
+            &references,
+            SimilarityMethod::CosineTfIdf,
+            0.0,
+            &TokenizerOptions::default(),
+            true,
+        );
+
+        assert_eq!(matches.len(), references.len());
+        assert!(matches.iter().all(|(_, similarity, _)| *similarity < 5.0));
+    }
+
+    #[test]
+    fn test_cosine_tfidf_rewards_shared_rare_terms() {
+        let source = "the quantum entanglement experiment results for photons";
+        let references = vec![
+            "the quantum entanglement experiment results for electrons".to_string(),
+            "the history of the city".to_string(),
+            "the price of the house".to_string(),
+            "the results of the election".to_string(),
+        ];
+
+        let matches = compare_with_documents(
+            source,
+            &references,
+            SimilarityMethod::CosineTfIdf,
+            0.0,
+            &TokenizerOptions::default(),
+            true,
+        );
+        let score = |index: usize| {
+            matches
+                .iter()
+                .find(|(idx, _, _)| *idx == index)
+                .map(|(_, similarity, _)| *similarity)
+                .unwrap_or(0.0)
+        };
+
+        assert!(score(0) > 50.0);
+        assert!(score(1) < 10.0);
+        assert!(score(2) < 10.0);
+        assert!(score(3) < 10.0);
+    }
+
+    #[test]
+    fn test_ngram_size_changes_score() {
+        let bigram = calculate_similarity("abcd", "abce", SimilarityMethod::Ngram(2));
+        let trigram = calculate_similarity("abcd", "abce", SimilarityMethod::Ngram(3));
+
+        assert_eq!(bigram, 50.0);
+        assert!((trigram - 100.0 / 3.0).abs() < 1e-9);
+        assert!(bigram > trigram);
+    }
+
+    #[test]
+    fn test_ngram_size_zero_is_clamped() {
+        assert_eq!(
+            ngram_similarity("abcd", "abce", 0),
+            ngram_similarity("abcd", "abce", 1)
+        );
+    }
+
+    #[test]
+    fn test_word_ngram_similarity_matches_shared_phrase() {
+        let source = "The quarterly revenue report shows strong growth";
+        let target = "Analysts say quarterly revenue report was late";
+        let shuffled = "growth strong shows report revenue quarterly the";
+
+        // Only "quarterly revenue report" is shared, out of 9 distinct trigrams
+        let method = SimilarityMethod::WordNgram(3);
+        let phrase = calculate_similarity(source, target, method);
+        assert!((phrase - 100.0 / 9.0).abs() < 1e-9);
+
+        // The same words without a shared phrase have no trigrams in common
+        assert_eq!(calculate_similarity(source, shuffled, method), 0.0);
+        assert_eq!(jaccard_similarity(source, shuffled), 100.0);
+    }
+
+    #[test]
+    fn test_sliding_window_finds_embedded_reference() {
+        // Pseudo-random six-letter words from a linear congruential generator
+        let mut state: u64 = 42;
+        let filler: Vec<String> = (0..490)
+            .map(|_| {
+                (0..6)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6_364_136_223_846_793_005)
+                            .wrapping_add(1);
+                        (b'a' + (state >> 33) as u8 % 26) as char
+                    })
+                    .collect()
+            })
+            .collect();
+        let reference = "the committee approved the revised budget for regional rail expansion";
+        let mut words: Vec<&str> = filler.iter().map(String::as_str).collect();
+        words.splice(250..250, reference.split_whitespace());
+        let document = words.join(" ");
+        assert_eq!(document.split_whitespace().count(), 500);
+
+        let method = SimilarityMethod::SlidingWindow(DEFAULT_NGRAM_SIZE);
+        assert_eq!(calculate_similarity(&document, reference, method), 100.0);
+        assert!(calculate_similarity(&document, reference, SimilarityMethod::Ngram(3)) < 20.0);
+
+        let references = vec![
+            "unrelated words entirely".to_string(),
+            reference.to_string(),
+        ];
+        let options = TokenizerOptions::default();
+        let matches = compare_with_documents(&document, &references, method, 80.0, &options, true);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].0, 1);
+        assert!(matches[0].1 >= 90.0);
+    }
+
+    #[test]
+    fn test_sliding_window_scores_every_window() {
+        // Words from a small vocabulary, so many windows score differently
+        let vocabulary = [
+            "Rail",
+            "budget",
+            "a",
+            "of",
+            "committee",
+            "ΟΔΟΣ",
+            "plan",
+            "rails",
+        ];
+        let mut state: u64 = 7;
+        let document = (0..300)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1);
+                vocabulary[(state >> 33) as usize % vocabulary.len()]
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let words: Vec<&str> = document.split_whitespace().collect();
+
+        for (reference, n, case_sensitive) in [
+            ("rail budget of the committee", 3, false),
+            ("Rail plan a", 2, true),
+            ("a of a", 8, false),
+            ("οδος rails plan budget", 4, false),
+        ] {
+            let options = TokenizerOptions {
+                case_sensitive,
+                ..Default::default()
+            };
+            let window = reference.split_whitespace().count();
+            let reference_ngrams = character_ngrams(reference, n, &options);
+            let (best_start, best_score) = (0..=words.len() - window)
+                .map(|start| {
+                    let passage = words[start..start + window].join(" ");
+                    let ngrams = character_ngrams(&passage, n, &options);
+                    (start, set_similarity(&reference_ngrams, &ngrams))
+                })
+                .fold(
+                    (0, 0.0),
+                    |best, next| if next.1 > best.1 { next } else { best },
+                );
+
+            let (score, passage) = sliding_window_match_with(&document, reference, n, &options);
+            assert_eq!(score, best_score);
+            assert_eq!(
+                &document[passage.unwrap()],
+                words[best_start..best_start + window].join(" ")
+            );
+        }
+    }
+
+    #[test]
+    fn test_substring_finds_short_id_in_long_document() {
+        let document = format!(
+            "{} Reference:\n  INV-2024-0042 {}",
+            "lorem ipsum dolor sit amet ".repeat(200),
+            "consectetur adipiscing elit ".repeat(200)
+        );
+        let references = vec!["inv-2024-0042".to_string(), "INV-2024-0043".to_string()];
+        let options = TokenizerOptions::default();
+
+        // The length pre-filter would reject the short IDs before scoring
+        let matches = compare_with_documents(
+            &document,
+            &references,
+            SimilarityMethod::Substring,
+            100.0,
+            &options,
+            true,
+        );
+        assert_eq!(matches, vec![(0, 100.0, "substring")]);
+
+        let case_sensitive = TokenizerOptions {
+            case_sensitive: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            substring_similarity_with(&document, "inv-2024-0042", &case_sensitive),
+            0.0
+        );
+        assert_eq!(substring_similarity_with(&document, " \n", &options), 0.0);
+    }
+
+    #[test]
+    fn test_substring_pairs_do_not_depend_on_order() {
+        let id = "INV-2024-0042".to_string();
+        let document = "Payment received for invoice INV-2024-0042, thank you.".to_string();
+        let other = "Cafeteria menu for the week".to_string();
+
+        for texts in [
+            vec![id.clone(), document.clone(), other.clone()],
+            vec![document.clone(), id.clone(), other.clone()],
+        ] {
+            assert_eq!(
+                pairwise_similarities(&texts, SimilarityMethod::Substring, 100.0),
+                vec![(0, 1, 100.0)]
+            );
+        }
+    }
+
+    #[test]
+    fn test_low_confidence_characters_count_less() {
+        let source = "Inv0ice 2O24";
+        let target = "Invoice 2024";
+        let mut confidence = vec![0.95; source.chars().count()];
+        confidence[3] = 0.2;
+        confidence[9] = 0.3;
+
+        let plain = levenshtein_similarity(source, target, None);
+        let weighted = weighted_levenshtein_similarity(source, &confidence, target);
+
+        // Two misreads in twelve characters, at costs 0.2 and 0.3
+        assert!((plain - 100.0 * 10.0 / 12.0).abs() < 1e-9);
+        assert!((weighted - 100.0 * 11.5 / 12.0).abs() < 1e-9);
+
+        // Without confidence, every edit counts in full
+        assert_eq!(weighted_levenshtein_similarity(source, &[], target), plain);
+        assert_eq!(weighted_levenshtein_distance("", &[], "abc"), 3.0);
+        assert_eq!(weighted_levenshtein_distance("abc", &[0.5; 3], ""), 1.5);
+    }
+
+    #[test]
+    fn test_weighted_similarity_blends_scores() {
+        let (source, target) = ("the quick brown fox", "the quick brown cat");
+        let scores = [
+            calculate_similarity(source, target, SimilarityMethod::Jaccard),
+            calculate_similarity(source, target, SimilarityMethod::Ngram(DEFAULT_NGRAM_SIZE)),
+            calculate_similarity(source, target, SimilarityMethod::Levenshtein),
+        ];
+        let min = scores.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        let method = SimilarityMethod::weighted(1.0, 1.0, 1.0, DEFAULT_NGRAM_SIZE).unwrap();
+        let blended = calculate_similarity(source, target, method);
+
+        assert!(min < max);
+        assert!(min <= blended && blended <= max);
+        assert!((blended - scores.iter().sum::<f64>() / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_weighted_rejects_invalid_weights() {
+        assert!(SimilarityMethod::weighted(-0.5, 1.0, 1.0, 3).is_err());
+        assert!(SimilarityMethod::weighted(f64::NAN, 1.0, 1.0, 3).is_err());
+        assert!(SimilarityMethod::weighted(0.0, 0.0, 0.0, 3).is_err());
+    }
+
+    #[test]
+    fn test_whitespace_is_normalized_uniformly() {
+        let options = TokenizerOptions::default();
+
+        assert_eq!(
+            character_ngrams("a\tb\nc", 3, &options),
+            character_ngrams("a b c", 3, &options)
+        );
+        assert_eq!(ngram_similarity("a\tb\nc", "a b c", 3), 100.0);
+        assert_eq!(jaccard_similarity("a\tb\nc", "a b c"), 100.0);
+    }
+
+    #[test]
+    fn test_rank_matches() {
+        let scored = |pairs: &[(usize, f64)]| -> Vec<(usize, f64, &'static str)> {
+            pairs
+                .iter()
+                .map(|&(idx, score)| (idx, score, "jaccard"))
+                .collect()
+        };
+        let mut matches = scored(&[(3, 55.0), (1, 80.0), (4, 80.0), (0, 40.0), (2, 95.0)]);
+
+        rank_matches(&mut matches, Some(3));
+        assert_eq!(matches, scored(&[(2, 95.0), (1, 80.0), (4, 80.0)]));
+
+        let mut all = scored(&[(1, 10.0), (0, 10.0)]);
+        rank_matches(&mut all, None);
+        assert_eq!(all, scored(&[(0, 10.0), (1, 10.0)]));
+    }
+
+    #[test]
+    fn test_streaming_delivers_every_match() {
+        let references: Vec<String> = (0..200)
+            .map(|i| match i % 3 {
+                0 => format!("invoice {} for consulting services", i),
+                1 => format!("invoice {} for consulting", i),
+                _ => "cafeteria menu for the week".to_string(),
+            })
+            .collect();
+        let source = "invoice 42 for consulting services";
+        let tokenizer = TokenizerOptions::default();
+
+        let delivered = std::sync::Mutex::new(Vec::new());
+        let count = compare_with_documents_streaming(
+            source,
+            &references,
+            SimilarityMethod::Ngram(3),
+            50.0,
+            &tokenizer,
+            true,
+            |idx, similarity, method_used| {
+                delivered
+                    .lock()
+                    .unwrap()
+                    .push((idx, similarity, method_used));
+                Ok::<(), ()>(())
+            },
+        );
+
+        let mut delivered = delivered.into_inner().unwrap();
+        let mut expected = compare_with_documents(
+            source,
+            &references,
+            SimilarityMethod::Ngram(3),
+            50.0,
+            &tokenizer,
+            true,
+        );
+        delivered.sort_by_key(|m| m.0);
+        expected.sort_by_key(|m| m.0);
+        assert!(!expected.is_empty());
+        assert_eq!(count, Ok(expected.len()));
+        assert_eq!(delivered, expected);
+    }
+
+    #[test]
+    fn test_streaming_stops_when_a_match_is_rejected() {
+        let references = vec!["invoice for consulting".to_string(); 10];
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+
+        let result = compare_with_documents_streaming(
+            "invoice for consulting",
+            &references,
+            SimilarityMethod::Jaccard,
+            50.0,
+            &TokenizerOptions::default(),
+            false,
+            |_, _, _| {
+                calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                Err("callback closed")
+            },
+        );
+
+        assert_eq!(result, Err("callback closed"));
+        assert_eq!(calls.into_inner(), 1);
+    }
+
+    #[test]
+    fn test_jaro_winkler_beats_levenshtein_on_transposition() {
+        let jaro_winkler = jaro_winkler_similarity("MARTHA", "MARHTA");
+        let levenshtein = levenshtein_similarity("MARTHA", "MARHTA", None);
+
+        assert!((jaro_winkler - 96.11).abs() < 0.01);
+        assert!(jaro_winkler > levenshtein);
+        assert_eq!(jaro_winkler_similarity("invoice", "invoice"), 100.0);
+    }
 }
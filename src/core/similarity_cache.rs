@@ -0,0 +1,228 @@
+//! Optional process-wide cache of similarity scores.
+//!
+//! Batches that compare overlapping sets of texts recompute the same pairs
+//! over and over. Once `configure` sets a capacity, the reference comparisons
+//! of `compare_with_documents` and `best_document_match` store each score
+//! under a hash of the pair, the method, and the tokenizer settings, and reuse
+//! it for identical comparisons. The cache is off by default.
+//!
+//! Entries hold hashes instead of the compared texts, so each one takes the
+//! same few bytes however long the texts are, and the capacity bounds the
+//! cache's memory. The source text is hashed once per batch of comparisons
+//! (`hash_source`), so only the target text is hashed for each lookup.
+
+use crate::core::similarity::SimilarityMethod;
+use crate::core::tokenize::TokenizerOptions;
+use dashmap::DashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A cached score with a fingerprint of the inputs it was computed from.
+///
+/// The fingerprint hashes the inputs again with independent keys, so a
+/// collision of the map key is detected by comparing fingerprints instead of
+/// returning the score of a different pair.
+struct CachedScore {
+    fingerprint: (u64, u64),
+    similarity: f64,
+    method_used: &'static str,
+}
+
+/// The maximum number of cached scores; 0 disables the cache.
+static CAPACITY: AtomicUsize = AtomicUsize::new(0);
+
+/// Cached scores, keyed by the hash of the source text and the hash of the
+/// target text, method, and tokenizer settings.
+static CACHE: LazyLock<DashMap<(u64, u64), CachedScore>> = LazyLock::new(DashMap::new);
+
+/// The randomly keyed hashers of the map keys and of the fingerprints.
+static KEY_HASHER: LazyLock<RandomState> = LazyLock::new(RandomState::new);
+static FINGERPRINT_HASHER: LazyLock<RandomState> = LazyLock::new(RandomState::new);
+
+/// The hashes of a source text, for the map key and for the fingerprint.
+#[derive(Clone, Copy)]
+pub struct SourceHash {
+    key: u64,
+    fingerprint: u64,
+}
+
+/// Enables the cache with room for `capacity` scores, or disables it with 0.
+///
+/// Once the cache is full, new pairs are computed without being stored until
+/// `clear` empties it. Lowering the capacity (or disabling the cache) clears
+/// the cached scores.
+///
+/// # Arguments
+///
+/// * `capacity` - The maximum number of cached scores
+pub fn configure(capacity: usize) {
+    let previous = CAPACITY.swap(capacity, Ordering::Relaxed);
+    if capacity < previous {
+        clear();
+    }
+}
+
+/// Removes every cached score.
+pub fn clear() {
+    CACHE.clear();
+}
+
+/// Hashes a source text once for all of its comparisons.
+///
+/// # Returns
+///
+/// The hashes to pass to `get_or_compute`, or `None` if the cache is
+/// disabled, so nothing is hashed.
+pub fn hash_source(source: &str) -> Option<SourceHash> {
+    (CAPACITY.load(Ordering::Relaxed) > 0).then(|| SourceHash {
+        key: KEY_HASHER.hash_one(source),
+        fingerprint: FINGERPRINT_HASHER.hash_one(source),
+    })
+}
+
+/// Returns the cached score of a comparison, computing and caching it with
+/// `compute` on a miss.
+///
+/// Without a configured capacity or a source hash, `compute` runs directly
+/// and nothing is hashed or stored.
+///
+/// # Arguments
+///
+/// * `source` - The `hash_source` of the source text
+/// * `target` - The target text
+/// * `method` - The similarity method
+/// * `tokenizer` - The tokenizer settings
+/// * `compute` - Computes the `(similarity, method_used)` of the comparison
+///
+/// # Returns
+///
+/// The `(similarity, method_used)` of the comparison.
+pub fn get_or_compute(
+    source: Option<SourceHash>,
+    target: &str,
+    method: SimilarityMethod,
+    tokenizer: &TokenizerOptions,
+    compute: impl FnOnce() -> (f64, &'static str),
+) -> (f64, &'static str) {
+    let capacity = CAPACITY.load(Ordering::Relaxed);
+    let Some(source) = source.filter(|_| capacity > 0) else {
+        return compute();
+    };
+
+    let key = (
+        source.key,
+        target_hash(&KEY_HASHER, target, method, tokenizer),
+    );
+    let fingerprint = (
+        source.fingerprint,
+        target_hash(&FINGERPRINT_HASHER, target, method, tokenizer),
+    );
+    if let Some(cached) = CACHE.get(&key)
+        && cached.fingerprint == fingerprint
+    {
+        return (cached.similarity, cached.method_used);
+    }
+
+    let (similarity, method_used) = compute();
+    // A colliding pair already stored keeps its slot
+    if CACHE.len() < capacity && !CACHE.contains_key(&key) {
+        CACHE.insert(
+            key,
+            CachedScore {
+                fingerprint,
+                similarity,
+                method_used,
+            },
+        );
+    }
+
+    (similarity, method_used)
+}
+
+/// Hashes the inputs of a comparison other than the source text: the target
+/// text, the method, and the tokenizer settings.
+fn target_hash(
+    hasher: &RandomState,
+    target: &str,
+    method: SimilarityMethod,
+    tokenizer: &TokenizerOptions,
+) -> u64 {
+    hasher.hash_one((target, method, tokenizer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Disables the process-wide cache when dropped, so other tests do not
+    /// run against it even if this one fails.
+    struct DisableOnDrop;
+
+    impl Drop for DisableOnDrop {
+        fn drop(&mut self) {
+            configure(0);
+        }
+    }
+
+    #[test]
+    fn test_second_comparison_uses_cached_score() {
+        configure(10_000);
+        let _guard = DisableOnDrop;
+        let source = hash_source("cached invoice 2024-001");
+        let target = "cached invoice 2024-002";
+        let tokenizer = TokenizerOptions::default();
+        let method = SimilarityMethod::Ngram(3);
+
+        let first = get_or_compute(source, target, method, &tokenizer, || (91.5, "ngram"));
+        assert_eq!(first, (91.5, "ngram"));
+
+        // The stored score is returned without computing it again
+        let second = get_or_compute(source, target, method, &tokenizer, || {
+            panic!("cached score was recomputed")
+        });
+        assert_eq!(second, first);
+
+        // A different method is a different comparison
+        let other = get_or_compute(
+            source,
+            target,
+            SimilarityMethod::Ngram(2),
+            &tokenizer,
+            || (88.0, "ngram"),
+        );
+        assert_eq!(other, (88.0, "ngram"));
+
+        clear();
+        let recomputed = get_or_compute(source, target, method, &tokenizer, || (50.0, "ngram"));
+        assert_eq!(recomputed, (50.0, "ngram"));
+
+        // Weights equal as numbers are the same comparison
+        let weighted = |ngram: f64| SimilarityMethod::Weighted {
+            jaccard: 1.0,
+            ngram,
+            levenshtein: 0.0,
+            ngram_size: 3,
+        };
+        let first = get_or_compute(source, target, weighted(0.0), &tokenizer, || {
+            (70.0, "weighted")
+        });
+        let second = get_or_compute(source, target, weighted(-0.0), &tokenizer, || {
+            panic!("cached score was recomputed")
+        });
+        assert_eq!(second, first);
+    }
+
+    #[test]
+    fn test_comparison_without_source_hash_is_computed() {
+        // As when the cache was disabled while the source was hashed
+        let score = get_or_compute(
+            None,
+            "uncached invoice",
+            SimilarityMethod::Jaccard,
+            &TokenizerOptions::default(),
+            || (40.0, "jaccard"),
+        );
+        assert_eq!(score, (40.0, "jaccard"));
+    }
+}
@@ -0,0 +1,303 @@
+//! Porter stemming for English words.
+//!
+//! Stemming reduces inflected words to a common stem ("running", "runs", and
+//! "run" all become "run"), so word-based similarity treats them as the same
+//! token. This is the original algorithm by Martin Porter (1980).
+
+/// Reduces an English word to its stem using the Porter algorithm.
+///
+/// The word is expected in lowercase. Words of one or two letters and words
+/// containing characters other than ASCII lowercase letters are returned
+/// unchanged. Stems are not always dictionary words ("happy" becomes "happi"),
+/// but related forms reliably share the same stem.
+///
+/// # Arguments
+///
+/// * `word` - The lowercase word to stem
+///
+/// # Returns
+///
+/// The stemmed word.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::stem::porter_stem;
+/// assert_eq!(porter_stem("running"), "run");
+/// assert_eq!(porter_stem("cats"), "cat");
+/// assert_eq!(porter_stem("relational"), "relat");
+/// ```
+pub fn porter_stem(word: &str) -> String {
+    if word.len() <= 2 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+        return word.to_string();
+    }
+
+    let mut stemmer = Stemmer {
+        word: word.as_bytes().to_vec(),
+    };
+    stemmer.step1a();
+    stemmer.step1b();
+    stemmer.step1c();
+    stemmer.step2();
+    stemmer.step3();
+    stemmer.step4();
+    stemmer.step5();
+
+    String::from_utf8(stemmer.word).unwrap_or_else(|_| word.to_string())
+}
+
+/// Working state of the Porter algorithm: the word being stemmed.
+struct Stemmer {
+    word: Vec<u8>,
+}
+
+impl Stemmer {
+    /// Returns whether the letter at `i` is a consonant.
+    ///
+    /// `y` is a consonant at the start of a word or after a vowel, and a vowel
+    /// after a consonant.
+    fn is_consonant(&self, i: usize) -> bool {
+        match self.word[i] {
+            b'a' | b'e' | b'i' | b'o' | b'u' => false,
+            b'y' => i == 0 || !self.is_consonant(i - 1),
+            _ => true,
+        }
+    }
+
+    /// Returns the measure of the first `len` letters: the number of
+    /// vowel-consonant sequences in `[C](VC)^m[V]`.
+    fn measure(&self, len: usize) -> usize {
+        let mut measure = 0;
+        let mut i = 0;
+
+        while i < len && self.is_consonant(i) {
+            i += 1;
+        }
+        while i < len {
+            while i < len && !self.is_consonant(i) {
+                i += 1;
+            }
+            if i == len {
+                break;
+            }
+            while i < len && self.is_consonant(i) {
+                i += 1;
+            }
+            measure += 1;
+        }
+
+        measure
+    }
+
+    /// Returns whether the first `len` letters contain a vowel.
+    fn has_vowel(&self, len: usize) -> bool {
+        (0..len).any(|i| !self.is_consonant(i))
+    }
+
+    /// Returns whether the first `len` letters end in a double consonant.
+    fn ends_double_consonant(&self, len: usize) -> bool {
+        len >= 2 && self.word[len - 1] == self.word[len - 2] && self.is_consonant(len - 1)
+    }
+
+    /// Returns whether the first `len` letters end consonant-vowel-consonant,
+    /// where the last consonant is not `w`, `x`, or `y` (as in "hop").
+    fn ends_cvc(&self, len: usize) -> bool {
+        len >= 3
+            && self.is_consonant(len - 1)
+            && !self.is_consonant(len - 2)
+            && self.is_consonant(len - 3)
+            && !matches!(self.word[len - 1], b'w' | b'x' | b'y')
+    }
+
+    /// Returns the length of the stem left by removing `suffix`, if the word
+    /// ends with it.
+    fn stem_len(&self, suffix: &str) -> Option<usize> {
+        self.word
+            .ends_with(suffix.as_bytes())
+            .then(|| self.word.len() - suffix.len())
+    }
+
+    /// Replaces the last `len - stem_len` letters with `replacement`.
+    fn replace_from(&mut self, stem_len: usize, replacement: &str) {
+        self.word.truncate(stem_len);
+        self.word.extend_from_slice(replacement.as_bytes());
+    }
+
+    /// Applies the first rule whose suffix the word ends with, if the
+    /// remaining stem has a measure above `min_measure`.
+    fn apply_rules(&mut self, rules: &[(&str, &str)], min_measure: usize) {
+        for (suffix, replacement) in rules {
+            if let Some(stem_len) = self.stem_len(suffix) {
+                if self.measure(stem_len) > min_measure {
+                    self.replace_from(stem_len, replacement);
+                }
+                return;
+            }
+        }
+    }
+
+    /// Removes plurals: "caresses" to "caress", "ponies" to "poni", "cats" to "cat".
+    fn step1a(&mut self) {
+        if let Some(stem_len) = self.stem_len("sses") {
+            self.replace_from(stem_len, "ss");
+        } else if let Some(stem_len) = self.stem_len("ies") {
+            self.replace_from(stem_len, "i");
+        } else if self.stem_len("ss").is_none()
+            && let Some(stem_len) = self.stem_len("s")
+        {
+            self.word.truncate(stem_len);
+        }
+    }
+
+    /// Removes "-ed" and "-ing": "agreed" to "agree", "hopping" to "hop".
+    fn step1b(&mut self) {
+        if let Some(stem_len) = self.stem_len("eed") {
+            if self.measure(stem_len) > 0 {
+                self.replace_from(stem_len, "ee");
+            }
+            return;
+        }
+
+        let Some(stem_len) = self.stem_len("ed").or_else(|| self.stem_len("ing")) else {
+            return;
+        };
+        if !self.has_vowel(stem_len) {
+            return;
+        }
+        self.word.truncate(stem_len);
+
+        let len = self.word.len();
+        if ["at", "bl", "iz"]
+            .iter()
+            .any(|suffix| self.stem_len(suffix).is_some())
+        {
+            self.word.push(b'e');
+        } else if self.ends_double_consonant(len)
+            && !matches!(self.word[len - 1], b'l' | b's' | b'z')
+        {
+            self.word.pop();
+        } else if self.measure(len) == 1 && self.ends_cvc(len) {
+            self.word.push(b'e');
+        }
+    }
+
+    /// Turns a final "y" into "i" after a vowel-containing stem: "happy" to "happi".
+    fn step1c(&mut self) {
+        if let Some(stem_len) = self.stem_len("y")
+            && self.has_vowel(stem_len)
+        {
+            self.replace_from(stem_len, "i");
+        }
+    }
+
+    /// Maps double suffixes to single ones: "relational" to "relate".
+    fn step2(&mut self) {
+        self.apply_rules(
+            &[
+                ("ational", "ate"),
+                ("tional", "tion"),
+                ("enci", "ence"),
+                ("anci", "ance"),
+                ("izer", "ize"),
+                ("abli", "able"),
+                ("alli", "al"),
+                ("entli", "ent"),
+                ("eli", "e"),
+                ("ousli", "ous"),
+                ("ization", "ize"),
+                ("ation", "ate"),
+                ("ator", "ate"),
+                ("alism", "al"),
+                ("iveness", "ive"),
+                ("fulness", "ful"),
+                ("ousness", "ous"),
+                ("aliti", "al"),
+                ("iviti", "ive"),
+                ("biliti", "ble"),
+            ],
+            0,
+        );
+    }
+
+    /// Removes "-ic-", "-full", "-ness" and similar: "hopeful" to "hope".
+    fn step3(&mut self) {
+        self.apply_rules(
+            &[
+                ("icate", "ic"),
+                ("ative", ""),
+                ("alize", "al"),
+                ("iciti", "ic"),
+                ("ical", "ic"),
+                ("ful", ""),
+                ("ness", ""),
+            ],
+            0,
+        );
+    }
+
+    /// Removes remaining suffixes from longer stems: "adjustment" to "adjust".
+    fn step4(&mut self) {
+        const SUFFIXES: &[&str] = &[
+            "ement", "ance", "ence", "able", "ible", "ment", "ant", "ent", "ion", "ism", "ate",
+            "iti", "ous", "ive", "ize", "al", "er", "ic", "ou",
+        ];
+
+        for suffix in SUFFIXES {
+            if let Some(stem_len) = self.stem_len(suffix) {
+                let ion_allowed = *suffix != "ion"
+                    || (stem_len > 0 && matches!(self.word[stem_len - 1], b's' | b't'));
+                if ion_allowed && self.measure(stem_len) > 1 {
+                    self.word.truncate(stem_len);
+                }
+                return;
+            }
+        }
+    }
+
+    /// Removes a final "e" and reduces a final "ll": "probate" to "probat",
+    /// "controll" to "control".
+    fn step5(&mut self) {
+        if let Some(stem_len) = self.stem_len("e") {
+            let measure = self.measure(stem_len);
+            if measure > 1 || (measure == 1 && !self.ends_cvc(stem_len)) {
+                self.word.truncate(stem_len);
+            }
+        }
+
+        let len = self.word.len();
+        if self.word.ends_with(b"ll") && self.measure(len) > 1 {
+            self.word.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_porter_stem() {
+        let cases = [
+            ("caresses", "caress"),
+            ("ponies", "poni"),
+            ("cats", "cat"),
+            ("running", "run"),
+            ("runs", "run"),
+            ("agreed", "agre"),
+            ("hopping", "hop"),
+            ("hoping", "hope"),
+            ("happy", "happi"),
+            ("relational", "relat"),
+            ("conditional", "condit"),
+            ("generalizations", "gener"),
+            ("adjustment", "adjust"),
+            ("controlling", "control"),
+            ("is", "is"),
+            ("café", "café"),
+        ];
+
+        for (word, stem) in cases {
+            assert_eq!(porter_stem(word), stem, "stem of {}", word);
+        }
+    }
+}
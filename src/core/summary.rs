@@ -0,0 +1,155 @@
+//! Aggregate statistics over similarity comparison results.
+//!
+//! This module reduces the output of `process_and_compare_files` into a
+//! compact `SimilaritySummary`, so callers don't have to walk potentially
+//! huge result sets on the Node.js side.
+
+use crate::models::file::GroupedFilesWithSimilarity;
+use crate::models::summary::{SimilarityHistogramBucket, SimilaritySummary};
+
+/// Number of buckets in the similarity histogram (each 10 points wide).
+const HISTOGRAM_BUCKETS: usize = 10;
+
+/// Computes aggregate statistics for a batch of similarity results.
+///
+/// Iterates every file in every MIME type group once, counting files and
+/// matches and accumulating the score distribution.
+///
+/// # Arguments
+///
+/// * `results` - The grouped results returned by `process_and_compare_files`
+///
+/// # Returns
+///
+/// A `SimilaritySummary` with counts, score extremes, averages, and a
+/// 10-bucket histogram of similarity percentages.
+pub fn summarize_results(results: &[GroupedFilesWithSimilarity]) -> SimilaritySummary {
+    let mut total_files = 0u32;
+    let mut files_with_matches = 0u32;
+    let mut total_matches = 0u32;
+    let mut similarity_sum = 0.0;
+    let mut min_similarity: Option<f64> = None;
+    let mut max_similarity: Option<f64> = None;
+    let mut counts = [0u32; HISTOGRAM_BUCKETS];
+
+    for file in results.iter().flat_map(|group| group.files.iter()) {
+        total_files += 1;
+
+        if !file.similarity_matches.is_empty() {
+            files_with_matches += 1;
+        }
+
+        for similarity_match in &file.similarity_matches {
+            let score = similarity_match.similarity_percentage;
+
+            total_matches += 1;
+            similarity_sum += score;
+            min_similarity = Some(min_similarity.map_or(score, |min| min.min(score)));
+            max_similarity = Some(max_similarity.map_or(score, |max| max.max(score)));
+
+            let bucket = ((score / 10.0).floor().max(0.0) as usize).min(HISTOGRAM_BUCKETS - 1);
+            counts[bucket] += 1;
+        }
+    }
+
+    let histogram = counts
+        .iter()
+        .enumerate()
+        .map(|(idx, count)| SimilarityHistogramBucket {
+            range_start: idx as f64 * 10.0,
+            range_end: (idx + 1) as f64 * 10.0,
+            count: *count,
+        })
+        .collect();
+
+    SimilaritySummary {
+        total_files,
+        files_with_matches,
+        total_matches,
+        average_matches_per_file: if total_files == 0 {
+            0.0
+        } else {
+            total_matches as f64 / total_files as f64
+        },
+        average_similarity: if total_matches == 0 {
+            0.0
+        } else {
+            similarity_sum / total_matches as f64
+        },
+        min_similarity,
+        max_similarity,
+        histogram,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::file::{FileMetadataWithSimilarity, SimilarityMatch};
+
+    fn file_with_scores(name: &str, scores: &[f64]) -> FileMetadataWithSimilarity {
+        FileMetadataWithSimilarity {
+            name: name.to_string(),
+            size: 0.0,
+            processing_time_ms: 0.0,
+            encoding: "utf-8".to_string(),
+            text_content: String::new(),
+            error: None,
+            similarity_time_ms: 0.0,
+            similarity_matches: scores
+                .iter()
+                .enumerate()
+                .map(|(idx, score)| SimilarityMatch {
+                    reference_index: idx as u32,
+                    reference_label: None,
+                    similarity_percentage: *score,
+                    approximate: false,
+                    method_used: None,
+                    match_start: None,
+                    match_text: None,
+                    paragraph_index: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_summarize_results() {
+        let results = vec![
+            GroupedFilesWithSimilarity {
+                mime_type: "text/plain".to_string(),
+                files: vec![
+                    file_with_scores("a.txt", &[35.0, 100.0]),
+                    file_with_scores("b.txt", &[]),
+                ],
+            },
+            GroupedFilesWithSimilarity {
+                mime_type: "application/pdf".to_string(),
+                files: vec![file_with_scores("c.pdf", &[39.0])],
+            },
+        ];
+
+        let summary = summarize_results(&results);
+
+        assert_eq!(summary.total_files, 3);
+        assert_eq!(summary.files_with_matches, 2);
+        assert_eq!(summary.total_matches, 3);
+        assert_eq!(summary.average_matches_per_file, 1.0);
+        assert_eq!(summary.average_similarity, 58.0);
+        assert_eq!(summary.min_similarity, Some(35.0));
+        assert_eq!(summary.max_similarity, Some(100.0));
+        assert_eq!(summary.histogram.len(), 10);
+        assert_eq!(summary.histogram[3].count, 2);
+        assert_eq!(summary.histogram[9].count, 1);
+    }
+
+    #[test]
+    fn test_summarize_empty_results() {
+        let summary = summarize_results(&[]);
+
+        assert_eq!(summary.total_files, 0);
+        assert_eq!(summary.average_matches_per_file, 0.0);
+        assert_eq!(summary.min_similarity, None);
+        assert!(summary.histogram.iter().all(|bucket| bucket.count == 0));
+    }
+}
@@ -0,0 +1,112 @@
+//! Optional process-wide Rayon thread pool with a fixed size.
+//!
+//! By default, parallel work runs on Rayon's global pool, which starts one
+//! thread per logical core. In containers and serverless environments with a
+//! CPU quota that is far more threads than the process may use, and the
+//! threads end up competing for the quota. `set_thread_pool_size` builds a
+//! dedicated pool once; every entry point then runs its parallel work inside
+//! it with `install`, unless it is called from a Rayon worker thread, which
+//! already belongs to a pool.
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::OnceLock;
+
+/// The configured pool, if `configure` has been called.
+static THREAD_POOL: OnceLock<ThreadPool> = OnceLock::new();
+
+/// Builds the process-wide pool with `threads` worker threads.
+///
+/// The pool can only be configured once; its threads live for the rest of the
+/// process.
+///
+/// # Arguments
+///
+/// * `threads` - The number of worker threads, at least 1
+///
+/// # Returns
+///
+/// `Ok(())` once the pool is configured, or an error if `threads` is 0, the
+/// pool was already configured, or its threads could not be started.
+pub fn configure(threads: usize) -> Result<(), String> {
+    configure_pool(&THREAD_POOL, threads)
+}
+
+/// Builds a pool with `threads` worker threads into `slot`, like `configure`.
+fn configure_pool(slot: &OnceLock<ThreadPool>, threads: usize) -> Result<(), String> {
+    if threads == 0 {
+        return Err("Thread pool size must be at least 1".to_string());
+    }
+    if slot.get().is_some() {
+        return Err("Thread pool size is already set".to_string());
+    }
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|index| format!("dms-toolkit-{index}"))
+        .build()
+        .map_err(|e| format!("Failed to build thread pool: {}", e))?;
+
+    slot.set(pool)
+        .map_err(|_| "Thread pool size is already set".to_string())
+}
+
+/// Runs `op` in the configured pool, so its parallel iterators use the pool's
+/// threads.
+///
+/// Without a configured pool, `op` runs on the calling thread and its
+/// parallel iterators use Rayon's global pool.
+///
+/// When the caller is itself a Rayon worker (an embedder calling the library
+/// from inside its own `install` scope or parallel iterator), `op` also runs
+/// on the calling thread, so its parallel iterators join the caller's pool.
+/// Installing into the configured pool there would block the caller's worker
+/// while another pool's threads run the work, oversubscribing the cores.
+pub fn install<OP, R>(op: OP) -> R
+where
+    OP: FnOnce() -> R + Send,
+    R: Send,
+{
+    install_in(&THREAD_POOL, op)
+}
+
+/// Runs `op` in the pool of `slot`, like `install`.
+fn install_in<OP, R>(slot: &OnceLock<ThreadPool>, op: OP) -> R
+where
+    OP: FnOnce() -> R + Send,
+    R: Send,
+{
+    match slot.get() {
+        Some(pool) if rayon::current_thread_index().is_none() => pool.install(op),
+        _ => op(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The process-wide pool can only be set once, so tests configure a local
+    // slot and leave the pool of every other test untouched.
+    #[test]
+    fn test_configure_and_install() {
+        let slot = OnceLock::new();
+        assert!(configure_pool(&slot, 0).is_err());
+        configure_pool(&slot, 1).unwrap();
+        assert!(configure_pool(&slot, 2).is_err());
+        assert_eq!(install_in(&slot, rayon::current_num_threads), 1);
+
+        // Called from a Rayon worker, the work stays in the caller's pool
+        let outer = ThreadPoolBuilder::new().num_threads(3).build().unwrap();
+        assert_eq!(
+            outer.install(|| install_in(&slot, rayon::current_num_threads)),
+            3
+        );
+
+        // Without a configured pool, the work runs on the calling thread
+        let unset = OnceLock::new();
+        assert_eq!(
+            install_in(&unset, rayon::current_thread_index),
+            rayon::current_thread_index()
+        );
+    }
+}
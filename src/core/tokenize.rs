@@ -0,0 +1,237 @@
+//! Word tokenization for word-based similarity.
+//!
+//! By default, word-based similarity splits text on whitespace and lowercases
+//! each word. `TokenizerOptions` can additionally drop common English
+//! stopwords and reduce words to their Porter stems, so that texts sharing
+//! meaningful words in different forms ("running" and "runs") are recognized
+//! as similar, or keep the original case for case-significant identifiers.
+
+use crate::core::stem::porter_stem;
+use crate::models::options::CompareOptions;
+
+/// Common English words removed when `remove_stopwords` is enabled.
+const STOPWORDS: &[&str] = &[
+    "a",
+    "about",
+    "above",
+    "after",
+    "again",
+    "against",
+    "all",
+    "am",
+    "an",
+    "and",
+    "any",
+    "are",
+    "as",
+    "at",
+    "be",
+    "because",
+    "been",
+    "before",
+    "being",
+    "below",
+    "between",
+    "both",
+    "but",
+    "by",
+    "can",
+    "could",
+    "did",
+    "do",
+    "does",
+    "doing",
+    "down",
+    "during",
+    "each",
+    "few",
+    "for",
+    "from",
+    "further",
+    "had",
+    "has",
+    "have",
+    "having",
+    "he",
+    "her",
+    "here",
+    "hers",
+    "herself",
+    "him",
+    "himself",
+    "his",
+    "how",
+    "i",
+    "if",
+    "in",
+    "into",
+    "is",
+    "it",
+    "its",
+    "itself",
+    "just",
+    "me",
+    "more",
+    "most",
+    "my",
+    "myself",
+    "no",
+    "nor",
+    "not",
+    "now",
+    "of",
+    "off",
+    "on",
+    "once",
+    "only",
+    "or",
+    "other",
+    "our",
+    "ours",
+    "ourselves",
+    "out",
+    "over",
+    "own",
+    "same",
+    "she",
+    "should",
+    "so",
+    "some",
+    "such",
+    "than",
+    "that",
+    "the",
+    "their",
+    "theirs",
+    "them",
+    "themselves",
+    "then",
+    "there",
+    "these",
+    "they",
+    "this",
+    "those",
+    "through",
+    "to",
+    "too",
+    "under",
+    "until",
+    "up",
+    "very",
+    "was",
+    "we",
+    "were",
+    "what",
+    "when",
+    "where",
+    "which",
+    "while",
+    "who",
+    "whom",
+    "why",
+    "will",
+    "with",
+    "would",
+    "you",
+    "your",
+    "yours",
+    "yourself",
+    "yourselves",
+];
+
+/// Settings for splitting text into words for word-based similarity.
+///
+/// The default settings split on whitespace and lowercase each word, keeping
+/// punctuation attached. When stopword removal, stemming, or a minimum token
+/// length is enabled, leading and trailing punctuation is also stripped from
+/// each word ("cats," becomes "cats") so the word can be looked up, stemmed,
+/// and measured.
+///
+/// With `min_token_len`, words with fewer characters than that (measured
+/// before stemming) are dropped, so single letters and short numbers don't
+/// inflate the overlap of word sets.
+///
+/// With `case_sensitive`, words keep their original case. Stopwords are still
+/// recognized in any case, but only all-lowercase words are stemmed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TokenizerOptions {
+    /// Drop common English stopwords ("the", "a", "are", ...).
+    pub remove_stopwords: bool,
+    /// Reduce each word to its Porter stem ("running" becomes "run").
+    pub stem: bool,
+    /// Keep the case of each word instead of lowercasing it.
+    pub case_sensitive: bool,
+    /// Drop words with fewer characters than this (0 keeps every word).
+    pub min_token_len: usize,
+}
+
+impl From<&CompareOptions> for TokenizerOptions {
+    fn from(options: &CompareOptions) -> Self {
+        Self {
+            remove_stopwords: options.remove_stopwords.unwrap_or(false),
+            stem: options.stem.unwrap_or(false),
+            case_sensitive: options.case_sensitive.unwrap_or(false),
+            min_token_len: options.min_token_len.unwrap_or(0) as usize,
+        }
+    }
+}
+
+/// Splits text into words according to `options`.
+///
+/// Words are separated by runs of whitespace (spaces, tabs, and line breaks
+/// alike), the same rule character n-grams use when collapsing whitespace, so
+/// texts that differ only in their whitespace produce the same words.
+///
+/// # Arguments
+///
+/// * `text` - The text to split
+/// * `options` - The tokenizer settings
+///
+/// # Returns
+///
+/// An iterator over the words of `text`, in order, lowercased unless
+/// `case_sensitive` is set.
+///
+/// # Example
+///
+/// ```
+/// # use dms_toolkit_rs::core::tokenize::{tokenize, TokenizerOptions};
+/// let options = TokenizerOptions { remove_stopwords: true, stem: true, ..Default::default() };
+/// let words: Vec<String> = tokenize("The cats are running.", &options).collect();
+/// assert_eq!(words, vec!["cat", "run"]);
+/// ```
+pub fn tokenize<'a>(
+    text: &'a str,
+    options: &'a TokenizerOptions,
+) -> impl Iterator<Item = String> + 'a {
+    let normalize = options.remove_stopwords || options.stem || options.min_token_len > 0;
+
+    text.split_whitespace().filter_map(move |word| {
+        let word = if options.case_sensitive {
+            word.to_string()
+        } else {
+            word.to_lowercase()
+        };
+        if !normalize {
+            return Some(word);
+        }
+
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+        let is_stopword = || {
+            STOPWORDS
+                .iter()
+                .any(|stopword| stopword.eq_ignore_ascii_case(word))
+        };
+        if word.chars().count() < options.min_token_len.max(1)
+            || (options.remove_stopwords && is_stopword())
+        {
+            return None;
+        }
+
+        Some(if options.stem {
+            porter_stem(word)
+        } else {
+            word.to_string()
+        })
+    })
+}
@@ -0,0 +1,307 @@
+//! Config file handler for extracting keys and values from YAML and TOML files.
+//!
+//! This handler parses structured configuration files with `serde_yaml` and
+//! `toml` and flattens them into `key: value` lines, so searches and similarity
+//! comparisons see keys and values rather than syntax.
+
+use crate::core::handler::FileHandler;
+use crate::handlers::text::TextHandler;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Handler for processing YAML and TOML configuration files.
+///
+/// The `ConfigHandler` parses a config file into a tree of values and emits one
+/// `key: value` line per scalar value. Nested keys are joined with dots and
+/// array elements are addressed by index, so a nested port setting becomes
+/// `server.port: 8080` and the first host in a list becomes `hosts[0]: a`.
+///
+/// # Supported MIME Types
+///
+/// - `application/yaml`, `application/x-yaml`, `text/yaml`, `text/x-yaml` - YAML
+/// - `application/toml`, `text/x-toml` - TOML
+///
+/// # Processing Flow
+///
+/// 1. Decodes the bytes to text (the same encoding detection as `TextHandler`)
+/// 2. Parses the text as YAML or TOML depending on the MIME type
+/// 3. Walks the parsed tree in document order, emitting a `key: value` line for
+///    each string, number, boolean, or date value (null values are skipped)
+///
+/// # Fallback
+///
+/// Files that fail to parse are not treated as errors; the decoded raw text is
+/// returned instead, so malformed configs remain searchable.
+///
+/// # Limitations
+///
+/// - Comments are dropped
+/// - YAML files with several documents (`---`) emit the lines of each document in order
+pub struct ConfigHandler {
+    text_handler: TextHandler,
+}
+
+/// Configuration file formats understood by `ConfigHandler`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ConfigFormat {
+    Yaml,
+    Toml,
+}
+
+impl ConfigHandler {
+    /// Creates a new `ConfigHandler` instance.
+    ///
+    /// # Returns
+    ///
+    /// A new `ConfigHandler` ready to process YAML and TOML files.
+    pub fn new() -> Self {
+        Self {
+            text_handler: TextHandler::new(),
+        }
+    }
+
+    /// Sets the byte entropy at or above which configuration content is rejected as
+    /// binary before it is decoded (see
+    /// `TextHandler::with_binary_entropy_threshold`).
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Entropy in bits per byte (0-8); above 8 disables the check
+    ///
+    /// # Returns
+    ///
+    /// The handler with the threshold applied.
+    pub fn with_binary_entropy_threshold(mut self, threshold: f64) -> Self {
+        self.text_handler = self.text_handler.with_binary_entropy_threshold(threshold);
+        self
+    }
+
+    /// Returns the config format for a MIME type, if it is supported.
+    fn format_for(&self, mime_type: &str) -> Option<ConfigFormat> {
+        match mime_type {
+            "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => {
+                Some(ConfigFormat::Yaml)
+            }
+            "application/toml" | "text/x-toml" => Some(ConfigFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// Parses decoded config text into `key: value` lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The decoded config file text
+    /// * `format` - The config format to parse
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The flattened `key: value` lines, separated by newlines
+    /// * `Err(String)` - Error message if the text is not valid in the given format
+    fn extract_text_from_config(&self, text: &str, format: ConfigFormat) -> Result<String, String> {
+        let documents = match format {
+            ConfigFormat::Yaml => serde_yaml::Deserializer::from_str(text)
+                .map(|document| {
+                    Value::deserialize(document).map_err(|e| format!("Failed to parse YAML: {}", e))
+                })
+                .collect::<Result<Vec<Value>, String>>()?,
+            ConfigFormat::Toml => {
+                let table: toml::Table =
+                    toml::from_str(text).map_err(|e| format!("Failed to parse TOML: {}", e))?;
+                vec![toml_to_json(toml::Value::Table(table))]
+            }
+        };
+
+        let mut lines = Vec::new();
+        for document in &documents {
+            flatten_value(document, "", &mut lines);
+        }
+
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Converts a TOML value into the JSON value tree used for flattening.
+///
+/// TOML dates and times have no JSON equivalent and become strings.
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::from(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Appends a `key: value` line for every scalar in `value`.
+///
+/// `path` is the dotted key path of `value`; a scalar at the root is emitted
+/// without a key.
+pub(crate) fn flatten_value(value: &Value, path: &str, lines: &mut Vec<String>) {
+    let line = |scalar: String| {
+        if path.is_empty() {
+            scalar
+        } else {
+            format!("{}: {}", path, scalar)
+        }
+    };
+
+    match value {
+        Value::Null => {}
+        Value::Bool(b) => lines.push(line(b.to_string())),
+        Value::Number(n) => lines.push(line(n.to_string())),
+        Value::String(s) => lines.push(line(s.clone())),
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                flatten_value(item, &format!("{}[{}]", path, index), lines);
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map {
+                let item_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                flatten_value(item, &item_path, lines);
+            }
+        }
+    }
+}
+
+impl FileHandler for ConfigHandler {
+    /// Determines if this handler can process YAML or TOML files.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_type` - The MIME type string to check
+    ///
+    /// # Returns
+    ///
+    /// `true` if the MIME type represents a YAML or TOML file, `false` otherwise.
+    fn can_handle(&self, mime_type: &str) -> bool {
+        self.format_for(mime_type).is_some()
+    }
+
+    /// Lists the MIME types this handler accepts.
+    fn supported_mimes(&self) -> Vec<String> {
+        [
+            "application/yaml",
+            "application/x-yaml",
+            "text/yaml",
+            "text/x-yaml",
+            "application/toml",
+            "text/x-toml",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect()
+    }
+
+    /// Returns the handler's name, `ConfigHandler`.
+    fn name(&self) -> &str {
+        "ConfigHandler"
+    }
+
+    /// Extracts `key: value` lines from a YAML or TOML file.
+    ///
+    /// Decodes the content with `TextHandler`, then flattens the parsed config.
+    /// If parsing fails, the decoded raw text is returned instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw config file content as a byte slice
+    /// * `filename` - The filename, passed through to `TextHandler`
+    /// * `mime_type` - The MIME type, used to choose between YAML and TOML
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The flattened config, or the raw text for invalid configs
+    /// * `Err(String)` - Error message if the content cannot be decoded as text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::config::ConfigHandler;
+    /// # use crate::core::handler::FileHandler;
+    /// let handler = ConfigHandler::new();
+    /// let text = handler.extract_text(b"server:\n  port: 8080\n", "app.yaml", "application/yaml");
+    /// assert_eq!(text.unwrap(), "server.port: 8080");
+    /// ```
+    fn extract_text(
+        &self,
+        content: &[u8],
+        filename: &str,
+        mime_type: &str,
+    ) -> Result<String, String> {
+        let text = self
+            .text_handler
+            .extract_text(content, filename, mime_type)?;
+
+        match self.format_for(mime_type) {
+            Some(format) => Ok(self.extract_text_from_config(&text, format).unwrap_or(text)),
+            None => Ok(text),
+        }
+    }
+
+    /// Returns the estimated peak memory use per input byte.
+    ///
+    /// Config files are decoded and parsed into a value tree, roughly four times
+    /// the file size.
+    fn memory_multiplier(&self) -> f64 {
+        4.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_yaml() {
+        let yaml = "server:\n  host: example.com\n  port: 8080\nfeatures:\n  - search\n  - ocr\ndebug: false\nempty: ~\n";
+
+        let text = ConfigHandler::new()
+            .extract_text(yaml.as_bytes(), "app.yaml", "application/yaml")
+            .unwrap();
+
+        assert_eq!(
+            text,
+            "server.host: example.com\nserver.port: 8080\nfeatures[0]: search\nfeatures[1]: ocr\ndebug: false"
+        );
+    }
+
+    #[test]
+    fn test_extract_toml() {
+        let toml =
+            "title = \"Config\"\n\n[database]\nurl = \"postgres://db\"\nmax_connections = 10\n";
+
+        let text = ConfigHandler::new()
+            .extract_text(toml.as_bytes(), "app.toml", "application/toml")
+            .unwrap();
+
+        assert_eq!(
+            text,
+            "title: Config\ndatabase.url: postgres://db\ndatabase.max_connections: 10"
+        );
+    }
+
+    #[test]
+    fn test_invalid_config_falls_back_to_raw_text() {
+        let invalid = "key = \"unterminated";
+
+        let text = ConfigHandler::new()
+            .extract_text(invalid.as_bytes(), "broken.toml", "application/toml")
+            .unwrap();
+
+        assert_eq!(text, invalid);
+    }
+}
@@ -0,0 +1,259 @@
+//! CSV file handler for extracting cell text from delimited data files.
+//!
+//! This handler parses comma-, semicolon-, and tab-separated files with the
+//! `csv` library and re-emits each record as one tab-separated line, the same
+//! layout `XlsxHandler` produces for spreadsheet rows.
+
+use crate::core::handler::FileHandler;
+use crate::handlers::text::TextHandler;
+
+/// Delimiters recognized when detecting the format of a file.
+const DELIMITERS: &[u8] = b",;\t";
+
+/// Handler for processing CSV and TSV files.
+///
+/// The `CsvHandler` parses delimited text into records and fields, so quoting
+/// is resolved before comparison: a quoted field containing delimiters or line
+/// breaks stays a single cell, and doubled quotes (`""`) become one quote.
+///
+/// # Supported MIME Types
+///
+/// - `text/csv` - Comma-separated values files
+/// - `text/tsv` - Tab-separated values files
+/// - `text/tab-separated-values` - Alternative TSV MIME type
+///
+/// # Processing Flow
+///
+/// 1. Decodes the bytes to text (the same encoding detection as `TextHandler`)
+/// 2. Uses a tab as the delimiter for TSV MIME types; for CSV, detects the
+///    delimiter (`,`, `;`, or tab) from the first line
+/// 3. Parses the records, collapsing whitespace (including line breaks) within
+///    each field
+/// 4. Emits one line per record with the fields separated by tabs
+///
+/// # Fallback
+///
+/// Files that fail to parse are not treated as errors; the decoded raw text is
+/// returned instead, so malformed files remain searchable.
+pub struct CsvHandler {
+    text_handler: TextHandler,
+}
+
+impl CsvHandler {
+    /// Creates a new `CsvHandler` instance.
+    ///
+    /// # Returns
+    ///
+    /// A new `CsvHandler` ready to process CSV and TSV files.
+    pub fn new() -> Self {
+        Self {
+            text_handler: TextHandler::new(),
+        }
+    }
+
+    /// Sets the byte entropy at or above which CSV content is rejected as
+    /// binary before it is decoded (see
+    /// `TextHandler::with_binary_entropy_threshold`).
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Entropy in bits per byte (0-8); above 8 disables the check
+    ///
+    /// # Returns
+    ///
+    /// The handler with the threshold applied.
+    pub fn with_binary_entropy_threshold(mut self, threshold: f64) -> Self {
+        self.text_handler = self.text_handler.with_binary_entropy_threshold(threshold);
+        self
+    }
+
+    /// Parses decoded CSV text into tab-separated lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The decoded file text
+    /// * `delimiter` - The field delimiter
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - One line per record, with fields separated by tabs
+    /// * `Err(String)` - Error message if the text is not valid delimited data
+    fn extract_text_from_csv(&self, text: &str, delimiter: u8) -> Result<String, String> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(text.as_bytes());
+
+        let mut lines = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(|e| format!("Failed to parse CSV: {}", e))?;
+            let fields: Vec<String> = record
+                .iter()
+                .map(|field| field.split_whitespace().collect::<Vec<_>>().join(" "))
+                .collect();
+            lines.push(fields.join("\t"));
+        }
+
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Returns `true` for the MIME types of tab-separated files.
+fn is_tsv(mime_type: &str) -> bool {
+    mime_type == "text/tsv" || mime_type == "text/tab-separated-values"
+}
+
+/// Picks the delimiter that occurs most often in the first line of `text`.
+///
+/// Delimiters inside quoted fields are not counted. Ties and lines without
+/// any delimiter resolve to a comma.
+fn detect_delimiter(text: &str) -> u8 {
+    let mut counts = [0usize; DELIMITERS.len()];
+    let mut in_quotes = false;
+
+    for byte in text.bytes() {
+        match byte {
+            b'"' => in_quotes = !in_quotes,
+            b'\n' | b'\r' if !in_quotes => break,
+            _ if !in_quotes => {
+                if let Some(index) = DELIMITERS.iter().position(|&delimiter| delimiter == byte) {
+                    counts[index] += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // `max_by_key` keeps the last maximum, so scan in reverse to prefer earlier delimiters
+    DELIMITERS
+        .iter()
+        .zip(counts)
+        .rev()
+        .max_by_key(|&(_, count)| count)
+        .map_or(b',', |(&delimiter, _)| delimiter)
+}
+
+impl FileHandler for CsvHandler {
+    /// Determines if this handler can process CSV and TSV files.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_type` - The MIME type string to check
+    ///
+    /// # Returns
+    ///
+    /// `true` if the MIME type represents a CSV or TSV file, `false` otherwise.
+    fn can_handle(&self, mime_type: &str) -> bool {
+        mime_type == "text/csv" || is_tsv(mime_type)
+    }
+
+    /// Lists the MIME types this handler accepts.
+    fn supported_mimes(&self) -> Vec<String> {
+        ["text/csv", "text/tsv", "text/tab-separated-values"]
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    /// Returns the handler's name, `CsvHandler`.
+    fn name(&self) -> &str {
+        "CsvHandler"
+    }
+
+    /// Extracts the cell text of a CSV or TSV file.
+    ///
+    /// Decodes the content with `TextHandler`, then parses the records. TSV
+    /// files are always split on tabs, so commas and semicolons inside their
+    /// fields never start a new field; CSV files use the detected delimiter.
+    /// If parsing fails, the decoded raw text is returned instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw file content as a byte slice
+    /// * `filename` - The filename, passed through to `TextHandler`
+    /// * `mime_type` - The MIME type, which selects the delimiter and is passed
+    ///   through to `TextHandler`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - One tab-separated line per record, or the raw text for invalid files
+    /// * `Err(String)` - Error message if the content cannot be decoded as text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::csv::CsvHandler;
+    /// # use crate::core::handler::FileHandler;
+    /// let handler = CsvHandler::new();
+    /// let text = handler.extract_text(b"name;city\nAda;London\n", "people.csv", "text/csv");
+    /// assert_eq!(text.unwrap(), "name\tcity\nAda\tLondon");
+    /// ```
+    fn extract_text(
+        &self,
+        content: &[u8],
+        filename: &str,
+        mime_type: &str,
+    ) -> Result<String, String> {
+        let text = self
+            .text_handler
+            .extract_text(content, filename, mime_type)?;
+
+        let delimiter = if is_tsv(mime_type) {
+            b'\t'
+        } else {
+            detect_delimiter(&text)
+        };
+
+        Ok(self.extract_text_from_csv(&text, delimiter).unwrap_or(text))
+    }
+
+    /// Returns the estimated peak memory use per input byte.
+    ///
+    /// CSV files are decoded and re-emitted, roughly three times the file size.
+    fn memory_multiplier(&self) -> f64 {
+        3.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quoted_field_is_not_split() {
+        let csv = "name,address,notes\n\"Lovelace, Ada\",\"12 St James's Square\nLondon\",\"said \"\"hello\"\"\"\n";
+
+        let text = CsvHandler::new()
+            .extract_text(csv.as_bytes(), "people.csv", "text/csv")
+            .unwrap();
+
+        assert_eq!(
+            text,
+            "name\taddress\tnotes\nLovelace, Ada\t12 St James's Square London\tsaid \"hello\""
+        );
+    }
+
+    #[test]
+    fn test_tsv_splits_on_tabs_only() {
+        let tsv =
+            "name\tcities, visited\n\"Lovelace, Ada\"\t\"London, Paris\tRome\"\nBabbage\tLondon\n";
+
+        let text = CsvHandler::new()
+            .extract_text(tsv.as_bytes(), "trips.tsv", "text/tab-separated-values")
+            .unwrap();
+
+        assert_eq!(
+            text,
+            "name\tcities, visited\nLovelace, Ada\tLondon, Paris Rome\nBabbage\tLondon"
+        );
+    }
+
+    #[test]
+    fn test_detect_delimiter() {
+        assert_eq!(detect_delimiter("a;b;c\n1,5;2;3"), b';');
+        assert_eq!(detect_delimiter("a\tb\tc"), b'\t');
+        assert_eq!(detect_delimiter("\"x;y\",b\n"), b',');
+        assert_eq!(detect_delimiter("single column"), b',');
+    }
+}
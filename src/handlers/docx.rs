@@ -2,9 +2,29 @@
 //!
 //! This handler uses the `docx-rs` library to parse DOCX files (which are
 //! ZIP archives containing XML files) and extract text content from them.
+//! Document properties, which `docx-rs` does not fully expose, are read from
+//! the package parts directly with `zip` and `quick-xml`.
 
 use crate::core::handler::FileHandler;
+use crate::models::docx::DocxProperties;
 use docx_rs::*;
+use quick_xml::Reader;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::Event;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+/// Targets of a document's external hyperlinks, keyed by relationship id.
+type LinkTargets<'a> = HashMap<&'a str, &'a str>;
+
+/// What is added to the text of the runs inside paragraphs.
+struct InlineTargets<'a> {
+    /// Hyperlink targets appended after the link text.
+    links: LinkTargets<'a>,
+    /// Alt text of the images, keyed by the relationship id of the image.
+    image_alts: HashMap<String, String>,
+}
 
 /// Handler for processing Microsoft Word documents (DOCX format).
 ///
@@ -20,17 +40,47 @@ use docx_rs::*;
 /// # Processing Flow
 ///
 /// 1. Parses the DOCX file structure using `docx-rs` library
-/// 2. Iterates through document children (paragraphs)
-/// 3. Extracts text from paragraph runs (text segments with formatting)
-/// 4. Combines all text with newlines between paragraphs
-/// 5. Trims leading/trailing whitespace
+/// 2. Iterates through document children (paragraphs and tables) in order
+/// 3. Extracts text from paragraph runs (text segments with formatting),
+///    including the runs inside hyperlinks
+/// 4. Extracts tables row by row, joining cells with tabs and rows with newlines
+/// 5. Combines all text with newlines between paragraphs and tables
+/// 6. Trims leading/trailing whitespace
+///
+/// # Merged Table Cells
+///
+/// A cell spanning several grid columns (`gridSpan`) is followed by empty
+/// fields for the extra columns, and cells continuing a vertical merge
+/// (`vMerge`) are empty, so every row has one field per grid column. With
+/// `with_expand_merged_cells(true)`, the merged value is repeated in those
+/// fields instead.
+///
+/// # Hyperlinks
+///
+/// The display text of a hyperlink is extracted in place, between the runs
+/// around it. With `with_link_targets(true)`, the URL of each external link is
+/// appended after its text in brackets (`see section 5 [https://...]`);
+/// links to bookmarks within the document have no URL and are left as is.
+///
+/// # Image Alt Text
+///
+/// With `with_image_alt(true)`, each picture carrying an alt text (the
+/// `descr` attribute of its `wp:docPr`, or its `title` if it has no
+/// description) is extracted as `[image: alt text]` where it appears in the
+/// paragraph. `docx-rs` does not read these attributes, so they are read from
+/// `word/document.xml` directly and matched to pictures by image relationship,
+/// so an image placed several times shows the alt text of its first use.
 ///
 /// # Limitations
 ///
-/// - Extracts plain text only (no formatting, images, tables, or complex elements)
-/// - Does not preserve document structure or layout
-/// - Only processes text from paragraphs (headers, footers, footnotes may be included)
-pub struct DocxHandler;
+/// - Extracts plain text only (no formatting, images, or complex elements)
+/// - Does not preserve document layout beyond table rows and columns
+/// - Only processes text from the document body (headers, footers, footnotes may be included)
+pub struct DocxHandler {
+    expand_merged_cells: bool,
+    link_targets: bool,
+    image_alt: bool,
+}
 
 impl DocxHandler {
     /// Creates a new `DocxHandler` instance.
@@ -39,7 +89,59 @@ impl DocxHandler {
     ///
     /// A new `DocxHandler` ready to process DOCX files.
     pub fn new() -> Self {
-        Self
+        Self {
+            expand_merged_cells: false,
+            link_targets: false,
+            image_alt: false,
+        }
+    }
+
+    /// Enables or disables repeating merged table cell values across their span.
+    ///
+    /// When enabled, a cell spanning several columns repeats its text in each
+    /// spanned column, and cells continuing a vertical merge repeat the text of
+    /// the cell above.
+    ///
+    /// # Arguments
+    ///
+    /// * `expand_merged_cells` - Whether to repeat merged values across the merged region
+    ///
+    /// # Returns
+    ///
+    /// The handler with the setting applied.
+    pub fn with_expand_merged_cells(mut self, expand_merged_cells: bool) -> Self {
+        self.expand_merged_cells = expand_merged_cells;
+        self
+    }
+
+    /// Enables or disables appending hyperlink URLs after their display text.
+    ///
+    /// # Arguments
+    ///
+    /// * `link_targets` - Whether to append the URL of each external hyperlink
+    ///   in brackets after its text
+    ///
+    /// # Returns
+    ///
+    /// The handler with the setting applied.
+    pub fn with_link_targets(mut self, link_targets: bool) -> Self {
+        self.link_targets = link_targets;
+        self
+    }
+
+    /// Enables or disables extracting the alt text of pictures.
+    ///
+    /// # Arguments
+    ///
+    /// * `image_alt` - Whether to emit `[image: alt text]` for each picture
+    ///   with an alt text, at its position in the paragraph
+    ///
+    /// # Returns
+    ///
+    /// The handler with the setting applied.
+    pub fn with_image_alt(mut self, image_alt: bool) -> Self {
+        self.image_alt = image_alt;
+        self
     }
 
     /// Extracts text content from a DOCX document.
@@ -67,33 +169,329 @@ impl DocxHandler {
     /// # Text Extraction Details
     ///
     /// The method:
-    /// - Iterates through all document children (typically paragraphs)
-    /// - For each paragraph, extracts text from runs (formatted text segments)
-    /// - Combines text from all runs in a paragraph
-    /// - Adds a newline after each paragraph
+    /// - Iterates through all document children in document order
+    /// - For each paragraph, extracts text from runs (formatted text segments),
+    ///   descending into hyperlinks
+    /// - For each table, extracts one line per row with tab-separated cells
+    /// - Adds a newline after each paragraph and table
     /// - Trims the final result to remove leading/trailing whitespace
     fn extract_text_from_docx(&self, content: &[u8]) -> Result<String, String> {
-        let docx = read_docx(&content).map_err(|e| format!("Failed to read DOCX: {}", e))?;
+        let docx = read_docx(content).map_err(|e| format!("Failed to read DOCX: {}", e))?;
+
+        let links: LinkTargets = if self.link_targets {
+            docx.hyperlinks
+                .iter()
+                .map(|(id, target, _)| (id.as_str(), target.as_str()))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        let image_alts = if self.image_alt {
+            ZipArchive::new(Cursor::new(content))
+                .ok()
+                .and_then(|mut archive| read_part(&mut archive, "word/document.xml"))
+                .map(|xml| image_alt_texts(&xml))
+                .unwrap_or_default()
+        } else {
+            HashMap::new()
+        };
+        let inline = InlineTargets { links, image_alts };
 
         let mut text = String::new();
 
-        for child in docx.document.children {
-            if let DocumentChild::Paragraph(para) = child {
-                for run in para.children {
-                    if let ParagraphChild::Run(run_child) = run {
-                        for run_content in run_child.children {
-                            if let RunChild::Text(text_node) = run_content {
-                                text.push_str(&text_node.text);
+        for child in &docx.document.children {
+            match child {
+                DocumentChild::Paragraph(para) => {
+                    text.push_str(&paragraph_text(para, &inline));
+                    text.push('\n');
+                }
+                DocumentChild::Table(table) => {
+                    text.push_str(&self.table_text(table, &inline));
+                    text.push('\n');
+                }
+                _ => {}
+            }
+        }
+
+        Ok(text.trim().to_string())
+    }
+
+    /// Reads the properties of a DOCX document.
+    ///
+    /// The title, author, and dates are read from `docProps/core.xml`; the
+    /// word count from `docProps/app.xml`. Elements are matched by local name,
+    /// so documents using other namespace prefixes are read the same way.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw DOCX file content as a byte slice
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(DocxProperties)` - The document's properties, with `None` for
+    ///   missing or blank properties and for those of a missing part
+    /// * `Err(String)` - Error message if the content is not a ZIP package
+    ///   (e.g., "Failed to read DOCX: ...")
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::docx::DocxHandler;
+    /// let handler = DocxHandler::new();
+    /// let properties = handler.extract_properties(&docx_bytes)?;
+    /// println!("{:?} by {:?}", properties.title, properties.author);
+    /// ```
+    pub fn extract_properties(&self, content: &[u8]) -> Result<DocxProperties, String> {
+        let mut archive = ZipArchive::new(Cursor::new(content))
+            .map_err(|e| format!("Failed to read DOCX: {}", e))?;
+
+        let core = read_part(&mut archive, "docProps/core.xml");
+        let app = read_part(&mut archive, "docProps/app.xml");
+        let core_entry = |name: &[u8]| core.as_deref().and_then(|xml| element_text(xml, name));
+
+        Ok(DocxProperties {
+            title: core_entry(b"title"),
+            author: core_entry(b"creator"),
+            created: core_entry(b"created"),
+            modified: core_entry(b"modified"),
+            word_count: app
+                .as_deref()
+                .and_then(|xml| element_text(xml, b"Words"))
+                .and_then(|words| words.parse().ok()),
+        })
+    }
+
+    /// Extracts a table as tab-separated cells, one line per row.
+    ///
+    /// Merged cells are laid out on the table grid as described on
+    /// `DocxHandler`, so each row has one field per grid column.
+    fn table_text(&self, table: &Table, inline: &InlineTargets) -> String {
+        let mut rows: Vec<Vec<String>> = Vec::new();
+
+        for TableChild::TableRow(row) in &table.rows {
+            let mut fields: Vec<String> = Vec::new();
+
+            for TableRowChild::TableCell(cell) in &row.cells {
+                let (span, continues_merge) = cell_merge(cell);
+                let column = fields.len();
+
+                let value = match rows.last() {
+                    Some(above) if continues_merge && self.expand_merged_cells => {
+                        above.get(column).cloned().unwrap_or_default()
+                    }
+                    _ => cell_text(cell, inline),
+                };
+                let filler = if self.expand_merged_cells {
+                    value.clone()
+                } else {
+                    String::new()
+                };
+
+                fields.push(value);
+                fields.extend(std::iter::repeat_n(filler, span - 1));
+            }
+
+            rows.push(fields);
+        }
+
+        rows.iter()
+            .map(|fields| fields.join("\t"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Concatenates the text of all runs in a paragraph.
+///
+/// Hyperlink targets and image alt texts found in `inline` are added in
+/// place.
+fn paragraph_text(para: &Paragraph, inline: &InlineTargets) -> String {
+    let mut text = String::new();
+    push_runs_text(&para.children, inline, &mut text);
+    text
+}
+
+/// Appends the text of the runs in `children` to `text`, in document order.
+///
+/// Hyperlinks contain runs of their own; their text is appended where the
+/// link appears, followed by ` [target]` if the link's target is in `inline`.
+/// Pictures whose image has an alt text in `inline` are appended as
+/// `[image: alt text]`, separated from preceding text by a space.
+fn push_runs_text(children: &[ParagraphChild], inline: &InlineTargets, text: &mut String) {
+    for child in children {
+        match child {
+            ParagraphChild::Run(run) => {
+                for run_content in &run.children {
+                    match run_content {
+                        RunChild::Text(text_node) => text.push_str(&text_node.text),
+                        RunChild::Drawing(drawing) => {
+                            if let Some(DrawingData::Pic(pic)) = &drawing.data
+                                && let Some(alt) = inline.image_alts.get(&pic.id)
+                            {
+                                if text.chars().last().is_some_and(|c| !c.is_whitespace()) {
+                                    text.push(' ');
+                                }
+                                text.push_str(&format!("[image: {}]", alt));
                             }
                         }
+                        _ => {}
                     }
                 }
-                text.push('\n');
             }
+            ParagraphChild::Hyperlink(hyperlink) => {
+                push_runs_text(&hyperlink.children, inline, text);
+
+                if let HyperlinkData::External { rid, .. } = &hyperlink.link
+                    && let Some(target) = inline.links.get(rid.as_str())
+                {
+                    text.push_str(&format!(" [{}]", target));
+                }
+            }
+            _ => {}
         }
+    }
+}
 
-        Ok(text.trim().to_string())
+/// Extracts the text of a table cell on a single line.
+///
+/// Paragraphs (and the cells of nested tables) are joined with spaces so the
+/// cell stays within its tab-separated field.
+fn cell_text(cell: &TableCell, inline: &InlineTargets) -> String {
+    let mut parts: Vec<String> = Vec::new();
+
+    for content in &cell.children {
+        match content {
+            TableCellContent::Paragraph(para) => parts.push(paragraph_text(para, inline)),
+            TableCellContent::Table(table) => {
+                for TableChild::TableRow(row) in &table.rows {
+                    for TableRowChild::TableCell(nested) in &row.cells {
+                        parts.push(cell_text(nested, inline));
+                    }
+                }
+            }
+            _ => {}
+        }
     }
+
+    parts
+        .iter()
+        .map(|part| part.trim())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns a cell's column span and whether it continues a vertical merge.
+///
+/// `docx-rs` keeps these properties private; they are read back through the
+/// property's serialized form (`gridSpan`, `verticalMerge`).
+fn cell_merge(cell: &TableCell) -> (usize, bool) {
+    let Ok(property) = serde_json::to_value(&cell.property) else {
+        return (1, false);
+    };
+
+    let span = property["gridSpan"]
+        .as_u64()
+        .map_or(1, |span| span.max(1) as usize);
+    let continues_merge = property["verticalMerge"].as_str() == Some("continue");
+
+    (span, continues_merge)
+}
+
+/// Reads a package part as a string, or `None` if it is missing or unreadable.
+fn read_part(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Option<String> {
+    let mut part = archive.by_name(name).ok()?;
+
+    let mut xml = String::new();
+    part.read_to_string(&mut xml).ok()?;
+
+    Some(xml)
+}
+
+/// Collects the alt text of the pictures in `document.xml`, keyed by the
+/// relationship id of their image (`r:embed` of `a:blip`).
+///
+/// The alt text is the `descr` attribute of the drawing's `wp:docPr`, or its
+/// `title` without a description, with whitespace collapsed so it stays on
+/// one line. Pictures without one are left out; an image used several times
+/// keeps the alt text of its first use.
+fn image_alt_texts(xml: &str) -> HashMap<String, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut alts = HashMap::new();
+    let mut pending: Option<String> = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e) | Event::Empty(e)) => match e.local_name().as_ref() {
+                b"docPr" => {
+                    let attribute = |name: &[u8]| {
+                        e.attributes()
+                            .flatten()
+                            .find(|attribute| attribute.key.local_name().as_ref() == name)
+                            .and_then(|attribute| {
+                                attribute.decode_and_unescape_value(reader.decoder()).ok()
+                            })
+                            .map(|value| value.split_whitespace().collect::<Vec<_>>().join(" "))
+                            .filter(|value| !value.is_empty())
+                    };
+                    pending = attribute(b"descr").or_else(|| attribute(b"title"));
+                }
+                b"blip" => {
+                    let rid = e
+                        .attributes()
+                        .flatten()
+                        .find(|attribute| attribute.key.local_name().as_ref() == b"embed")
+                        .and_then(|attribute| {
+                            attribute.decode_and_unescape_value(reader.decoder()).ok()
+                        });
+                    if let (Some(rid), Some(alt)) = (rid, pending.take()) {
+                        alts.entry(rid.into_owned()).or_insert(alt);
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::End(e)) if e.local_name().as_ref() == b"drawing" => pending = None,
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    alts
+}
+
+/// Returns the trimmed text of the first element of `xml` whose local name
+/// (without namespace prefix) is `local_name`.
+///
+/// Returns `None` if there is no such element, its text is blank, or the XML
+/// is malformed before it.
+fn element_text(xml: &str, local_name: &[u8]) -> Option<String> {
+    let mut reader = Reader::from_str(xml);
+    let mut text = String::new();
+    let mut inside = false;
+
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(e) if e.local_name().as_ref() == local_name => inside = true,
+            Event::End(e) if inside && e.local_name().as_ref() == local_name => break,
+            Event::Text(e) if inside => text.push_str(&e.decode().ok()?),
+            Event::GeneralRef(e) if inside => {
+                if let Ok(Some(ch)) = e.resolve_char_ref() {
+                    text.push(ch);
+                } else if let Some(entity) = e
+                    .decode()
+                    .ok()
+                    .and_then(|name| resolve_predefined_entity(&name))
+                {
+                    text.push_str(entity);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
 }
 
 impl FileHandler for DocxHandler {
@@ -115,6 +513,22 @@ impl FileHandler for DocxHandler {
             || mime_type == "application/docx"
     }
 
+    /// Lists the MIME types this handler accepts.
+    fn supported_mimes(&self) -> Vec<String> {
+        [
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            "application/docx",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect()
+    }
+
+    /// Returns the handler's name, `DocxHandler`.
+    fn name(&self) -> &str {
+        "DocxHandler"
+    }
+
     /// Extracts text content from a DOCX document.
     ///
     /// This is the main entry point for DOCX text extraction. It delegates
@@ -148,4 +562,246 @@ impl FileHandler for DocxHandler {
     ) -> Result<String, String> {
         self.extract_text_from_docx(content)
     }
+
+    /// Returns the estimated peak memory use per input byte.
+    ///
+    /// DOCX files are compressed ZIP packages; the decompressed XML and the
+    /// parsed document tree take roughly eight times the file size.
+    fn memory_multiplier(&self) -> f64 {
+        8.0
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    /// Builds a DOCX with one paragraph per entry and the given files stored
+    /// under `word/embeddings/`.
+    pub(crate) fn build_docx(paragraphs: &[&str], embeddings: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let docx = paragraphs.iter().fold(Docx::new(), |docx, text| {
+            docx.add_paragraph(Paragraph::new().add_run(Run::new().add_text(*text)))
+        });
+        pack_docx(docx, embeddings)
+    }
+
+    /// Packs a `docx-rs` document, storing the given files under `word/embeddings/`.
+    pub(crate) fn pack_docx(docx: Docx, embeddings: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut cursor = Cursor::new(Vec::new());
+        docx.build().pack(&mut cursor).unwrap();
+
+        let mut writer = ZipWriter::new_append(cursor).unwrap();
+        for (name, content) in embeddings {
+            writer
+                .start_file(
+                    format!("word/embeddings/{}", name),
+                    SimpleFileOptions::default(),
+                )
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    /// Builds a ZIP package holding the given parts.
+    fn pack_parts(parts: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in parts {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    /// Replaces the first occurrence of `from` with `to` in a part of a package.
+    fn patch_part(package: &[u8], name: &str, from: &str, to: &str) -> Vec<u8> {
+        let mut archive = ZipArchive::new(Cursor::new(package)).unwrap();
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for i in 0..archive.len() {
+            let mut part = archive.by_index(i).unwrap();
+            let mut content = Vec::new();
+            part.read_to_end(&mut content).unwrap();
+            if part.name() == name {
+                content = String::from_utf8(content)
+                    .unwrap()
+                    .replacen(from, to, 1)
+                    .into_bytes();
+            }
+            writer
+                .start_file(part.name(), SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(&content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn text_cell(text: &str) -> TableCell {
+        TableCell::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text(text)))
+    }
+
+    #[test]
+    fn test_extract_table() {
+        let table = Table::new(vec![
+            TableRow::new(vec![text_cell("Term"), text_cell("Value")]),
+            TableRow::new(vec![text_cell("Notice period"), text_cell("30 days")]),
+        ]);
+        let docx = Docx::new()
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("Before")))
+            .add_table(table)
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("After")));
+        let docx = pack_docx(docx, &[]);
+
+        let text = DocxHandler::new().extract_text_from_docx(&docx).unwrap();
+
+        assert_eq!(text, "Before\nTerm\tValue\nNotice period\t30 days\nAfter");
+    }
+
+    #[test]
+    fn test_expand_merged_table_cells() {
+        let table = Table::new(vec![
+            TableRow::new(vec![text_cell("Header").grid_span(2), text_cell("Region")]),
+            TableRow::new(vec![
+                text_cell("A"),
+                text_cell("B"),
+                text_cell("North").vertical_merge(VMergeType::Restart),
+            ]),
+            TableRow::new(vec![
+                text_cell("C"),
+                text_cell("D"),
+                TableCell::new().vertical_merge(VMergeType::Continue),
+            ]),
+        ]);
+        let docx = Docx::new()
+            .add_table(table)
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("End")));
+        let docx = pack_docx(docx, &[]);
+
+        let text = DocxHandler::new().extract_text_from_docx(&docx).unwrap();
+        assert_eq!(text, "Header\t\tRegion\nA\tB\tNorth\nC\tD\t\nEnd");
+
+        let text = DocxHandler::new()
+            .with_expand_merged_cells(true)
+            .extract_text_from_docx(&docx)
+            .unwrap();
+        assert_eq!(
+            text,
+            "Header\tHeader\tRegion\nA\tB\tNorth\nC\tD\tNorth\nEnd"
+        );
+    }
+
+    #[test]
+    fn test_extract_hyperlink_text() {
+        let paragraph = Paragraph::new()
+            .add_run(Run::new().add_text("Termination rules apply, "))
+            .add_hyperlink(
+                Hyperlink::new("https://example.com/terms#s5", HyperlinkType::External)
+                    .add_run(Run::new().add_text("see section 5")),
+            )
+            .add_run(Run::new().add_text(" and "))
+            .add_hyperlink(
+                Hyperlink::new("annex", HyperlinkType::Anchor)
+                    .add_run(Run::new().add_text("the annex")),
+            )
+            .add_run(Run::new().add_text("."));
+        let docx = pack_docx(Docx::new().add_paragraph(paragraph), &[]);
+
+        let text = DocxHandler::new().extract_text_from_docx(&docx).unwrap();
+        assert_eq!(
+            text,
+            "Termination rules apply, see section 5 and the annex."
+        );
+
+        let text = DocxHandler::new()
+            .with_link_targets(true)
+            .extract_text_from_docx(&docx)
+            .unwrap();
+        assert_eq!(
+            text,
+            "Termination rules apply, see section 5 [https://example.com/terms#s5] and the annex."
+        );
+    }
+
+    #[test]
+    fn test_extract_image_alt_text() {
+        // Distinct image bytes, since identical images share a relationship
+        let picture = |byte| Run::new().add_image(Pic::new_with_dimensions(vec![byte; 16], 4, 4));
+        let docx = Docx::new()
+            .add_paragraph(
+                Paragraph::new()
+                    .add_run(Run::new().add_text("Revenue by region:"))
+                    .add_run(picture(1)),
+            )
+            .add_paragraph(Paragraph::new().add_run(picture(2)))
+            .add_paragraph(Paragraph::new().add_run(Run::new().add_text("End")));
+        let docx = patch_part(
+            &pack_docx(docx, &[]),
+            "word/document.xml",
+            r#"name="Figure""#,
+            r#"name="Figure" descr="Bar chart of revenue,&#10;North ahead""#,
+        );
+
+        let text = DocxHandler::new().extract_text_from_docx(&docx).unwrap();
+        assert_eq!(text, "Revenue by region:\n\nEnd");
+
+        // Only the picture with an alt text is emitted, on one line
+        let text = DocxHandler::new()
+            .with_image_alt(true)
+            .extract_text_from_docx(&docx)
+            .unwrap();
+        assert_eq!(
+            text,
+            "Revenue by region: [image: Bar chart of revenue, North ahead]\n\nEnd"
+        );
+    }
+
+    #[test]
+    fn test_extract_text_from_docx() {
+        let docx = build_docx(&["First paragraph", "Second paragraph"], &[]);
+
+        let text = DocxHandler::new().extract_text_from_docx(&docx).unwrap();
+
+        assert_eq!(text, "First paragraph\nSecond paragraph");
+    }
+
+    #[test]
+    fn test_extract_properties() {
+        let core = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <dc:title>Service Agreement</dc:title>
+  <dc:creator>Legal &amp; Compliance</dc:creator>
+  <cp:lastModifiedBy>Zoë Fischer</cp:lastModifiedBy>
+  <dcterms:created xsi:type="dcterms:W3CDTF">2024-01-15T10:30:00Z</dcterms:created>
+  <dcterms:modified xsi:type="dcterms:W3CDTF">2024-02-01T08:00:00Z</dcterms:modified>
+</cp:coreProperties>"#;
+        let app = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/extended-properties">
+  <Pages>3</Pages>
+  <Words>1834</Words>
+</Properties>"#;
+        let docx = pack_parts(&[("docProps/core.xml", core), ("docProps/app.xml", app)]);
+
+        let properties = DocxHandler::new().extract_properties(&docx).unwrap();
+
+        assert_eq!(properties.title.as_deref(), Some("Service Agreement"));
+        assert_eq!(properties.author.as_deref(), Some("Legal & Compliance"));
+        assert_eq!(properties.created.as_deref(), Some("2024-01-15T10:30:00Z"));
+        assert_eq!(properties.modified.as_deref(), Some("2024-02-01T08:00:00Z"));
+        assert_eq!(properties.word_count, Some(1834));
+
+        // Missing elements and a missing part are None
+        let core = r#"<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title> </dc:title><dc:creator>Ada</dc:creator></cp:coreProperties>"#;
+        let docx = pack_parts(&[("docProps/core.xml", core)]);
+
+        let properties = DocxHandler::new().extract_properties(&docx).unwrap();
+
+        assert_eq!(properties.title, None);
+        assert_eq!(properties.author.as_deref(), Some("Ada"));
+        assert_eq!(properties.created, None);
+        assert_eq!(properties.word_count, None);
+    }
 }
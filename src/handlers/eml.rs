@@ -0,0 +1,263 @@
+//! Email file handler for extracting headers and body text from `.eml` files.
+//!
+//! An `.eml` file is a single RFC 5322 message, usually MIME multipart with
+//! quoted-printable or base64 encoded parts. This handler parses it with
+//! `mail-parser`, which decodes the transfer encodings and character sets,
+//! and keeps the headers and body text useful for matching.
+
+use crate::core::handler::FileHandler;
+use crate::handlers::html::HtmlHandler;
+use mail_parser::{Address, Message, MessageParser, PartType};
+
+/// Handler for processing email messages.
+///
+/// The `EmlHandler` extracts the `Subject`, `From`, and `Date` headers
+/// followed by the message body:
+///
+/// ```text
+/// Subject: Quarterly report
+/// From: Alice Smith <alice@example.com>
+/// Date: 2024-01-15T10:30:00+01:00
+///
+/// Hi team, the report is attached.
+/// ```
+///
+/// Missing headers are left out, and the date is converted to ISO 8601.
+///
+/// # Supported MIME Types
+///
+/// - `message/rfc822` - Email messages (`.eml` files)
+///
+/// # Processing Flow
+///
+/// 1. Parses the message with `mail-parser`, decoding quoted-printable and
+///    base64 parts and their character sets
+/// 2. Formats the `Subject`, `From`, and `Date` headers
+/// 3. Takes the inline body parts, preferring `text/plain`; an HTML-only
+///    body is reduced to its visible text with `HtmlHandler`
+/// 4. Joins the headers and the body parts with blank lines
+///
+/// # Limitations
+///
+/// - Attachments, including attached messages, are not extracted
+/// - Other headers (`To`, `Cc`, ...) are not extracted
+pub struct EmlHandler {
+    html_handler: HtmlHandler,
+}
+
+impl EmlHandler {
+    /// Creates a new `EmlHandler` instance.
+    ///
+    /// # Returns
+    ///
+    /// A new `EmlHandler` ready to process email messages.
+    pub fn new() -> Self {
+        Self {
+            html_handler: HtmlHandler::new(),
+        }
+    }
+
+    /// Extracts the headers and body text from an email message.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw message content as a byte slice
+    /// * `filename` - The filename, passed through to `HtmlHandler`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The header lines, a blank line, and the body text
+    /// * `Err(String)` - Error message if the content is not an email message
+    fn extract_text_from_eml(&self, content: &[u8], filename: &str) -> Result<String, String> {
+        let message = MessageParser::default()
+            .parse(content)
+            .filter(|message| !message.headers().is_empty())
+            .ok_or_else(|| "Failed to parse email message".to_string())?;
+
+        let mut headers = Vec::new();
+        if let Some(subject) = message.subject() {
+            headers.push(format!("Subject: {}", subject));
+        }
+        if let Some(from) = message.from() {
+            headers.push(format!("From: {}", format_address(from)));
+        }
+        if let Some(date) = message.date() {
+            headers.push(format!("Date: {}", date.to_rfc3339()));
+        }
+
+        let mut sections = Vec::new();
+        if !headers.is_empty() {
+            sections.push(headers.join("\n"));
+        }
+        let body = self.body_text(&message, filename)?;
+        if !body.is_empty() {
+            sections.push(body);
+        }
+
+        Ok(sections.join("\n\n"))
+    }
+
+    /// Returns the text of a message's inline body parts.
+    ///
+    /// `mail-parser` lists the `text/plain` alternative of each body part
+    /// where one exists, and the HTML part otherwise; HTML parts are reduced
+    /// to their visible text.
+    fn body_text(&self, message: &Message, filename: &str) -> Result<String, String> {
+        let mut parts = Vec::new();
+        for index in 0..message.text_body_count() {
+            let Some(part) = message.text_part(index as u32) else {
+                continue;
+            };
+            let text = match &part.body {
+                PartType::Text(text) => text.trim().to_string(),
+                PartType::Html(html) => {
+                    self.html_handler
+                        .extract_text(html.as_bytes(), filename, "text/html")?
+                }
+                _ => continue,
+            };
+            if !text.is_empty() {
+                parts.push(text);
+            }
+        }
+
+        Ok(parts.join("\n\n"))
+    }
+}
+
+/// Formats an address header as `Name <address>` entries separated by commas.
+fn format_address(address: &Address) -> String {
+    address
+        .iter()
+        .map(|addr| match (addr.name(), addr.address()) {
+            (Some(name), Some(email)) => format!("{} <{}>", name, email),
+            (Some(name), None) => name.to_string(),
+            (None, Some(email)) => email.to_string(),
+            (None, None) => String::new(),
+        })
+        .filter(|entry| !entry.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl FileHandler for EmlHandler {
+    /// Determines if this handler can process email messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_type` - The MIME type string to check
+    ///
+    /// # Returns
+    ///
+    /// `true` for `message/rfc822`, `false` otherwise.
+    fn can_handle(&self, mime_type: &str) -> bool {
+        mime_type == "message/rfc822"
+    }
+
+    /// Lists the MIME types this handler accepts.
+    fn supported_mimes(&self) -> Vec<String> {
+        vec!["message/rfc822".to_string()]
+    }
+
+    /// Returns the handler's name, `EmlHandler`.
+    fn name(&self) -> &str {
+        "EmlHandler"
+    }
+
+    /// Extracts headers and body text from an email message.
+    ///
+    /// This is the main entry point for email text extraction. It delegates
+    /// to `extract_text_from_eml()` to perform the actual extraction.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw message content as a byte slice
+    /// * `filename` - The filename, passed through to `HtmlHandler`
+    /// * `_mime_type` - The MIME type (unused, already verified by `can_handle()`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The `Subject`, `From`, and `Date` lines and the body text
+    /// * `Err(String)` - Error message if the message cannot be parsed
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::eml::EmlHandler;
+    /// # use crate::core::handler::FileHandler;
+    /// let handler = EmlHandler::new();
+    /// let eml = b"Subject: Hello\r\nFrom: alice@example.com\r\n\r\nSee you soon.";
+    /// let text = handler.extract_text(eml, "hello.eml", "message/rfc822");
+    /// assert_eq!(text.unwrap(), "Subject: Hello\nFrom: alice@example.com\n\nSee you soon.");
+    /// ```
+    fn extract_text(
+        &self,
+        content: &[u8],
+        filename: &str,
+        _mime_type: &str,
+    ) -> Result<String, String> {
+        self.extract_text_from_eml(content, filename)
+    }
+
+    /// Returns the estimated peak memory use per input byte.
+    ///
+    /// Encoded parts are decoded alongside the parsed message, roughly three
+    /// times the file size.
+    fn memory_multiplier(&self) -> f64 {
+        3.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_text_from_multipart_email() {
+        let eml = "From: Alice Smith <alice@example.com>\r\n\
+            To: team@example.com\r\n\
+            Subject: =?UTF-8?Q?Quarterly_r=C3=A9sum=C3=A9?=\r\n\
+            Date: Mon, 15 Jan 2024 10:30:00 +0100\r\n\
+            MIME-Version: 1.0\r\n\
+            Content-Type: multipart/alternative; boundary=\"sep\"\r\n\
+            \r\n\
+            --sep\r\n\
+            Content-Type: text/plain; charset=utf-8\r\n\
+            Content-Transfer-Encoding: quoted-printable\r\n\
+            \r\n\
+            Revenue grew 12% in the caf=C3=A9 division.\r\n\
+            --sep\r\n\
+            Content-Type: text/html; charset=utf-8\r\n\
+            Content-Transfer-Encoding: base64\r\n\
+            \r\n\
+            PHA+UmV2ZW51ZSBncmV3IDEyJSBpbiB0aGUgY2Fmw6kgZGl2aXNpb24uPC9wPg==\r\n\
+            --sep--\r\n";
+
+        let text = EmlHandler::new()
+            .extract_text(eml.as_bytes(), "report.eml", "message/rfc822")
+            .unwrap();
+
+        assert_eq!(
+            text,
+            "Subject: Quarterly résumé\n\
+             From: Alice Smith <alice@example.com>\n\
+             Date: 2024-01-15T10:30:00+01:00\n\
+             \n\
+             Revenue grew 12% in the café division."
+        );
+    }
+
+    #[test]
+    fn test_html_only_email_body_is_stripped() {
+        let eml = "Subject: Menu\r\n\
+            Content-Type: text/html\r\n\
+            \r\n\
+            <p>Fish &amp; Chips</p><script>track()</script>\r\n";
+
+        let text = EmlHandler::new()
+            .extract_text(eml.as_bytes(), "menu.eml", "message/rfc822")
+            .unwrap();
+
+        assert_eq!(text, "Subject: Menu\n\nFish & Chips");
+    }
+}
@@ -0,0 +1,368 @@
+//! EPUB file handler for extracting text from e-books.
+//!
+//! EPUB files are ZIP packages of XHTML documents. The package document (OPF),
+//! located through `META-INF/container.xml`, lists the documents in its
+//! manifest and their reading order in its spine. This handler reads the
+//! package with `zip` and `quick-xml` and extracts each document's visible
+//! text with `HtmlHandler`.
+
+use crate::core::handler::FileHandler;
+use crate::handlers::html::HtmlHandler;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+/// Handler for processing EPUB e-books.
+///
+/// The `EpubHandler` extracts the visible text of every document in the
+/// book's spine, in reading order, with chapters separated by blank lines.
+///
+/// # Supported MIME Types
+///
+/// - `application/epub+zip` - EPUB 2 and EPUB 3 e-books
+///
+/// # Processing Flow
+///
+/// 1. Opens the EPUB as a ZIP archive
+/// 2. Finds the package document (OPF) from the `rootfile` listed in
+///    `META-INF/container.xml`
+/// 3. Resolves the spine's `itemref` entries to documents through the
+///    manifest, relative to the package document's folder
+/// 4. Extracts the visible text of each document with `HtmlHandler`
+/// 5. Joins the non-empty chapters with blank lines
+///
+/// # Limitations
+///
+/// - Documents outside the spine (such as a separate navigation document) are
+///   not extracted
+/// - Encrypted (DRM-protected) content cannot be read
+/// - Images, including text in images, are ignored
+pub struct EpubHandler {
+    html_handler: HtmlHandler,
+}
+
+impl EpubHandler {
+    /// Creates a new `EpubHandler` instance.
+    ///
+    /// # Returns
+    ///
+    /// A new `EpubHandler` ready to process EPUB files.
+    pub fn new() -> Self {
+        Self {
+            html_handler: HtmlHandler::new(),
+        }
+    }
+
+    /// Extracts text from an EPUB e-book.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw EPUB file content as a byte slice
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The text of each chapter in reading order, separated by
+    ///   blank lines
+    /// * `Err(String)` - Error message if the package, its package document, or
+    ///   a chapter cannot be read
+    fn extract_text_from_epub(&self, content: &[u8]) -> Result<String, String> {
+        let mut archive = ZipArchive::new(Cursor::new(content))
+            .map_err(|e| format!("Failed to open EPUB: {}", e))?;
+
+        let container = read_part(&mut archive, "META-INF/container.xml")?;
+        let package_path = element_attributes(&container, b"rootfile")
+            .into_iter()
+            .find_map(|mut attributes| attributes.remove("full-path"))
+            .ok_or_else(|| "Failed to find the EPUB package document".to_string())?;
+        let package = read_part(&mut archive, &package_path)?;
+
+        let mut chapters = Vec::new();
+        for path in spine_paths(&package, &package_path) {
+            let document = read_part(&mut archive, &path)?;
+            let text = self.html_handler.extract_text(
+                document.as_bytes(),
+                &path,
+                "application/xhtml+xml",
+            )?;
+            if !text.is_empty() {
+                chapters.push(text);
+            }
+        }
+
+        Ok(chapters.join("\n\n"))
+    }
+}
+
+/// Reads a package part as a string.
+fn read_part(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<String, String> {
+    let mut part = archive
+        .by_name(name)
+        .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+
+    let mut xml = String::new();
+    part.read_to_string(&mut xml)
+        .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+
+    Ok(xml)
+}
+
+/// Returns the package paths of the spine documents in reading order.
+///
+/// Each spine `itemref` names a manifest `item` by id; the item's `href` is
+/// relative to the folder of the package document at `package_path`. Spine
+/// entries without a manifest item are skipped.
+fn spine_paths(package: &str, package_path: &str) -> Vec<String> {
+    let hrefs: HashMap<String, String> = element_attributes(package, b"item")
+        .into_iter()
+        .filter_map(|mut attributes| Some((attributes.remove("id")?, attributes.remove("href")?)))
+        .collect();
+    let folder = package_path
+        .rsplit_once('/')
+        .map_or("", |(folder, _)| folder);
+
+    element_attributes(package, b"itemref")
+        .iter()
+        .filter_map(|attributes| hrefs.get(attributes.get("idref")?))
+        .map(|href| resolve_href(folder, href))
+        .collect()
+}
+
+/// Resolves a manifest `href` against the folder of the package document.
+///
+/// Fragments are dropped, percent-encoded bytes are decoded, and `.` and `..`
+/// segments are applied.
+fn resolve_href(folder: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or_default();
+
+    let mut segments: Vec<String> = folder
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect();
+    for segment in href.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(percent_decode(segment)),
+        }
+    }
+
+    segments.join("/")
+}
+
+/// Decodes `%XX` escapes in a URL path segment.
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| segment.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Returns the attributes of every element named `element` in `xml`.
+///
+/// Element and attribute names are matched without their namespace prefix,
+/// so `<opf:item>` matches `item`.
+fn element_attributes(xml: &str, element: &[u8]) -> Vec<HashMap<String, String>> {
+    let mut reader = Reader::from_str(xml);
+    let mut elements = Vec::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e) | Event::Empty(e)) if e.local_name().as_ref() == element => {
+                let attributes = e
+                    .attributes()
+                    .flatten()
+                    .filter_map(|attribute| {
+                        let name = String::from_utf8(attribute.key.local_name().as_ref().to_vec());
+                        let value = attribute.decode_and_unescape_value(reader.decoder());
+                        Some((name.ok()?, value.ok()?.into_owned()))
+                    })
+                    .collect();
+                elements.push(attributes);
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    elements
+}
+
+impl FileHandler for EpubHandler {
+    /// Determines if this handler can process EPUB files.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_type` - The MIME type string to check
+    ///
+    /// # Returns
+    ///
+    /// `true` if the MIME type represents an EPUB e-book, `false` otherwise.
+    fn can_handle(&self, mime_type: &str) -> bool {
+        mime_type == "application/epub+zip"
+    }
+
+    /// Lists the MIME types this handler accepts.
+    fn supported_mimes(&self) -> Vec<String> {
+        vec!["application/epub+zip".to_string()]
+    }
+
+    /// Returns the handler's name, `EpubHandler`.
+    fn name(&self) -> &str {
+        "EpubHandler"
+    }
+
+    /// Extracts text content from an EPUB e-book.
+    ///
+    /// This is the main entry point for EPUB text extraction. It delegates
+    /// to `extract_text_from_epub()` to perform the actual extraction.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw EPUB file content as a byte slice
+    /// * `_filename` - The filename (unused, kept for trait compatibility)
+    /// * `_mime_type` - The MIME type (unused, already verified by `can_handle()`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The text of each chapter in reading order
+    /// * `Err(String)` - Error message if extraction fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::epub::EpubHandler;
+    /// # use crate::core::handler::FileHandler;
+    /// let handler = EpubHandler::new();
+    /// let epub_bytes = vec![...]; // EPUB file bytes
+    /// let text = handler.extract_text(&epub_bytes, "manual.epub", "application/epub+zip");
+    /// ```
+    fn extract_text(
+        &self,
+        content: &[u8],
+        _filename: &str,
+        _mime_type: &str,
+    ) -> Result<String, String> {
+        self.extract_text_from_epub(content)
+    }
+
+    /// Returns the estimated peak memory use per input byte.
+    ///
+    /// Chapters are decompressed and parsed into a DOM one at a time, roughly
+    /// four times the file size.
+    fn memory_multiplier(&self) -> f64 {
+        4.0
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    /// Builds a minimal EPUB with one XHTML chapter per `(file name, body)`
+    /// entry, stored under `OEBPS/Text/`. The spine lists the chapters in
+    /// reverse manifest order, so reading order differs from file order.
+    pub(crate) fn build_epub(chapters: &[(&str, &str)]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+
+        zip.start_file("mimetype", options).unwrap();
+        zip.write_all(b"application/epub+zip").unwrap();
+
+        zip.start_file("META-INF/container.xml", options).unwrap();
+        zip.write_all(
+            br#"<?xml version="1.0"?><container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container"><rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles></container>"#,
+        )
+        .unwrap();
+
+        let mut manifest = String::new();
+        let mut spine = String::new();
+        for (index, (name, body)) in chapters.iter().enumerate() {
+            manifest.push_str(&format!(
+                r#"<item id="chapter{}" href="Text/{}" media-type="application/xhtml+xml"/>"#,
+                index, name
+            ));
+            spine.insert_str(0, &format!(r#"<itemref idref="chapter{}"/>"#, index));
+
+            zip.start_file(format!("OEBPS/Text/{}", name), options)
+                .unwrap();
+            zip.write_all(
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?><html xmlns="http://www.w3.org/1999/xhtml"><head><title>{0}</title></head><body>{1}</body></html>"#,
+                    name, body
+                )
+                .as_bytes(),
+            )
+            .unwrap();
+        }
+
+        zip.start_file("OEBPS/content.opf", options).unwrap();
+        zip.write_all(
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><package xmlns="http://www.idpf.org/2007/opf" version="3.0"><manifest>{}</manifest><spine>{}</spine></package>"#,
+                manifest, spine
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_extract_text_in_spine_order() {
+        // The spine reverses the list, so "intro.xhtml" is read first
+        let epub = build_epub(&[
+            ("setup.xhtml", "<h1>Setup</h1><p>Plug in the device.</p>"),
+            (
+                "intro.xhtml",
+                "<h1>Introduction</h1><p>Welcome &amp; thanks.</p>",
+            ),
+        ]);
+
+        let text = EpubHandler::new().extract_text_from_epub(&epub).unwrap();
+
+        assert_eq!(
+            text,
+            "Introduction\nWelcome & thanks.\n\nSetup\nPlug in the device."
+        );
+    }
+
+    #[test]
+    fn test_resolve_href() {
+        assert_eq!(
+            resolve_href("OEBPS", "Text/ch%201.xhtml#top"),
+            "OEBPS/Text/ch 1.xhtml"
+        );
+        assert_eq!(
+            resolve_href("OEBPS/pkg", "../Text/ch1.xhtml"),
+            "OEBPS/Text/ch1.xhtml"
+        );
+        assert_eq!(resolve_href("", "./ch1.xhtml"), "ch1.xhtml");
+    }
+}
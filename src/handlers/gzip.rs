@@ -0,0 +1,330 @@
+//! Gzip handler for extracting text from gzip-compressed files.
+//!
+//! The compressed file is inflated in memory and the decompressed bytes are
+//! routed back through a set of file handlers, so a gzipped log or document
+//! yields the same text as the uncompressed file. An output size limit guards
+//! against decompression bombs.
+
+use crate::core::detect::sniff_mime;
+use crate::core::handler::{ExtractedText, FileHandler};
+use crate::core::mime::mime_from_filename;
+use flate2::read::MultiGzDecoder;
+use std::io::Read;
+use std::sync::Arc;
+
+/// Default limit on the decompressed size of a gzip file (256 MiB).
+pub const DEFAULT_MAX_OUTPUT_BYTES: u64 = 256 * 1024 * 1024;
+
+/// The decompressed content of a gzip file, ready for extraction.
+struct InnerFile<'a> {
+    content: Vec<u8>,
+    /// The gzip file's name without its `.gz` extension.
+    filename: String,
+    mime_type: String,
+    handler: &'a Arc<dyn FileHandler>,
+}
+
+/// Handler for processing gzip-compressed files.
+///
+/// The `GzipHandler` decompresses a file and extracts the result with the
+/// first handler that accepts its MIME type. The MIME type of the
+/// decompressed content is, in order of preference:
+///
+/// 1. The inner MIME type set with `with_inner_mime_type()`
+/// 2. The type inferred from the filename without its `.gz` extension
+///    (`app.log.gz` is `text/plain`)
+/// 3. The type detected from the decompressed content's magic bytes
+/// 4. `text/plain`, since compressed uploads are mostly logs and exports
+///
+/// # Supported MIME Types
+///
+/// - `application/gzip` - Gzip-compressed files
+/// - `application/x-gzip` - Alternative gzip MIME type
+///
+/// # Limits
+///
+/// Decompression stops once the output exceeds the size limit, and the
+/// extraction fails instead of returning partial text.
+///
+/// # Limitations
+///
+/// - Tarballs (`.tar.gz`) are not unpacked; their decompressed content is not
+///   a format any handler accepts
+/// - Gzip files nested in the decompressed content are not decompressed again
+pub struct GzipHandler {
+    handlers: Vec<Arc<dyn FileHandler>>,
+    max_output_bytes: u64,
+    inner_mime_type: Option<String>,
+}
+
+impl GzipHandler {
+    /// Creates a new `GzipHandler` dispatching decompressed content to `handlers`.
+    ///
+    /// # Arguments
+    ///
+    /// * `handlers` - The handlers used for the decompressed content, in priority order
+    ///
+    /// # Returns
+    ///
+    /// A new `GzipHandler` with the default limit and no inner MIME type.
+    pub fn new(handlers: Vec<Arc<dyn FileHandler>>) -> Self {
+        Self {
+            handlers,
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            inner_mime_type: None,
+        }
+    }
+
+    /// Sets the limit on the decompressed size of a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_output_bytes` - Maximum number of decompressed bytes
+    ///
+    /// # Returns
+    ///
+    /// The handler with the limit applied.
+    pub fn with_max_output_bytes(mut self, max_output_bytes: u64) -> Self {
+        self.max_output_bytes = max_output_bytes;
+        self
+    }
+
+    /// Sets the MIME type of the decompressed content of every file.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner_mime_type` - The MIME type used to extract the decompressed
+    ///   content, instead of inferring it
+    ///
+    /// # Returns
+    ///
+    /// The handler with the inner MIME type applied.
+    pub fn with_inner_mime_type(mut self, inner_mime_type: String) -> Self {
+        self.inner_mime_type = Some(inner_mime_type);
+        self
+    }
+
+    /// Decompresses a gzip file, including files of several gzip members.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw gzip content
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<u8>)` - The decompressed content
+    /// * `Err(String)` - Error message if the content is not valid gzip or
+    ///   exceeds the output size limit
+    fn decompress(&self, content: &[u8]) -> Result<Vec<u8>, String> {
+        // Read one byte past the limit to detect output that exceeds it
+        let mut output = Vec::new();
+        MultiGzDecoder::new(content)
+            .take(self.max_output_bytes.saturating_add(1))
+            .read_to_end(&mut output)
+            .map_err(|e| format!("Failed to decompress gzip file: {}", e))?;
+
+        if output.len() as u64 > self.max_output_bytes {
+            return Err(format!(
+                "Gzip file exceeds the limit of {} decompressed bytes",
+                self.max_output_bytes
+            ));
+        }
+
+        Ok(output)
+    }
+
+    /// Decompresses a file and finds the handler for its content.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw gzip content
+    /// * `filename` - The filename of the gzip file
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(InnerFile)` - The decompressed file and the handler accepting it
+    /// * `Err(String)` - Error message if decompression fails or no handler
+    ///   accepts the content
+    fn inner_file(&self, content: &[u8], filename: &str) -> Result<InnerFile<'_>, String> {
+        let decompressed = self.decompress(content)?;
+
+        let inner_filename = if filename.to_ascii_lowercase().ends_with(".gz") {
+            &filename[..filename.len() - 3]
+        } else {
+            filename
+        };
+
+        let mime_type = self
+            .inner_mime_type
+            .clone()
+            .or_else(|| mime_from_filename(inner_filename).map(str::to_string))
+            .or_else(|| sniff_mime(&decompressed))
+            .unwrap_or_else(|| "text/plain".to_string());
+
+        let handler = self
+            .handlers
+            .iter()
+            .find(|h| h.can_handle(&mime_type))
+            .ok_or_else(|| format!("No handler for the decompressed {} content", mime_type))?;
+
+        Ok(InnerFile {
+            content: decompressed,
+            filename: inner_filename.to_string(),
+            mime_type,
+            handler,
+        })
+    }
+}
+
+impl FileHandler for GzipHandler {
+    /// Determines if this handler can process gzip files.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_type` - The MIME type string to check
+    ///
+    /// # Returns
+    ///
+    /// `true` for `application/gzip` and `application/x-gzip`, `false` otherwise.
+    fn can_handle(&self, mime_type: &str) -> bool {
+        mime_type == "application/gzip" || mime_type == "application/x-gzip"
+    }
+
+    /// Lists the MIME types this handler accepts.
+    fn supported_mimes(&self) -> Vec<String> {
+        ["application/gzip", "application/x-gzip"]
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    /// Returns the handler's name, `GzipHandler`.
+    fn name(&self) -> &str {
+        "GzipHandler"
+    }
+
+    /// Extracts the text of a gzip-compressed file.
+    ///
+    /// Decompresses the content and extracts it with the handler for its
+    /// MIME type (see `GzipHandler`).
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw gzip content as a byte slice
+    /// * `filename` - The filename, used without its `.gz` extension to infer
+    ///   the inner MIME type
+    /// * `_mime_type` - The MIME type (unused, already verified by `can_handle()`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The text of the decompressed file
+    /// * `Err(String)` - Error message if the content cannot be decompressed,
+    ///   exceeds the limit, or cannot be extracted
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::gzip::GzipHandler;
+    /// # use crate::core::handler::FileHandler;
+    /// let handler = GzipHandler::new(vec![Arc::new(TextHandler::new())]);
+    /// let text = handler.extract_text(&gzip_bytes, "app.log.gz", "application/gzip");
+    /// ```
+    fn extract_text(
+        &self,
+        content: &[u8],
+        filename: &str,
+        _mime_type: &str,
+    ) -> Result<String, String> {
+        let inner = self.inner_file(content, filename)?;
+        inner
+            .handler
+            .extract_text(&inner.content, &inner.filename, &inner.mime_type)
+    }
+
+    /// Extracts the text of a gzip-compressed file with the encoding reported
+    /// by the handler of its content.
+    fn extract_text_with_encoding(
+        &self,
+        content: &[u8],
+        filename: &str,
+        _mime_type: &str,
+    ) -> Result<ExtractedText, String> {
+        let inner = self.inner_file(content, filename)?;
+        inner
+            .handler
+            .extract_text_with_encoding(&inner.content, &inner.filename, &inner.mime_type)
+    }
+
+    /// Returns the estimated peak memory use per input byte.
+    ///
+    /// The whole file is decompressed before extraction, and compressed text
+    /// commonly expands several times; roughly eight times the file size.
+    fn memory_multiplier(&self) -> f64 {
+        8.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::csv::CsvHandler;
+    use crate::handlers::text::TextHandler;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    fn gzip(content: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn handler() -> GzipHandler {
+        GzipHandler::new(vec![
+            Arc::new(CsvHandler::new()),
+            Arc::new(TextHandler::new()),
+        ])
+    }
+
+    #[test]
+    fn test_extract_decompressed_text() {
+        let log = gzip(b"2024-05-01 INFO started\n2024-05-01 ERROR failed\n");
+
+        let extracted = handler()
+            .extract_text_with_encoding(&log, "app.log.gz", "application/gzip")
+            .unwrap();
+        assert_eq!(
+            extracted.text,
+            "2024-05-01 INFO started\n2024-05-01 ERROR failed\n"
+        );
+        assert_eq!(extracted.encoding, "utf-8");
+
+        // The inner MIME type comes from the filename, or from the caller
+        let csv = gzip(b"name,city\nAda,London\n");
+        let text = handler()
+            .extract_text(&csv, "people.csv.gz", "application/gzip")
+            .unwrap();
+        assert_eq!(text, "name\tcity\nAda\tLondon");
+        let text = handler()
+            .with_inner_mime_type("text/csv".to_string())
+            .extract_text(&csv, "export.gz", "application/gzip")
+            .unwrap();
+        assert_eq!(text, "name\tcity\nAda\tLondon");
+    }
+
+    #[test]
+    fn test_output_limit() {
+        let bomb = gzip(&[b'a'; 2000]);
+
+        let err = handler()
+            .with_max_output_bytes(1000)
+            .extract_text(&bomb, "bomb.txt.gz", "application/gzip")
+            .unwrap_err();
+        assert!(err.contains("1000 decompressed bytes"));
+
+        let err = handler()
+            .extract_text(b"not gzip", "plain.gz", "application/gzip")
+            .unwrap_err();
+        assert!(err.starts_with("Failed to decompress gzip file"));
+    }
+}
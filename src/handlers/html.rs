@@ -0,0 +1,253 @@
+//! HTML file handler for extracting visible text from web pages.
+//!
+//! This handler uses the `scraper` library (built on `html5ever`) to parse HTML
+//! into a DOM and collect the text a reader would see, leaving out markup,
+//! scripts, and styles.
+
+use crate::core::handler::FileHandler;
+use crate::handlers::text::TextHandler;
+use scraper::{ElementRef, Html, Node};
+
+/// Elements whose content is never visible text.
+const SKIPPED_ELEMENTS: &[&str] = &["head", "script", "style", "noscript", "template"];
+
+/// Elements that start a new line of text.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "tr",
+    "ul",
+];
+
+/// Handler for processing HTML documents.
+///
+/// The `HtmlHandler` parses HTML and extracts the visible text content.
+/// Entities such as `&amp;` are decoded by the parser, and block-level
+/// elements (paragraphs, headings, list items, table cells, ...) are placed
+/// on their own lines.
+///
+/// # Supported MIME Types
+///
+/// - `text/html` - HTML documents
+/// - `application/xhtml+xml` - XHTML documents
+///
+/// # Processing Flow
+///
+/// 1. Decodes the bytes to text (the same encoding detection as `TextHandler`)
+/// 2. Parses the text into a DOM using `scraper`
+/// 3. Walks the DOM, skipping `head`, `script`, `style`, `noscript`, and
+///    `template` elements
+/// 4. Collects text nodes, breaking lines at block-level elements
+/// 5. Collapses whitespace within lines and drops empty lines
+///
+/// # Limitations
+///
+/// - CSS visibility (`display: none`, `hidden`) is not evaluated
+/// - Attribute text such as `alt` and `title` is not extracted
+pub struct HtmlHandler {
+    text_handler: TextHandler,
+}
+
+impl HtmlHandler {
+    /// Creates a new `HtmlHandler` instance.
+    ///
+    /// # Returns
+    ///
+    /// A new `HtmlHandler` ready to process HTML files.
+    pub fn new() -> Self {
+        Self {
+            text_handler: TextHandler::new(),
+        }
+    }
+
+    /// Sets the byte entropy at or above which HTML content is rejected as
+    /// binary before it is decoded (see
+    /// `TextHandler::with_binary_entropy_threshold`).
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Entropy in bits per byte (0-8); above 8 disables the check
+    ///
+    /// # Returns
+    ///
+    /// The handler with the threshold applied.
+    pub fn with_binary_entropy_threshold(mut self, threshold: f64) -> Self {
+        self.text_handler = self.text_handler.with_binary_entropy_threshold(threshold);
+        self
+    }
+
+    /// Extracts the visible text from an HTML document.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The decoded HTML source
+    ///
+    /// # Returns
+    ///
+    /// The visible text, one line per block-level element.
+    fn extract_text_from_html(&self, html: &str) -> String {
+        let document = Html::parse_document(html);
+
+        let mut text = String::new();
+        collect_text(document.root_element(), &mut text);
+
+        text.lines()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Appends the visible text below `element` to `text`.
+fn collect_text(element: ElementRef, text: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(node) => text.push_str(node),
+            Node::Element(child_element) => {
+                let name = child_element.name();
+                if SKIPPED_ELEMENTS.contains(&name) {
+                    continue;
+                }
+
+                let is_block = BLOCK_ELEMENTS.contains(&name);
+                if is_block {
+                    text.push('\n');
+                }
+                if let Some(child_ref) = ElementRef::wrap(child) {
+                    collect_text(child_ref, text);
+                }
+                if is_block {
+                    text.push('\n');
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl FileHandler for HtmlHandler {
+    /// Determines if this handler can process HTML files.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_type` - The MIME type string to check
+    ///
+    /// # Returns
+    ///
+    /// `true` for `text/html` and `application/xhtml+xml`, `false` otherwise.
+    fn can_handle(&self, mime_type: &str) -> bool {
+        mime_type == "text/html" || mime_type == "application/xhtml+xml"
+    }
+
+    /// Lists the MIME types this handler accepts.
+    fn supported_mimes(&self) -> Vec<String> {
+        ["text/html", "application/xhtml+xml"]
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    /// Returns the handler's name, `HtmlHandler`.
+    fn name(&self) -> &str {
+        "HtmlHandler"
+    }
+
+    /// Extracts visible text content from an HTML document.
+    ///
+    /// Decodes the content with `TextHandler`, then delegates to
+    /// `extract_text_from_html()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw HTML file content as a byte slice
+    /// * `filename` - The filename, passed through to `TextHandler`
+    /// * `mime_type` - The MIME type, passed through to `TextHandler`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The visible text content
+    /// * `Err(String)` - Error message if the content cannot be decoded as text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::html::HtmlHandler;
+    /// # use crate::core::handler::FileHandler;
+    /// let handler = HtmlHandler::new();
+    /// let html = b"<p>Fish &amp; Chips</p><script>track()</script>";
+    /// let text = handler.extract_text(html, "menu.html", "text/html");
+    /// assert_eq!(text.unwrap(), "Fish & Chips");
+    /// ```
+    fn extract_text(
+        &self,
+        content: &[u8],
+        filename: &str,
+        mime_type: &str,
+    ) -> Result<String, String> {
+        let html = self
+            .text_handler
+            .extract_text(content, filename, mime_type)?;
+        Ok(self.extract_text_from_html(&html))
+    }
+
+    /// Returns the estimated peak memory use per input byte.
+    ///
+    /// HTML is decoded and parsed into a DOM, roughly four times the file size.
+    fn memory_multiplier(&self) -> f64 {
+        4.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_text_from_html() {
+        let html = r#"<!DOCTYPE html>
+            <html>
+              <head><title>Menu</title><style>p { color: red; }</style></head>
+              <body>
+                <h1>Today's   specials</h1>
+                <script>var tracking = "secret";</script>
+                <p>Fish &amp; Chips</p>
+                <ul><li>Soup</li><li>Salad &lt;fresh&gt;</li></ul>
+              </body>
+            </html>"#;
+
+        let text = HtmlHandler::new()
+            .extract_text(html.as_bytes(), "menu.html", "text/html")
+            .unwrap();
+
+        assert_eq!(text, "Today's specials\nFish & Chips\nSoup\nSalad <fresh>");
+    }
+}
@@ -4,10 +4,23 @@
 //! text from images. It uses pre-trained models for text detection and recognition.
 
 use crate::core::handler::FileHandler;
-use image::ImageReader;
+use crate::models::ocr::OcrLine;
+use crate::models::options::ProcessOptions;
+use image::codecs::gif::GifDecoder;
+use image::imageops::FilterType;
+use image::{
+    AnimationDecoder, DynamicImage, GrayAlphaImage, GrayImage, ImageBuffer, ImageFormat,
+    ImageReader, RgbImage, RgbaImage,
+};
+use ocrs::{OcrInput, TextItem, TextLine};
 use rten::Model;
+use rten_tensor::prelude::*;
+use std::borrow::Cow;
 use std::io::Cursor;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tiff::ColorType;
+use tiff::decoder::{Decoder as TiffDecoder, DecodingResult};
 
 /// Handler for processing image files and extracting text using OCR.
 ///
@@ -35,14 +48,28 @@ use std::path::PathBuf;
 ///    regions into actual text strings
 /// 6. **Text Assembly**: Combines all recognized text lines with newlines
 ///
+/// Multi-page TIFFs and animated GIFs are recognized frame by frame, and each
+/// frame's text is prefixed with a `--- Page N ---` marker, as PDFs are with
+/// `pdf_page_markers`. Single-frame images have no markers.
+///
+/// Small or low-contrast scans can be upscaled, converted to grayscale, and
+/// binarized before recognition with `with_preprocessing()` (see
+/// `ImagePreprocessOptions`).
+///
 /// # Model Requirements
 ///
 /// The handler requires two model files in the project root:
 /// - `text-detection-model.rten` - Model for detecting text regions in images
 /// - `text-recognition-model.rten` - Model for recognizing text in detected regions
 ///
-/// These models are loaded once when the handler is created and reused for all
-/// image processing operations.
+/// These models are loaded when the first image is processed and reused for
+/// all subsequent image processing operations. Creating the handler never
+/// touches the model files, so deployments without them can still process
+/// other file types; images then fail with a per-file error.
+///
+/// Alternate models, such as a recognition model for another language, can be
+/// loaded with `ImageHandler::with_models()` or
+/// `ImageHandler::with_models_and_alphabet()`.
 ///
 /// # Limitations
 ///
@@ -51,26 +78,111 @@ use std::path::PathBuf;
 /// - Complex layouts or rotated text may reduce accuracy
 /// - Processing time increases with image size
 pub struct ImageHandler {
-    /// The OCR engine containing detection and recognition models.
-    model: ocrs::OcrEngine,
+    /// Path to the text detection model.
+    detection_path: PathBuf,
+    /// Path to the text recognition model.
+    recognition_path: PathBuf,
+    /// Alphabet of the recognition model, if it differs from the default.
+    alphabet: Option<String>,
+    /// The OCR engine, or the error from loading it, created on first use.
+    engine: OnceLock<Result<ocrs::OcrEngine, String>>,
+    /// Image transforms applied to each frame before recognition.
+    preprocessing: ImagePreprocessOptions,
+}
+
+/// Image transforms applied before OCR to improve recognition of poor scans.
+///
+/// The default applies no transform, so images are recognized as decoded.
+/// Enabled transforms run in this order:
+///
+/// 1. **Upscaling**: Images narrower than `min_width` pixels are resized to
+///    that width, keeping their aspect ratio
+/// 2. **Grayscale**: Color is dropped, with `grayscale` or `binarize_threshold`
+/// 3. **Binarization**: Pixels at least as bright as `binarize_threshold`
+///    become white and all others black, maximizing contrast
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImagePreprocessOptions {
+    /// Convert images to grayscale before recognition.
+    pub grayscale: bool,
+    /// Luma (0-255) at and above which pixels become white; the rest become black.
+    pub binarize_threshold: Option<u8>,
+    /// Minimum width in pixels; narrower images are upscaled to it.
+    pub min_width: Option<u32>,
+}
+
+impl ImagePreprocessOptions {
+    /// Returns `true` if no transform is enabled.
+    pub fn is_noop(&self) -> bool {
+        !self.grayscale && self.binarize_threshold.is_none() && self.min_width.is_none()
+    }
+
+    /// Returns the factor by which an image `width` pixels wide is upscaled.
+    fn scale(&self, width: u32) -> f64 {
+        match self.min_width {
+            Some(min_width) if width > 0 && width < min_width => min_width as f64 / width as f64,
+            _ => 1.0,
+        }
+    }
+
+    /// Applies the enabled transforms to an image.
+    ///
+    /// # Returns
+    ///
+    /// The transformed image, borrowed unchanged when no transform is enabled.
+    pub fn apply<'a>(&self, image: &'a DynamicImage) -> Cow<'a, DynamicImage> {
+        let mut image = Cow::Borrowed(image);
+        if self.is_noop() {
+            return image;
+        }
+
+        let scale = self.scale(image.width());
+        if scale > 1.0 {
+            let height = (image.height() as f64 * scale).round().max(1.0) as u32;
+            let width = self.min_width.unwrap_or(image.width());
+            image = Cow::Owned(image.resize_exact(width, height, FilterType::CatmullRom));
+        }
+
+        if let Some(threshold) = self.binarize_threshold {
+            let mut luma = image.to_luma8();
+            for pixel in luma.pixels_mut() {
+                pixel.0[0] = if pixel.0[0] >= threshold { 255 } else { 0 };
+            }
+            image = Cow::Owned(DynamicImage::ImageLuma8(luma));
+        } else if self.grayscale {
+            image = Cow::Owned(DynamicImage::ImageLuma8(image.to_luma8()));
+        }
+
+        image
+    }
+}
+
+impl From<&ProcessOptions> for ImagePreprocessOptions {
+    fn from(options: &ProcessOptions) -> Self {
+        Self {
+            grayscale: options.ocr_grayscale.unwrap_or(false),
+            binarize_threshold: options
+                .ocr_binarize_threshold
+                .map(|threshold| threshold.min(255) as u8),
+            min_width: options.ocr_min_width,
+        }
+    }
 }
 
 impl ImageHandler {
     /// Creates a new `ImageHandler` instance.
     ///
-    /// This method loads the required OCR models from files in the project root.
-    /// The models are loaded once and reused for all subsequent image processing.
+    /// This method uses the OCR models from files in the project root. The
+    /// models are loaded when the first image is processed and reused for all
+    /// subsequent image processing.
     ///
     /// # Returns
     ///
     /// A new `ImageHandler` ready to process image files.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// This method will panic if:
-    /// - The model files cannot be found in the project root
-    /// - The model files are corrupted or invalid
-    /// - The OCR engine cannot be initialized
+    /// Missing or invalid model files do not fail construction. Instead, every
+    /// image processed by the handler returns an `Err` describing the problem.
     ///
     /// # Model Files
     ///
@@ -78,24 +190,145 @@ impl ImageHandler {
     /// - `text-detection-model.rten`
     /// - `text-recognition-model.rten`
     pub fn new() -> Self {
-        let detection_model_path =
-            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("text-detection-model.rten");
-        let recognition_model_path =
-            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("text-recognition-model.rten");
+        Self::with_models(
+            Self::default_detection_model(),
+            Self::default_recognition_model(),
+        )
+    }
+
+    /// Returns the path of the bundled text detection model.
+    pub fn default_detection_model() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("text-detection-model.rten")
+    }
+
+    /// Returns the path of the bundled (English) text recognition model.
+    pub fn default_recognition_model() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("text-recognition-model.rten")
+    }
+
+    /// Creates an `ImageHandler` using the OCR models at the given paths.
+    ///
+    /// Use this to load alternate `.rten` models, such as a recognition model
+    /// trained for a specific language. The recognition model must output the
+    /// default `ocrs` alphabet; a language-specific model usually changes the
+    /// alphabet, in which case use `with_models_and_alphabet()` instead.
+    ///
+    /// Like `new()`, the models are loaded when the first image is processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `detection_path` - Path to the text detection model
+    /// * `recognition_path` - Path to the text recognition model
+    ///
+    /// # Returns
+    ///
+    /// A new `ImageHandler` using the given models.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::image::ImageHandler;
+    /// let handler = ImageHandler::with_models("models/detection.rten", "models/recognition-es.rten");
+    /// ```
+    pub fn with_models(
+        detection_path: impl AsRef<Path>,
+        recognition_path: impl AsRef<Path>,
+    ) -> Self {
+        Self {
+            detection_path: detection_path.as_ref().to_path_buf(),
+            recognition_path: recognition_path.as_ref().to_path_buf(),
+            alphabet: None,
+            engine: OnceLock::new(),
+            preprocessing: ImagePreprocessOptions::default(),
+        }
+    }
+
+    /// Creates an `ImageHandler` using the given OCR models and recognition alphabet.
+    ///
+    /// A recognition model trained for another language outputs characters from
+    /// its own alphabet (for example including accented letters such as `ñ` or
+    /// `é`). Passing a language-specific recognition model changes the alphabet,
+    /// so the alphabet the model was trained with must be given here, in the
+    /// model's output order; a mismatched alphabet produces garbled text.
+    ///
+    /// # Arguments
+    ///
+    /// * `detection_path` - Path to the text detection model
+    /// * `recognition_path` - Path to the text recognition model
+    /// * `alphabet` - The characters output by the recognition model, in order
+    ///
+    /// # Returns
+    ///
+    /// A new `ImageHandler` using the given models and alphabet.
+    pub fn with_models_and_alphabet(
+        detection_path: impl AsRef<Path>,
+        recognition_path: impl AsRef<Path>,
+        alphabet: &str,
+    ) -> Self {
+        Self {
+            alphabet: Some(alphabet.to_string()),
+            ..Self::with_models(detection_path, recognition_path)
+        }
+    }
+
+    /// Sets the image transforms applied before recognition.
+    ///
+    /// # Arguments
+    ///
+    /// * `preprocessing` - The transforms to apply to each image frame
+    ///
+    /// # Returns
+    ///
+    /// The handler, preprocessing images before OCR.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::image::{ImageHandler, ImagePreprocessOptions};
+    /// let handler = ImageHandler::new().with_preprocessing(ImagePreprocessOptions {
+    ///     binarize_threshold: Some(128),
+    ///     min_width: Some(1000),
+    ///     ..Default::default()
+    /// });
+    /// ```
+    pub fn with_preprocessing(mut self, preprocessing: ImagePreprocessOptions) -> Self {
+        self.preprocessing = preprocessing;
+        self
+    }
+
+    /// Returns the OCR engine, loading the models on first use.
+    ///
+    /// The outcome of the first load is cached, so a missing model file is
+    /// reported for every image without retrying the load each time.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&OcrEngine)` - The initialized OCR engine
+    /// * `Err(String)` - Error message if a model cannot be loaded:
+    ///   - "Failed to load detection model: ..."
+    ///   - "Failed to load recognition model: ..."
+    ///   - "Failed to initialize OCR engine: ..."
+    fn engine(&self) -> Result<&ocrs::OcrEngine, String> {
+        self.engine
+            .get_or_init(|| self.load_engine())
+            .as_ref()
+            .map_err(String::clone)
+    }
 
-        let detection_model =
-            Model::load_file(detection_model_path).expect("Failed to load detection model");
-        let recognition_model =
-            Model::load_file(recognition_model_path).expect("Failed to load recognition model");
+    /// Loads the OCR models and initializes the engine.
+    fn load_engine(&self) -> Result<ocrs::OcrEngine, String> {
+        let detection_model = Model::load_file(&self.detection_path)
+            .map_err(|e| format!("Failed to load detection model: {}", e))?;
+        let recognition_model = Model::load_file(&self.recognition_path)
+            .map_err(|e| format!("Failed to load recognition model: {}", e))?;
 
-        let model = ocrs::OcrEngine::new(ocrs::OcrEngineParams {
+        ocrs::OcrEngine::new(ocrs::OcrEngineParams {
             detection_model: Some(detection_model),
             recognition_model: Some(recognition_model),
+            alphabet: self.alphabet.clone(),
             ..Default::default()
         })
-        .expect("Failed to initialize OCR engine");
-
-        Self { model }
+        .map_err(|e| format!("Failed to initialize OCR engine: {}", e))
     }
 
     /// Extracts text from an image using OCR.
@@ -112,6 +345,7 @@ impl ImageHandler {
     /// * `Ok(String)` - Successfully extracted text content, or "No text found in image"
     ///   if no text was detected
     /// * `Err(String)` - Error message if any step fails:
+    ///   - "Failed to load ... model: ..." - OCR model loading error (see `engine()`)
     ///   - "Failed to read image: ..." - Image loading/decoding error
     ///   - "Failed to create image source: ..." - Image format conversion error
     ///   - "Failed to prepare OCR input: ..." - OCR input preparation error
@@ -132,57 +366,308 @@ impl ImageHandler {
     ///
     /// Each recognized text line is separated by a newline character. Empty lines
     /// (after trimming) are filtered out. If no text is found, returns "No text found in image".
+    ///
+    /// Images with several frames (multi-page TIFFs, animated GIFs) are
+    /// recognized frame by frame; each frame's text follows a `--- Page N ---`
+    /// marker (1-based), and frames without text keep just their marker.
     fn extract_text_from_image(&self, content: &[u8]) -> Result<String, String> {
-        let cursor = Cursor::new(content);
-        let img = ImageReader::new(cursor)
-            .with_guessed_format()
-            .map_err(|e| format!("Failed to read image: {}", e))?
-            .decode()
-            .map_err(|e| format!("Failed to decode image: {}", e))?;
-
-        let rgb_img = img.to_rgb8();
+        // Report missing models before spending time decoding every frame
+        self.engine()?;
+        let frames = decode_frames(content)?;
+        if let [frame] = frames.as_slice() {
+            return self.extract_text_from_frame(frame);
+        }
+
+        let mut pages = Vec::with_capacity(frames.len());
+        for (idx, frame) in frames.iter().enumerate() {
+            let text = self.recognize_text(frame)?;
+            if text.is_empty() {
+                pages.push(format!("--- Page {} ---", idx + 1));
+            } else {
+                pages.push(format!("--- Page {} ---\n{}", idx + 1, text));
+            }
+        }
+
+        Ok(pages.join("\n"))
+    }
+
+    /// Recognizes the text of a single image frame.
+    ///
+    /// # Returns
+    ///
+    /// The recognized lines joined with newlines, or "No text found in image".
+    fn extract_text_from_frame(&self, image: &DynamicImage) -> Result<String, String> {
+        let text = self.recognize_text(image)?;
+
+        if text.is_empty() {
+            Ok("No text found in image".to_string())
+        } else {
+            Ok(text)
+        }
+    }
+
+    /// Recognizes the non-empty text lines of a decoded image, joined with
+    /// newlines.
+    ///
+    /// Unlike `extract_text()`, an image without text yields an empty string,
+    /// so callers recognizing images embedded in other documents can skip it.
+    pub fn recognize_text(&self, image: &DynamicImage) -> Result<String, String> {
+        let ocr_input = self.prepare_frame(image)?;
+        let text_lines = self.recognize_lines(&ocr_input)?;
+
+        let mut extracted_text = String::new();
+        for text_line in &text_lines {
+            let text = text_line.to_string();
+            if !text.trim().is_empty() {
+                extracted_text.push_str(&text);
+                extracted_text.push('\n');
+            }
+        }
+
+        Ok(extracted_text.trim().to_string())
+    }
+
+    /// Recognizes the lines of text in an image along with their locations.
+    ///
+    /// Runs the same OCR pipeline as `extract_text()`, but keeps each line's
+    /// bounding rectangle and a confidence score instead of joining the text.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw image file content as a byte slice
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<OcrLine>)` - One entry per non-empty line of the first frame,
+    ///   in reading order (empty if no text was found)
+    /// * `Err(String)` - Error message if any OCR step fails (see
+    ///   `extract_text_from_image()`)
+    ///
+    /// # Confidence
+    ///
+    /// The recognition model does not report per-line scores. The confidence is
+    /// the mean probability the detection model assigns to the text pixels
+    /// (those above its detection threshold) within the line's rectangle. This
+    /// runs the detection model a second time, so it roughly doubles the cost of
+    /// detection.
+    ///
+    /// Rectangles are in the coordinates of the original image, also when it
+    /// was upscaled by `ImagePreprocessOptions::min_width`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::image::ImageHandler;
+    /// let handler = ImageHandler::new();
+    /// let lines = handler.extract_regions(&image_bytes)?;
+    /// for line in lines.iter().filter(|line| line.confidence < Some(0.6)) {
+    ///     println!("Review: {} at ({}, {})", line.text, line.x, line.y);
+    /// }
+    /// ```
+    pub fn extract_regions(&self, content: &[u8]) -> Result<Vec<OcrLine>, String> {
+        let engine = self.engine()?;
+        let (ocr_input, scale) = self.prepare_input(content)?;
+        let text_lines = self.recognize_lines(&ocr_input)?;
+        let unscale = |value: i32| (value as f64 / scale).round() as i32;
+
+        let text_mask = engine
+            .detect_text_pixels(&ocr_input)
+            .map_err(|e| format!("Failed to detect text pixels: {}", e))?;
+        let text_mask = text_mask.view();
+        let threshold = engine.detection_threshold();
+
+        let lines = text_lines
+            .iter()
+            .filter(|text_line| !text_line.to_string().trim().is_empty())
+            .map(|text_line| {
+                let rect = text_line.bounding_rect();
+
+                let x_range = rect.left().max(0) as usize..rect.right().max(0) as usize;
+                let y_range = rect.top().max(0) as usize..rect.bottom().max(0) as usize;
+                let text_pixels: Vec<f32> = y_range
+                    .flat_map(|y| x_range.clone().map(move |x| [y, x]))
+                    .filter_map(|index| text_mask.get(index).copied())
+                    .filter(|probability| *probability > threshold)
+                    .collect();
+                let confidence = (!text_pixels.is_empty()).then(|| {
+                    text_pixels.iter().map(|p| *p as f64).sum::<f64>() / text_pixels.len() as f64
+                });
+
+                OcrLine {
+                    text: text_line.to_string().trim().to_string(),
+                    x: unscale(rect.left()),
+                    y: unscale(rect.top()),
+                    width: unscale(rect.width()),
+                    height: unscale(rect.height()),
+                    confidence,
+                }
+            })
+            .collect();
+
+        Ok(lines)
+    }
+
+    /// Decodes an image and prepares it for the OCR engine.
+    ///
+    /// Only the first frame of a multi-frame image is prepared.
+    ///
+    /// # Returns
+    ///
+    /// The OCR input and the factor by which preprocessing upscaled the image.
+    fn prepare_input(&self, content: &[u8]) -> Result<(OcrInput, f64), String> {
+        let img = decode_image(content)?;
+        let scale = self.preprocessing.scale(img.width());
+        Ok((self.prepare_frame(&img)?, scale))
+    }
+
+    /// Preprocesses a decoded image frame and prepares it for the OCR engine.
+    fn prepare_frame(&self, img: &DynamicImage) -> Result<OcrInput, String> {
+        let engine = self.engine()?;
+        let rgb_img = self.preprocessing.apply(img).to_rgb8();
         let (width, height) = rgb_img.dimensions();
         let image_source = ocrs::ImageSource::from_bytes(rgb_img.as_raw(), (width, height))
             .map_err(|e| format!("Failed to create image source: {}", e))?;
 
-        let ocr_input = self
-            .model
+        engine
             .prepare_input(image_source)
-            .map_err(|e| format!("Failed to prepare OCR input: {}", e))?;
+            .map_err(|e| format!("Failed to prepare OCR input: {}", e))
+    }
 
-        let word_rects = self
-            .model
-            .detect_words(&ocr_input)
+    /// Detects, groups, and recognizes the text lines of a prepared image.
+    ///
+    /// Lines in which no text was recognized are omitted.
+    fn recognize_lines(&self, ocr_input: &OcrInput) -> Result<Vec<TextLine>, String> {
+        let engine = self.engine()?;
+        let word_rects = engine
+            .detect_words(ocr_input)
             .map_err(|e| format!("Failed to detect words: {}", e))?;
 
-        let line_rects = self.model.find_text_lines(&ocr_input, &word_rects);
+        let line_rects = engine.find_text_lines(ocr_input, &word_rects);
 
-        let line_texts = self
-            .model
-            .recognize_text(&ocr_input, &line_rects)
+        let line_texts = engine
+            .recognize_text(ocr_input, &line_rects)
             .map_err(|e| format!("OCR recognition failed: {}", e))?;
 
-        let mut extracted_text = String::new();
-        for line_text in line_texts {
-            if let Some(text_line) = line_text {
-                let text = text_line.to_string();
-                if !text.trim().is_empty() {
-                    extracted_text.push_str(&text);
-                    extracted_text.push('\n');
-                }
-            }
-        }
+        Ok(line_texts.into_iter().flatten().collect())
+    }
+}
 
-        let cleaned = extracted_text.trim().to_string();
+/// Decodes the first frame of an image.
+fn decode_image(content: &[u8]) -> Result<DynamicImage, String> {
+    ImageReader::new(Cursor::new(content))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .decode()
+        .map_err(|e| format!("Failed to decode image: {}", e))
+}
 
-        if cleaned.is_empty() {
-            Ok("No text found in image".to_string())
-        } else {
-            Ok(cleaned)
+/// Decodes every frame of an image.
+///
+/// Multi-page TIFFs yield one frame per page and animated GIFs one composited
+/// frame per animation step. Other images, and TIFFs and GIFs with a single
+/// frame, are decoded as usual into a single frame.
+fn decode_frames(content: &[u8]) -> Result<Vec<DynamicImage>, String> {
+    let format = ImageReader::new(Cursor::new(content))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .format();
+
+    let frames = match format {
+        Some(ImageFormat::Tiff) => decode_tiff_pages(content)?,
+        Some(ImageFormat::Gif) => decode_gif_frames(content)?,
+        _ => Vec::new(),
+    };
+
+    if frames.len() > 1 {
+        Ok(frames)
+    } else {
+        Ok(vec![decode_image(content)?])
+    }
+}
+
+/// Decodes the pages of a TIFF, or returns no pages if it has just one.
+///
+/// Pages are decoded with the `tiff` crate, since `image` only reads the
+/// first page. 8- and 16-bit grayscale and RGB pages, with or without alpha,
+/// are supported.
+fn decode_tiff_pages(content: &[u8]) -> Result<Vec<DynamicImage>, String> {
+    let mut decoder = TiffDecoder::new(Cursor::new(content))
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+    if !decoder.more_images() {
+        return Ok(Vec::new());
+    }
+
+    let mut pages = Vec::new();
+    loop {
+        let page = pages.len() + 1;
+        let decode_error =
+            |e: tiff::TiffError| format!("Failed to decode TIFF page {}: {}", page, e);
+        let (width, height) = decoder.dimensions().map_err(decode_error)?;
+        let color_type = decoder.colortype().map_err(decode_error)?;
+        let pixels = decoder.read_image().map_err(decode_error)?;
+
+        let image = match (color_type, pixels) {
+            (ColorType::Gray(8), DecodingResult::U8(data)) => {
+                GrayImage::from_raw(width, height, data).map(DynamicImage::ImageLuma8)
+            }
+            (ColorType::GrayA(8), DecodingResult::U8(data)) => {
+                GrayAlphaImage::from_raw(width, height, data).map(DynamicImage::ImageLumaA8)
+            }
+            (ColorType::RGB(8), DecodingResult::U8(data)) => {
+                RgbImage::from_raw(width, height, data).map(DynamicImage::ImageRgb8)
+            }
+            (ColorType::RGBA(8), DecodingResult::U8(data)) => {
+                RgbaImage::from_raw(width, height, data).map(DynamicImage::ImageRgba8)
+            }
+            (ColorType::Gray(16), DecodingResult::U16(data)) => {
+                ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLuma16)
+            }
+            (ColorType::GrayA(16), DecodingResult::U16(data)) => {
+                ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLumaA16)
+            }
+            (ColorType::RGB(16), DecodingResult::U16(data)) => {
+                ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgb16)
+            }
+            (ColorType::RGBA(16), DecodingResult::U16(data)) => {
+                ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgba16)
+            }
+            (color_type, _) => {
+                return Err(format!(
+                    "Failed to decode TIFF page {}: unsupported color type {:?}",
+                    page, color_type
+                ));
+            }
+        };
+        pages.push(
+            image.ok_or_else(|| {
+                format!("Failed to decode TIFF page {}: truncated pixel data", page)
+            })?,
+        );
+
+        if !decoder.more_images() {
+            return Ok(pages);
         }
+        decoder
+            .next_image()
+            .map_err(|e| format!("Failed to decode TIFF page {}: {}", page + 1, e))?;
     }
 }
 
+/// Decodes the frames of an animated GIF, each composited onto the canvas.
+fn decode_gif_frames(content: &[u8]) -> Result<Vec<DynamicImage>, String> {
+    let decoder = GifDecoder::new(Cursor::new(content))
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+    let frames = decoder
+        .into_frames()
+        .collect_frames()
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+
+    Ok(frames
+        .into_iter()
+        .map(|frame| DynamicImage::ImageRgba8(frame.into_buffer()))
+        .collect())
+}
+
 impl FileHandler for ImageHandler {
     /// Determines if this handler can process image files.
     ///
@@ -212,6 +697,27 @@ impl FileHandler for ImageHandler {
                 || mime_type == "image/webp")
     }
 
+    /// Lists the MIME types this handler accepts.
+    fn supported_mimes(&self) -> Vec<String> {
+        [
+            "image/jpeg",
+            "image/jpg",
+            "image/png",
+            "image/gif",
+            "image/bmp",
+            "image/tiff",
+            "image/webp",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect()
+    }
+
+    /// Returns the handler's name, `ImageHandler`.
+    fn name(&self) -> &str {
+        "ImageHandler"
+    }
+
     /// Extracts text content from an image using OCR.
     ///
     /// This is the main entry point for image text extraction. It delegates
@@ -245,4 +751,155 @@ impl FileHandler for ImageHandler {
     ) -> Result<String, String> {
         self.extract_text_from_image(content)
     }
+
+    /// Returns the estimated peak memory use per input byte.
+    ///
+    /// Compressed images expand greatly when decoded to pixels, and OCR keeps
+    /// several intermediate tensors of the image, roughly thirty times the
+    /// file size (on top of the shared OCR models).
+    fn memory_multiplier(&self) -> f64 {
+        30.0
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_models() {
+        let model_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+        let handler = ImageHandler::with_models(
+            model_dir.join("text-detection-model.rten"),
+            model_dir.join("text-recognition-model.rten"),
+        );
+        assert!(handler.can_handle("image/png"));
+        assert!(handler.engine().is_ok());
+
+        let handler = ImageHandler::with_models_and_alphabet(
+            ImageHandler::default_detection_model(),
+            ImageHandler::default_recognition_model(),
+            "0123456789abcdefghijklmnopqrstuvwxyzñáéíóúü",
+        );
+
+        assert!(handler.can_handle("image/png"));
+        assert!(handler.engine().is_ok());
+    }
+
+    /// Renders the word "HI" as thick black strokes on a white PNG.
+    pub(crate) fn word_image() -> Vec<u8> {
+        let strokes = [
+            (20, 20, 8, 48),
+            (52, 20, 8, 48),
+            (20, 40, 40, 8),
+            (76, 20, 8, 48),
+        ];
+        let img = image::RgbImage::from_fn(120, 90, |x, y| {
+            let in_stroke = strokes
+                .iter()
+                .any(|&(sx, sy, w, h)| (sx..sx + w).contains(&x) && (sy..sy + h).contains(&y));
+            if in_stroke {
+                image::Rgb([0, 0, 0])
+            } else {
+                image::Rgb([255, 255, 255])
+            }
+        });
+
+        let mut png = Vec::new();
+        img.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        png
+    }
+
+    /// Encodes RGB pages as a multi-page TIFF.
+    fn multipage_tiff(pages: &[RgbImage]) -> Vec<u8> {
+        let mut tiff = Cursor::new(Vec::new());
+        let mut encoder = tiff::encoder::TiffEncoder::new(&mut tiff).unwrap();
+        for page in pages {
+            encoder
+                .write_image::<tiff::encoder::colortype::RGB8>(
+                    page.width(),
+                    page.height(),
+                    page.as_raw(),
+                )
+                .unwrap();
+        }
+        tiff.into_inner()
+    }
+
+    #[test]
+    fn test_multipage_tiff_is_recognized_per_page() {
+        let page = image::load_from_memory(&word_image()).unwrap().to_rgb8();
+        let tiff = multipage_tiff(&[page.clone(), page.clone()]);
+        let handler = ImageHandler::new();
+
+        let text = handler
+            .extract_text(&tiff, "scan.tiff", "image/tiff")
+            .unwrap();
+
+        let single = handler
+            .extract_text(&multipage_tiff(&[page]), "scan.tiff", "image/tiff")
+            .unwrap();
+        assert!(!single.contains("--- Page"));
+        assert_ne!(single, "No text found in image");
+        assert_eq!(
+            text,
+            format!("--- Page 1 ---\n{single}\n--- Page 2 ---\n{single}")
+        );
+    }
+
+    #[test]
+    fn test_preprocessing_upscales_and_binarizes() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(10, 4, |x, _| {
+            image::Rgb([x as u8 * 25, 100, 100])
+        }));
+
+        assert!(matches!(
+            ImagePreprocessOptions::default().apply(&img),
+            Cow::Borrowed(_)
+        ));
+
+        let options = ImagePreprocessOptions {
+            binarize_threshold: Some(128),
+            min_width: Some(40),
+            ..Default::default()
+        };
+        let processed = options.apply(&img);
+        assert_eq!((processed.width(), processed.height()), (40, 16));
+        let luma = processed.as_luma8().unwrap();
+        assert!(luma.pixels().all(|pixel| matches!(pixel.0[0], 0 | 255)));
+    }
+
+    #[test]
+    fn test_upscaled_tiny_image_is_recognized() {
+        let tiny = image::load_from_memory(&word_image())
+            .unwrap()
+            .resize_exact(30, 22, FilterType::Triangle);
+        let mut png = Vec::new();
+        tiny.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        let handler = ImageHandler::new().with_preprocessing(ImagePreprocessOptions {
+            grayscale: true,
+            min_width: Some(240),
+            ..Default::default()
+        });
+
+        let text = handler.extract_text(&png, "tiny.png", "image/png").unwrap();
+        assert!(!text.is_empty());
+    }
+
+    #[test]
+    fn test_extract_regions() {
+        let lines = ImageHandler::new().extract_regions(&word_image()).unwrap();
+
+        assert!(!lines.is_empty());
+        for line in &lines {
+            assert!(!line.text.is_empty());
+            assert!(line.width > 0 && line.height > 0);
+            assert!(line.x >= 0 && line.y >= 0);
+            assert!(line.confidence.is_some_and(|c| c > 0.0 && c <= 1.0));
+        }
+    }
 }
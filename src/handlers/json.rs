@@ -0,0 +1,219 @@
+//! JSON file handler for extracting the values of JSON documents.
+//!
+//! This handler parses JSON with `serde_json` and keeps only the string and
+//! number values, so braces, quotes, and repeated key names do not dilute
+//! searches and similarity comparisons.
+
+use crate::core::handler::FileHandler;
+use crate::handlers::text::TextHandler;
+use serde_json::Value;
+
+/// Handler for processing JSON documents.
+///
+/// The `JsonHandler` walks the parsed document depth-first, in document order,
+/// and emits one line per string or number value. Object keys, booleans, and
+/// nulls are skipped, so `{"title": "Report", "tags": ["q1", "sales"]}` becomes:
+///
+/// ```text
+/// Report
+/// q1
+/// sales
+/// ```
+///
+/// # Supported MIME Types
+///
+/// - `application/json` - JSON documents
+/// - `application/ld+json` - JSON-LD linked data documents
+///
+/// # Fallback
+///
+/// Files that fail to parse are not treated as errors; the decoded raw text is
+/// returned instead, so malformed JSON remains searchable.
+pub struct JsonHandler {
+    text_handler: TextHandler,
+}
+
+impl JsonHandler {
+    /// Creates a new `JsonHandler` instance.
+    ///
+    /// # Returns
+    ///
+    /// A new `JsonHandler` ready to process JSON files.
+    pub fn new() -> Self {
+        Self {
+            text_handler: TextHandler::new(),
+        }
+    }
+
+    /// Sets the byte entropy at or above which JSON content is rejected as
+    /// binary before it is decoded (see
+    /// `TextHandler::with_binary_entropy_threshold`).
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Entropy in bits per byte (0-8); above 8 disables the check
+    ///
+    /// # Returns
+    ///
+    /// The handler with the threshold applied.
+    pub fn with_binary_entropy_threshold(mut self, threshold: f64) -> Self {
+        self.text_handler = self.text_handler.with_binary_entropy_threshold(threshold);
+        self
+    }
+
+    /// Parses decoded JSON text into its string and number values.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The decoded JSON text
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The values, one per line, in document order
+    /// * `Err(String)` - Error message if the text is not valid JSON
+    fn extract_text_from_json(&self, text: &str) -> Result<String, String> {
+        let document: Value =
+            serde_json::from_str(text).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+        let mut values = Vec::new();
+        collect_values(&document, &mut values);
+
+        Ok(values.join("\n"))
+    }
+}
+
+/// Appends every non-empty string and every number in `value`, depth-first.
+fn collect_values(value: &Value, values: &mut Vec<String>) {
+    match value {
+        Value::Null | Value::Bool(_) => {}
+        Value::Number(n) => values.push(n.to_string()),
+        Value::String(s) => {
+            let trimmed = s.trim();
+            if !trimmed.is_empty() {
+                values.push(trimmed.to_string());
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_values(item, values);
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values() {
+                collect_values(item, values);
+            }
+        }
+    }
+}
+
+impl FileHandler for JsonHandler {
+    /// Determines if this handler can process JSON files.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_type` - The MIME type string to check
+    ///
+    /// # Returns
+    ///
+    /// `true` for `application/json` and `application/ld+json`, `false` otherwise.
+    fn can_handle(&self, mime_type: &str) -> bool {
+        mime_type == "application/json" || mime_type == "application/ld+json"
+    }
+
+    /// Lists the MIME types this handler accepts.
+    fn supported_mimes(&self) -> Vec<String> {
+        ["application/json", "application/ld+json"]
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    /// Returns the handler's name, `JsonHandler`.
+    fn name(&self) -> &str {
+        "JsonHandler"
+    }
+
+    /// Extracts the string and number values of a JSON document.
+    ///
+    /// Decodes the content with `TextHandler`, then delegates to
+    /// `extract_text_from_json()`. If parsing fails, the decoded raw text is
+    /// returned instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw JSON file content as a byte slice
+    /// * `filename` - The filename, passed through to `TextHandler`
+    /// * `mime_type` - The MIME type, passed through to `TextHandler`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The values, or the raw text for invalid JSON
+    /// * `Err(String)` - Error message if the content cannot be decoded as text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::json::JsonHandler;
+    /// # use crate::core::handler::FileHandler;
+    /// let handler = JsonHandler::new();
+    /// let text = handler.extract_text(br#"{"name": "Ada", "age": 36}"#, "user.json", "application/json");
+    /// assert_eq!(text.unwrap(), "Ada\n36");
+    /// ```
+    fn extract_text(
+        &self,
+        content: &[u8],
+        filename: &str,
+        mime_type: &str,
+    ) -> Result<String, String> {
+        let text = self
+            .text_handler
+            .extract_text(content, filename, mime_type)?;
+
+        Ok(self.extract_text_from_json(&text).unwrap_or(text))
+    }
+
+    /// Returns the estimated peak memory use per input byte.
+    ///
+    /// JSON is decoded and parsed into a value tree, roughly four times the
+    /// file size.
+    fn memory_multiplier(&self) -> f64 {
+        4.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_values_from_nested_json() {
+        let json = r#"{
+            "title": "Quarterly report",
+            "meta": {"author": {"name": "Ada"}, "draft": false, "reviewer": null},
+            "sections": [
+                {"heading": "Revenue", "figures": [12.5, 40]},
+                {"heading": "Outlook", "notes": ["", "steady growth"]}
+            ]
+        }"#;
+
+        let text = JsonHandler::new()
+            .extract_text(json.as_bytes(), "report.json", "application/json")
+            .unwrap();
+
+        assert_eq!(
+            text,
+            "Quarterly report\nAda\nRevenue\n12.5\n40\nOutlook\nsteady growth"
+        );
+    }
+
+    #[test]
+    fn test_malformed_json_falls_back_to_raw_text() {
+        let json = r#"{"title": "Unfinished"#;
+
+        let text = JsonHandler::new()
+            .extract_text(json.as_bytes(), "broken.json", "application/json")
+            .unwrap();
+
+        assert_eq!(text, json);
+    }
+}
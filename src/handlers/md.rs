@@ -0,0 +1,204 @@
+//! Markdown file handler for extracting plain text.
+//!
+//! This handler uses the `pulldown-cmark` parser to read CommonMark (plus
+//! GitHub-style tables and strikethrough) and keep only the text a reader
+//! would see once the document is rendered, without any Markdown syntax.
+
+use crate::core::handler::FileHandler;
+use crate::handlers::text::TextHandler;
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+
+/// Handler for processing Markdown documents.
+///
+/// The `MarkdownHandler` renders Markdown to plain text, so that formatting
+/// syntax does not skew similarity comparisons:
+///
+/// - Heading markers (`#`) are dropped, keeping the heading text
+/// - Emphasis (`*`, `_`, `~~`) is stripped, keeping the emphasized text
+/// - Links keep their text and drop their URL; images keep their alt text
+/// - Inline code and code blocks keep their inner text, without backticks or
+///   fences
+/// - Table cells are separated by tabs, one row per line
+///
+/// Paragraphs, headings, list items, and code block lines are placed on their
+/// own lines.
+///
+/// # Supported MIME Types
+///
+/// - `text/markdown` - Markdown documents
+/// - `text/x-markdown` - Markdown documents (legacy MIME type)
+///
+/// # Limitations
+///
+/// - Raw HTML embedded in the document is dropped, including its text
+/// - List markers and blockquote markers are dropped, not rendered
+pub struct MarkdownHandler {
+    text_handler: TextHandler,
+}
+
+impl MarkdownHandler {
+    /// Creates a new `MarkdownHandler` instance.
+    ///
+    /// # Returns
+    ///
+    /// A new `MarkdownHandler` ready to process Markdown files.
+    pub fn new() -> Self {
+        Self {
+            text_handler: TextHandler::new(),
+        }
+    }
+
+    /// Sets the byte entropy at or above which Markdown content is rejected as
+    /// binary before it is decoded (see
+    /// `TextHandler::with_binary_entropy_threshold`).
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Entropy in bits per byte (0-8); above 8 disables the check
+    ///
+    /// # Returns
+    ///
+    /// The handler with the threshold applied.
+    pub fn with_binary_entropy_threshold(mut self, threshold: f64) -> Self {
+        self.text_handler = self.text_handler.with_binary_entropy_threshold(threshold);
+        self
+    }
+
+    /// Renders a Markdown document to plain text.
+    ///
+    /// # Arguments
+    ///
+    /// * `markdown` - The decoded Markdown source
+    ///
+    /// # Returns
+    ///
+    /// The plain text, one line per block and without empty lines.
+    fn extract_text_from_markdown(&self, markdown: &str) -> String {
+        let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+
+        let mut text = String::new();
+        for event in Parser::new_ext(markdown, options) {
+            match event {
+                Event::Text(value) | Event::Code(value) => text.push_str(&value),
+                Event::SoftBreak => text.push(' '),
+                Event::HardBreak | Event::Rule => text.push('\n'),
+                Event::Start(Tag::Item) => text.push('\n'),
+                Event::End(
+                    TagEnd::Paragraph
+                    | TagEnd::Heading(_)
+                    | TagEnd::Item
+                    | TagEnd::CodeBlock
+                    | TagEnd::TableHead
+                    | TagEnd::TableRow,
+                ) => text.push('\n'),
+                Event::End(TagEnd::TableCell) => text.push('\t'),
+                _ => {}
+            }
+        }
+
+        text.lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl FileHandler for MarkdownHandler {
+    /// Determines if this handler can process Markdown files.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_type` - The MIME type string to check
+    ///
+    /// # Returns
+    ///
+    /// `true` for `text/markdown` and `text/x-markdown`, `false` otherwise.
+    fn can_handle(&self, mime_type: &str) -> bool {
+        mime_type == "text/markdown" || mime_type == "text/x-markdown"
+    }
+
+    /// Lists the MIME types this handler accepts.
+    fn supported_mimes(&self) -> Vec<String> {
+        ["text/markdown", "text/x-markdown"]
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    /// Returns the handler's name, `MarkdownHandler`.
+    fn name(&self) -> &str {
+        "MarkdownHandler"
+    }
+
+    /// Extracts plain text content from a Markdown document.
+    ///
+    /// Decodes the content with `TextHandler`, then delegates to
+    /// `extract_text_from_markdown()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw Markdown file content as a byte slice
+    /// * `filename` - The filename, passed through to `TextHandler`
+    /// * `mime_type` - The MIME type, passed through to `TextHandler`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The plain text content
+    /// * `Err(String)` - Error message if the content cannot be decoded as text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::md::MarkdownHandler;
+    /// # use crate::core::handler::FileHandler;
+    /// let handler = MarkdownHandler::new();
+    /// let markdown = b"# Setup\n\nRun `make` **first**.";
+    /// let text = handler.extract_text(markdown, "README.md", "text/markdown");
+    /// assert_eq!(text.unwrap(), "Setup\nRun make first.");
+    /// ```
+    fn extract_text(
+        &self,
+        content: &[u8],
+        filename: &str,
+        mime_type: &str,
+    ) -> Result<String, String> {
+        let markdown = self
+            .text_handler
+            .extract_text(content, filename, mime_type)?;
+        Ok(self.extract_text_from_markdown(&markdown))
+    }
+
+    /// Returns the estimated peak memory use per input byte.
+    ///
+    /// Markdown is decoded and parsed into events alongside the output text,
+    /// roughly three times the file size.
+    fn memory_multiplier(&self) -> f64 {
+        3.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_text_from_markdown() {
+        let markdown = "# Release notes\n\
+            \n\
+            The **new** importer is described in the [guide](https://example.com/guide).\n\
+            \n\
+            ```sh\n\
+            npm install\n\
+            ```\n";
+
+        let text = MarkdownHandler::new()
+            .extract_text(markdown.as_bytes(), "notes.md", "text/markdown")
+            .unwrap();
+
+        assert_eq!(
+            text,
+            "Release notes\nThe new importer is described in the guide.\nnpm install"
+        );
+    }
+}
@@ -1,5 +1,15 @@
+pub mod config;
+pub mod csv;
 pub mod docx;
+pub mod eml;
+pub mod epub;
+pub mod gzip;
+pub mod html;
 pub mod image;
+pub mod json;
+pub mod md;
 pub mod pdf;
+pub mod pptx;
 pub mod text;
 pub mod xlsx;
+pub mod zip;
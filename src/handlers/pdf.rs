@@ -1,10 +1,28 @@
 //! PDF file handler for extracting text from PDF documents.
 //!
-//! This handler uses the `pdf-extract` library to parse PDF files and extract
-//! readable text content from them.
+//! This handler uses the `pdf-extract` library (built on `lopdf`) to parse PDF
+//! files and extract readable text content from them.
 
 use crate::core::handler::FileHandler;
-use pdf_extract::extract_text_from_mem;
+use crate::handlers::image::ImageHandler;
+use crate::models::options::ProcessOptions;
+use crate::models::pdf::PdfMetadata;
+use fax::Color;
+use fax::decoder::{decode_g3, decode_g4, pels};
+use image::{DynamicImage, GrayImage, ImageFormat, Luma, RgbImage};
+use pdf_extract::xobject::PdfImage;
+use pdf_extract::{
+    Dictionary, Document, Object, PlainTextOutput, decode_text_string, output_doc, output_doc_page,
+};
+use std::sync::Arc;
+
+/// Extracted text with fewer letters and digits than this counts as missing,
+/// so the OCR fallback runs on it (see `text_chars`).
+const OCR_FALLBACK_MIN_CHARS: usize = 16;
+
+/// The largest CCITT fax image decoded for OCR, in pixels (about four A4
+/// pages scanned at 600 dpi). Larger dimensions are treated as invalid data.
+const MAX_CCITT_PIXELS: u64 = 1 << 27;
 
 /// Handler for processing PDF (Portable Document Format) files.
 ///
@@ -25,12 +43,78 @@ use pdf_extract::extract_text_from_mem;
 ///    - Joins lines with newline characters
 /// 3. Returns the cleaned text content
 ///
+/// How lines are joined can be changed with `with_clean_options()` (see
+/// `PdfCleanOptions`): PDFs that place one word per line can have their lines
+/// rejoined into sentences, and blank lines can be kept as paragraph breaks.
+///
+/// # Page Markers
+///
+/// When created with `with_page_markers(true)`, the handler extracts each page
+/// separately and prefixes it with a `--- Page N ---` marker, so callers can
+/// tell which page a piece of text came from.
+///
+/// # Encrypted PDFs
+///
+/// PDFs encrypted with an empty open password (typically only an owner
+/// password restricting printing or copying) are decrypted automatically.
+/// PDFs that need an open password cannot be extracted: the underlying `lopdf`
+/// parser does not load their objects. Extraction fails with an error that
+/// says whether a password is required, the password set with
+/// `with_password()` is wrong, or it is right but the document is unsupported.
+///
+/// # OCR Fallback
+///
+/// Scanned PDFs hold each page as an image, so they have no text to extract.
+/// When created with `with_ocr_fallback()`, the handler recognizes the images
+/// of every page with the given `ImageHandler` whenever the extracted text has
+/// fewer than 16 letters and digits, and returns the recognized text instead
+/// if OCR finds more.
+/// Page images are read as stored rather than rendering the pages: JPEG,
+/// CCITT Group 4 fax images (and Group 3 with end-of-line markers), 1-, 2-,
+/// 4-, or 8-bit grayscale samples and image masks, and 8-bit RGB samples
+/// (optionally Flate-compressed). Text drawn as vector paths is not
+/// recognized, and images in other encodings (JBIG2, JPEG 2000, CMYK, ...)
+/// are skipped. When neither extraction nor OCR finds any text and images
+/// were skipped, the extraction fails with an error naming their encodings
+/// instead of returning empty text.
+///
 /// # Limitations
 ///
 /// - Extracts text only (no images, tables, or complex layouts)
 /// - May not preserve exact formatting or structure
-/// - Scanned PDFs (image-based) require OCR and should use ImageHandler instead
-pub struct PdfHandler;
+/// - Scanned PDFs (image-based) yield no text without the OCR fallback
+pub struct PdfHandler {
+    /// Whether extracted text is split per page with `--- Page N ---` markers.
+    page_markers: bool,
+    /// Open password checked against encrypted PDFs.
+    password: Option<String>,
+    /// How extracted lines are joined.
+    clean_options: PdfCleanOptions,
+    /// Handler recognizing the page images of PDFs without extractable text.
+    ocr_fallback: Option<Arc<ImageHandler>>,
+}
+
+/// Settings for joining the lines of extracted PDF text.
+///
+/// Lines are always trimmed. Runs of non-empty lines form paragraphs, which
+/// are separated by one or more blank lines. The default keeps every line on
+/// its own line and drops the blank lines.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PdfCleanOptions {
+    /// Join the lines of a paragraph with spaces instead of line breaks.
+    pub collapse_linebreaks: bool,
+    /// Separate paragraphs with a blank line instead of a single line break.
+    pub preserve_paragraphs: bool,
+}
+
+impl From<&ProcessOptions> for PdfCleanOptions {
+    fn from(options: &ProcessOptions) -> Self {
+        Self {
+            collapse_linebreaks: options.pdf_collapse_linebreaks.unwrap_or(false),
+            preserve_paragraphs: options.pdf_preserve_paragraphs.unwrap_or(false),
+        }
+    }
+}
 
 impl PdfHandler {
     /// Creates a new `PdfHandler` instance.
@@ -39,8 +123,531 @@ impl PdfHandler {
     ///
     /// A new `PdfHandler` ready to process PDF files.
     pub fn new() -> Self {
-        Self
+        Self {
+            page_markers: false,
+            password: None,
+            clean_options: PdfCleanOptions::default(),
+            ocr_fallback: None,
+        }
+    }
+
+    /// Enables or disables per-page extraction with page markers.
+    ///
+    /// # Arguments
+    ///
+    /// * `page_markers` - If `true`, `extract_text` delegates to `extract_text_paged`
+    ///
+    /// # Returns
+    ///
+    /// The handler with the updated setting.
+    pub fn with_page_markers(mut self, page_markers: bool) -> Self {
+        self.page_markers = page_markers;
+        self
+    }
+
+    /// Sets the open password of encrypted PDFs.
+    ///
+    /// The password is checked against documents that need an open password,
+    /// so a wrong password is reported as such. See "Encrypted PDFs" above for
+    /// why these documents still cannot be extracted. Unencrypted PDFs are
+    /// extracted as usual.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The PDF's open (user) password
+    ///
+    /// # Returns
+    ///
+    /// The handler with the password set.
+    pub fn with_password(mut self, password: String) -> Self {
+        self.password = Some(password);
+        self
+    }
+
+    /// Sets how the lines of extracted text are joined.
+    ///
+    /// # Arguments
+    ///
+    /// * `clean_options` - The line and paragraph joining settings
+    ///
+    /// # Returns
+    ///
+    /// The handler with the updated settings.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::pdf::{PdfCleanOptions, PdfHandler};
+    /// let handler = PdfHandler::new().with_clean_options(PdfCleanOptions {
+    ///     collapse_linebreaks: true,
+    ///     preserve_paragraphs: true,
+    /// });
+    /// ```
+    pub fn with_clean_options(mut self, clean_options: PdfCleanOptions) -> Self {
+        self.clean_options = clean_options;
+        self
+    }
+
+    /// Enables OCR of the page images of PDFs without extractable text.
+    ///
+    /// See "OCR Fallback" above for when the fallback runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `ocr` - The handler recognizing the page images; sharing the one
+    ///   used for image files loads the OCR models only once
+    ///
+    /// # Returns
+    ///
+    /// The handler with the OCR fallback enabled.
+    pub fn with_ocr_fallback(mut self, ocr: Arc<ImageHandler>) -> Self {
+        self.ocr_fallback = Some(ocr);
+        self
+    }
+
+    /// Loads a PDF document, checking that encrypted documents were decrypted.
+    ///
+    /// `lopdf` decrypts documents with an empty open password while loading,
+    /// but skips the objects of documents that need a real one, so those
+    /// cannot be extracted even with the right password.
+    fn load_document(&self, content: &[u8]) -> Result<Document, String> {
+        let document =
+            Document::load_mem(content).map_err(|e| format!("PDF extraction failed: {}", e))?;
+
+        if document.is_encrypted() && document.encryption_state.is_none() {
+            let error = match &self.password {
+                None => "PDF is encrypted and requires a password",
+                Some(password) if document.authenticate_password(password).is_err() => {
+                    "incorrect password for encrypted PDF"
+                }
+                Some(_) => {
+                    "password accepted, but PDFs encrypted with an open password are not supported"
+                }
+            };
+            return Err(format!("PDF extraction failed: {}", error));
+        }
+
+        Ok(document)
+    }
+
+    /// Extracts text from a PDF document page by page.
+    ///
+    /// Each page is cleaned the same way as in `extract_text` and prefixed with
+    /// a `--- Page N ---` marker (1-based). Pages without text still get a
+    /// marker so page numbers stay aligned with the document.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw PDF file content as a byte slice
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The text of all pages, each preceded by its page marker
+    /// * `Err(String)` - Error message if extraction fails (e.g., "PDF extraction failed: ...")
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::pdf::PdfHandler;
+    /// let handler = PdfHandler::new();
+    /// let text = handler.extract_text_paged(&pdf_bytes)?;
+    /// // "--- Page 1 ---\nFirst page text\n--- Page 2 ---\nSecond page text"
+    /// ```
+    pub fn extract_text_paged(&self, content: &[u8]) -> Result<String, String> {
+        let document = self.load_document(content)?;
+        let page_count = document.get_pages().len() as u32;
+        // Like `pdf_extract::extract_text_from_mem_by_pages`, stop at the first unreadable page
+        let pages: Vec<String> = (1..=page_count)
+            .map_while(|page_num| {
+                let mut page = String::new();
+                output_doc_page(&document, &mut PlainTextOutput::new(&mut page), page_num)
+                    .ok()
+                    .map(|_| clean_text(&page, &self.clean_options))
+            })
+            .collect();
+
+        Ok(join_pages(&pages))
+    }
+
+    /// Recognizes the text of the images on each page of a loaded document.
+    ///
+    /// # Arguments
+    ///
+    /// * `document` - The loaded PDF document
+    /// * `ocr` - The handler recognizing the images
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((String, Vec<String>))` - The recognized text, with page markers
+    ///   if enabled, and the encodings of the images that could not be
+    ///   decoded, without duplicates
+    /// * `Err(String)` - Error message if OCR fails (e.g., a model is missing)
+    fn recognize_pages(
+        &self,
+        document: &Document,
+        ocr: &ImageHandler,
+    ) -> Result<(String, Vec<String>), String> {
+        let mut pages = Vec::new();
+        let mut skipped: Vec<String> = Vec::new();
+        for page_id in document.get_pages().into_values() {
+            // Pages without image resources have no images to recognize
+            let images = document.get_page_images(page_id).unwrap_or_default();
+            let mut lines = Vec::new();
+            for image in &images {
+                let image = match decode_page_image(document, image) {
+                    Ok(image) => image,
+                    Err(encoding) => {
+                        if !skipped.contains(&encoding) {
+                            skipped.push(encoding);
+                        }
+                        continue;
+                    }
+                };
+                let text = ocr.recognize_text(&image)?;
+                if !text.is_empty() {
+                    lines.push(text);
+                }
+            }
+            pages.push(lines.join("\n"));
+        }
+
+        let text = if self.page_markers {
+            join_pages(&pages)
+        } else {
+            pages.retain(|page| !page.is_empty());
+            pages.join("\n")
+        };
+        Ok((text, skipped))
+    }
+
+    /// Reads the metadata of a PDF document.
+    ///
+    /// The title, author, subject, and creation date are read from the
+    /// document information dictionary; the page count from the page tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw PDF file content as a byte slice
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PdfMetadata)` - The document's metadata, with `None` for missing entries
+    /// * `Err(String)` - Error message if the PDF cannot be loaded (e.g., "PDF extraction failed: ...")
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::pdf::PdfHandler;
+    /// let handler = PdfHandler::new();
+    /// let metadata = handler.extract_metadata(&pdf_bytes)?;
+    /// println!("{:?} ({} pages)", metadata.title, metadata.page_count);
+    /// ```
+    pub fn extract_metadata(&self, content: &[u8]) -> Result<PdfMetadata, String> {
+        let document = self.load_document(content)?;
+
+        let info = document
+            .trailer
+            .get(b"Info")
+            .and_then(|info| document.dereference(info))
+            .and_then(|(_, info)| info.as_dict())
+            .ok();
+        let entry = |key: &[u8]| info.and_then(|info| info_string(&document, info, key));
+
+        Ok(PdfMetadata {
+            title: entry(b"Title"),
+            author: entry(b"Author"),
+            subject: entry(b"Subject"),
+            page_count: document.get_pages().len() as u32,
+            created: entry(b"CreationDate").map(|date| parse_pdf_date(&date).unwrap_or(date)),
+        })
+    }
+}
+
+/// Reads a text string entry of the document information dictionary.
+///
+/// Returns `None` if the entry is missing, not a string, or blank.
+fn info_string(document: &Document, info: &Dictionary, key: &[u8]) -> Option<String> {
+    let (_, value) = document.dereference(info.get(key).ok()?).ok()?;
+    let text = decode_text_string(value).ok()?;
+    let text = text.trim();
+
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Converts a PDF date (`D:YYYYMMDDHHmmSSOHH'mm'`) to ISO 8601.
+///
+/// Every part after the year is optional and defaults to the start of its
+/// range; `O` is `Z`, `+`, or `-`. Dates without a time zone get none.
+///
+/// # Returns
+///
+/// The ISO 8601 date and time, or `None` if `date` doesn't start with the
+/// (optionally `D:`-prefixed) four-digit year.
+fn parse_pdf_date(date: &str) -> Option<String> {
+    let date = date.strip_prefix("D:").unwrap_or(date);
+    let digit_count = date.bytes().take_while(u8::is_ascii_digit).count();
+    if digit_count < 4 {
+        return None;
+    }
+    let (digits, zone) = date.split_at(digit_count);
+    let part =
+        |start: usize, default: &'static str| digits.get(start..start + 2).unwrap_or(default);
+
+    let zone = match zone.chars().next() {
+        Some('Z') => "Z".to_string(),
+        Some(sign @ ('+' | '-')) => {
+            let offset: String = zone.chars().filter(char::is_ascii_digit).collect();
+            match (offset.get(..2), offset.get(2..4)) {
+                (Some(hours), minutes) => format!("{}{}:{}", sign, hours, minutes.unwrap_or("00")),
+                (None, _) => String::new(),
+            }
+        }
+        _ => String::new(),
+    };
+
+    Some(format!(
+        "{}-{}-{}T{}:{}:{}{}",
+        &digits[..4],
+        part(4, "01"),
+        part(6, "01"),
+        part(8, "00"),
+        part(10, "00"),
+        part(12, "00"),
+        zone
+    ))
+}
+
+/// Joins the cleaned text of each page, prefixing it with a `--- Page N ---` marker.
+fn join_pages(pages: &[String]) -> String {
+    pages
+        .iter()
+        .enumerate()
+        .map(|(idx, page)| {
+            if page.is_empty() {
+                format!("--- Page {} ---", idx + 1)
+            } else {
+                format!("--- Page {} ---\n{}", idx + 1, page)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Counts the letters and digits of extracted text, ignoring page markers.
+fn text_chars(text: &str) -> usize {
+    text.lines()
+        .filter(|line| !(line.starts_with("--- Page ") && line.ends_with(" ---")))
+        .flat_map(str::chars)
+        .filter(|c| c.is_alphanumeric())
+        .count()
+}
+
+/// Decodes an image XObject of a page for OCR.
+///
+/// JPEG (`DCTDecode`) images are decoded as files, and CCITT fax images with
+/// `decode_ccitt_image`. Other images must be uncompressed or use
+/// `FlateDecode`, and hold `DeviceGray` samples or an image mask of 1, 2, 4,
+/// or 8 bits, or 8-bit `DeviceRGB` samples.
+///
+/// # Returns
+///
+/// * `Ok(DynamicImage)` - The decoded image
+/// * `Err(String)` - The image's encoding (e.g. `JBIG2Decode`) if it is not
+///   supported or its data is invalid
+fn decode_page_image(document: &Document, image: &PdfImage) -> Result<DynamicImage, String> {
+    let filters = image.filters.as_deref().unwrap_or_default();
+    let bits = image.bits_per_component.unwrap_or(1);
+    let color_space = image.color_space.as_deref();
+    let image_mask = matches!(
+        image.origin_dict.get(b"ImageMask"),
+        Ok(Object::Boolean(true))
+    );
+    let encoding = || match (filters, color_space) {
+        ([], Some(color_space)) => format!("{}-bit {}", bits, color_space),
+        ([], None) => format!("{}-bit samples", bits),
+        _ => filters.join(" + "),
+    };
+
+    let width = u32::try_from(image.width).map_err(|_| encoding())?;
+    let height = u32::try_from(image.height).map_err(|_| encoding())?;
+    match filters {
+        [filter] if filter == "DCTDecode" => {
+            return image::load_from_memory_with_format(image.content, ImageFormat::Jpeg)
+                .map_err(|_| encoding());
+        }
+        [filter] if filter == "CCITTFaxDecode" => {
+            return decode_ccitt_image(image, width, height)
+                .map(DynamicImage::ImageLuma8)
+                .ok_or_else(encoding);
+        }
+        [] => {}
+        [filter] if filter == "FlateDecode" => {}
+        _ => return Err(encoding()),
+    }
+
+    let samples = match filters {
+        [] => image.content.to_vec(),
+        _ => document
+            .get_object(image.id)
+            .and_then(|object| object.as_stream())
+            .and_then(|stream| stream.decompressed_content())
+            .map_err(|_| encoding())?,
+    };
+    let decoded = match color_space {
+        _ if image_mask => unpack_gray(&samples, width, height, 1, inverted_decode(image)),
+        Some("DeviceGray") if matches!(bits, 1 | 2 | 4 | 8) => {
+            unpack_gray(&samples, width, height, bits as u32, inverted_decode(image))
+        }
+        Some("DeviceRGB") if bits == 8 => {
+            RgbImage::from_raw(width, height, samples).map(DynamicImage::ImageRgb8)
+        }
+        _ => None,
+    };
+    decoded.ok_or_else(encoding)
+}
+
+/// Checks whether an image's `Decode` array maps samples in reverse
+/// (`[1 0]`), as bilevel scans often do to store black as 1.
+fn inverted_decode(image: &PdfImage) -> bool {
+    let Ok(Object::Array(decode)) = image.origin_dict.get(b"Decode") else {
+        return false;
+    };
+    match decode.as_slice() {
+        [low, high, ..] => low.as_float().unwrap_or(0.0) > high.as_float().unwrap_or(1.0),
+        _ => false,
+    }
+}
+
+/// Unpacks grayscale samples of `bits` bits each, with every row starting on
+/// a byte boundary, into an 8-bit grayscale image.
+///
+/// # Returns
+///
+/// The image, or `None` if `samples` holds fewer rows than `height`.
+fn unpack_gray(
+    samples: &[u8],
+    width: u32,
+    height: u32,
+    bits: u32,
+    invert: bool,
+) -> Option<DynamicImage> {
+    let row_bytes = (width as usize * bits as usize).div_ceil(8);
+    if samples.len() < row_bytes * height as usize {
+        return None;
+    }
+
+    let max = (1u32 << bits) - 1;
+    let image = GrayImage::from_fn(width, height, |x, y| {
+        let bit = x as usize * bits as usize;
+        let byte = samples[y as usize * row_bytes + bit / 8];
+        let shift = 8 - bits as usize - bit % 8;
+        let value = (byte as u32 >> shift) & max;
+        let value = if invert { max - value } else { value };
+        Luma([(value * 255 / max) as u8])
+    });
+    Some(DynamicImage::ImageLuma8(image))
+}
+
+/// Decodes a CCITT fax image (`CCITTFaxDecode`) into an 8-bit grayscale
+/// image.
+///
+/// Group 4 (`K < 0`) data is decoded, and one-dimensional Group 3 (`K = 0`)
+/// data if its rows start with end-of-line markers; mixed two-dimensional
+/// Group 3 (`K > 0`) is not supported. Rows missing from truncated data are
+/// left out, so the image can be shorter than its declared height.
+///
+/// # Returns
+///
+/// The image, or `None` if the encoding is not supported, the data cannot be
+/// decoded, or the image has more than `MAX_CCITT_PIXELS` pixels.
+fn decode_ccitt_image(image: &PdfImage, width: u32, height: u32) -> Option<GrayImage> {
+    // `DecodeParms` is a dictionary, or an array with one per filter
+    let params = match image.origin_dict.get(b"DecodeParms") {
+        Ok(Object::Dictionary(params)) => Some(params),
+        Ok(Object::Array(params)) => params.first().and_then(|p| p.as_dict().ok()),
+        _ => None,
+    };
+    let param = |key: &[u8]| params.and_then(|params| params.get(key).ok());
+    let k = param(b"K").and_then(|k| k.as_i64().ok()).unwrap_or(0);
+    let black_is_1 = matches!(param(b"BlackIs1"), Some(Object::Boolean(true)));
+    let columns = param(b"Columns")
+        .and_then(|columns| columns.as_i64().ok())
+        .unwrap_or(1728);
+    let columns = u16::try_from(columns).ok().filter(|&c| c as u32 == width)?;
+
+    // The sample of a black pixel is 1 with `BlackIs1`, and a sample of 1
+    // is white unless `Decode` is inverted
+    let black = if black_is_1 == inverted_decode(image) {
+        0
+    } else {
+        255
+    };
+    // The dimensions come from the file, so they are checked before decoding,
+    // and the pixels grow with the rows actually decoded
+    let size = width as u64 * height as u64;
+    if size > MAX_CCITT_PIXELS {
+        return None;
+    }
+    let size = size as usize;
+    let mut pixels = Vec::new();
+    let push_line = |transitions: &[u16]| {
+        if pixels.len() < size {
+            pixels.extend(pels(transitions, columns).map(|color| match color {
+                Color::Black => black,
+                Color::White => 255 - black,
+            }));
+        }
+    };
+
+    let bytes = image.content.iter().copied();
+    let decoded = match k {
+        k if k < 0 => decode_g4(bytes, columns, u16::try_from(height).ok(), push_line),
+        0 => decode_g3(bytes, push_line),
+        _ => None,
+    };
+    if decoded.is_none() && pixels.is_empty() {
+        return None;
+    }
+
+    let rows = (pixels.len() / width as usize).min(height as usize);
+    pixels.truncate(rows * width as usize);
+    GrayImage::from_raw(width, rows as u32, pixels)
+}
+
+/// Cleans up extracted PDF text by trimming lines and removing empty ones.
+///
+/// Lines within a paragraph are joined with line breaks, or with spaces if
+/// `collapse_linebreaks` is set; paragraphs are joined with a line break, or
+/// with a blank line if `preserve_paragraphs` is set.
+fn clean_text(text: &str, options: &PdfCleanOptions) -> String {
+    let line_separator = if options.collapse_linebreaks {
+        " "
+    } else {
+        "\n"
+    };
+    let paragraph_separator = if options.preserve_paragraphs {
+        "\n\n"
+    } else {
+        "\n"
+    };
+
+    let mut paragraphs: Vec<Vec<&str>> = vec![Vec::new()];
+    for line in text.lines().map(str::trim) {
+        match paragraphs.last_mut() {
+            Some(paragraph) if line.is_empty() && !paragraph.is_empty() => {
+                paragraphs.push(Vec::new())
+            }
+            Some(paragraph) if !line.is_empty() => paragraph.push(line),
+            _ => {}
+        }
     }
+
+    paragraphs
+        .iter()
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| paragraph.join(line_separator))
+        .collect::<Vec<_>>()
+        .join(paragraph_separator)
 }
 
 impl FileHandler for PdfHandler {
@@ -59,6 +666,16 @@ impl FileHandler for PdfHandler {
         mime_type == "application/pdf"
     }
 
+    /// Lists the MIME types this handler accepts.
+    fn supported_mimes(&self) -> Vec<String> {
+        vec!["application/pdf".to_string()]
+    }
+
+    /// Returns the handler's name, `PdfHandler`.
+    fn name(&self) -> &str {
+        "PdfHandler"
+    }
+
     /// Extracts text content from a PDF document.
     ///
     /// This method extracts text from PDF files loaded in memory and performs
@@ -89,6 +706,13 @@ impl FileHandler for PdfHandler {
     /// - Removing completely empty lines
     /// - Joining non-empty lines with newline characters
     ///
+    /// `PdfCleanOptions` can join the lines of a paragraph with spaces and keep
+    /// blank lines between paragraphs instead.
+    ///
+    /// With the OCR fallback enabled, documents without a text layer return
+    /// the text recognized in their page images (see "OCR Fallback" on
+    /// `PdfHandler`).
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -104,19 +728,542 @@ impl FileHandler for PdfHandler {
         _filename: &str,
         _mime_type: &str,
     ) -> Result<String, String> {
-        match extract_text_from_mem(content) {
-            Ok(text) => {
-                // Clean up the extracted text (remove excessive whitespace)
-                let cleaned = text
-                    .lines()
-                    .map(|line| line.trim())
-                    .filter(|line| !line.is_empty())
-                    .collect::<Vec<_>>()
-                    .join("\n");
-
-                Ok(cleaned)
+        let text = if self.page_markers {
+            self.extract_text_paged(content)?
+        } else {
+            let document = self.load_document(content)?;
+            let mut text = String::new();
+            output_doc(&document, &mut PlainTextOutput::new(&mut text))
+                .map_err(|e| format!("PDF extraction failed: {}", e))?;
+
+            // Clean up the extracted text (remove excessive whitespace)
+            clean_text(&text, &self.clean_options)
+        };
+
+        match &self.ocr_fallback {
+            Some(ocr) if text_chars(&text) < OCR_FALLBACK_MIN_CHARS => {
+                // Loading the document again costs little next to OCR
+                let (recognized, skipped) =
+                    self.recognize_pages(&self.load_document(content)?, ocr)?;
+                if text_chars(&recognized) > text_chars(&text) {
+                    Ok(recognized)
+                } else if text_chars(&text) == 0 && !skipped.is_empty() {
+                    // An empty result would hide that the scan was never read
+                    Err(format!(
+                        "No decodable page images for OCR: unsupported image encoding {}",
+                        skipped.join(", ")
+                    ))
+                } else {
+                    Ok(text)
+                }
+            }
+            _ => Ok(text),
+        }
+    }
+
+    /// Returns the estimated peak memory use per input byte.
+    ///
+    /// PDF parsing keeps the object tree and decompressed content streams in
+    /// memory, roughly four times the file size.
+    fn memory_multiplier(&self) -> f64 {
+        4.0
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::handlers::image::tests::word_image;
+    use lopdf::content::{Content, Operation};
+    use lopdf::{
+        Document, EncryptionState, EncryptionVersion, Object, Permissions, Stream, dictionary,
+    };
+
+    /// Builds a PDF with one page per entry, each page showing its lines of text.
+    pub(crate) fn build_pdf(pages: &[&[&str]]) -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+
+        let mut kids: Vec<Object> = Vec::new();
+        for lines in pages {
+            let mut operations = vec![
+                Operation::new("BT", vec![]),
+                Operation::new("Tf", vec!["F1".into(), 12.into()]),
+                Operation::new("TL", vec![14.into()]),
+                Operation::new("Td", vec![72.into(), 720.into()]),
+            ];
+            for line in *lines {
+                operations.push(Operation::new("Tj", vec![Object::string_literal(*line)]));
+                operations.push(Operation::new("T*", vec![]));
             }
-            Err(e) => Err(format!("PDF extraction failed: {}", e)),
+            operations.push(Operation::new("ET", vec![]));
+
+            let content = Content { operations };
+            let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+            });
+            kids.push(page_id.into());
         }
+
+        let count = kids.len() as i64;
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => count,
+                "Resources" => resources_id,
+                "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// Encrypts a PDF built by `build_pdf` with the given open password.
+    fn encrypt_pdf(pdf: &[u8], password: &str) -> Vec<u8> {
+        let mut doc = Document::load_mem(pdf).unwrap();
+        // Encryption keys are derived from the file identifier
+        let id = Object::string_literal("dms-toolkit-test");
+        doc.trailer.set("ID", vec![id.clone(), id]);
+        let version = EncryptionVersion::V2 {
+            document: &doc,
+            owner_password: "owner",
+            user_password: password,
+            key_length: 128,
+            permissions: Permissions::all(),
+        };
+        let state = EncryptionState::try_from(version).unwrap();
+        doc.encrypt(&state).unwrap();
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_extract_encrypted_pdf() {
+        // Encrypted with only an owner password: decrypted without a password
+        let pdf = encrypt_pdf(&build_pdf(&[&["Confidential terms"]]), "");
+        for handler in [
+            PdfHandler::new(),
+            PdfHandler::new().with_password("anything".to_string()),
+        ] {
+            let text = handler
+                .extract_text(&pdf, "locked.pdf", "application/pdf")
+                .unwrap();
+            assert!(text.contains("Confidential terms"));
+        }
+
+        let pdf = encrypt_pdf(&build_pdf(&[&["Confidential terms"]]), "secret");
+        let extract = |handler: PdfHandler| {
+            handler
+                .extract_text(&pdf, "locked.pdf", "application/pdf")
+                .unwrap_err()
+        };
+
+        assert_eq!(
+            extract(PdfHandler::new()),
+            "PDF extraction failed: PDF is encrypted and requires a password"
+        );
+        assert_eq!(
+            extract(PdfHandler::new().with_password("wrong".to_string())),
+            "PDF extraction failed: incorrect password for encrypted PDF"
+        );
+        assert!(
+            extract(PdfHandler::new().with_password("secret".to_string()))
+                .contains("not supported")
+        );
+
+        // A password does not get in the way of unencrypted documents
+        let plain = PdfHandler::new()
+            .with_password("secret".to_string())
+            .extract_text(
+                &build_pdf(&[&["Public terms"]]),
+                "open.pdf",
+                "application/pdf",
+            )
+            .unwrap();
+        assert!(plain.contains("Public terms"));
+    }
+
+    #[test]
+    fn test_extract_metadata() {
+        let mut doc = Document::load_mem(&build_pdf(&[&["One"], &["Two"], &["Three"]])).unwrap();
+        let info_id = doc.add_object(dictionary! {
+            "Title" => Object::string_literal("Service Agreement"),
+            "Author" => lopdf::text_string("Zoë Fischer"),
+            "Subject" => Object::string_literal("  "),
+            "CreationDate" => Object::string_literal("D:20240115103000+01'00'"),
+        });
+        doc.trailer.set("Info", info_id);
+        let mut pdf = Vec::new();
+        doc.save_to(&mut pdf).unwrap();
+
+        let metadata = PdfHandler::new().extract_metadata(&pdf).unwrap();
+
+        assert_eq!(metadata.title.as_deref(), Some("Service Agreement"));
+        assert_eq!(metadata.author.as_deref(), Some("Zoë Fischer"));
+        assert_eq!(metadata.subject, None);
+        assert_eq!(metadata.page_count, 3);
+        assert_eq!(
+            metadata.created.as_deref(),
+            Some("2024-01-15T10:30:00+01:00")
+        );
+
+        // Documents without an information dictionary only have a page count
+        let metadata = PdfHandler::new()
+            .extract_metadata(&build_pdf(&[&["One"]]))
+            .unwrap();
+        assert_eq!(metadata.title, None);
+        assert_eq!(metadata.created, None);
+        assert_eq!(metadata.page_count, 1);
+    }
+
+    #[test]
+    fn test_parse_pdf_date() {
+        assert_eq!(
+            parse_pdf_date("D:20231105").as_deref(),
+            Some("2023-11-05T00:00:00")
+        );
+        assert_eq!(
+            parse_pdf_date("D:20231105081500Z").as_deref(),
+            Some("2023-11-05T08:15:00Z")
+        );
+        assert_eq!(parse_pdf_date("last tuesday"), None);
+    }
+
+    #[test]
+    fn test_collapse_linebreaks_rejoins_words() {
+        let pdf = build_pdf(&[&["The", "contract", "expires", "in", "June."]]);
+
+        let text = PdfHandler::new()
+            .extract_text(&pdf, "words.pdf", "application/pdf")
+            .unwrap();
+        assert_eq!(text, "The\ncontract\nexpires\nin\nJune.");
+
+        let text = PdfHandler::new()
+            .with_clean_options(PdfCleanOptions {
+                collapse_linebreaks: true,
+                ..Default::default()
+            })
+            .extract_text(&pdf, "words.pdf", "application/pdf")
+            .unwrap();
+        assert_eq!(text, "The contract expires in June.");
+    }
+
+    #[test]
+    fn test_clean_text_paragraphs() {
+        let text = "  First line\nsecond line \n\n\nNext paragraph\n";
+        let clean = |collapse_linebreaks, preserve_paragraphs| {
+            let options = PdfCleanOptions {
+                collapse_linebreaks,
+                preserve_paragraphs,
+            };
+            clean_text(text, &options)
+        };
+
+        assert_eq!(
+            clean(false, false),
+            "First line\nsecond line\nNext paragraph"
+        );
+        assert_eq!(
+            clean(false, true),
+            "First line\nsecond line\n\nNext paragraph"
+        );
+        assert_eq!(
+            clean(true, true),
+            "First line second line\n\nNext paragraph"
+        );
+        assert_eq!(clean(true, false), "First line second line\nNext paragraph");
+    }
+
+    #[test]
+    fn test_extract_text_paged() {
+        let pdf = build_pdf(&[&["First page clause"], &["Second page clause"]]);
+        let handler = PdfHandler::new().with_page_markers(true);
+
+        let text = handler
+            .extract_text(&pdf, "document.pdf", "application/pdf")
+            .unwrap();
+
+        let first = text.find("--- Page 1 ---").unwrap();
+        let second = text.find("--- Page 2 ---").unwrap();
+        assert!(first < second);
+        assert!(text[first..second].contains("First page clause"));
+        assert!(text[second..].contains("Second page clause"));
+
+        let plain = PdfHandler::new()
+            .extract_text(&pdf, "document.pdf", "application/pdf")
+            .unwrap();
+        assert!(!plain.contains("--- Page"));
+    }
+
+    /// Renders three lines of the word "HI" from `word_image`, six words per line.
+    fn text_image() -> DynamicImage {
+        let word = image::load_from_memory(&word_image()).unwrap().to_rgb8();
+        let (width, height) = (word.width() + 30, word.height());
+        let mut page = RgbImage::from_pixel(width * 6, height * 3, image::Rgb([255; 3]));
+        for row in 0..3 {
+            for col in 0..6 {
+                let (x, y) = (col * width, row * height);
+                image::imageops::replace(&mut page, &word, x as i64, y as i64);
+            }
+        }
+        DynamicImage::ImageRgb8(page)
+    }
+
+    /// Builds a scanned PDF: one page per image, each page showing only its
+    /// image. The first page stores RGB samples, the others JPEG files.
+    fn build_scanned_pdf(pages: &[DynamicImage]) -> Vec<u8> {
+        let images = pages
+            .iter()
+            .enumerate()
+            .map(|(idx, page)| {
+                let mut dict = image_dict(page.width(), page.height());
+                dict.set("ColorSpace", "DeviceRGB");
+                dict.set("BitsPerComponent", 8);
+                if idx == 0 {
+                    let mut stream = Stream::new(dict, page.to_rgb8().into_raw());
+                    stream.compress().unwrap();
+                    stream
+                } else {
+                    let mut jpeg = Vec::new();
+                    page.write_to(&mut std::io::Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+                        .unwrap();
+                    dict.set("Filter", "DCTDecode");
+                    Stream::new(dict, jpeg)
+                }
+            })
+            .collect();
+        build_image_pdf(images)
+    }
+
+    /// Returns the dictionary of an image XObject without its encoding.
+    fn image_dict(width: u32, height: u32) -> Dictionary {
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => width as i64,
+            "Height" => height as i64,
+        }
+    }
+
+    /// Builds a PDF with one page per image stream, each page showing only
+    /// its image.
+    fn build_image_pdf(images: Vec<Stream>) -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+
+        let mut kids: Vec<Object> = Vec::new();
+        for image in images {
+            let width = image.dict.get(b"Width").unwrap().as_i64().unwrap();
+            let height = image.dict.get(b"Height").unwrap().as_i64().unwrap();
+            let image_id = doc.add_object(image);
+
+            let content = Content {
+                operations: vec![
+                    Operation::new("q", vec![]),
+                    Operation::new(
+                        "cm",
+                        vec![
+                            width.into(),
+                            0.into(),
+                            0.into(),
+                            height.into(),
+                            0.into(),
+                            0.into(),
+                        ],
+                    ),
+                    Operation::new("Do", vec!["Im1".into()]),
+                    Operation::new("Q", vec![]),
+                ],
+            };
+            let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+                "Resources" => dictionary! { "XObject" => dictionary! { "Im1" => image_id } },
+                "MediaBox" => vec![0.into(), 0.into(), width.into(), height.into()],
+            });
+            kids.push(page_id.into());
+        }
+
+        let count = kids.len() as i64;
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => count,
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_ocr_fallback_for_scanned_pdf() {
+        let scan = text_image();
+        let pdf = build_scanned_pdf(&[scan.clone(), scan.clone()]);
+        let ocr = Arc::new(ImageHandler::new());
+        let expected = ocr.recognize_text(&scan).unwrap();
+        assert!(text_chars(&expected) >= OCR_FALLBACK_MIN_CHARS);
+
+        // Without the fallback, the scan has no text
+        let text = PdfHandler::new()
+            .extract_text(&pdf, "scan.pdf", "application/pdf")
+            .unwrap();
+        assert_eq!(text, "");
+
+        let text = PdfHandler::new()
+            .with_page_markers(true)
+            .with_ocr_fallback(ocr.clone())
+            .extract_text(&pdf, "scan.pdf", "application/pdf")
+            .unwrap();
+        let (first, second) = text.split_once("\n--- Page 2 ---\n").unwrap();
+        assert_eq!(first, format!("--- Page 1 ---\n{expected}"));
+        assert!(text_chars(second) > 0);
+
+        // PDFs with a text layer are extracted as usual
+        let pdf = build_pdf(&[&["Service agreement between the parties"]]);
+        let text = PdfHandler::new()
+            .with_ocr_fallback(ocr)
+            .extract_text(&pdf, "document.pdf", "application/pdf")
+            .unwrap();
+        assert_eq!(text, "Service agreement between the parties");
+    }
+
+    #[test]
+    fn test_ocr_fallback_for_bilevel_scans() {
+        // Scanners store black-and-white pages as 1-bit or CCITT fax images
+        let scan = text_image().to_luma8();
+        let (width, height) = scan.dimensions();
+        let bilevel = GrayImage::from_fn(width, height, |x, y| {
+            Luma([if scan.get_pixel(x, y).0[0] < 128 {
+                0
+            } else {
+                255
+            }])
+        });
+        let ocr = Arc::new(ImageHandler::new());
+        let expected = ocr
+            .recognize_text(&DynamicImage::ImageLuma8(bilevel.clone()))
+            .unwrap();
+        assert!(text_chars(&expected) >= OCR_FALLBACK_MIN_CHARS);
+        let is_black = |x, y| bilevel.get_pixel(x, y).0[0] == 0;
+
+        // Group 4 fax data, black stored as 1
+        let mut encoder = fax::encoder::Encoder::new(fax::VecWriter::new());
+        for y in 0..height {
+            let line = (0..width).map(|x| {
+                if is_black(x, y) {
+                    Color::Black
+                } else {
+                    Color::White
+                }
+            });
+            encoder.encode_line(line, width as u16).unwrap();
+        }
+        let mut dict = image_dict(width, height);
+        dict.set("ColorSpace", "DeviceGray");
+        dict.set("BitsPerComponent", 1);
+        dict.set("Filter", "CCITTFaxDecode");
+        dict.set(
+            "DecodeParms",
+            dictionary! { "K" => -1, "Columns" => width as i64, "BlackIs1" => true },
+        );
+        dict.set("Decode", vec![1.into(), 0.into()]);
+        let ccitt = Stream::new(dict, encoder.finish().unwrap().finish());
+
+        // A Flate-compressed image mask, rows padded to whole bytes
+        let row_bytes = (width as usize).div_ceil(8);
+        let mut packed = vec![0xFFu8; row_bytes * height as usize];
+        for y in 0..height {
+            for x in (0..width).filter(|&x| is_black(x, y)) {
+                packed[y as usize * row_bytes + x as usize / 8] &= !(0x80 >> (x % 8));
+            }
+        }
+        let mut dict = image_dict(width, height);
+        dict.set("ImageMask", true);
+        let mut mask = Stream::new(dict, packed);
+        mask.compress().unwrap();
+
+        let pdf = build_image_pdf(vec![ccitt, mask]);
+        let text = PdfHandler::new()
+            .with_page_markers(true)
+            .with_ocr_fallback(ocr.clone())
+            .extract_text(&pdf, "scan.pdf", "application/pdf")
+            .unwrap();
+        assert_eq!(
+            text,
+            format!("--- Page 1 ---\n{expected}\n--- Page 2 ---\n{expected}")
+        );
+
+        // A scan whose images cannot be decoded fails instead of being empty
+        let mut dict = image_dict(width, height);
+        dict.set("ColorSpace", "DeviceGray");
+        dict.set("BitsPerComponent", 1);
+        dict.set("Filter", "JBIG2Decode");
+        let pdf = build_image_pdf(vec![Stream::new(dict, vec![0; 16])]);
+        let err = PdfHandler::new()
+            .with_ocr_fallback(ocr)
+            .extract_text(&pdf, "scan.pdf", "application/pdf")
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "No decodable page images for OCR: unsupported image encoding JBIG2Decode"
+        );
+    }
+
+    #[test]
+    fn test_oversized_ccitt_image_is_rejected() {
+        // A few bytes of fax data claiming billions of rows
+        let mut encoder = fax::encoder::Encoder::new(fax::VecWriter::new());
+        encoder
+            .encode_line(std::iter::repeat_n(Color::White, 1728), 1728)
+            .unwrap();
+        let mut dict = image_dict(1728, u32::MAX);
+        dict.set("ColorSpace", "DeviceGray");
+        dict.set("BitsPerComponent", 1);
+        dict.set("Filter", "CCITTFaxDecode");
+        dict.set("DecodeParms", dictionary! { "K" => -1, "Columns" => 1728 });
+        let ccitt = Stream::new(dict, encoder.finish().unwrap().finish());
+
+        let pdf = build_image_pdf(vec![ccitt]);
+        let err = PdfHandler::new()
+            .with_ocr_fallback(Arc::new(ImageHandler::new()))
+            .extract_text(&pdf, "scan.pdf", "application/pdf")
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "No decodable page images for OCR: unsupported image encoding CCITTFaxDecode"
+        );
     }
 }
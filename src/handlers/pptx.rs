@@ -0,0 +1,406 @@
+//! PPTX file handler for extracting text from Microsoft PowerPoint presentations.
+//!
+//! PPTX files are ZIP packages of XML parts, one per slide. This handler reads
+//! the slide parts with `zip` and collects their text runs (`a:t` elements)
+//! with `quick-xml`.
+
+use crate::core::handler::FileHandler;
+use quick_xml::Reader;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::Event;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+/// Handler for processing Microsoft PowerPoint presentations (PPTX format).
+///
+/// The `PptxHandler` extracts the text of every slide in presentation order.
+/// Each slide starts with a `Slide N:` marker line (numbered from 1), followed
+/// by one line per text paragraph on the slide.
+///
+/// # Supported MIME Types
+///
+/// - `application/vnd.openxmlformats-officedocument.presentationml.presentation` - Standard PPTX format
+///
+/// # Processing Flow
+///
+/// 1. Opens the PPTX package as a ZIP archive
+/// 2. Determines the slide order from `ppt/presentation.xml` and its
+///    relationships (falling back to the slide file numbers)
+/// 3. For each slide:
+///    - Adds a `Slide N:` marker line
+///    - Collects the text of all `a:t` runs, one line per paragraph
+/// 4. Separates slides with double newlines
+/// 5. Trims the final output
+///
+/// # Output Format
+///
+/// The extracted text follows this structure:
+/// ```
+/// Slide 1:
+/// Quarterly Review
+/// Revenue grew 12%
+///
+/// Slide 2:
+/// ...
+/// ```
+///
+/// # Limitations
+///
+/// - Speaker notes, comments, and slide masters are not extracted
+/// - Text in charts and embedded objects is not extracted
+/// - Shapes are read in document order, which may differ from their visual layout
+pub struct PptxHandler;
+
+impl PptxHandler {
+    /// Creates a new `PptxHandler` instance.
+    ///
+    /// # Returns
+    ///
+    /// A new `PptxHandler` ready to process PPTX files.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extracts text from a PPTX presentation.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw PPTX file content as a byte slice
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - Successfully extracted text with slide markers
+    /// * `Err(String)` - Error message if the package or a slide cannot be read
+    fn extract_text_from_pptx(&self, content: &[u8]) -> Result<String, String> {
+        let mut archive = ZipArchive::new(Cursor::new(content))
+            .map_err(|e| format!("Failed to open PPTX: {}", e))?;
+
+        let mut text = String::new();
+
+        for (index, slide_path) in slide_paths(&mut archive).iter().enumerate() {
+            let slide_xml = read_part(&mut archive, slide_path)?;
+
+            text.push_str(&format!("Slide {}:\n", index + 1));
+            for paragraph in slide_paragraphs(&slide_xml)? {
+                text.push_str(&paragraph);
+                text.push('\n');
+            }
+            text.push('\n');
+        }
+
+        Ok(text.trim().to_string())
+    }
+}
+
+/// Reads a package part as a string.
+fn read_part(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<String, String> {
+    let mut part = archive
+        .by_name(name)
+        .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+
+    let mut xml = String::new();
+    part.read_to_string(&mut xml)
+        .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+
+    Ok(xml)
+}
+
+/// Returns the package paths of the slides in presentation order.
+///
+/// The order is given by the slide list in `ppt/presentation.xml`, whose
+/// relationship ids resolve to slide parts through
+/// `ppt/_rels/presentation.xml.rels`. If these parts are missing or list no
+/// slides, the `ppt/slides/slideN.xml` parts are ordered by their number.
+fn slide_paths(archive: &mut ZipArchive<Cursor<&[u8]>>) -> Vec<String> {
+    let ordered = read_part(archive, "ppt/presentation.xml")
+        .and_then(|presentation| {
+            let relationships = read_part(archive, "ppt/_rels/presentation.xml.rels")?;
+            Ok((presentation, relationships))
+        })
+        .map(|(presentation, relationships)| {
+            let targets = attribute_pairs(&relationships, b"Relationship", "Id", "Target");
+            let targets: HashMap<String, String> = targets.into_iter().collect();
+
+            attribute_values(&presentation, b"p:sldId", "r:id")
+                .iter()
+                .filter_map(|id| targets.get(id))
+                .map(|target| resolve_target(target))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    if !ordered.is_empty() {
+        return ordered;
+    }
+
+    let mut numbered: Vec<(usize, String)> = archive
+        .file_names()
+        .filter_map(|name| {
+            let number = name
+                .strip_prefix("ppt/slides/slide")?
+                .strip_suffix(".xml")?
+                .parse()
+                .ok()?;
+            Some((number, name.to_string()))
+        })
+        .collect();
+    numbered.sort();
+
+    numbered.into_iter().map(|(_, name)| name).collect()
+}
+
+/// Resolves a relationship target of `ppt/presentation.xml` to a package path.
+fn resolve_target(target: &str) -> String {
+    match target.strip_prefix('/') {
+        Some(absolute) => absolute.to_string(),
+        None => format!("ppt/{}", target),
+    }
+}
+
+/// Returns the value of attribute `attribute` on every `element` in `xml`.
+fn attribute_values(xml: &str, element: &[u8], attribute: &str) -> Vec<String> {
+    attribute_pairs(xml, element, attribute, attribute)
+        .into_iter()
+        .map(|(value, _)| value)
+        .collect()
+}
+
+/// Returns the values of attributes `first` and `second` on every `element`
+/// in `xml` that has both.
+fn attribute_pairs(xml: &str, element: &[u8], first: &str, second: &str) -> Vec<(String, String)> {
+    let mut reader = Reader::from_str(xml);
+    let mut pairs = Vec::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e) | Event::Empty(e)) if e.name().as_ref() == element => {
+                let value = |name: &str| {
+                    e.try_get_attribute(name)
+                        .ok()
+                        .flatten()
+                        .and_then(|attribute| {
+                            attribute.decode_and_unescape_value(reader.decoder()).ok()
+                        })
+                        .map(|value| value.into_owned())
+                };
+                if let (Some(first), Some(second)) = (value(first), value(second)) {
+                    pairs.push((first, second));
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    pairs
+}
+
+/// Collects the text of a slide, one entry per non-empty paragraph (`a:p`).
+///
+/// Text runs (`a:t`) within a paragraph are concatenated, and line breaks
+/// (`a:br`) become spaces.
+fn slide_paragraphs(xml: &str) -> Result<Vec<String>, String> {
+    let mut reader = Reader::from_str(xml);
+
+    let mut paragraphs = Vec::new();
+    let mut paragraph = String::new();
+    let mut in_text = false;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Failed to parse slide XML: {}", e))?;
+
+        match event {
+            Event::Start(e) if e.name().as_ref() == b"a:t" => in_text = true,
+            Event::End(e) if e.name().as_ref() == b"a:t" => in_text = false,
+            Event::Empty(e) if e.name().as_ref() == b"a:br" => paragraph.push(' '),
+            Event::End(e) if e.name().as_ref() == b"a:p" => {
+                let text = paragraph.trim();
+                if !text.is_empty() {
+                    paragraphs.push(text.to_string());
+                }
+                paragraph.clear();
+            }
+            Event::Text(e) if in_text => {
+                let text = e
+                    .decode()
+                    .map_err(|e| format!("Failed to decode slide text: {}", e))?;
+                paragraph.push_str(&text);
+            }
+            Event::GeneralRef(e) if in_text => {
+                if let Ok(Some(ch)) = e.resolve_char_ref() {
+                    paragraph.push(ch);
+                } else if let Some(entity) = e
+                    .decode()
+                    .ok()
+                    .and_then(|name| resolve_predefined_entity(&name))
+                {
+                    paragraph.push_str(entity);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(paragraphs)
+}
+
+impl FileHandler for PptxHandler {
+    /// Determines if this handler can process PPTX files.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_type` - The MIME type string to check
+    ///
+    /// # Returns
+    ///
+    /// `true` if the MIME type represents a PPTX presentation, `false` otherwise.
+    fn can_handle(&self, mime_type: &str) -> bool {
+        mime_type == "application/vnd.openxmlformats-officedocument.presentationml.presentation"
+    }
+
+    /// Lists the MIME types this handler accepts.
+    fn supported_mimes(&self) -> Vec<String> {
+        vec![
+            "application/vnd.openxmlformats-officedocument.presentationml.presentation".to_string(),
+        ]
+    }
+
+    /// Returns the handler's name, `PptxHandler`.
+    fn name(&self) -> &str {
+        "PptxHandler"
+    }
+
+    /// Extracts text content from a PPTX presentation.
+    ///
+    /// This is the main entry point for PPTX text extraction. It delegates
+    /// to `extract_text_from_pptx()` to perform the actual extraction.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw PPTX file content as a byte slice
+    /// * `_filename` - The filename (unused, kept for trait compatibility)
+    /// * `_mime_type` - The MIME type (unused, already verified by `can_handle()`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - Successfully extracted text content with slide markers
+    /// * `Err(String)` - Error message if extraction fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::pptx::PptxHandler;
+    /// # use crate::core::handler::FileHandler;
+    /// let handler = PptxHandler::new();
+    /// let pptx_bytes = vec![...]; // PPTX file bytes
+    /// let text = handler.extract_text(
+    ///     &pptx_bytes,
+    ///     "deck.pptx",
+    ///     "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    /// );
+    /// ```
+    fn extract_text(
+        &self,
+        content: &[u8],
+        _filename: &str,
+        _mime_type: &str,
+    ) -> Result<String, String> {
+        self.extract_text_from_pptx(content)
+    }
+
+    /// Returns the estimated peak memory use per input byte.
+    ///
+    /// Slides are decompressed and parsed one at a time, so peak use is
+    /// dominated by the package itself and the largest slide, roughly four
+    /// times the file size.
+    fn memory_multiplier(&self) -> f64 {
+        4.0
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    /// Builds a minimal PPTX with one slide per entry, each holding one text
+    /// paragraph per string. Slides are listed in `presentation.xml` in
+    /// reverse file order, so `slides[0]` is stored as the last slide part.
+    pub(crate) fn build_pptx(slides: &[&[&str]]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+
+        let mut slide_ids = String::new();
+        let mut relationships = String::new();
+
+        for (index, paragraphs) in slides.iter().enumerate() {
+            let number = slides.len() - index;
+            slide_ids.push_str(&format!(
+                r#"<p:sldId id="{}" r:id="rId{}"/>"#,
+                256 + index,
+                number
+            ));
+            relationships.push_str(&format!(
+                r#"<Relationship Id="rId{0}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide{0}.xml"/>"#,
+                number
+            ));
+
+            let body: String = paragraphs
+                .iter()
+                .map(|text| format!("<a:p><a:r><a:t>{}</a:t></a:r></a:p>", text))
+                .collect();
+            let slide = format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:spTree><p:sp><p:txBody>{}</p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#,
+                body
+            );
+
+            zip.start_file(format!("ppt/slides/slide{}.xml", number), options)
+                .unwrap();
+            zip.write_all(slide.as_bytes()).unwrap();
+        }
+
+        zip.start_file("ppt/presentation.xml", options).unwrap();
+        zip.write_all(
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:sldIdLst>{}</p:sldIdLst></p:presentation>"#,
+                slide_ids
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        zip.start_file("ppt/_rels/presentation.xml.rels", options)
+            .unwrap();
+        zip.write_all(
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">{}</Relationships>"#,
+                relationships
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_extract_text_from_pptx() {
+        let pptx = build_pptx(&[
+            &["Quarterly Review", "Sales &amp; Marketing"],
+            &["Revenue grew 12%"],
+        ]);
+
+        let text = PptxHandler::new().extract_text_from_pptx(&pptx).unwrap();
+
+        assert_eq!(
+            text,
+            "Slide 1:\nQuarterly Review\nSales & Marketing\n\nSlide 2:\nRevenue grew 12%"
+        );
+    }
+}
@@ -1,12 +1,26 @@
 //! Text file handler for processing plain text and text-based file formats.
 //!
 //! This handler supports various text-based MIME types and automatically detects
-//! character encoding to properly decode text content.
+//! character encoding to properly decode text content. Content that looks
+//! binary is rejected instead of being decoded into garbage.
 
-use crate::core::handler::FileHandler;
+use crate::core::handler::{ExtractedText, FileHandler};
 use chardetng::EncodingDetector;
 use encoding_rs::Encoding;
 
+/// Default byte entropy, in bits per byte, at or above which content is
+/// treated as binary.
+///
+/// Natural-language text stays well below it, in any encoding; compressed,
+/// encrypted, and random data come close to the maximum of 8.
+pub const DEFAULT_BINARY_ENTROPY_THRESHOLD: f64 = 7.5;
+
+/// Share of control bytes above which content is treated as binary.
+const MAX_CONTROL_BYTE_RATIO: f64 = 0.1;
+
+/// Number of leading bytes examined by the binary check.
+const BINARY_SAMPLE_BYTES: usize = 64 * 1024;
+
 /// Handler for processing text files and text-based formats.
 ///
 /// The `TextHandler` is responsible for extracting text from plain text files
@@ -29,24 +43,99 @@ use encoding_rs::Encoding;
 ///
 /// # Processing Flow
 ///
-/// 1. Detects the character encoding of the file content
-/// 2. Decodes the bytes using the detected encoding
-/// 3. Returns the decoded text content
+/// 1. Detects the character encoding of the file content; a byte-order mark
+///    (`EF BB BF`, `FF FE`, or `FE FF`) picks UTF-8, UTF-16LE, or UTF-16BE
+///    directly, without running the detector
+/// 2. Without a byte-order mark, rejects content that looks binary (see
+///    `looks_binary()`)
+/// 3. Decodes the bytes after any byte-order mark using the detected encoding
+/// 4. Returns the decoded text content, without a leading U+FEFF
 ///
 /// # Error Handling
 ///
-/// If decoding fails (e.g., invalid encoding or corrupted content), the handler
-/// returns an error message indicating the failure.
-pub struct TextHandler;
+/// Decoding is lossy: byte sequences that are invalid in the detected
+/// encoding are replaced with U+FFFD, and `extract_text_with_encoding`
+/// reports that replacements occurred. Binary content mislabeled as text
+/// fails with "Content does not appear to be text", since the encoding
+/// detector would otherwise pick some single-byte encoding and return noise.
+pub struct TextHandler {
+    binary_entropy_threshold: f64,
+}
 
 impl TextHandler {
     /// Creates a new `TextHandler` instance.
     ///
     /// # Returns
     ///
-    /// A new `TextHandler` ready to process text files.
+    /// A new `TextHandler` ready to process text files, rejecting binary
+    /// content at `DEFAULT_BINARY_ENTROPY_THRESHOLD`.
     pub fn new() -> Self {
-        Self
+        Self {
+            binary_entropy_threshold: DEFAULT_BINARY_ENTROPY_THRESHOLD,
+        }
+    }
+
+    /// Sets the byte entropy at or above which content is treated as binary.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Entropy in bits per byte (0-8). Lower values reject
+    ///   more content; a value above 8 disables the binary check entirely
+    ///
+    /// # Returns
+    ///
+    /// The handler with the threshold applied.
+    pub fn with_binary_entropy_threshold(mut self, threshold: f64) -> Self {
+        self.binary_entropy_threshold = threshold;
+        self
+    }
+
+    /// Checks whether the given content looks like binary data rather than
+    /// text.
+    ///
+    /// Only the first 64 KiB are examined. The content is binary if more than
+    /// 10% of those bytes are control bytes other than whitespace (typical of
+    /// executables and other structured binary formats), or if their Shannon
+    /// entropy reaches the configured threshold (typical of compressed,
+    /// encrypted, and random data).
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw file content, without a byte-order mark
+    ///
+    /// # Returns
+    ///
+    /// `true` if the content should not be decoded as text.
+    fn looks_binary(&self, content: &[u8]) -> bool {
+        if self.binary_entropy_threshold > 8.0 || content.is_empty() {
+            return false;
+        }
+
+        let sample = &content[..content.len().min(BINARY_SAMPLE_BYTES)];
+        let mut counts = [0usize; 256];
+        for &byte in sample {
+            counts[byte as usize] += 1;
+        }
+
+        let control_bytes: usize = (0u8..=0x1f)
+            .chain([0x7f])
+            .filter(|byte| !matches!(byte, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b))
+            .map(|byte| counts[byte as usize])
+            .sum();
+        if control_bytes as f64 / sample.len() as f64 > MAX_CONTROL_BYTE_RATIO {
+            return true;
+        }
+
+        let len = sample.len() as f64;
+        let entropy: f64 = counts
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let p = count as f64 / len;
+                -p * p.log2()
+            })
+            .sum();
+        entropy >= self.binary_entropy_threshold
     }
 
     /// Detects the character encoding of the given file content.
@@ -61,7 +150,8 @@ impl TextHandler {
     ///
     /// # Returns
     ///
-    /// The name of the detected encoding as a string (e.g., "utf-8", "iso-8859-1")
+    /// The lowercase label of the detected encoding (e.g., "utf-8", "windows-1252").
+    /// ASCII-only content is reported as "utf-8".
     ///
     /// # Algorithm
     ///
@@ -76,10 +166,36 @@ impl TextHandler {
     /// texts with mixed content. The decoder will attempt to handle encoding errors
     /// gracefully.
     fn detect_encoding(&self, content: &[u8]) -> String {
+        // ASCII decodes the same in every candidate encoding, and the detector
+        // would report its fallback (windows-1252) for it
+        if content.is_ascii() {
+            return "utf-8".to_string();
+        }
+
         let mut detector = EncodingDetector::new();
         detector.feed(content, true);
         let encoding = detector.guess(None, true);
-        encoding.name().to_string()
+        encoding.name().to_ascii_lowercase()
+    }
+
+    /// Detects a byte-order mark at the start of the given file content.
+    ///
+    /// A BOM names its encoding exactly, so it takes precedence over
+    /// `detect_encoding()`, which does not recognize UTF-16 and would report
+    /// the BOM bytes of a UTF-8 file as part of the text.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw file content as a byte slice
+    ///
+    /// # Returns
+    ///
+    /// The lowercase label of the encoding ("utf-8", "utf-16le", or
+    /// "utf-16be") and the length of the BOM in bytes, or `None` if the
+    /// content does not start with a BOM.
+    fn detect_bom(&self, content: &[u8]) -> Option<(String, usize)> {
+        Encoding::for_bom(content)
+            .map(|(encoding, bom_length)| (encoding.name().to_ascii_lowercase(), bom_length))
     }
 
     /// Checks if the given MIME type represents a text-based format.
@@ -118,7 +234,12 @@ impl TextHandler {
     ///
     /// Uses the `encoding_rs` library to decode bytes according to the given
     /// encoding name. If the encoding is not recognized, falls back to UTF-8.
-    /// Handles decoding errors gracefully by returning an empty string if errors occur.
+    /// Malformed byte sequences are replaced with U+FFFD (the replacement
+    /// character) instead of failing the whole file, so a mostly valid file
+    /// with a few stray bytes keeps its readable text.
+    ///
+    /// The content must not start with a byte-order mark (see `detect_bom()`);
+    /// a U+FEFF left at the start of the decoded text is removed.
     ///
     /// # Arguments
     ///
@@ -127,23 +248,12 @@ impl TextHandler {
     ///
     /// # Returns
     ///
-    /// The decoded text as a `String`. Returns an empty string if decoding errors occur.
-    ///
-    /// # Error Handling
-    ///
-    /// If the encoding name is not recognized, the function falls back to UTF-8.
-    /// If decoding errors occur (malformed sequences), the function returns an
-    /// empty string. The caller should check for empty results when the content
-    /// is known to be non-empty.
-    fn decode_text(&self, content: &[u8], encoding_name: &str) -> String {
+    /// The decoded text, and whether any malformed sequences were replaced.
+    fn decode_text(&self, content: &[u8], encoding_name: &str) -> (String, bool) {
         let encoding = Encoding::for_label(encoding_name.as_bytes()).unwrap_or(encoding_rs::UTF_8);
-        let (decoded, _encoding_used, had_errors) = encoding.decode(content);
-
-        if had_errors {
-            String::new()
-        } else {
-            decoded.to_string()
-        }
+        let (decoded, had_errors) = encoding.decode_without_bom_handling(content);
+        let text = decoded.strip_prefix('\u{feff}').unwrap_or(&decoded);
+        (text.to_string(), had_errors)
     }
 }
 
@@ -169,29 +279,56 @@ impl FileHandler for TextHandler {
             || mime_type == "text/tab-separated-values"
     }
 
+    /// Lists the MIME types this handler accepts.
+    ///
+    /// `text/*` stands for every other `text/` subtype, which is accepted as
+    /// plain text.
+    fn supported_mimes(&self) -> Vec<String> {
+        [
+            "text/*",
+            "text/plain",
+            "text/csv",
+            "text/tsv",
+            "text/tab-separated-values",
+            "application/json",
+            "application/xml",
+            "application/javascript",
+            "application/typescript",
+            "application/x-javascript",
+            "application/xhtml+xml",
+            "application/ld+json",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect()
+    }
+
+    /// Returns the handler's name, `TextHandler`.
+    fn name(&self) -> &str {
+        "TextHandler"
+    }
+
     /// Extracts text content from text-based file formats.
     ///
     /// This method performs the complete text extraction pipeline:
-    /// 1. Detects the character encoding of the file
-    /// 2. Decodes the bytes using the detected encoding
-    /// 3. Returns the decoded text content
+    /// 1. Detects the character encoding of the file, from its byte-order mark
+    ///    if it has one
+    /// 2. Rejects content without a byte-order mark that looks binary
+    /// 3. Decodes the bytes after the byte-order mark using the detected
+    ///    encoding
+    /// 4. Returns the decoded text content
     ///
     /// # Arguments
     ///
     /// * `content` - The raw file content as a byte slice
-    /// * `_filename` - The filename (unused, kept for trait compatibility)
-    /// * `_mime_type` - The MIME type (unused, already verified by `can_handle()`)
+    /// * `filename` - The filename (unused, kept for trait compatibility)
+    /// * `mime_type` - The MIME type (unused, already verified by `can_handle()`)
     ///
     /// # Returns
     ///
-    /// * `Ok(String)` - Successfully extracted and decoded text content
-    /// * `Err(String)` - Error message if decoding fails (e.g., "Failed to decode text content")
-    ///
-    /// # Error Conditions
-    ///
-    /// Returns an error if:
-    /// - The content is non-empty but decoding results in an empty string
-    /// - Encoding detection or decoding fails
+    /// * `Ok(String)` - The decoded text content; invalid byte sequences are
+    ///   replaced with U+FFFD
+    /// * `Err(String)` - Error message if the content appears to be binary
     ///
     /// # Example
     ///
@@ -204,18 +341,142 @@ impl FileHandler for TextHandler {
     /// assert!(text.is_ok());
     /// ```
     fn extract_text(
+        &self,
+        content: &[u8],
+        filename: &str,
+        mime_type: &str,
+    ) -> Result<String, String> {
+        self.extract_text_with_encoding(content, filename, mime_type)
+            .map(|extracted| extracted.text)
+    }
+
+    /// Extracts text content and reports the detected encoding.
+    ///
+    /// Runs the same pipeline as `extract_text()`, keeping the label of the
+    /// encoding the text was decoded from.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(ExtractedText)` with the decoded text, the detected encoding label,
+    /// and `lossy` set if invalid byte sequences were replaced, or an error
+    /// if the content appears to be binary.
+    fn extract_text_with_encoding(
         &self,
         content: &[u8],
         _filename: &str,
         _mime_type: &str,
-    ) -> Result<String, String> {
-        let encoding = self.detect_encoding(content);
-        let text = self.decode_text(content, &encoding);
+    ) -> Result<ExtractedText, String> {
+        let (encoding, body) = match self.detect_bom(content) {
+            Some((encoding, bom_length)) => (encoding, &content[bom_length..]),
+            None => {
+                if self.looks_binary(content) {
+                    return Err("Content does not appear to be text".to_string());
+                }
+                (self.detect_encoding(content), content)
+            }
+        };
+        let (text, lossy) = self.decode_text(body, &encoding);
+
+        Ok(ExtractedText {
+            text,
+            encoding,
+            lossy,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_bytes_keep_valid_text() {
+        let mut content = b"Quarterly report: revenue grew ".to_vec();
+        content.push(0xff);
+        content.extend_from_slice(b" percent");
+        let handler = TextHandler::new();
 
-        if text.is_empty() && !content.is_empty() {
-            Err("Failed to decode text content".to_string())
-        } else {
-            Ok(text)
+        let text = handler
+            .extract_text(&content, "report.txt", "text/plain")
+            .unwrap();
+        assert!(text.starts_with("Quarterly report: revenue grew "));
+        assert!(text.ends_with(" percent"));
+
+        // Bytes invalid in the decoding encoding become replacement characters
+        let (text, lossy) = handler.decode_text(&content, "utf-8");
+        assert!(lossy);
+        assert_eq!(text, "Quarterly report: revenue grew \u{fffd} percent");
+    }
+
+    #[test]
+    fn test_byte_order_mark_picks_encoding_and_is_stripped() {
+        let handler = TextHandler::new();
+
+        let mut utf8 = vec![0xef, 0xbb, 0xbf];
+        utf8.extend_from_slice("Café menu".as_bytes());
+        let extracted = handler
+            .extract_text_with_encoding(&utf8, "menu.txt", "text/plain")
+            .unwrap();
+        assert_eq!(extracted.text, "Café menu");
+        assert_eq!(extracted.encoding, "utf-8");
+
+        let mut utf16le = vec![0xff, 0xfe];
+        for unit in "Résumé: 2024".encode_utf16() {
+            utf16le.extend_from_slice(&unit.to_le_bytes());
         }
+        let extracted = handler
+            .extract_text_with_encoding(&utf16le, "resume.txt", "text/plain")
+            .unwrap();
+        assert_eq!(extracted.text, "Résumé: 2024");
+        assert_eq!(extracted.encoding, "utf-16le");
+        assert!(!extracted.lossy);
+    }
+
+    #[test]
+    fn test_binary_content_is_rejected() {
+        // Bytes from a fixed xorshift sequence stand in for compressed data
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let random: Vec<u8> = (0..4096)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+        let handler = TextHandler::new();
+
+        let err = handler
+            .extract_text(&random, "upload.txt", "text/plain")
+            .unwrap_err();
+        assert_eq!(err, "Content does not appear to be text");
+
+        // Mostly NUL bytes have low entropy but are still not text
+        let mut header = b"ELF".to_vec();
+        header.extend_from_slice(&[0u8; 200]);
+        assert!(
+            handler
+                .extract_text(&header, "a.out", "text/plain")
+                .is_err()
+        );
+
+        // Real text in any encoding passes
+        let text =
+            "Résumé: quarterly revenue grew 12% over the previous year.\n\tDetails follow.\n"
+                .repeat(50);
+        assert_eq!(
+            handler
+                .extract_text(text.as_bytes(), "report.txt", "text/plain")
+                .unwrap(),
+            text
+        );
+
+        // A threshold above 8 disables the check
+        assert!(
+            TextHandler::new()
+                .with_binary_entropy_threshold(8.5)
+                .extract_text(&random, "upload.txt", "text/plain")
+                .is_ok()
+        );
     }
 }
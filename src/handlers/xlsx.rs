@@ -1,15 +1,65 @@
 //! XLSX file handler for extracting text from Microsoft Excel spreadsheets.
 //!
 //! This handler uses the `calamine` library to read Excel workbooks and extract
-//! text content from all sheets and cells.
+//! text content from all sheets and cells. The XML-based XLSX format, the
+//! legacy binary XLS (BIFF) format, and OpenDocument spreadsheets (ODS) are
+//! supported.
 
 use crate::core::handler::FileHandler;
-use calamine::{Reader, Xlsx, open_workbook_from_rs};
-use std::io::Cursor;
+use crate::models::xlsx::{XlsxCell, XlsxSheet};
+use calamine::{Data, Dimensions, Ods, Range, Reader, Xls, Xlsx, open_workbook_from_rs};
+use std::io::{Cursor, Read, Seek};
+use zip::ZipArchive;
 
-/// Handler for processing Microsoft Excel spreadsheets (XLSX format).
+/// Signature of OLE compound files, the container of legacy XLS workbooks.
+const OLE_SIGNATURE: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// MIME type of OpenDocument spreadsheets, also stored in their `mimetype` entry.
+const ODS_MIME_TYPE: &str = "application/vnd.oasis.opendocument.spreadsheet";
+
+/// The file formats read by `XlsxHandler`.
+enum WorkbookFormat {
+    Xls,
+    Xlsx,
+    Ods,
+}
+
+impl WorkbookFormat {
+    /// Detects the format of a workbook from its content.
+    ///
+    /// Files starting with the OLE signature are XLS. ZIP packages whose
+    /// `mimetype` entry holds the ODS MIME type are ODS; any other content is
+    /// treated as XLSX.
+    fn detect(content: &[u8]) -> Self {
+        if content.starts_with(OLE_SIGNATURE) {
+            return WorkbookFormat::Xls;
+        }
+
+        let is_ods = ZipArchive::new(Cursor::new(content))
+            .ok()
+            .and_then(|mut archive| {
+                let mut mime_type = String::new();
+                archive
+                    .by_name("mimetype")
+                    .ok()?
+                    .take(ODS_MIME_TYPE.len() as u64 + 1)
+                    .read_to_string(&mut mime_type)
+                    .ok()?;
+                Some(mime_type.trim() == ODS_MIME_TYPE)
+            })
+            .unwrap_or(false);
+
+        if is_ods {
+            WorkbookFormat::Ods
+        } else {
+            WorkbookFormat::Xlsx
+        }
+    }
+}
+
+/// Handler for processing spreadsheets (XLSX, XLS, and ODS formats).
 ///
-/// The `XlsxHandler` extracts text content from XLSX files by reading all
+/// The `XlsxHandler` extracts text content from Excel files by reading all
 /// sheets and converting cell values to text. Cells are separated by tabs
 /// to preserve column structure, and rows are separated by newlines.
 ///
@@ -18,11 +68,19 @@ use std::io::Cursor;
 /// - `application/vnd.openxmlformats-officedocument.spreadsheetml.sheet` - Standard XLSX format
 /// - `application/vnd.ms-excel` - Legacy Excel format (also handled)
 /// - `application/xlsx` - Alternative XLSX MIME type
+/// - `application/vnd.oasis.opendocument.spreadsheet` - OpenDocument spreadsheets
+///
+/// The MIME type doesn't decide the format: files starting with the OLE
+/// compound file signature (`D0 CF 11 E0`) are read as legacy XLS workbooks,
+/// ZIP packages with the ODS `mimetype` entry as OpenDocument spreadsheets,
+/// and everything else as XLSX (ZIP) packages.
 ///
 /// # Processing Flow
 ///
-/// 1. Opens the Excel workbook from memory using `calamine` library
-/// 2. Iterates through all sheets in the workbook
+/// 1. Opens the workbook from memory using `calamine` library, as XLS, ODS,
+///    or XLSX depending on its content
+/// 2. Iterates through all sheets in the workbook (or only the sheets selected
+///    with `with_sheets`)
 /// 3. For each sheet:
 ///    - Adds a header line with the sheet name
 ///    - Processes each row in the sheet
@@ -49,8 +107,16 @@ use std::io::Cursor;
 ///
 /// - Extracts text values only (formulas are converted to their calculated values)
 /// - Does not preserve formatting, colors, or styles
-/// - Empty cells are filtered out (may affect column alignment in output)
-pub struct XlsxHandler;
+/// - Empty cells are filtered out (may affect column alignment in output),
+///   unless `with_preserve_empty_cells(true)` is set
+/// - Merged cells only hold their value in the top-left cell, unless
+///   `with_expand_merged_cells(true)` is set. `calamine` doesn't report the
+///   merged regions of ODS files, so they are never expanded
+pub struct XlsxHandler {
+    expand_merged_cells: bool,
+    preserve_empty_cells: bool,
+    sheets: Vec<String>,
+}
 
 impl XlsxHandler {
     /// Creates a new `XlsxHandler` instance.
@@ -59,7 +125,83 @@ impl XlsxHandler {
     ///
     /// A new `XlsxHandler` ready to process XLSX files.
     pub fn new() -> Self {
-        Self
+        Self {
+            expand_merged_cells: false,
+            preserve_empty_cells: false,
+            sheets: Vec::new(),
+        }
+    }
+
+    /// Enables or disables keeping empty cells in the output.
+    ///
+    /// By default empty cells are dropped, which shifts later values in a row
+    /// to the left. When enabled, every cell of the sheet's used range is
+    /// emitted (empty cells as empty strings) and empty rows are kept, so each
+    /// value stays in its original tab-separated column.
+    ///
+    /// # Arguments
+    ///
+    /// * `preserve_empty_cells` - Whether to keep empty cells and rows
+    ///
+    /// # Returns
+    ///
+    /// The handler with the setting applied.
+    pub fn with_preserve_empty_cells(mut self, preserve_empty_cells: bool) -> Self {
+        self.preserve_empty_cells = preserve_empty_cells;
+        self
+    }
+
+    /// Restricts extraction to the given sheets.
+    ///
+    /// Each entry selects a sheet by name, or by its zero-based position in the
+    /// workbook if the entry is a number (e.g., "0" for the first sheet). An
+    /// entry that is both a sheet name and a number matches either. Sheets that
+    /// are not selected are skipped entirely.
+    ///
+    /// # Arguments
+    ///
+    /// * `sheets` - Sheet names or indices to extract; an empty list extracts all sheets
+    ///
+    /// # Returns
+    ///
+    /// The handler with the sheet selection applied.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::xlsx::XlsxHandler;
+    /// let handler = XlsxHandler::new().with_sheets(vec!["Summary".to_string()]);
+    /// ```
+    pub fn with_sheets(mut self, sheets: Vec<String>) -> Self {
+        self.sheets = sheets;
+        self
+    }
+
+    /// Returns whether the sheet at `index` named `name` is selected.
+    fn is_sheet_selected(&self, index: usize, name: &str) -> bool {
+        self.sheets.is_empty()
+            || self
+                .sheets
+                .iter()
+                .any(|sheet| sheet == name || sheet.parse::<usize>() == Ok(index))
+    }
+
+    /// Enables or disables repeating merged cell values across their span.
+    ///
+    /// A merged region stores its value in its top-left cell only, so the other
+    /// covered cells read as empty. When enabled, the value is copied into every
+    /// cell of the region, keeping each row complete.
+    ///
+    /// # Arguments
+    ///
+    /// * `expand_merged_cells` - Whether to repeat merged values across the merged region
+    ///
+    /// # Returns
+    ///
+    /// The handler with the setting applied.
+    pub fn with_expand_merged_cells(mut self, expand_merged_cells: bool) -> Self {
+        self.expand_merged_cells = expand_merged_cells;
+        self
     }
 
     /// Extracts text content from an XLSX spreadsheet.
@@ -79,8 +221,8 @@ impl XlsxHandler {
     /// # Error Conditions
     ///
     /// Returns an error if:
-    /// - The XLSX file is corrupted or invalid
-    /// - The file is not a valid XLSX format
+    /// - The XLSX or XLS file is corrupted or invalid
+    /// - The file is neither a valid XLSX nor a valid XLS workbook
     /// - Opening or reading the workbook fails
     ///
     /// # Cell Value Conversion
@@ -90,18 +232,153 @@ impl XlsxHandler {
     /// - Numbers are converted to their string representation
     /// - Dates are converted to their string format
     /// - Formulas are converted to their calculated values
-    /// - Empty cells are filtered out
+    /// - Empty cells are filtered out, unless `preserve_empty_cells` is enabled
+    /// - Merged regions repeat their value in every covered cell when
+    ///   `expand_merged_cells` is enabled
     fn extract_text_from_xlsx(&self, content: &[u8]) -> Result<String, String> {
         let cursor = Cursor::new(content);
-        let mut workbook: Xlsx<_> = open_workbook_from_rs(cursor)
-            .map_err(|e| format!("Failed to open Excel file: {}", e))?;
 
-        let mut text = String::new();
+        match WorkbookFormat::detect(content) {
+            WorkbookFormat::Xls => {
+                let mut workbook: Xls<_> = open_workbook_from_rs(cursor)
+                    .map_err(|e| format!("Failed to open Excel file: {}", e))?;
+                Ok(self.extract_sheets(&mut workbook, |workbook, sheet_name| {
+                    workbook.worksheet_merge_cells(sheet_name)
+                }))
+            }
+            WorkbookFormat::Xlsx => {
+                let mut workbook: Xlsx<_> = open_workbook_from_rs(cursor)
+                    .map_err(|e| format!("Failed to open Excel file: {}", e))?;
+                Ok(self.extract_sheets(&mut workbook, |workbook, sheet_name| {
+                    workbook.worksheet_merge_cells(sheet_name)?.ok()
+                }))
+            }
+            WorkbookFormat::Ods => {
+                let mut workbook: Ods<_> = open_workbook_from_rs(cursor)
+                    .map_err(|e| format!("Failed to open OpenDocument spreadsheet: {}", e))?;
+                Ok(self.extract_sheets(&mut workbook, |_, _| None))
+            }
+        }
+    }
+
+    /// Extracts the cells of a spreadsheet with their types.
+    ///
+    /// Unlike the text extraction, which converts every cell to a string, this
+    /// keeps whether each cell holds a number, date, text, boolean, or nothing,
+    /// and converts date serial numbers to ISO 8601. Only the sheets selected
+    /// with `with_sheets` are extracted; the other settings don't apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw XLSX, XLS, or ODS file content as a byte slice
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<XlsxSheet>)` - The selected sheets in workbook order, each
+    ///   with the rows of its used range
+    /// * `Err(String)` - Error message if the workbook cannot be opened
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::xlsx::XlsxHandler;
+    /// let sheets = XlsxHandler::new().extract_cells(&xlsx_bytes)?;
+    /// for row in &sheets[0].rows {
+    ///     let kinds: Vec<&str> = row.iter().map(|cell| cell.kind.as_str()).collect();
+    ///     println!("{:?}", kinds);
+    /// }
+    /// ```
+    pub fn extract_cells(&self, content: &[u8]) -> Result<Vec<XlsxSheet>, String> {
+        let cursor = Cursor::new(content);
+
+        match WorkbookFormat::detect(content) {
+            WorkbookFormat::Xls => {
+                let mut workbook: Xls<_> = open_workbook_from_rs(cursor)
+                    .map_err(|e| format!("Failed to open Excel file: {}", e))?;
+                Ok(self.extract_sheet_cells(&mut workbook))
+            }
+            WorkbookFormat::Xlsx => {
+                let mut workbook: Xlsx<_> = open_workbook_from_rs(cursor)
+                    .map_err(|e| format!("Failed to open Excel file: {}", e))?;
+                Ok(self.extract_sheet_cells(&mut workbook))
+            }
+            WorkbookFormat::Ods => {
+                let mut workbook: Ods<_> = open_workbook_from_rs(cursor)
+                    .map_err(|e| format!("Failed to open OpenDocument spreadsheet: {}", e))?;
+                Ok(self.extract_sheet_cells(&mut workbook))
+            }
+        }
+    }
 
+    /// Converts the selected sheets of an opened workbook to typed cells.
+    fn extract_sheet_cells<RS, W>(&self, workbook: &mut W) -> Vec<XlsxSheet>
+    where
+        RS: Read + Seek,
+        W: Reader<RS>,
+    {
         let sheet_names = workbook.sheet_names().to_vec();
+        let mut sheets = Vec::new();
+
+        for (index, sheet_name) in sheet_names.into_iter().enumerate() {
+            if !self.is_sheet_selected(index, &sheet_name) {
+                continue;
+            }
 
-        for sheet_name in sheet_names {
             if let Ok(range) = workbook.worksheet_range(&sheet_name) {
+                let (first_row, first_column) = range.start().unwrap_or((0, 0));
+                sheets.push(XlsxSheet {
+                    name: sheet_name,
+                    first_row,
+                    first_column,
+                    rows: range
+                        .rows()
+                        .map(|row| row.iter().map(typed_cell).collect())
+                        .collect(),
+                });
+            }
+        }
+
+        sheets
+    }
+
+    /// Converts the selected sheets of an opened workbook to text.
+    ///
+    /// `calamine` exposes merged regions per format rather than through the
+    /// `Reader` trait, so they are looked up with `merged_regions`.
+    ///
+    /// # Arguments
+    ///
+    /// * `workbook` - The opened XLS, XLSX, or ODS workbook
+    /// * `merged_regions` - Returns the merged regions of the named sheet, if any
+    ///
+    /// # Returns
+    ///
+    /// The text of all selected sheets, with sheet headers and cell values.
+    fn extract_sheets<RS, W>(
+        &self,
+        workbook: &mut W,
+        merged_regions: impl Fn(&mut W, &str) -> Option<Vec<Dimensions>>,
+    ) -> String
+    where
+        RS: Read + Seek,
+        W: Reader<RS>,
+    {
+        let mut text = String::new();
+
+        let sheet_names = workbook.sheet_names().to_vec();
+
+        for (index, sheet_name) in sheet_names.into_iter().enumerate() {
+            if !self.is_sheet_selected(index, &sheet_name) {
+                continue;
+            }
+
+            if let Ok(mut range) = workbook.worksheet_range(&sheet_name) {
+                if self.expand_merged_cells
+                    && let Some(merged_regions) = merged_regions(workbook, &sheet_name)
+                {
+                    expand_merged_regions(&mut range, &merged_regions);
+                }
+
                 if !text.is_empty() {
                     text.push_str("\n\n");
                 }
@@ -112,10 +389,10 @@ impl XlsxHandler {
                     let row_text: Vec<String> = row
                         .iter()
                         .map(|cell| cell.to_string())
-                        .filter(|s| !s.is_empty())
+                        .filter(|s| self.preserve_empty_cells || !s.is_empty())
                         .collect();
 
-                    if !row_text.is_empty() {
+                    if self.preserve_empty_cells || !row_text.is_empty() {
                         text.push_str(&row_text.join("\t"));
                         text.push('\n');
                     }
@@ -123,7 +400,63 @@ impl XlsxHandler {
             }
         }
 
-        Ok(text.trim().to_string())
+        if self.preserve_empty_cells {
+            // Trailing tabs are empty cells, so only strip the final newlines
+            text.trim_end_matches('\n').to_string()
+        } else {
+            text.trim().to_string()
+        }
+    }
+}
+
+/// Converts a cell value to an `XlsxCell` with its type.
+///
+/// Date and time cells become ISO 8601 dates, with the time only when it is
+/// not midnight. Durations are kept as numbers of days, and error cells as
+/// text.
+fn typed_cell(cell: &Data) -> XlsxCell {
+    let (value, kind) = match cell {
+        Data::Int(_) | Data::Float(_) => (cell.to_string(), "number"),
+        Data::DateTime(datetime) if datetime.is_datetime() => {
+            let (year, month, day, hour, minute, second, milli) = datetime.to_ymd_hms_milli();
+            let date = format!("{:04}-{:02}-{:02}", year, month, day);
+            let value = match (hour, minute, second, milli) {
+                (0, 0, 0, 0) => date,
+                (_, _, _, 0) => format!("{}T{:02}:{:02}:{:02}", date, hour, minute, second),
+                _ => format!(
+                    "{}T{:02}:{:02}:{:02}.{:03}",
+                    date, hour, minute, second, milli
+                ),
+            };
+            (value, "date")
+        }
+        Data::DateTime(duration) => (duration.as_f64().to_string(), "number"),
+        Data::DateTimeIso(value) => (value.clone(), "date"),
+        Data::Bool(_) => (cell.to_string(), "bool"),
+        Data::String(_) | Data::DurationIso(_) | Data::Error(_) => (cell.to_string(), "text"),
+        Data::Empty => (String::new(), "empty"),
+    };
+
+    XlsxCell {
+        value,
+        kind: kind.to_string(),
+    }
+}
+
+/// Copies the top-left value of each merged region into all cells it covers.
+///
+/// Regions whose top-left cell is empty are left unchanged.
+fn expand_merged_regions(range: &mut Range<Data>, merged_regions: &[Dimensions]) {
+    for region in merged_regions {
+        let Some(value) = range.get_value(region.start).cloned() else {
+            continue;
+        };
+
+        for row in region.start.0..=region.end.0 {
+            for col in region.start.1..=region.end.1 {
+                range.set_value((row, col), value.clone());
+            }
+        }
     }
 }
 
@@ -134,6 +467,7 @@ impl FileHandler for XlsxHandler {
     /// - `application/vnd.openxmlformats-officedocument.spreadsheetml.sheet` (standard XLSX)
     /// - `application/vnd.ms-excel` (legacy Excel format)
     /// - `application/xlsx` (alternative MIME type)
+    /// - `application/vnd.oasis.opendocument.spreadsheet` (OpenDocument spreadsheet)
     ///
     /// # Arguments
     ///
@@ -141,11 +475,30 @@ impl FileHandler for XlsxHandler {
     ///
     /// # Returns
     ///
-    /// `true` if the MIME type represents an Excel file, `false` otherwise.
+    /// `true` if the MIME type represents a spreadsheet, `false` otherwise.
     fn can_handle(&self, mime_type: &str) -> bool {
         mime_type == "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
             || mime_type == "application/vnd.ms-excel"
             || mime_type == "application/xlsx"
+            || mime_type == ODS_MIME_TYPE
+    }
+
+    /// Lists the MIME types this handler accepts.
+    fn supported_mimes(&self) -> Vec<String> {
+        [
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            "application/vnd.ms-excel",
+            "application/xlsx",
+            ODS_MIME_TYPE,
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect()
+    }
+
+    /// Returns the handler's name, `XlsxHandler`.
+    fn name(&self) -> &str {
+        "XlsxHandler"
     }
 
     /// Extracts text content from an XLSX spreadsheet.
@@ -181,4 +534,428 @@ impl FileHandler for XlsxHandler {
     ) -> Result<String, String> {
         self.extract_text_from_xlsx(content)
     }
+
+    /// Returns the estimated peak memory use per input byte.
+    ///
+    /// XLSX files are compressed ZIP packages; the decompressed XML and the
+    /// loaded cell ranges take roughly eight times the file size.
+    fn memory_multiplier(&self) -> f64 {
+        8.0
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    /// A worksheet fixture: its name, rows of cell values, and merged ranges.
+    ///
+    /// Empty strings are written as blank cells. Merged ranges use A1 notation
+    /// (e.g., "A1:B1").
+    pub(crate) type SheetFixture<'a> = (&'a str, &'a [&'a [&'a str]], &'a [&'a str]);
+
+    /// Builds a minimal XLSX workbook with number and inline string cells.
+    ///
+    /// Values that parse as numbers are written as number cells.
+    pub(crate) fn build_xlsx(sheets: &[SheetFixture]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let mut add = |name: &str, content: String| {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        };
+
+        let mut content_types = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+        );
+        let mut workbook = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#,
+        );
+        let mut workbook_rels = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        );
+
+        for (index, (name, rows, merges)) in sheets.iter().enumerate() {
+            let id = index + 1;
+            content_types.push_str(&format!(
+                r#"<Override PartName="/xl/worksheets/sheet{id}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#
+            ));
+            workbook.push_str(&format!(
+                r#"<sheet name="{name}" sheetId="{id}" r:id="rId{id}"/>"#
+            ));
+            workbook_rels.push_str(&format!(
+                r#"<Relationship Id="rId{id}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{id}.xml"/>"#
+            ));
+
+            let mut sheet = String::from(
+                r#"<?xml version="1.0" encoding="UTF-8"?><worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            );
+            for (row_index, row) in rows.iter().enumerate() {
+                sheet.push_str(&format!(r#"<row r="{}">"#, row_index + 1));
+                for (col_index, value) in row.iter().enumerate().filter(|(_, v)| !v.is_empty()) {
+                    let column = (b'A' + col_index as u8) as char;
+                    let row_number = row_index + 1;
+                    if value.parse::<f64>().is_ok() {
+                        sheet.push_str(&format!(
+                            r#"<c r="{column}{row_number}"><v>{value}</v></c>"#
+                        ));
+                    } else {
+                        sheet.push_str(&format!(
+                            r#"<c r="{column}{row_number}" t="inlineStr"><is><t>{value}</t></is></c>"#
+                        ));
+                    }
+                }
+                sheet.push_str("</row>");
+            }
+            sheet.push_str("</sheetData>");
+            if !merges.is_empty() {
+                sheet.push_str(&format!(r#"<mergeCells count="{}">"#, merges.len()));
+                for merge in *merges {
+                    sheet.push_str(&format!(r#"<mergeCell ref="{merge}"/>"#));
+                }
+                sheet.push_str("</mergeCells>");
+            }
+            sheet.push_str("</worksheet>");
+            add(&format!("xl/worksheets/sheet{id}.xml"), sheet);
+        }
+
+        content_types.push_str("</Types>");
+        workbook.push_str("</sheets></workbook>");
+        workbook_rels.push_str("</Relationships>");
+
+        add("[Content_Types].xml", content_types);
+        add(
+            "_rels/.rels",
+            r#"<?xml version="1.0" encoding="UTF-8"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#.to_string(),
+        );
+        add("xl/workbook.xml", workbook);
+        add("xl/_rels/workbook.xml.rels", workbook_rels);
+
+        writer.finish().unwrap().into_inner()
+    }
+
+    /// Appends a BIFF record with the given type and body.
+    fn push_record(stream: &mut Vec<u8>, record_type: u16, body: &[u8]) {
+        stream.extend_from_slice(&record_type.to_le_bytes());
+        stream.extend_from_slice(&(body.len() as u16).to_le_bytes());
+        stream.extend_from_slice(body);
+    }
+
+    /// Encodes `text` as UTF-16LE, the uncompressed BIFF8 string encoding.
+    fn utf16(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    /// Builds a minimal legacy XLS (BIFF8) workbook with string cells.
+    ///
+    /// The workbook stream holds the globals (BOF, one BoundSheet8 per sheet,
+    /// EOF) followed by each sheet (BOF, one Label record per non-empty cell,
+    /// EOF). It is stored in an OLE compound file with 512-byte sectors: the
+    /// header, one FAT sector, one directory sector, and the stream, padded
+    /// to the 4096-byte cutoff so it lives in regular sectors.
+    pub(crate) fn build_xls(sheets: &[(&str, &[&[&str]])]) -> Vec<u8> {
+        const FREE: u32 = 0xFFFF_FFFF;
+        const END_OF_CHAIN: u32 = 0xFFFF_FFFE;
+        const FAT_SECTOR: u32 = 0xFFFF_FFFD;
+        let bof = |sheet_type: u16| {
+            let mut body = vec![0x00, 0x06];
+            body.extend_from_slice(&sheet_type.to_le_bytes());
+            body.extend_from_slice(&[0xBB, 0x0D, 0xCC, 0x07, 0, 0, 0, 0, 0x06, 0, 0, 0]);
+            body
+        };
+
+        let mut sheet_streams = Vec::new();
+        for (_, rows) in sheets {
+            let mut sheet = Vec::new();
+            push_record(&mut sheet, 0x0809, &bof(0x0010));
+            for (row_index, row) in rows.iter().enumerate() {
+                for (col_index, value) in row.iter().enumerate().filter(|(_, v)| !v.is_empty()) {
+                    let mut label = Vec::new();
+                    label.extend_from_slice(&(row_index as u16).to_le_bytes());
+                    label.extend_from_slice(&(col_index as u16).to_le_bytes());
+                    label.extend_from_slice(&0u16.to_le_bytes());
+                    label.extend_from_slice(&(value.encode_utf16().count() as u16).to_le_bytes());
+                    label.push(0x01);
+                    label.extend_from_slice(&utf16(value));
+                    push_record(&mut sheet, 0x0204, &label);
+                }
+            }
+            push_record(&mut sheet, 0x000A, &[]);
+            sheet_streams.push(sheet);
+        }
+
+        let globals_len = 20
+            + sheets
+                .iter()
+                .map(|(name, _)| 12 + 2 * name.encode_utf16().count())
+                .sum::<usize>()
+            + 4;
+        let mut stream = Vec::new();
+        push_record(&mut stream, 0x0809, &bof(0x0005));
+        let mut offset = globals_len;
+        for ((name, _), sheet) in sheets.iter().zip(&sheet_streams) {
+            let mut bound_sheet = (offset as u32).to_le_bytes().to_vec();
+            bound_sheet.extend_from_slice(&[0x00, 0x00, name.encode_utf16().count() as u8, 0x01]);
+            bound_sheet.extend_from_slice(&utf16(name));
+            push_record(&mut stream, 0x0085, &bound_sheet);
+            offset += sheet.len();
+        }
+        push_record(&mut stream, 0x000A, &[]);
+        stream.extend(sheet_streams.concat());
+        stream.resize(stream.len().max(4096).next_multiple_of(512), 0);
+        let stream_sectors = (stream.len() / 512) as u32;
+
+        let mut header = vec![0u8; 512];
+        header[..8].copy_from_slice(OLE_SIGNATURE);
+        header[24..26].copy_from_slice(&0x003Eu16.to_le_bytes());
+        header[26..28].copy_from_slice(&3u16.to_le_bytes());
+        header[28..30].copy_from_slice(&0xFFFEu16.to_le_bytes());
+        header[30..32].copy_from_slice(&9u16.to_le_bytes());
+        header[32..34].copy_from_slice(&6u16.to_le_bytes());
+        header[44..48].copy_from_slice(&1u32.to_le_bytes());
+        header[48..52].copy_from_slice(&1u32.to_le_bytes());
+        header[56..60].copy_from_slice(&4096u32.to_le_bytes());
+        header[60..64].copy_from_slice(&END_OF_CHAIN.to_le_bytes());
+        header[68..72].copy_from_slice(&END_OF_CHAIN.to_le_bytes());
+        for (index, entry) in header[76..].chunks_mut(4).enumerate() {
+            entry.copy_from_slice(&if index == 0 { 0 } else { FREE }.to_le_bytes());
+        }
+
+        // Sector 0 is the FAT, sector 1 the directory, the rest the stream
+        let mut fat = vec![FAT_SECTOR, END_OF_CHAIN];
+        fat.extend((3..2 + stream_sectors).chain([END_OF_CHAIN]));
+        fat.resize(128, FREE);
+
+        let directory_entry = |name: &str, object_type: u8, child: u32, start: u32, size: u32| {
+            let mut entry = vec![0u8; 128];
+            let name = utf16(name);
+            entry[..name.len()].copy_from_slice(&name);
+            entry[64..66].copy_from_slice(&(name.len() as u16 + 2).to_le_bytes());
+            entry[66] = object_type;
+            entry[67] = 1;
+            entry[68..72].copy_from_slice(&FREE.to_le_bytes());
+            entry[72..76].copy_from_slice(&FREE.to_le_bytes());
+            entry[76..80].copy_from_slice(&child.to_le_bytes());
+            entry[116..120].copy_from_slice(&start.to_le_bytes());
+            entry[120..124].copy_from_slice(&size.to_le_bytes());
+            entry
+        };
+        let mut directory = directory_entry("Root Entry", 5, 1, END_OF_CHAIN, 0);
+        directory.extend(directory_entry("Workbook", 2, FREE, 2, stream.len() as u32));
+        for _ in 0..2 {
+            directory.extend(directory_entry("", 0, FREE, 0, 0));
+        }
+
+        let mut xls = header;
+        xls.extend(fat.iter().flat_map(|entry| entry.to_le_bytes()));
+        xls.extend(directory);
+        xls.extend(stream);
+        xls
+    }
+
+    #[test]
+    fn test_extract_legacy_xls() {
+        let xls = build_xls(&[
+            ("Budget", &[&["Item", "Cost"], &["Café", "12.50"]]),
+            ("Notes", &[&["Approved"]]),
+        ]);
+
+        let text = XlsxHandler::new()
+            .extract_text(&xls, "budget.xls", "application/vnd.ms-excel")
+            .unwrap();
+
+        assert_eq!(
+            text,
+            "Sheet: Budget\nItem\tCost\nCafé\t12.50\n\n\nSheet: Notes\nApproved"
+        );
+    }
+
+    /// Builds a minimal ODS spreadsheet with float and string cells.
+    ///
+    /// Values that parse as numbers are written as float cells; empty strings
+    /// as empty cells.
+    pub(crate) fn build_ods(sheets: &[(&str, &[&[&str]])]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let stored =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        writer.start_file("mimetype", stored).unwrap();
+        writer.write_all(ODS_MIME_TYPE.as_bytes()).unwrap();
+
+        writer
+            .start_file("META-INF/manifest.xml", SimpleFileOptions::default())
+            .unwrap();
+        writer
+            .write_all(
+                format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?><manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0"><manifest:file-entry manifest:full-path="/" manifest:media-type="{ODS_MIME_TYPE}"/><manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/></manifest:manifest>"#
+                )
+                .as_bytes(),
+            )
+            .unwrap();
+
+        let mut content = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?><office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.2"><office:body><office:spreadsheet>"#,
+        );
+        for (name, rows) in sheets {
+            content.push_str(&format!(r#"<table:table table:name="{name}">"#));
+            for row in *rows {
+                content.push_str("<table:table-row>");
+                for value in *row {
+                    if value.is_empty() {
+                        content.push_str("<table:table-cell/>");
+                    } else if value.parse::<f64>().is_ok() {
+                        content.push_str(&format!(
+                            r#"<table:table-cell office:value-type="float" office:value="{value}"><text:p>{value}</text:p></table:table-cell>"#
+                        ));
+                    } else {
+                        content.push_str(&format!(
+                            r#"<table:table-cell office:value-type="string"><text:p>{value}</text:p></table:table-cell>"#
+                        ));
+                    }
+                }
+                content.push_str("</table:table-row>");
+            }
+            content.push_str("</table:table>");
+        }
+        content.push_str("</office:spreadsheet></office:body></office:document-content>");
+
+        writer
+            .start_file("content.xml", SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_extract_ods() {
+        let ods = build_ods(&[
+            ("Budget", &[&["Item", "Cost"], &["Café", "12.5"]]),
+            ("Notes", &[&["Approved", "", "Ada"]]),
+        ]);
+
+        let text = XlsxHandler::new()
+            .extract_text(&ods, "budget.ods", ODS_MIME_TYPE)
+            .unwrap();
+
+        assert_eq!(
+            text,
+            "Sheet: Budget\nItem\tCost\nCafé\t12.5\n\n\nSheet: Notes\nApproved\tAda"
+        );
+    }
+
+    #[test]
+    fn test_extract_cells_keeps_types() {
+        let xlsx = build_xlsx(&[("Budget", &[&["Coffee", "12.5"], &["", "3"]], &[])]);
+
+        let sheets = XlsxHandler::new().extract_cells(&xlsx).unwrap();
+        let cell = |value: &str, kind: &str| XlsxCell {
+            value: value.to_string(),
+            kind: kind.to_string(),
+        };
+
+        assert_eq!(sheets.len(), 1);
+        assert_eq!(sheets[0].name, "Budget");
+        assert_eq!(
+            sheets[0].rows,
+            vec![
+                vec![cell("Coffee", "text"), cell("12.5", "number")],
+                vec![cell("", "empty"), cell("3", "number")],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_typed_cell_formats_dates() {
+        use calamine::{ExcelDateTime, ExcelDateTimeType};
+
+        let date = |serial| {
+            Data::DateTime(ExcelDateTime::new(
+                serial,
+                ExcelDateTimeType::DateTime,
+                false,
+            ))
+        };
+
+        assert_eq!(typed_cell(&date(45306.0)).value, "2024-01-15");
+        assert_eq!(typed_cell(&date(45306.4375)).value, "2024-01-15T10:30:00");
+        assert_eq!(typed_cell(&date(45306.0)).kind, "date");
+        assert_eq!(typed_cell(&Data::Bool(true)).kind, "bool");
+    }
+
+    #[test]
+    fn test_preserve_empty_cells() {
+        let xlsx = build_xlsx(&[(
+            "Sheet1",
+            &[&["A", "", "C"], &["", "", ""], &["D", "E", ""]],
+            &[],
+        )]);
+
+        let text = XlsxHandler::new().extract_text_from_xlsx(&xlsx).unwrap();
+        assert_eq!(text, "Sheet: Sheet1\nA\tC\nD\tE");
+
+        let text = XlsxHandler::new()
+            .with_preserve_empty_cells(true)
+            .extract_text_from_xlsx(&xlsx)
+            .unwrap();
+        assert_eq!(text, "Sheet: Sheet1\nA\t\tC\n\t\t\nD\tE\t");
+    }
+
+    #[test]
+    fn test_with_sheets() {
+        let xlsx = build_xlsx(&[
+            ("Details", &[&["Line item", "42"]], &[]),
+            ("Summary", &[&["Total", "100"]], &[]),
+        ]);
+
+        let text = XlsxHandler::new()
+            .with_sheets(vec!["Summary".to_string()])
+            .extract_text_from_xlsx(&xlsx)
+            .unwrap();
+        assert_eq!(text, "Sheet: Summary\nTotal\t100");
+
+        let text = XlsxHandler::new()
+            .with_sheets(vec!["0".to_string()])
+            .extract_text_from_xlsx(&xlsx)
+            .unwrap();
+        assert_eq!(text, "Sheet: Details\nLine item\t42");
+
+        let text = XlsxHandler::new()
+            .with_sheets(Vec::new())
+            .extract_text_from_xlsx(&xlsx)
+            .unwrap();
+        assert!(text.contains("Details") && text.contains("Summary"));
+    }
+
+    #[test]
+    fn test_expand_merged_cells() {
+        let xlsx = build_xlsx(&[(
+            "Report",
+            &[
+                &["Quarter", "", "Region"],
+                &["Q1", "Q2", "North"],
+                &["Q3", "Q4", ""],
+            ],
+            &["A1:B1", "C2:C3"],
+        )]);
+
+        let text = XlsxHandler::new().extract_text_from_xlsx(&xlsx).unwrap();
+        assert_eq!(
+            text,
+            "Sheet: Report\nQuarter\tRegion\nQ1\tQ2\tNorth\nQ3\tQ4"
+        );
+
+        let text = XlsxHandler::new()
+            .with_expand_merged_cells(true)
+            .extract_text_from_xlsx(&xlsx)
+            .unwrap();
+        assert_eq!(
+            text,
+            "Sheet: Report\nQuarter\tQuarter\tRegion\nQ1\tQ2\tNorth\nQ3\tQ4\tNorth"
+        );
+    }
 }
@@ -0,0 +1,350 @@
+//! ZIP archive handler for extracting text from the files inside an archive.
+//!
+//! Archive entries are decompressed in memory and routed back through a set of
+//! file handlers, so a `.zip` of mixed documents yields the text of every
+//! supported document it contains. Size and entry limits guard against
+//! decompression bombs.
+
+use crate::core::detect::sniff_mime;
+use crate::core::embedded::MAX_EMBEDDED_DEPTH;
+use crate::core::handler::FileHandler;
+use crate::core::mime::mime_from_filename;
+use std::io::{Cursor, Read};
+use std::sync::Arc;
+use zip::ZipArchive;
+
+/// Default limit on the total uncompressed size of an archive's entries (256 MiB).
+pub const DEFAULT_MAX_UNCOMPRESSED_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Default limit on the number of entries in an archive.
+pub const DEFAULT_MAX_ENTRIES: usize = 1_000;
+
+/// The limits left for an archive and the archives nested inside it.
+struct Budget {
+    bytes: u64,
+    entries: usize,
+}
+
+/// Handler for processing ZIP archives.
+///
+/// The `ZipHandler` extracts every file in an archive with the first handler
+/// that accepts it, and joins the results under a `=== entry: name ===` line
+/// per entry. Archives nested inside the archive are extracted the same way,
+/// up to `MAX_EMBEDDED_DEPTH` levels deep, with their entries named by their
+/// path through the outer archives (`inner.zip/notes.txt`).
+///
+/// # Supported MIME Types
+///
+/// - `application/zip` - ZIP archives
+/// - `application/x-zip-compressed` - Alternative ZIP MIME type
+///
+/// # Processing Flow
+///
+/// 1. Opens the archive and checks its entry count against the limit
+/// 2. For each file entry, in archive order:
+///    - Decompresses it, counting its actual (not declared) size against the
+///      uncompressed size limit
+///    - Infers its MIME type from the file extension, or detects it from the
+///      content's magic bytes
+///    - Extracts its text with the first matching handler
+/// 3. Joins the non-empty entries, each under its `=== entry: name ===` line
+///
+/// # Limits
+///
+/// The entry count and uncompressed size limits apply to the archive and all
+/// archives nested in it together. Exceeding either fails the extraction of
+/// the whole archive instead of returning partial text.
+///
+/// # Limitations
+///
+/// - Entries without a matching handler, and entries that fail to extract,
+///   are skipped
+/// - Encrypted entries cannot be read and are skipped
+pub struct ZipHandler {
+    handlers: Vec<Arc<dyn FileHandler>>,
+    max_uncompressed_bytes: u64,
+    max_entries: usize,
+}
+
+impl ZipHandler {
+    /// Creates a new `ZipHandler` dispatching entries to `handlers`.
+    ///
+    /// # Arguments
+    ///
+    /// * `handlers` - The handlers used for archive entries, in priority order
+    ///
+    /// # Returns
+    ///
+    /// A new `ZipHandler` with the default limits.
+    pub fn new(handlers: Vec<Arc<dyn FileHandler>>) -> Self {
+        Self {
+            handlers,
+            max_uncompressed_bytes: DEFAULT_MAX_UNCOMPRESSED_BYTES,
+            max_entries: DEFAULT_MAX_ENTRIES,
+        }
+    }
+
+    /// Sets the limit on the total uncompressed size of the archive's entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_uncompressed_bytes` - Maximum number of decompressed bytes
+    ///
+    /// # Returns
+    ///
+    /// The handler with the limit applied.
+    pub fn with_max_uncompressed_bytes(mut self, max_uncompressed_bytes: u64) -> Self {
+        self.max_uncompressed_bytes = max_uncompressed_bytes;
+        self
+    }
+
+    /// Sets the limit on the number of entries in the archive.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_entries` - Maximum number of entries, directories included
+    ///
+    /// # Returns
+    ///
+    /// The handler with the limit applied.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Extracts the text of the entries of an opened ZIP archive.
+    ///
+    /// The entries of nested archives are added in place of the archive, so
+    /// the sections of all levels form a single flat list.
+    ///
+    /// # Arguments
+    ///
+    /// * `archive` - The opened archive
+    /// * `prefix` - Path of the archive inside its outer archives, prepended
+    ///   to entry names ("" for the top-level archive)
+    /// * `depth` - Nesting depth of the archive (1 for the top-level archive)
+    /// * `budget` - The limits left, shared with the outer archives
+    /// * `sections` - Receives the text of each extracted entry under its header line
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All entries were visited
+    /// * `Err(String)` - Error message if the archive, or an archive nested in
+    ///   it, exceeds a limit
+    fn extract_text_from_zip(
+        &self,
+        mut archive: ZipArchive<Cursor<&[u8]>>,
+        prefix: &str,
+        depth: usize,
+        budget: &mut Budget,
+        sections: &mut Vec<String>,
+    ) -> Result<(), String> {
+        if archive.len() > budget.entries {
+            return Err(format!(
+                "ZIP archive exceeds the limit of {} entries",
+                self.max_entries
+            ));
+        }
+        budget.entries -= archive.len();
+
+        for index in 0..archive.len() {
+            let Ok(entry) = archive.by_index(index) else {
+                continue;
+            };
+            if entry.is_dir() {
+                continue;
+            }
+            let name = format!("{}{}", prefix, entry.name());
+
+            // Read one byte past the budget to detect entries that exceed it
+            let mut entry_content = Vec::new();
+            if entry
+                .take(budget.bytes.saturating_add(1))
+                .read_to_end(&mut entry_content)
+                .is_err()
+            {
+                continue;
+            }
+            if entry_content.len() as u64 > budget.bytes {
+                return Err(format!(
+                    "ZIP archive exceeds the limit of {} uncompressed bytes",
+                    self.max_uncompressed_bytes
+                ));
+            }
+            budget.bytes -= entry_content.len() as u64;
+
+            let Some(mime_type) = mime_from_filename(&name)
+                .map(str::to_string)
+                .or_else(|| sniff_mime(&entry_content))
+            else {
+                continue;
+            };
+
+            if self.can_handle(&mime_type) {
+                if depth < MAX_EMBEDDED_DEPTH
+                    && let Ok(nested) = ZipArchive::new(Cursor::new(entry_content.as_slice()))
+                {
+                    let prefix = format!("{}/", name);
+                    self.extract_text_from_zip(nested, &prefix, depth + 1, budget, sections)?;
+                }
+                continue;
+            }
+
+            let Some(handler) = self.handlers.iter().find(|h| h.can_handle(&mime_type)) else {
+                continue;
+            };
+            if let Ok(text) = handler.extract_text(&entry_content, &name, &mime_type)
+                && !text.is_empty()
+            {
+                sections.push(format!("=== entry: {} ===\n{}", name, text));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FileHandler for ZipHandler {
+    /// Determines if this handler can process ZIP archives.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_type` - The MIME type string to check
+    ///
+    /// # Returns
+    ///
+    /// `true` if the MIME type represents a ZIP archive, `false` otherwise.
+    fn can_handle(&self, mime_type: &str) -> bool {
+        mime_type == "application/zip" || mime_type == "application/x-zip-compressed"
+    }
+
+    /// Lists the MIME types this handler accepts.
+    fn supported_mimes(&self) -> Vec<String> {
+        ["application/zip", "application/x-zip-compressed"]
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    /// Returns the handler's name, `ZipHandler`.
+    fn name(&self) -> &str {
+        "ZipHandler"
+    }
+
+    /// Extracts the text of the files inside a ZIP archive.
+    ///
+    /// This is the main entry point for ZIP text extraction. It opens the
+    /// archive and delegates to `extract_text_from_zip()` with the handler's
+    /// full limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw archive content as a byte slice
+    /// * `_filename` - The filename (unused, kept for trait compatibility)
+    /// * `_mime_type` - The MIME type (unused, already verified by `can_handle()`)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The text of each extracted entry under its header line
+    /// * `Err(String)` - Error message if the archive cannot be opened or exceeds a limit
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crate::handlers::zip::ZipHandler;
+    /// # use crate::core::handler::FileHandler;
+    /// let handler = ZipHandler::new(vec![Arc::new(TextHandler::new())]);
+    /// let text = handler.extract_text(&zip_bytes, "documents.zip", "application/zip");
+    /// ```
+    fn extract_text(
+        &self,
+        content: &[u8],
+        _filename: &str,
+        _mime_type: &str,
+    ) -> Result<String, String> {
+        let archive = ZipArchive::new(Cursor::new(content))
+            .map_err(|e| format!("Failed to open ZIP archive: {}", e))?;
+        let mut budget = Budget {
+            bytes: self.max_uncompressed_bytes,
+            entries: self.max_entries,
+        };
+        let mut sections = Vec::new();
+        self.extract_text_from_zip(archive, "", 1, &mut budget, &mut sections)?;
+
+        Ok(sections.join("\n\n"))
+    }
+
+    /// Returns the estimated peak memory use per input byte.
+    ///
+    /// Entries are decompressed one at a time, but compressed text commonly
+    /// expands several times; roughly eight times the file size.
+    fn memory_multiplier(&self) -> f64 {
+        8.0
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::handlers::csv::CsvHandler;
+    use crate::handlers::text::TextHandler;
+    use std::io::Write;
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    /// Builds a ZIP archive with one deflated entry per `(name, content)`.
+    pub(crate) fn build_zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn handler() -> ZipHandler {
+        ZipHandler::new(vec![
+            Arc::new(CsvHandler::new()),
+            Arc::new(TextHandler::new()),
+        ])
+    }
+
+    #[test]
+    fn test_extract_entries() {
+        let inner = build_zip(&[("readme.txt", b"Nested notes")]);
+        let archive = build_zip(&[
+            ("notes.txt", b"Meeting notes"),
+            ("data/people.csv", b"name,city\nAda,London\n"),
+            ("logo.bin", &[0x00, 0x01, 0x02]),
+            ("inner.zip", &inner),
+        ]);
+
+        let text = handler()
+            .extract_text(&archive, "upload.zip", "application/zip")
+            .unwrap();
+
+        assert_eq!(
+            text,
+            "=== entry: notes.txt ===\nMeeting notes\n\n\
+             === entry: data/people.csv ===\nname\tcity\nAda\tLondon\n\n\
+             === entry: inner.zip/readme.txt ===\nNested notes"
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let archive = build_zip(&[("a.txt", &[b'a'; 2000]), ("b.txt", b"b")]);
+
+        let err = handler()
+            .with_max_uncompressed_bytes(1000)
+            .extract_text(&archive, "bomb.zip", "application/zip")
+            .unwrap_err();
+        assert!(err.contains("1000 uncompressed bytes"));
+
+        let err = handler()
+            .with_max_entries(1)
+            .extract_text(&archive, "many.zip", "application/zip")
+            .unwrap_err();
+        assert!(err.contains("limit of 1 entries"));
+    }
+}
@@ -1,24 +1,54 @@
 mod core;
 mod handlers;
 mod models;
+mod processor;
 
-use crate::core::handler::FileHandler;
-use crate::core::similarity::{SimilarityMethod, compare_with_documents};
+pub use crate::core::handler::{ExtractedText, FileHandler};
+pub use crate::models::options::ProcessOptions;
+pub use crate::processor::DocumentProcessor;
+
+use crate::core::clustering::cluster_texts;
+use crate::core::preprocess::{PreprocessOptions, preprocess_text};
+use crate::core::reference_index::{ReferenceIndex, build_reference_index, compare_with_index};
+use crate::core::sampling::{DEFAULT_SAMPLE_FRACTION, sample_text, should_sample};
+use crate::core::similarity::{
+    DEFAULT_NGRAM_SIZE, Granularity, Parallelism, SimilarityMethod, best_document_match,
+    calculate_similarity, compare_with_documents, compare_with_documents_streaming, dedupe_texts,
+    expand_duplicate_matches, pairwise_similarities, rank_matches, sliding_window_match_with,
+    split_paragraphs, weighted_levenshtein_similarity,
+};
+use crate::core::similarity_cache;
+use crate::core::summary::summarize_results;
+use crate::core::thread_pool;
+use crate::core::tokenize::TokenizerOptions;
 
 use crate::handlers::docx::DocxHandler;
-use crate::handlers::image::ImageHandler;
 use crate::handlers::pdf::PdfHandler;
-use crate::handlers::text::TextHandler;
 use crate::handlers::xlsx::XlsxHandler;
+use crate::models::alignment::EditOperation;
+use crate::models::docx::DocxProperties;
 use crate::models::file::FileMetadataWithSimilarity;
+use crate::models::ocr::OcrLine;
+use crate::models::options::CompareOptions;
+use crate::models::pdf::PdfMetadata;
+use crate::models::summary::SimilaritySummary;
+use crate::models::xlsx::XlsxSheet;
 
+use crate::processor::{build_image_handler, elapsed_ms};
 use dashmap::DashMap;
 use models::file::{
-    FileInput, FileMetadata, GroupedFiles, GroupedFilesWithSimilarity, SimilarityMatch,
+    ExtractedFile, FileInput, FileMetadata, GroupedAndFlatFiles, GroupedFiles,
+    GroupedFilesWithSimilarity, GroupedFilesWithStats, SimilarityMatch, SimilarityPair,
 };
+use napi::Status;
+use napi::bindgen_prelude::{AsyncTask, Buffer, FnArgs};
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Env, Task};
 use napi_derive::napi;
 use rayon::prelude::*;
-use std::sync::Arc;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::time::Instant;
 
 /// Processes an array of files and extracts text content from them.
 ///
@@ -29,10 +59,16 @@ use std::sync::Arc;
 /// # Supported File Types
 ///
 /// - Text files (text/plain, text/csv, text/tsv, and other text-based MIME types)
+/// - YAML and TOML config files, flattened into `key: value` lines
+/// - HTML documents (text/html, application/xhtml+xml), visible text only
+/// - Markdown documents (text/markdown, text/x-markdown), rendered to plain text
 /// - PDF documents (application/pdf)
 /// - Microsoft Word documents (DOCX format)
-/// - Excel spreadsheets (XLSX format)
+/// - Excel spreadsheets (XLSX and XLS formats) and OpenDocument spreadsheets (ODS)
 /// - Images with OCR support (PNG, JPEG, GIF, BMP, TIFF, WebP)
+/// - Email messages (message/rfc822): subject, sender, date, and body
+/// - ZIP archives, with each entry extracted by the matching handler
+/// - Gzip files (application/gzip), decompressed and extracted by the matching handler
 ///
 /// # Processing Flow
 ///
@@ -42,6 +78,11 @@ use std::sync::Arc;
 /// 4. Groups results by MIME type for easy access
 /// 5. Returns grouped results with metadata for each file
 ///
+/// If no handler accepts the declared MIME type (for example a PDF uploaded as
+/// `application/octet-stream`), the type is detected from the file's magic
+/// bytes and the file is dispatched to the matching handler. Results stay
+/// grouped by the declared MIME type.
+///
 /// # Parallel Processing
 ///
 /// Files are processed in parallel using Rayon, which automatically utilizes
@@ -53,16 +94,81 @@ use std::sync::Arc;
 /// If a file cannot be processed (no handler found, extraction fails, etc.),
 /// the function still includes it in the results with:
 /// - `encoding` set to "error" or "application/octet-stream"
-/// - `text_content` containing an error message or empty string
+/// - `error` containing the extraction error message, if extraction failed
+/// - `text_content` left empty
+///
+/// `handled` tells an unsupported file apart from one without text: it is
+/// `false` when no handler accepts the file, and `true` when a handler ran,
+/// even if it found no text (`encoding` is then the source encoding, e.g.
+/// "utf-8").
+///
+/// When `fail_fast` is enabled, the first extraction failure instead rejects
+/// the whole batch with an error naming the failing file. Files that have not
+/// started processing yet are skipped.
+///
+/// # Embedded Documents
+///
+/// When `extract_embedded` is enabled, documents stored under `word/embeddings/`
+/// or `xl/embeddings/` are routed through the same handlers, recursively up to
+/// `MAX_EMBEDDED_DEPTH` levels deep. Embedded documents that cannot be handled
+/// or fail to extract are skipped without affecting the host file.
 ///
 /// # Arguments
 ///
 /// * `files` - A vector of `FileInput` objects containing file content, MIME type, and filename
+/// * `options` - Optional `ProcessOptions` controlling extraction behavior:
+///   - `fail_fast`: Reject the whole batch on the first extraction error (default: false)
+///   - `timeout_ms`: Give up on a file whose extraction takes longer than this
+///     many milliseconds, reporting it with the "timeout" encoding (default: no timeout)
+///   - `max_bytes`: Skip files larger than this many bytes without running a
+///     handler, reporting them with the "too_large" encoding (default: no limit)
+///   - `pdf_page_markers`: Split PDF text per page with `--- Page N ---` markers (default: false)
+///   - `pdf_password`: Open password for encrypted PDFs (default: none). PDFs
+///     that need an open password are not supported yet; the password makes
+///     their error report whether it was correct.
+///   - `extract_embedded`: Append the text of documents embedded in DOCX and XLSX
+///     files, each under an `Embedded: name` header (default: false)
+///   - `expand_merged_cells`: Repeat merged XLSX cell and DOCX table cell values
+///     across the merged region so rows stay column-aligned (default: false)
+///   - `preserve_empty_cells`: Keep empty XLSX cells and rows so tab-separated
+///     columns stay aligned (default: false)
+///   - `sheets`: Only extract the listed XLSX sheets, by name or zero-based
+///     index such as "0" (default: all sheets)
+///   - `report_memory`: Fill `estimated_peak_bytes` with a heuristic estimate of
+///     each file's peak extraction memory (default: false)
+///   - `include_hash`: Fill `content_hash` with the hex SHA-256 of each file's
+///     content, for exact-duplicate detection (default: false)
+///   - `detect_language`: Fill `language` and `language_confidence` with the
+///     detected language of each extracted text (default: false)
+///   - `normalize_whitespace`: Normalize the whitespace of every extracted text
+///     to single spaces and `\n` line breaks, without empty lines (default: false)
+///   - `redact_patterns` / `redact_mask`: Regular expressions (`regex` crate
+///     syntax) replaced with the mask in every extracted text; an invalid
+///     pattern fails the call before processing (default: none, "[REDACTED]")
+///   - `ocr_detection_model` / `ocr_recognition_model`: Paths to alternate `.rten`
+///     OCR models, e.g. a recognition model for another language (default: bundled models)
+///   - `ocr_alphabet`: Alphabet of a custom recognition model (default: bundled alphabet)
+///   - `docx_link_targets`: Append each DOCX hyperlink's URL in brackets after
+///     its display text (default: false)
+///   - `include_image_alt`: Emit the alt text of DOCX pictures as
+///     `[image: alt text]` where they appear (default: false)
+///   - `zip_max_uncompressed_bytes` / `zip_max_entries`: Limits on the total
+///     decompressed size and number of entries of ZIP archives, guarding
+///     against decompression bombs (default: 256 MiB and 1000 entries)
+///   - `gzip_max_output_bytes`: Limit on the decompressed size of gzip files
+///     (default: 256 MiB)
+///   - `gzip_inner_mime_type`: MIME type of the decompressed content of gzip
+///     files (default: inferred from the filename, the content, or "text/plain")
+///   - `binary_entropy_threshold`: Byte entropy (bits per byte) at or above
+///     which a text, CSV, JSON, Markdown, HTML, YAML, or TOML file is rejected
+///     as binary; above 8 disables the check, and a negative or NaN value
+///     fails the call (default: 7.5)
 ///
 /// # Returns
 ///
 /// A vector of `GroupedFiles` objects, where each group contains files of the same MIME type
-/// along with their extracted text content and metadata.
+/// along with their extracted text content and metadata, or an error if `fail_fast`
+/// is enabled and any file failed to extract.
 ///
 /// # Example
 ///
@@ -78,52 +184,359 @@ use std::sync::Arc;
 ///     }
 /// ];
 ///
-/// let results = process_files(files);
+/// let results = process_files(files, None)?;
 /// ```
 #[napi]
-pub fn process_files(files: Vec<FileInput>) -> Vec<GroupedFiles> {
-    let handlers: Vec<Arc<dyn FileHandler>> = vec![
-        Arc::new(DocxHandler::new()),
-        Arc::new(ImageHandler::new()),
-        Arc::new(PdfHandler::new()),
-        Arc::new(TextHandler::new()),
-        Arc::new(XlsxHandler::new()),
-    ];
+pub fn process_files(
+    files: Vec<FileInput>,
+    options: Option<ProcessOptions>,
+) -> napi::Result<Vec<GroupedFiles>> {
+    thread_pool::install(|| DocumentProcessor::new(options.unwrap_or_default()).process(&files))
+}
 
-    let grouped: DashMap<String, Vec<FileMetadata>> = DashMap::new();
+/// Processes multiple files like `process_files`, one at a time on the calling
+/// thread.
+///
+/// `process_files` extracts files in parallel on a Rayon thread pool. Rust
+/// embedders calling it from their own pool, and single-threaded environments,
+/// may not want it to start parallel work. This variant starts none: files are
+/// extracted in input order, with the same handlers and options. Groups are
+/// listed in the order their MIME type first occurs in `files`.
+///
+/// # Arguments
+///
+/// * `files` - A vector of `FileInput` objects containing file content, MIME type, and filename
+/// * `options` - Optional `ProcessOptions`, as for `process_files`
+///
+/// # Returns
+///
+/// The same `GroupedFiles` as `process_files`, or an error if `fail_fast` is
+/// enabled and any file failed to extract.
+///
+/// # Example
+///
+/// ```typescript
+/// const groups = processFilesSequential(files);
+/// ```
+#[napi]
+pub fn process_files_sequential(
+    files: Vec<FileInput>,
+    options: Option<ProcessOptions>,
+) -> napi::Result<Vec<GroupedFiles>> {
+    DocumentProcessor::new(options.unwrap_or_default()).process_sequential(&files)
+}
 
-    files.par_iter().for_each(|file| {
-        let content = file.content.as_ref();
-        let size = content.len() as f64;
+/// Processes multiple files like `process_files` and reports batch statistics.
+///
+/// Monitoring usually needs only a few numbers per batch. This function
+/// returns them next to the grouped results, counted while the files are
+/// processed, so callers don't have to walk every file.
+///
+/// # Arguments
+///
+/// * `files` - A vector of `FileInput` objects containing file content, MIME type, and filename
+/// * `options` - Optional `ProcessOptions`, as for `process_files`
+///
+/// # Returns
+///
+/// A `GroupedFilesWithStats` object with the `groups` that `process_files`
+/// returns and their `BatchStats`: the number of files, successes, failures,
+/// and unhandled files, the total size, and the summed processing time. Returns
+/// an error if `fail_fast` is enabled and any file failed to extract.
+///
+/// # Example
+///
+/// ```typescript
+/// const { groups, stats } = processFilesWithStats(files);
+/// metrics.gauge('extraction.failures', stats.failures);
+/// ```
+#[napi]
+pub fn process_files_with_stats(
+    files: Vec<FileInput>,
+    options: Option<ProcessOptions>,
+) -> napi::Result<GroupedFilesWithStats> {
+    let (groups, stats) = thread_pool::install(|| {
+        DocumentProcessor::new(options.unwrap_or_default()).process_with_stats(&files)
+    })?;
 
-        let handler = handlers.iter().find(|h| h.can_handle(&file.mime_type));
+    Ok(GroupedFilesWithStats { groups, stats })
+}
 
-        let (text_content, encoding) = match handler {
-            Some(h) => match h.extract_text(content, &file.filename, &file.mime_type) {
-                Ok(text) => (text, "utf-8".to_string()),
-                Err(err) => (format!("Error: {}", err), "error".to_string()),
-            },
-            None => (String::new(), "application/octet-stream".to_string()),
-        };
+/// Extracts text from a single file.
+///
+/// `process_files` returns its results grouped by MIME type, which is awkward
+/// when there is only one file. This function runs the same handler dispatch
+/// (including MIME type detection from the content and every option) on one
+/// file and returns its result directly.
+///
+/// # Arguments
+///
+/// * `content` - The raw file content
+/// * `mime_type` - The MIME type of the file
+/// * `filename` - The name of the file, used by some handlers and in error
+///   messages
+/// * `options` - Optional `ProcessOptions`, as for `process_files`
+///   (`fail_fast` has no effect: failures are always reported in `error`)
+///
+/// # Returns
+///
+/// An `ExtractedFile` object with the extracted `text`, the `encoding`, and
+/// the `error` message if extraction failed.
+///
+/// # Example
+///
+/// ```typescript
+/// const { text, error } = extractText(buffer, 'application/pdf', 'report.pdf');
+/// if (error) console.warn(error);
+/// ```
+#[napi]
+pub fn extract_text(
+    content: Buffer,
+    mime_type: String,
+    filename: String,
+    options: Option<ProcessOptions>,
+) -> ExtractedFile {
+    let file = FileInput {
+        content,
+        mime_type,
+        filename,
+    };
 
-        let metadata = FileMetadata {
-            name: file.filename.clone(),
-            size,
-            processing_time_ms: 0.0,
-            encoding,
-            text_content,
-        };
+    thread_pool::install(|| {
+        DocumentProcessor::new(options.unwrap_or_default())
+            .extract_file(&file)
+            .into()
+    })
+}
 
-        grouped
-            .entry(file.mime_type.clone())
-            .or_insert_with(Vec::new)
-            .push(metadata);
-    });
+/// The number of streamed results that may wait for the JavaScript thread
+/// before extraction pauses.
+const STREAMED_RESULT_QUEUE_SIZE: usize = 16;
 
-    grouped
-        .into_iter()
-        .map(|(mime_type, files)| GroupedFiles { mime_type, files })
-        .collect()
+/// Callback receiving each streamed result with the file's index in the input.
+///
+/// The queue of pending calls is bounded, so results are held in memory only
+/// until JavaScript catches up.
+type FileCallback = ThreadsafeFunction<
+    FnArgs<(FileMetadata, u32)>,
+    (),
+    FnArgs<(FileMetadata, u32)>,
+    Status,
+    false,
+    false,
+    { STREAMED_RESULT_QUEUE_SIZE },
+>;
+
+/// Background task behind `process_files_streaming`.
+///
+/// Extraction runs on the libuv thread pool so the event loop stays free to
+/// run the callback while files are still being processed.
+pub struct ProcessFilesStreaming {
+    files: Vec<FileInput>,
+    options: ProcessOptions,
+    callback: FileCallback,
+}
+
+impl Task for ProcessFilesStreaming {
+    type Output = u32;
+    type JsValue = u32;
+
+    fn compute(&mut self) -> napi::Result<u32> {
+        let processor = DocumentProcessor::new(std::mem::take(&mut self.options));
+        let callback = &self.callback;
+
+        thread_pool::install(|| {
+            processor.process_each(&self.files, |index, metadata| {
+                // Blocks the worker while the queue is full, so extraction
+                // cannot run ahead of the callback
+                match callback.call(
+                    (metadata, index as u32).into(),
+                    ThreadsafeFunctionCallMode::Blocking,
+                ) {
+                    Status::Ok => Ok(()),
+                    status => Err(napi::Error::new(
+                        status,
+                        "Failed to deliver a streamed result to the callback",
+                    )),
+                }
+            })
+        })
+    }
+
+    fn resolve(&mut self, _env: Env, output: u32) -> napi::Result<u32> {
+        Ok(output)
+    }
+}
+
+/// Processes files and streams each result to a callback as it completes.
+///
+/// `process_files` holds every extracted text in memory until the whole batch
+/// is done. For very large batches this function instead hands each file's
+/// result to `callback` as soon as it is extracted, so it can be written to
+/// disk or a database right away and released.
+///
+/// At most 16 results wait for the JavaScript thread at a time: when the
+/// callback falls behind, extraction pauses until it catches up, so memory
+/// stays bounded however slowly results are consumed.
+///
+/// # Ordering
+///
+/// Files are processed in parallel, so results arrive in completion order,
+/// which is non-deterministic and generally differs from the input order.
+/// The callback receives each file's index in `files` to match results to
+/// their inputs.
+///
+/// # Arguments
+///
+/// * `files` - A vector of `FileInput` objects containing file content, MIME type, and filename
+/// * `callback` - Called on the JavaScript thread with `(file, index)` for each
+///   processed file
+/// * `options` - Optional `ProcessOptions`, as for `process_files`. With
+///   `fail_fast`, the first extraction failure rejects the promise; results
+///   already delivered stay delivered and the failing file is not emitted.
+///
+/// # Returns
+///
+/// A promise resolving to the number of results delivered. Every callback
+/// has been queued by the time it resolves.
+///
+/// # Example
+///
+/// ```typescript
+/// const count = await processFilesStreaming(files, (file, index) => {
+///   db.insert(files[index].filename, file.textContent);
+/// });
+/// ```
+#[napi(
+    ts_args_type = "files: Array<FileInput>, callback: (file: FileMetadata, index: number) => void, options?: ProcessOptions | undefined | null"
+)]
+pub fn process_files_streaming(
+    files: Vec<FileInput>,
+    callback: FileCallback,
+    options: Option<ProcessOptions>,
+) -> AsyncTask<ProcessFilesStreaming> {
+    AsyncTask::new(ProcessFilesStreaming {
+        files,
+        options: options.unwrap_or_default(),
+        callback,
+    })
+}
+
+/// Background task behind `process_files_async`.
+///
+/// Extraction runs on the libuv thread pool, which hands the parallel work to
+/// Rayon, so the event loop is not blocked while the batch is processed.
+pub struct ProcessFilesAsync {
+    files: Vec<FileInput>,
+    options: ProcessOptions,
+}
+
+impl Task for ProcessFilesAsync {
+    type Output = Vec<GroupedFiles>;
+    type JsValue = Vec<GroupedFiles>;
+
+    fn compute(&mut self) -> napi::Result<Vec<GroupedFiles>> {
+        let processor = DocumentProcessor::new(std::mem::take(&mut self.options));
+
+        thread_pool::install(|| processor.process(&self.files))
+    }
+
+    fn resolve(&mut self, _env: Env, output: Vec<GroupedFiles>) -> napi::Result<Vec<GroupedFiles>> {
+        Ok(output)
+    }
+}
+
+/// Processes files like `process_files` without blocking the event loop.
+///
+/// `process_files` runs synchronously, so a large batch stalls every other
+/// request of a web server until it is done. This function runs the same
+/// extraction in the background and returns a promise.
+///
+/// # Arguments
+///
+/// * `files` - A vector of `FileInput` objects containing file content, MIME type, and filename
+/// * `options` - Optional `ProcessOptions`, as for `process_files`
+///
+/// # Returns
+///
+/// A promise resolving to the same `GroupedFiles` as `process_files`, or
+/// rejecting if `fail_fast` is enabled and any file failed to extract.
+///
+/// # Example
+///
+/// ```typescript
+/// app.post('/extract', async (req, res) => {
+///   res.json(await processFilesAsync(req.body.files));
+/// });
+/// ```
+#[napi]
+pub fn process_files_async(
+    files: Vec<FileInput>,
+    options: Option<ProcessOptions>,
+) -> AsyncTask<ProcessFilesAsync> {
+    AsyncTask::new(ProcessFilesAsync {
+        files,
+        options: options.unwrap_or_default(),
+    })
+}
+
+/// Processes files once and returns both grouped and flat result layouts.
+///
+/// Some consumers need results grouped by MIME type while others need a flat
+/// list in input order. This function runs the (expensive) extraction a single
+/// time and derives both shapes from the same results, instead of requiring
+/// two calls to `process_files`.
+///
+/// # Output Layouts
+///
+/// - `grouped`: Files grouped by MIME type, same as `process_files`. Groups
+///   appear in the order their MIME type first occurs in the input.
+/// - `flat`: One `FileMetadata` per input file, in input order.
+///
+/// # Arguments
+///
+/// * `files` - A vector of `FileInput` objects containing file content, MIME type, and filename
+/// * `options` - Optional `ProcessOptions`, as for `process_files`
+///
+/// # Returns
+///
+/// A `GroupedAndFlatFiles` object containing both layouts, or an error if
+/// `fail_fast` is enabled and any file failed to extract.
+///
+/// # Example
+///
+/// ```no_run
+/// use dms_toolkit_rs::process_files_grouped_and_flat;
+///
+/// let results = process_files_grouped_and_flat(files, None)?;
+/// let first_file = &results.flat[0];
+/// let pdf_group = results.grouped.iter().find(|g| g.mime_type == "application/pdf");
+/// ```
+#[napi]
+pub fn process_files_grouped_and_flat(
+    files: Vec<FileInput>,
+    options: Option<ProcessOptions>,
+) -> napi::Result<GroupedAndFlatFiles> {
+    // Collect into an input-ordered vector once, then derive the grouped view
+    let flat = thread_pool::install(|| {
+        DocumentProcessor::new(options.unwrap_or_default()).process_flat(&files)
+    })?;
+
+    let mut grouped: Vec<GroupedFiles> = Vec::new();
+    for (file, metadata) in files.iter().zip(&flat) {
+        match grouped
+            .iter_mut()
+            .find(|group| group.mime_type == file.mime_type)
+        {
+            Some(group) => group.files.push(metadata.clone()),
+            None => grouped.push(GroupedFiles {
+                mime_type: file.mime_type.clone(),
+                files: vec![metadata.clone()],
+            }),
+        }
+    }
+
+    Ok(GroupedAndFlatFiles { grouped, flat })
 }
 
 /// Processes files and compares extracted text against reference documents.
@@ -140,17 +553,47 @@ pub fn process_files(files: Vec<FileInput>) -> Vec<GroupedFiles> {
 ///   comparisons and initial filtering. Splits texts into words and calculates
 ///   intersection over union.
 ///
-/// - **"ngram"**: Character n-gram based similarity (uses 3-grams). Good for
-///   longer texts where word-based methods might miss character-level similarities.
+/// - **"ngram"**: Character n-gram based similarity (uses 3-grams unless
+///   `ngram_size` is set). Good for longer texts where word-based methods might
+///   miss character-level similarities.
+///
+/// - **"word_ngram"**: Word n-gram (shingle) based similarity (uses word 3-grams
+///   unless `ngram_size` is set). Rewards shared phrases, where "jaccard"
+///   rewards shared words in any order.
 ///
 /// - **"levenshtein"**: Edit distance based similarity. Calculates the minimum
 ///   number of edits needed to transform one string into another. More accurate
 ///   but slower for long texts.
 ///
+/// - **"damerau"**: Damerau-Levenshtein edit distance. Like "levenshtein", but a
+///   swap of two adjacent characters counts as one edit, which suits OCR output.
+///
+/// - **"cosine"**: Cosine similarity over TF-IDF weighted terms. The IDF weights
+///   are computed across the file's text plus all reference texts, so rare terms
+///   dominate and shared stopwords contribute almost nothing.
+///
+/// - **"jarowinkler"**: Jaro-Winkler similarity. Best for short strings such as
+///   names and titles, where transposed characters should be penalized lightly.
+///
+/// - **"weighted"**: A blend of "jaccard", "ngram", and "levenshtein" scores,
+///   weighted by the `weights` option (equal weights by default). Every method
+///   with a non-zero weight runs, so it is as slow as the slowest of them.
+///
+/// - **"sliding_window"**: The best "ngram" score of the shorter text against
+///   any window of the longer text with the same number of words. Finds a
+///   short reference quoted inside a long document, and skips the length
+///   pre-filter that rejects such pairs for the other methods.
+///
+/// - **"substring"**: 100 if the reference occurs verbatim in the file text
+///   (ignoring case and runs of whitespace), 0 otherwise. Finds short IDs or
+///   case numbers in long documents, and also skips the length pre-filter.
+///   When files are compared with each other (`compute_similarity_matrix`,
+///   `group_duplicates`), a pair matches if either text contains the other.
+///
 /// - **"hybrid"** (default): Progressive filtering approach that combines multiple
 ///   methods for optimal balance of speed and accuracy:
 ///   1. Fast Jaccard check - if score < 20%, return immediately
-///   2. For small texts (< 1000 chars): Use Levenshtein with early termination
+///   2. For small texts (< 1000 chars): Use the full Levenshtein similarity
 ///   3. For larger texts: Use N-gram similarity
 ///
 /// # Processing Flow
@@ -161,6 +604,8 @@ pub fn process_files(files: Vec<FileInput>) -> Vec<GroupedFiles> {
 ///    - Applies pre-filtering using length heuristics
 ///    - Calculates similarity using the selected method
 ///    - Filters results by threshold (only matches >= threshold are returned)
+///    - Sorts matches by similarity (highest first, ties by lower reference index)
+///      and keeps the top `max_results` if set
 /// 3. Returns grouped results with similarity match information
 ///
 /// # Parallel Processing
@@ -177,8 +622,82 @@ pub fn process_files(files: Vec<FileInput>) -> Vec<GroupedFiles> {
 /// * `similarity_threshold` - Optional similarity threshold percentage (0-100).
 ///   Defaults to 30.0. Only matches with similarity >= threshold are returned.
 /// * `similarity_method` - Optional similarity algorithm to use. Valid values:
-///   "jaccard", "ngram", "levenshtein", "hybrid" (default). Invalid values
-///   default to "hybrid".
+///   "jaccard", "ngram", "word_ngram", "levenshtein", "damerau", "cosine", "jarowinkler",
+///   "weighted", "sliding_window", "substring", "hybrid" (default). Invalid values default to "hybrid".
+/// * `options` - Optional `CompareOptions` controlling comparison behavior:
+///   - `clean_logs`: Strip leading ISO timestamps and log-level tokens
+///     (`INFO`, `ERROR`, etc.) from each line of both the extracted text and
+///     the reference texts before comparison. Useful for deduplicating repeated
+///     log errors. The returned `text_content` is not modified.
+///   - `collapse_whitespace`: Collapse whitespace runs into single spaces and
+///     trim, so texts differing only in spacing compare equal.
+///   - `canonicalize_numbers`: Rewrite formatted numbers ("1,000.00",
+///     "1.000,00") into a canonical form ("1000") before comparison.
+///   - `ngram_size`: N-gram size used by "ngram" and "word_ngram" (in words)
+///     and by the large-text branch of "hybrid" (default: 3). A size of 0 is clamped to 1.
+///   - `max_results`: Keep only the top K matches per file.
+///   - `sample_mode`: Estimate similarity for large texts (2,000+ characters)
+///     from windows at the beginning, middle, and end of the source and the
+///     reference texts. Much faster on huge documents, but the resulting
+///     matches are estimates and are marked `approximate`. Promising candidates
+///     can be re-checked with a full comparison.
+///   - `sample_fraction`: Fraction of each text kept in sampling mode (default: 0.1).
+///   - `remove_stopwords` / `stem`: Drop English stopwords and apply Porter
+///     stemming when tokenizing for the "jaccard" method (default: false).
+///   - `min_token_len`: Drop words shorter than this many characters when
+///     tokenizing for the "jaccard" method (default: 0).
+///   - `timeout_ms`: Give up on a file whose extraction takes longer than this
+///     many milliseconds, reporting it with the "timeout" encoding and no
+///     matches (default: no timeout).
+///   - `best_only`: Return at most one match per file, the highest-scoring
+///     reference. The best match is found while comparing, without collecting
+///     and sorting every match (default: false).
+///   - `normalize_unicode`: Normalize the extracted text and the reference
+///     texts to Unicode NFC before comparison, so accented characters match
+///     whether they were written precomposed or decomposed (default: true).
+///   - `case_sensitive`: Stop lowercasing in the "jaccard", "ngram", and
+///     "hybrid" methods, for case-significant identifiers such as product
+///     SKUs (default: false). "levenshtein", "damerau", and "jarowinkler"
+///     always compare case as written.
+///   - `dedupe_references`: Compare each distinct reference text once (after
+///     preprocessing) and copy its matches to every duplicate, so
+///     `reference_index` still covers every original index. The results are
+///     unchanged; "cosine" ignores this option because its term weights count
+///     every copy (default: false).
+///   - `include_match_text`: With "sliding_window", set `match_start` and
+///     `match_text` on every match to the passage of the file text that
+///     scored best, for highlighting. They are left unset when the file text
+///     is the shorter one, or is sampled (default: false).
+///   - `index_references`: Build a trigram index over the reference texts
+///     once and score each file only against the references sharing enough
+///     trigrams with it, which speeds up large reference sets. Exact for
+///     "ngram" with `ngram_size` 3; other methods skip references sharing no
+///     trigram with the file (default: false).
+///   - `reference_labels`: One label per reference text, such as a document
+///     ID, copied to the `reference_label` of every match so callers don't
+///     have to map `reference_index` back themselves (default: no labels).
+///   - `granularity`: "paragraph" splits the file text at blank lines,
+///     compares each paragraph with the references, and keeps the best
+///     paragraph per reference, reporting its `paragraph_index`; a section
+///     shared with a reference then matches even when the rest of the file
+///     differs. "document" (the default) compares the whole text.
+///   - `per_reference_thresholds`: Thresholds of the reference texts, by
+///     index, replacing `similarity_threshold` for their matches, so exact
+///     clauses can require a high score while boilerplate matches loosely.
+///     References past the end of the list use `similarity_threshold`
+///     (default: none).
+///   - `weights`: Relative weights of the Jaccard, n-gram, and Levenshtein
+///     scores blended by the "weighted" method. Omitted weights are 0; the
+///     weights are scaled to sum to 1 (default: equal weights).
+///   - `parallelism`: Which dimension runs in parallel. "by_file" compares
+///     files in parallel and each file with its references in sequence;
+///     "by_reference" compares files in sequence and each file with the
+///     references in parallel, which suits a few files against a large
+///     reference set. Extraction always runs in parallel by file. Both levels
+///     are never parallelized at once, since nested parallel iterators
+///     oversubscribe the thread pool when both are large. "auto" (the
+///     default) picks "by_file" when there are at least as many files as
+///     worker threads, and "by_reference" otherwise.
 ///
 /// # Returns
 ///
@@ -187,6 +706,9 @@ pub fn process_files(files: Vec<FileInput>) -> Vec<GroupedFiles> {
 /// - Extracted text content and metadata
 /// - Similarity matches for each file (reference index and similarity percentage)
 ///
+/// Returns an error if the "weighted" method is given invalid `weights`, or if
+/// `reference_labels` and `reference_texts` differ in length.
+///
 /// # Example
 ///
 /// ```no_run
@@ -211,7 +733,8 @@ pub fn process_files(files: Vec<FileInput>) -> Vec<GroupedFiles> {
 ///     reference_texts,
 ///     Some(30.0),  // 30% threshold
 ///     Some("hybrid".to_string()),  // Use hybrid method
-/// );
+///     None,  // Default comparison options
+/// )?;
 /// ```
 #[napi]
 pub fn process_and_compare_files(
@@ -219,80 +742,2464 @@ pub fn process_and_compare_files(
     reference_texts: Vec<String>,
     similarity_threshold: Option<f64>,
     similarity_method: Option<String>,
-) -> Vec<GroupedFilesWithSimilarity> {
+    options: Option<CompareOptions>,
+) -> napi::Result<Vec<GroupedFilesWithSimilarity>> {
+    thread_pool::install(|| {
+        compare_files(
+            files,
+            reference_texts,
+            similarity_threshold,
+            similarity_method,
+            options,
+        )
+    })
+}
+
+/// Extracts and compares files; the implementation of
+/// `process_and_compare_files`.
+fn compare_files(
+    files: Vec<FileInput>,
+    reference_texts: Vec<String>,
+    similarity_threshold: Option<f64>,
+    similarity_method: Option<String>,
+    options: Option<CompareOptions>,
+) -> napi::Result<Vec<GroupedFilesWithSimilarity>> {
     let threshold = similarity_threshold.unwrap_or(30.0);
+    let options = options.unwrap_or_default();
+    // References are scanned at the lowest threshold and each match is then
+    // checked against the threshold of its own reference
+    let reference_thresholds = options.per_reference_thresholds.as_deref();
+    let reference_threshold = |idx: usize| {
+        reference_thresholds
+            .and_then(|thresholds| thresholds.get(idx))
+            .copied()
+            .unwrap_or(threshold)
+    };
+    let scan_threshold = reference_thresholds
+        .unwrap_or_default()
+        .iter()
+        .fold(threshold, |lowest, &t| lowest.min(t));
+    let preprocess = PreprocessOptions::from(&options);
+    let tokenizer = TokenizerOptions::from(&options);
+    let max_results = options.max_results.map(|max| max as usize);
+    let best_only = options.best_only.unwrap_or(false);
+    let include_match_text = options.include_match_text.unwrap_or(false);
+    let granularity = parse_granularity(options.granularity.as_deref());
+
+    let reference_labels = options.reference_labels.as_deref();
+    if let Some(labels) = reference_labels
+        && labels.len() != reference_texts.len()
+    {
+        return Err(napi::Error::from_reason(format!(
+            "reference_labels has {} labels but there are {} reference texts",
+            labels.len(),
+            reference_texts.len()
+        )));
+    }
 
     // Parse similarity method
-    let method = match similarity_method.as_deref() {
-        Some("jaccard") => SimilarityMethod::Jaccard,
-        Some("ngram") => SimilarityMethod::Ngram,
-        Some("levenshtein") => SimilarityMethod::Levenshtein,
-        Some("hybrid") | _ => SimilarityMethod::Hybrid,
+    let ngram_size = options
+        .ngram_size
+        .map_or(DEFAULT_NGRAM_SIZE, |size| size.max(1) as usize);
+    let method = match (
+        parse_similarity_method(similarity_method.as_deref(), ngram_size),
+        &options.weights,
+    ) {
+        (SimilarityMethod::Weighted { .. }, Some(weights)) => SimilarityMethod::weighted(
+            weights.jaccard.unwrap_or(0.0),
+            weights.ngram.unwrap_or(0.0),
+            weights.levenshtein.unwrap_or(0.0),
+            ngram_size,
+        )
+        .map_err(napi::Error::from_reason)?,
+        (method, _) => method,
+    };
+
+    // Preprocess reference texts once, not once per file
+    let reference_texts: Cow<[String]> = if preprocess.is_noop() {
+        Cow::Borrowed(&reference_texts)
+    } else {
+        Cow::Owned(
+            reference_texts
+                .iter()
+                .map(|text| preprocess_text(text, &preprocess).into_owned())
+                .collect(),
+        )
+    };
+
+    // Score each distinct reference once; TF-IDF weights depend on every copy
+    let duplicates = match method {
+        SimilarityMethod::CosineTfIdf => None,
+        _ if options.dedupe_references.unwrap_or(false) => Some(dedupe_texts(&reference_texts)),
+        _ => None,
+    };
+    let (reference_texts, reference_origins) = match duplicates {
+        Some((unique, origins)) => (Cow::Owned(unique), Some(origins)),
+        None => (reference_texts, None),
     };
 
-    // Initialize handlers
-    let handlers: Vec<Arc<dyn FileHandler>> = vec![
-        Arc::new(TextHandler::new()),
-        Arc::new(PdfHandler::new()),
-        Arc::new(DocxHandler::new()),
-        Arc::new(XlsxHandler::new()),
-        Arc::new(ImageHandler::new()),
-    ];
+    // Index the references once, so each file only scores likely matches
+    let reference_index = options
+        .index_references
+        .unwrap_or(false)
+        .then(|| build_reference_index(&reference_texts));
+
+    // In sampling mode, sample reference texts once with the shared fraction
+    let sample_fraction = options
+        .sample_mode
+        .unwrap_or(false)
+        .then(|| options.sample_fraction.unwrap_or(DEFAULT_SAMPLE_FRACTION));
+    let sampled_references: Option<Vec<String>> = sample_fraction.map(|fraction| {
+        reference_texts
+            .par_iter()
+            .map(|text| sample_text(text, fraction))
+            .collect()
+    });
+
+    // Extract with the same handlers, in the same priority order, as `process_files`
+    let processor = DocumentProcessor::new(ProcessOptions {
+        timeout_ms: options.timeout_ms,
+        ..Default::default()
+    });
+
+    let parallelism = parse_parallelism(options.parallelism.as_deref(), files.len());
 
     // Thread-safe concurrent HashMap for grouping
     let grouped: DashMap<String, Vec<FileMetadataWithSimilarity>> = DashMap::new();
 
-    // Process files in parallel
-    files.par_iter().for_each(|file| {
-        let content = file.content.as_ref();
-        let size = content.len() as f64;
+    let compare_file = |file: &FileInput, metadata: FileMetadata, parallel: bool| {
+        let FileMetadata {
+            name,
+            size,
+            processing_time_ms,
+            encoding,
+            text_content,
+            error,
+            ..
+        } = metadata;
+
+        // Compare with reference texts (only if text was extracted successfully)
+        let similarity_start = Instant::now();
+        let similarity_matches = if error.is_none() && !text_content.is_empty() {
+            // Each source is compared on its own: the whole text, or one paragraph
+            let sources: Vec<Cow<str>> = match granularity {
+                Granularity::Document => vec![preprocess_text(&text_content, &preprocess)],
+                Granularity::Paragraph => split_paragraphs(&text_content)
+                    .into_iter()
+                    .map(|paragraph| preprocess_text(paragraph, &preprocess))
+                    .collect(),
+            };
+
+            // Indexed matches are all collected; ranking below keeps the best one
+            let compare =
+                |source: &str, references: &[String], index: Option<&ReferenceIndex>| match index {
+                    Some(index) => compare_with_index(
+                        source,
+                        index,
+                        method,
+                        scan_threshold,
+                        &tokenizer,
+                        parallel,
+                    ),
+                    // The best match may miss its own threshold while another reference meets its
+                    None if best_only && reference_thresholds.is_none() => best_document_match(
+                        source, references, method, threshold, &tokenizer, parallel,
+                    )
+                    .into_iter()
+                    .collect(),
+                    None => compare_with_documents(
+                        source,
+                        references,
+                        method,
+                        scan_threshold,
+                        &tokenizer,
+                        parallel,
+                    ),
+                };
 
-        // Find appropriate handler
-        let handler = handlers.iter().find(|h| h.can_handle(&file.mime_type));
+            // The best-scoring source of each reference, as (similarity, method_used,
+            // source position, sampled); the first source wins ties
+            let mut best: HashMap<usize, (f64, &'static str, usize, bool)> = HashMap::new();
+            for (position, source_text) in sources.iter().enumerate() {
+                // Large sources are estimated from samples of both sides
+                let sampled = sample_fraction
+                    .zip(sampled_references.as_deref())
+                    .filter(|_| should_sample(source_text));
+                let mut matches = match sampled {
+                    Some((fraction, references)) => {
+                        compare(&sample_text(source_text, fraction), references, None)
+                    }
+                    None => compare(source_text, &reference_texts, reference_index.as_ref()),
+                };
+                if let Some(origins) = &reference_origins {
+                    matches = expand_duplicate_matches(matches, origins);
+                }
+                if reference_thresholds.is_some() {
+                    matches.retain(|&(idx, similarity, _)| similarity >= reference_threshold(idx));
+                }
+                for (idx, similarity, method_used) in matches {
+                    let entry = best.entry(idx).or_insert((
+                        similarity,
+                        method_used,
+                        position,
+                        sampled.is_some(),
+                    ));
+                    if similarity > entry.0 {
+                        *entry = (similarity, method_used, position, sampled.is_some());
+                    }
+                }
+            }
 
-        let (text_content, encoding) = match handler {
-            Some(h) => match h.extract_text(content, &file.filename, &file.mime_type) {
-                Ok(text) => (text, "utf-8".to_string()),
-                Err(err) => (format!("Error: {}", err), "error".to_string()),
-            },
-            None => (String::new(), "application/octet-stream".to_string()),
-        };
+            let mut matches: Vec<_> = best
+                .iter()
+                .map(|(&idx, &(similarity, method_used, _, _))| (idx, similarity, method_used))
+                .collect();
+            rank_matches(&mut matches, max_results);
+            if best_only {
+                // The best distinct reference may occur several times; keep its first copy
+                matches.truncate(1);
+            }
 
-        // Compare with reference texts (only if text was extracted successfully)
-        let similarity_matches = if !text_content.is_empty() && !text_content.starts_with("Error:")
-        {
-            let matches =
-                compare_with_documents(&text_content, &reference_texts, method, threshold);
+            // Locate the passage of each returned match; sampled texts have no offsets
+            let passage = |idx: usize, source_text: &str, sampled: bool| match method {
+                SimilarityMethod::SlidingWindow(n) if include_match_text && !sampled => {
+                    let reference = match &reference_origins {
+                        Some(origins) => origins
+                            .iter()
+                            .position(|copies| copies.contains(&idx))
+                            .map(|unique| &reference_texts[unique]),
+                        None => reference_texts.get(idx),
+                    }?;
+                    let (_, range) =
+                        sliding_window_match_with(source_text, reference, n, &tokenizer);
+                    range
+                }
+                _ => None,
+            };
 
             matches
                 .into_iter()
-                .map(|(idx, similarity)| SimilarityMatch {
-                    reference_index: idx as u32,
-                    similarity_percentage: similarity,
+                .map(|(idx, similarity, method_used)| {
+                    let (_, _, position, sampled) = best[&idx];
+                    let source_text = &sources[position];
+                    let passage = passage(idx, source_text, sampled);
+                    SimilarityMatch {
+                        reference_index: idx as u32,
+                        reference_label: reference_labels
+                            .and_then(|labels| labels.get(idx))
+                            .cloned(),
+                        similarity_percentage: similarity,
+                        approximate: sampled,
+                        method_used: Some(method_used.to_string()),
+                        match_start: passage
+                            .as_ref()
+                            .map(|range| source_text[..range.start].encode_utf16().count() as u32),
+                        match_text: passage.map(|range| source_text[range].to_string()),
+                        paragraph_index: (granularity == Granularity::Paragraph)
+                            .then_some(position as u32),
+                    }
                 })
                 .collect()
         } else {
             Vec::new()
         };
+        let similarity_time_ms = elapsed_ms(similarity_start);
 
         let metadata = FileMetadataWithSimilarity {
-            name: file.filename.clone(),
+            name,
             size,
-            processing_time_ms: 0.0,
+            processing_time_ms,
             encoding,
             text_content,
+            error,
+            similarity_time_ms,
             similarity_matches,
         };
 
         grouped
             .entry(file.mime_type.clone())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(metadata);
-    });
+    };
+
+    match parallelism {
+        // Extract and compare each file in parallel
+        Parallelism::ByFile => files.par_iter().for_each(|file| {
+            compare_file(file, processor.extract_file(file), false);
+        }),
+        // Extraction still runs in parallel by file; only comparison switches axis
+        Parallelism::ByReference => {
+            let extracted: Vec<FileMetadata> = files
+                .par_iter()
+                .map(|file| processor.extract_file(file))
+                .collect();
+            for (file, metadata) in files.iter().zip(extracted) {
+                compare_file(file, metadata, true);
+            }
+        }
+    }
 
     // Convert DashMap to Vec<GroupedFilesWithSimilarity>
-    grouped
+    Ok(grouped
         .into_iter()
         .map(|(mime_type, files)| GroupedFilesWithSimilarity { mime_type, files })
-        .collect()
+        .collect())
+}
+
+/// Computes aggregate statistics over the output of `process_and_compare_files`.
+///
+/// After a large comparison run, this function reduces the grouped results
+/// into a compact summary in Rust, avoiding a reduction pass over potentially
+/// huge result sets in Node.js.
+///
+/// # Statistics
+///
+/// - Total number of files and how many matched at least one reference
+/// - Total number of matches and the average number of matches per file
+/// - Average, minimum, and maximum similarity percentage over all matches
+/// - A histogram of match scores in ten 10-point buckets (0-10, 10-20, ..., 90-100)
+///
+/// # Arguments
+///
+/// * `results` - The grouped results returned by `process_and_compare_files`
+///
+/// # Returns
+///
+/// A `SimilaritySummary` describing the distribution of similarity matches.
+///
+/// # Example
+///
+/// ```no_run
+/// use dms_toolkit_rs::{process_and_compare_files, summarize_similarity};
+///
+/// let results = process_and_compare_files(files, reference_texts, Some(30.0), None, None)?;
+/// let summary = summarize_similarity(results);
+///
+/// println!("{} of {} files matched", summary.files_with_matches, summary.total_files);
+/// ```
+#[napi]
+pub fn summarize_similarity(results: Vec<GroupedFilesWithSimilarity>) -> SimilaritySummary {
+    summarize_results(&results)
+}
+
+/// Recognizes the lines of text in an image along with their locations.
+///
+/// Where `process_files` returns only the OCR text of an image, this function
+/// keeps each recognized line's bounding rectangle and a confidence score, so
+/// low-confidence lines can be flagged for human review.
+///
+/// # Arguments
+///
+/// * `content` - The raw image file content (any format supported by
+///   `process_files`: JPEG, PNG, GIF, BMP, TIFF, WebP)
+/// * `options` - Optional processing options. Only the OCR model settings
+///   (`ocr_detection_model`, `ocr_recognition_model`, `ocr_alphabet`) apply.
+///
+/// # Returns
+///
+/// One `OcrLine` per recognized line in reading order (empty if the image
+/// contains no text), or an error if the image cannot be decoded or OCR fails.
+///
+/// # Confidence
+///
+/// The recognition model does not report scores, so `confidence` is the mean
+/// probability the text detection model assigns to the line's text pixels.
+///
+/// # Example
+///
+/// ```no_run
+/// use dms_toolkit_rs::extract_image_regions;
+///
+/// let lines = extract_image_regions(image_buffer, None)?;
+/// for line in lines {
+///     println!("{} at ({}, {}) {:?}", line.text, line.x, line.y, line.confidence);
+/// }
+/// ```
+#[napi]
+pub fn extract_image_regions(
+    content: Buffer,
+    options: Option<ProcessOptions>,
+) -> napi::Result<Vec<OcrLine>> {
+    let options = options.unwrap_or_default();
+
+    build_image_handler(&options)
+        .extract_regions(&content)
+        .map_err(napi::Error::from_reason)
+}
+
+/// Reads the metadata of a PDF document.
+///
+/// Catalogs often need a PDF's title, author, and page count alongside its
+/// text. This function reads them from the document information dictionary
+/// and the page tree without extracting any text.
+///
+/// # Arguments
+///
+/// * `content` - The raw PDF file content
+///
+/// # Returns
+///
+/// A `PdfMetadata` object. Entries missing from the document (or blank) are
+/// `None`; the creation date is converted to ISO 8601 where possible. Returns
+/// an error if the content is not a readable PDF, including PDFs that need an
+/// open password.
+///
+/// # Example
+///
+/// ```no_run
+/// use dms_toolkit_rs::extract_pdf_metadata;
+///
+/// let metadata = extract_pdf_metadata(pdf_buffer)?;
+/// println!("{:?} by {:?}, {} pages", metadata.title, metadata.author, metadata.page_count);
+/// ```
+#[napi]
+pub fn extract_pdf_metadata(content: Buffer) -> napi::Result<PdfMetadata> {
+    PdfHandler::new()
+        .extract_metadata(&content)
+        .map_err(napi::Error::from_reason)
+}
+
+/// Reads the properties of a DOCX document.
+///
+/// Records systems often need a Word document's title, author, and dates
+/// alongside its text. This function reads them from the `docProps/core.xml`
+/// and `docProps/app.xml` parts of the package without extracting any text.
+///
+/// # Arguments
+///
+/// * `content` - The raw DOCX file content
+///
+/// # Returns
+///
+/// A `DocxProperties` object. Properties missing from the document (or blank)
+/// are `None`; dates are returned as written, which is ISO 8601 in documents
+/// saved by Word. Returns an error if the content is not a ZIP package.
+///
+/// # Example
+///
+/// ```no_run
+/// use dms_toolkit_rs::extract_docx_properties;
+///
+/// let properties = extract_docx_properties(docx_buffer)?;
+/// println!("{:?} by {:?}, {:?} words", properties.title, properties.author, properties.word_count);
+/// ```
+#[napi]
+pub fn extract_docx_properties(content: Buffer) -> napi::Result<DocxProperties> {
+    DocxHandler::new()
+        .extract_properties(&content)
+        .map_err(napi::Error::from_reason)
+}
+
+/// Extracts the cells of a spreadsheet with their types.
+///
+/// `process_files` flattens every cell to text, so "12.5", a date, and the
+/// string "12.5" look the same. This function keeps each cell's type for
+/// downstream parsing: numbers, dates (converted from Excel serial numbers to
+/// ISO 8601), text, booleans, and empty cells.
+///
+/// # Arguments
+///
+/// * `content` - The raw XLSX, legacy XLS, or ODS file content
+///
+/// # Returns
+///
+/// One `XlsxSheet` per worksheet, in workbook order, holding the rows of the
+/// sheet's used range as `XlsxCell` objects. Returns an error if the content
+/// is not a readable workbook.
+///
+/// # Example
+///
+/// ```typescript
+/// const [sheet] = extractXlsxCells(buffer);
+/// const totals = sheet.rows.flat().filter(cell => cell.kind === 'number');
+/// ```
+#[napi]
+pub fn extract_xlsx_cells(content: Buffer) -> napi::Result<Vec<XlsxSheet>> {
+    XlsxHandler::new()
+        .extract_cells(&content)
+        .map_err(napi::Error::from_reason)
+}
+
+/// Computes pairwise similarities between the files of a batch.
+///
+/// Where `process_and_compare_files` compares files against separate reference
+/// texts, this function compares the files with each other, which finds
+/// near-duplicates within an uploaded batch. Text is extracted from each file
+/// as in `process_files` (with default options), then every pair of files is
+/// compared once.
+///
+/// # Arguments
+///
+/// * `files` - A vector of `FileInput` objects to compare with each other
+/// * `method` - Optional similarity algorithm, with the same names as
+///   `process_and_compare_files` (default: "hybrid")
+/// * `threshold` - Optional minimum similarity percentage (0-100) for a pair to
+///   be included (default: 30.0)
+///
+/// # Returns
+///
+/// One `SimilarityPair` per pair of files at or above the threshold, where `i`
+/// and `j` are indices into `files` with `i < j`, ordered by `i` and then `j`.
+/// Files that fail to extract or contain no text are not paired.
+///
+/// # Performance
+///
+/// A batch of `n` files needs `n * (n - 1) / 2` comparisons. The pairs are
+/// compared in parallel and pre-filtered by length, like reference comparisons.
+///
+/// # Example
+///
+/// ```no_run
+/// use dms_toolkit_rs::compute_similarity_matrix;
+///
+/// let pairs = compute_similarity_matrix(files, Some("jaccard".to_string()), Some(80.0));
+/// for pair in pairs {
+///     println!("Files {} and {} are {:.1}% similar", pair.i, pair.j, pair.similarity);
+/// }
+/// ```
+#[napi]
+pub fn compute_similarity_matrix(
+    files: Vec<FileInput>,
+    method: Option<String>,
+    threshold: Option<f64>,
+) -> Vec<SimilarityPair> {
+    let method = parse_similarity_method(method.as_deref(), DEFAULT_NGRAM_SIZE);
+    let pairs = thread_pool::install(|| {
+        let texts = extract_texts(&files);
+        pairwise_similarities(&texts, method, threshold.unwrap_or(30.0))
+    });
+
+    pairs
+        .into_iter()
+        .map(|(i, j, similarity)| SimilarityPair {
+            i: i as u32,
+            j: j as u32,
+            similarity,
+        })
+        .collect()
+}
+
+/// Groups the files of a batch into clusters of near-duplicates.
+///
+/// Files are linked when their texts are at least `threshold` percent similar,
+/// and linked files are merged transitively: if A matches B and B matches C,
+/// all three form one cluster even when A and C do not match directly. This
+/// groups the successive revisions of a document that drifted apart over time.
+///
+/// # Arguments
+///
+/// * `files` - A vector of `FileInput` objects to cluster
+/// * `threshold` - Optional minimum similarity percentage (0-100) for two files
+///   to be linked (default: 30.0)
+/// * `method` - Optional similarity algorithm, with the same names as
+///   `process_and_compare_files` (default: "hybrid")
+///
+/// # Returns
+///
+/// The clusters as arrays of indices into `files`. Every file appears in
+/// exactly one cluster; files without near-duplicates (including files that
+/// fail to extract) are clusters of one. Indices within a cluster are
+/// ascending and clusters are ordered by their smallest index.
+///
+/// # Example
+///
+/// ```no_run
+/// use dms_toolkit_rs::group_duplicates;
+///
+/// let clusters = group_duplicates(files, Some(80.0), None);
+/// for cluster in clusters.iter().filter(|cluster| cluster.len() > 1) {
+///     println!("Near-duplicates: {:?}", cluster);
+/// }
+/// ```
+#[napi]
+pub fn group_duplicates(
+    files: Vec<FileInput>,
+    threshold: Option<f64>,
+    method: Option<String>,
+) -> Vec<Vec<u32>> {
+    let method = parse_similarity_method(method.as_deref(), DEFAULT_NGRAM_SIZE);
+    let clusters = thread_pool::install(|| {
+        let texts = extract_texts(&files);
+        cluster_texts(&texts, method, threshold.unwrap_or(30.0))
+    });
+
+    clusters
+        .into_iter()
+        .map(|cluster| cluster.into_iter().map(|index| index as u32).collect())
+        .collect()
+}
+
+/// Computes the similarity of two texts.
+///
+/// Runs a similarity method directly on strings, without file extraction or
+/// comparison options. Use it when the texts are already at hand.
+///
+/// # Arguments
+///
+/// * `source` - The source text
+/// * `target` - The text to compare against
+/// * `method` - Optional similarity algorithm, with the same names as
+///   `process_and_compare_files` (default: "hybrid", also used for unknown names)
+///
+/// # Returns
+///
+/// The similarity percentage (0-100).
+///
+/// # Example
+///
+/// ```typescript
+/// const similarity = compareTexts('Invoice 2024-001', 'Invoice 2024-002', 'jarowinkler');
+/// ```
+#[napi]
+pub fn compare_texts(source: String, target: String, method: Option<String>) -> f64 {
+    let method = parse_similarity_method(method.as_deref(), DEFAULT_NGRAM_SIZE);
+    calculate_similarity(&source, &target, method)
+}
+
+/// Computes the similarity of an OCR text to a reference, discounting
+/// uncertain characters.
+///
+/// A Levenshtein similarity in which substituting or deleting source character
+/// `i` costs `source_confidence[i]` instead of a full edit, so characters the
+/// OCR engine was unsure about count less against the match. With full
+/// confidence the score equals the "levenshtein" method's.
+///
+/// # Arguments
+///
+/// * `source` - The OCR text
+/// * `source_confidence` - Confidence (0-1) of each character of `source`, by
+///   character index. Missing entries count as 1, so a line-level score such as
+///   `OcrLine.confidence` can be repeated for the characters of its line.
+/// * `target` - The reference text to compare against
+///
+/// # Returns
+///
+/// The similarity percentage (0-100).
+///
+/// # Example
+///
+/// ```typescript
+/// const confidence = [...text].map(() => 0.9);
+/// confidence[3] = 0.2; // a character flagged as uncertain
+/// const similarity = compareTextsWithConfidence(text, confidence, 'Invoice 2024');
+/// ```
+#[napi]
+pub fn compare_texts_with_confidence(
+    source: String,
+    source_confidence: Vec<f64>,
+    target: String,
+) -> f64 {
+    weighted_levenshtein_similarity(&source, &source_confidence, &target)
+}
+
+/// Computes the edit operations turning one text into another.
+///
+/// Returns the minimal sequence of matches, insertions, deletions, and
+/// substitutions found by the "levenshtein" method, for rendering a
+/// character-level diff. The full edit matrix is kept, so memory grows with
+/// the product of the text lengths; use it for lines or fields rather than
+/// whole documents.
+///
+/// # Arguments
+///
+/// * `source` - The original text
+/// * `target` - The text to align it with
+///
+/// # Returns
+///
+/// The `EditOperation`s in order, covering every character of both texts.
+/// The number of operations other than "match" is the Levenshtein distance.
+///
+/// # Example
+///
+/// ```typescript
+/// const ops = levenshteinAlignment('kitten', 'sitting');
+/// // substitute k→s, match i, t, t, substitute e→i, match n, insert g
+/// ```
+#[napi]
+pub fn levenshtein_alignment(source: String, target: String) -> Vec<EditOperation> {
+    crate::core::similarity::levenshtein_alignment(&source, &target)
+        .into_iter()
+        .map(EditOperation::from)
+        .collect()
+}
+
+/// Compares a text against several target texts.
+///
+/// Like `process_and_compare_files` for a single text that is already
+/// extracted: the targets are compared in parallel, pre-filtered by length,
+/// and only matches at or above the threshold are returned.
+///
+/// # Arguments
+///
+/// * `source` - The source text
+/// * `targets` - The texts to compare against
+/// * `method` - Optional similarity algorithm, with the same names as
+///   `process_and_compare_files` (default: "hybrid", also used for unknown names)
+/// * `threshold` - Optional minimum similarity percentage (0-100) for a target
+///   to be included (default: 30.0)
+///
+/// # Returns
+///
+/// One `SimilarityMatch` per matching target, where `reference_index` is the
+/// index into `targets`, ordered by similarity (highest first, ties by lower
+/// index).
+///
+/// # Example
+///
+/// ```typescript
+/// const matches = compareTextToMany(text, templates, 'ngram', 70.0);
+/// ```
+#[napi]
+pub fn compare_text_to_many(
+    source: String,
+    targets: Vec<String>,
+    method: Option<String>,
+    threshold: Option<f64>,
+) -> Vec<SimilarityMatch> {
+    let method = parse_similarity_method(method.as_deref(), DEFAULT_NGRAM_SIZE);
+    let mut matches = thread_pool::install(|| {
+        compare_with_documents(
+            &source,
+            &targets,
+            method,
+            threshold.unwrap_or(30.0),
+            &TokenizerOptions::default(),
+            true,
+        )
+    });
+    rank_matches(&mut matches, None);
+
+    matches
+        .into_iter()
+        .map(|(idx, similarity, method_used)| text_match(idx, similarity, method_used))
+        .collect()
+}
+
+/// Builds the `SimilarityMatch` of a target text compared with
+/// `compare_with_documents`.
+fn text_match(idx: usize, similarity: f64, method_used: &str) -> SimilarityMatch {
+    SimilarityMatch {
+        reference_index: idx as u32,
+        reference_label: None,
+        similarity_percentage: similarity,
+        approximate: false,
+        method_used: Some(method_used.to_string()),
+        match_start: None,
+        match_text: None,
+        paragraph_index: None,
+    }
+}
+
+/// Callback receiving each streamed match.
+///
+/// Like `FileCallback`, the queue of pending calls is bounded, so matches
+/// are held in memory only until JavaScript catches up.
+type MatchCallback = ThreadsafeFunction<
+    SimilarityMatch,
+    (),
+    SimilarityMatch,
+    Status,
+    false,
+    false,
+    { STREAMED_RESULT_QUEUE_SIZE },
+>;
+
+/// Background task behind `compare_text_to_many_streaming`.
+///
+/// The comparison runs on the libuv thread pool so the event loop stays free
+/// to run the callback while targets are still being compared.
+pub struct CompareTextToManyStreaming {
+    source: String,
+    targets: Vec<String>,
+    method: SimilarityMethod,
+    threshold: f64,
+    callback: MatchCallback,
+}
+
+impl Task for CompareTextToManyStreaming {
+    type Output = u32;
+    type JsValue = u32;
+
+    fn compute(&mut self) -> napi::Result<u32> {
+        let callback = &self.callback;
+
+        let count = thread_pool::install(|| {
+            compare_with_documents_streaming(
+                &self.source,
+                &self.targets,
+                self.method,
+                self.threshold,
+                &TokenizerOptions::default(),
+                true,
+                |idx, similarity, method_used| {
+                    // Blocks the worker while the queue is full, so the scan
+                    // cannot run ahead of the callback
+                    match callback.call(
+                        text_match(idx, similarity, method_used),
+                        ThreadsafeFunctionCallMode::Blocking,
+                    ) {
+                        Status::Ok => Ok(()),
+                        status => Err(napi::Error::new(
+                            status,
+                            "Failed to deliver a streamed match to the callback",
+                        )),
+                    }
+                },
+            )
+        })?;
+
+        Ok(count as u32)
+    }
+
+    fn resolve(&mut self, _env: Env, output: u32) -> napi::Result<u32> {
+        Ok(output)
+    }
+}
+
+/// Compares a text against several target texts and streams each match to a
+/// callback as it is found.
+///
+/// For interactive search, `compare_text_to_many` makes the caller wait for
+/// the slowest target before showing anything. This function runs the same
+/// comparison in the background and hands each match at or above the
+/// threshold to `callback` as soon as it is scored.
+///
+/// At most 16 matches wait for the JavaScript thread at a time: when the
+/// callback falls behind, the comparison pauses until it catches up. If a
+/// match cannot be delivered, for example because the callback was released,
+/// the comparison stops and the promise rejects.
+///
+/// # Ordering
+///
+/// Targets are compared in parallel, so matches arrive in completion order,
+/// not ranked by similarity. `reference_index` identifies the target.
+///
+/// # Arguments
+///
+/// * `source` - The source text
+/// * `targets` - The texts to compare against
+/// * `callback` - Called on the JavaScript thread with each `SimilarityMatch`
+/// * `method` - Optional similarity algorithm, as for `compare_text_to_many`
+///   (default: "hybrid")
+/// * `threshold` - Optional minimum similarity percentage (0-100) (default: 30.0)
+///
+/// # Returns
+///
+/// A promise resolving to the number of matches delivered. Every callback has
+/// been queued by the time it resolves.
+///
+/// # Example
+///
+/// ```typescript
+/// const count = await compareTextToManyStreaming(query, documents, (match) => {
+///   results.push(match);
+///   render(results);
+/// }, 'ngram', 60.0);
+/// ```
+#[napi(
+    ts_args_type = "source: string, targets: Array<string>, callback: (match: SimilarityMatch) => void, method?: string | undefined | null, threshold?: number | undefined | null"
+)]
+pub fn compare_text_to_many_streaming(
+    source: String,
+    targets: Vec<String>,
+    callback: MatchCallback,
+    method: Option<String>,
+    threshold: Option<f64>,
+) -> AsyncTask<CompareTextToManyStreaming> {
+    AsyncTask::new(CompareTextToManyStreaming {
+        source,
+        targets,
+        method: parse_similarity_method(method.as_deref(), DEFAULT_NGRAM_SIZE),
+        threshold: threshold.unwrap_or(30.0),
+        callback,
+    })
+}
+
+/// Lists the MIME types that `process_files` can extract text from.
+///
+/// Integrators can use this list to filter uploads before sending them for
+/// processing. Files of any other type are returned unhandled, with an empty
+/// text. `text/*` in the list means that every `text/` subtype is extracted
+/// as plain text.
+///
+/// # Returns
+///
+/// The supported MIME types, sorted and without duplicates.
+///
+/// # Example
+///
+/// ```typescript
+/// const supported = new Set(supportedMimeTypes());
+/// const accepted = uploads.filter(file => supported.has(file.mimeType));
+/// ```
+#[napi]
+pub fn supported_mime_types() -> Vec<String> {
+    DocumentProcessor::new(ProcessOptions::default()).supported_mime_types()
+}
+
+/// Returns the name of the handler that `process_files` uses for a MIME type.
+///
+/// Handlers are tried in priority order and the first one accepting the MIME
+/// type extracts the file, so this shows where a file is routed without
+/// running any extraction. Useful when a file comes back with unexpected
+/// (e.g. empty) text. Files whose declared type no handler accepts are routed
+/// by the type detected from their content, which this function does not see.
+///
+/// # Arguments
+///
+/// * `mime_type` - The MIME type to look up
+///
+/// # Returns
+///
+/// The handler name (e.g. "PdfHandler"), or `None` if no handler accepts the
+/// MIME type.
+///
+/// # Example
+///
+/// ```typescript
+/// whichHandler('text/csv'); // 'CsvHandler'
+/// whichHandler('application/x-unknown'); // null
+/// ```
+#[napi]
+pub fn which_handler(mime_type: String) -> Option<String> {
+    DocumentProcessor::new(ProcessOptions::default())
+        .handler_name(&mime_type)
+        .map(str::to_string)
+}
+
+/// Limits the number of threads used for parallel processing.
+///
+/// By default, files and comparisons are processed on Rayon's global thread
+/// pool, which starts one thread per logical core. In containers and
+/// serverless functions with a CPU quota below the machine's core count,
+/// those threads compete for the quota and latency suffers. After this call,
+/// `process_files`, `process_files_async`, `process_files_streaming`,
+/// `process_files_grouped_and_flat`,
+/// `process_and_compare_files`, `compute_similarity_matrix`,
+/// `group_duplicates`, and `compare_text_to_many` run their parallel work on a
+/// dedicated pool of `size` threads instead.
+///
+/// The size can only be set once per process, preferably at startup before
+/// any files are processed.
+///
+/// # Arguments
+///
+/// * `size` - The number of worker threads, at least 1
+///
+/// # Returns
+///
+/// An error if `size` is 0 or the size was already set.
+///
+/// # Example
+///
+/// ```typescript
+/// // Match the container's CPU quota of 2 cores
+/// setThreadPoolSize(2);
+/// ```
+#[napi]
+pub fn set_thread_pool_size(size: u32) -> napi::Result<()> {
+    thread_pool::configure(size as usize).map_err(napi::Error::from_reason)
+}
+
+/// Enables the similarity cache, or disables it with a capacity of 0.
+///
+/// Batches that compare overlapping reference sets recompute identical pairs.
+/// With the cache enabled, `process_and_compare_files` and
+/// `compare_text_to_many` store each score, keyed by a hash of the two texts,
+/// the method, and the tokenizer options, and reuse it when the same
+/// comparison comes up again. Each entry keeps a second, independently keyed
+/// hash of the comparison instead of the texts, so a hash collision is
+/// detected and an entry's size does not depend on the text lengths. The
+/// cache is off by default.
+///
+/// # Arguments
+///
+/// * `capacity` - The maximum number of cached scores. Once it is reached,
+///   new pairs are no longer cached until `clear_similarity_cache` is called.
+///   Lowering the capacity clears the cache.
+///
+/// # Example
+///
+/// ```typescript
+/// setSimilarityCacheCapacity(100_000);
+/// ```
+#[napi]
+pub fn set_similarity_cache_capacity(capacity: u32) {
+    similarity_cache::configure(capacity as usize);
+}
+
+/// Removes every score from the similarity cache.
+///
+/// Cached scores stay valid as long as the texts are the same, so clearing is
+/// only needed to release their memory or to make room once the cache is full.
+///
+/// # Example
+///
+/// ```typescript
+/// clearSimilarityCache();
+/// ```
+#[napi]
+pub fn clear_similarity_cache() {
+    similarity_cache::clear();
+}
+
+/// Extracts the text of each file with the default handlers, in input order.
+///
+/// Files that fail to extract or have no handler yield an empty string.
+fn extract_texts(files: &[FileInput]) -> Vec<String> {
+    let processor = DocumentProcessor::new(ProcessOptions::default());
+
+    files
+        .par_iter()
+        .map(|file| processor.extract_file(file).text_content)
+        .collect()
+}
+
+/// Maps a similarity method name to a `SimilarityMethod`.
+///
+/// Unknown names and `None` select the default hybrid method.
+fn parse_similarity_method(name: Option<&str>, ngram_size: usize) -> SimilarityMethod {
+    match name {
+        Some("jaccard") => SimilarityMethod::Jaccard,
+        Some("ngram") => SimilarityMethod::Ngram(ngram_size),
+        Some("word_ngram") => SimilarityMethod::WordNgram(ngram_size),
+        Some("levenshtein") => SimilarityMethod::Levenshtein,
+        Some("damerau") => SimilarityMethod::DamerauLevenshtein,
+        Some("cosine") => SimilarityMethod::CosineTfIdf,
+        Some("jarowinkler") => SimilarityMethod::JaroWinkler,
+        Some("sliding_window") => SimilarityMethod::SlidingWindow(ngram_size),
+        Some("substring") => SimilarityMethod::Substring,
+        Some("weighted") => SimilarityMethod::Weighted {
+            jaccard: 1.0 / 3.0,
+            ngram: 1.0 / 3.0,
+            levenshtein: 1.0 / 3.0,
+            ngram_size,
+        },
+        _ => SimilarityMethod::Hybrid(ngram_size),
+    }
+}
+
+/// Maps a granularity name to a `Granularity`.
+///
+/// Unknown names and `None` select `Granularity::Document`.
+fn parse_granularity(name: Option<&str>) -> Granularity {
+    match name {
+        Some("paragraph") => Granularity::Paragraph,
+        _ => Granularity::Document,
+    }
+}
+
+/// Maps a parallelism name to the `Parallelism` used for a batch of files.
+///
+/// "auto", unknown names, and `None` pick one with `Parallelism::auto`.
+fn parse_parallelism(name: Option<&str>, file_count: usize) -> Parallelism {
+    match name {
+        Some("by_file") => Parallelism::ByFile,
+        Some("by_reference") => Parallelism::ByReference,
+        _ => Parallelism::auto(file_count),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::embedded::MAX_EMBEDDED_DEPTH;
+    use crate::handlers::docx::tests::build_docx;
+    use crate::handlers::pdf::tests::build_pdf;
+    use crate::handlers::zip::tests::build_zip;
+    use crate::models::options::SimilarityWeights;
+    use napi::bindgen_prelude::Buffer;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    fn text_file(filename: &str, mime_type: &str, content: &str) -> FileInput {
+        FileInput {
+            content: Buffer::from(content.as_bytes().to_vec()),
+            mime_type: mime_type.to_string(),
+            filename: filename.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_process_files_fail_fast() {
+        let files = || {
+            vec![
+                text_file("a.txt", "text/plain", "valid text"),
+                text_file("broken.pdf", "application/pdf", "not a pdf"),
+            ]
+        };
+
+        let results = process_files(files(), None).unwrap();
+        assert_eq!(results.iter().map(|g| g.files.len()).sum::<usize>(), 2);
+
+        let options = ProcessOptions {
+            fail_fast: Some(true),
+            ..Default::default()
+        };
+        let result = process_files(files(), Some(options));
+        assert!(result.is_err_and(|err| err.reason.contains("broken.pdf")));
+    }
+
+    #[test]
+    fn test_process_files_with_stats() {
+        let files = vec![
+            text_file("a.txt", "text/plain", "valid text"),
+            text_file("b.bin", "application/x-unknown", "opaque"),
+            text_file("broken.pdf", "application/pdf", "not a pdf"),
+        ];
+
+        let result = process_files_with_stats(files, None).unwrap();
+
+        assert_eq!(
+            result.groups.iter().map(|g| g.files.len()).sum::<usize>(),
+            3
+        );
+        let stats = result.stats;
+        assert_eq!(stats.total_files, 3);
+        assert_eq!(stats.successes, 1);
+        assert_eq!(stats.failures, 1);
+        assert_eq!(stats.unhandled, 1);
+        assert_eq!(stats.total_bytes, 25.0);
+        assert!(stats.total_processing_time_ms >= 0.0);
+    }
+
+    #[test]
+    fn test_extract_text_single_file() {
+        let extracted = extract_text(
+            Buffer::from(b"caf\xe9 menu".to_vec()),
+            "text/plain".to_string(),
+            "menu.txt".to_string(),
+            None,
+        );
+        assert_eq!(extracted.text, "café menu");
+        assert_eq!(extracted.encoding, "windows-1252");
+        assert_eq!(extracted.error, None);
+
+        let broken = extract_text(
+            Buffer::from(b"not a pdf".to_vec()),
+            "application/pdf".to_string(),
+            "broken.pdf".to_string(),
+            None,
+        );
+        assert_eq!(broken.text, "");
+        assert_eq!(broken.encoding, "error");
+        assert!(broken.error.is_some());
+    }
+
+    #[test]
+    fn test_process_files_async_compute() {
+        let mut task = ProcessFilesAsync {
+            files: vec![
+                text_file("a.txt", "text/plain", "first file"),
+                text_file("b.txt", "text/plain", "second file"),
+                text_file("c.bin", "application/x-unknown", "opaque"),
+            ],
+            options: ProcessOptions::default(),
+        };
+
+        let mut groups = task.compute().unwrap();
+        groups.sort_by(|a, b| a.mime_type.cmp(&b.mime_type));
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].mime_type, "application/x-unknown");
+        assert!(!groups[0].files[0].handled);
+        let mut texts: Vec<_> = groups[1]
+            .files
+            .iter()
+            .map(|f| f.text_content.as_str())
+            .collect();
+        texts.sort();
+        assert_eq!(texts, ["first file", "second file"]);
+    }
+
+    #[test]
+    fn test_redact_patterns() {
+        let files = || {
+            vec![text_file(
+                "contact.txt",
+                "text/plain",
+                "Questions go to billing@example.com or ada@example.org.",
+            )]
+        };
+        let options = |patterns: &[&str]| ProcessOptions {
+            redact_patterns: Some(patterns.iter().map(|p| p.to_string()).collect()),
+            ..Default::default()
+        };
+
+        let groups = process_files(files(), Some(options(&[r"[\w.+-]+@[\w-]+\.\w+"]))).unwrap();
+        assert_eq!(
+            groups[0].files[0].text_content,
+            "Questions go to [REDACTED] or [REDACTED]."
+        );
+
+        // An invalid pattern fails the call before any file is processed
+        let result = process_files(files(), Some(options(&["[a-z"])));
+        assert!(result.is_err_and(|err| {
+            err.reason.starts_with("Invalid redaction pattern \"[a-z\"")
+                && err.reason.contains("unclosed character class")
+        }));
+    }
+
+    #[test]
+    fn test_binary_entropy_threshold_reaches_format_handlers() {
+        // Pseudo-random bytes from a linear congruential generator
+        let mut state: u64 = 7;
+        let random: Vec<u8> = (0..4096)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1);
+                (state >> 56) as u8
+            })
+            .collect();
+        let files = || {
+            vec![FileInput {
+                content: Buffer::from(random.clone()),
+                mime_type: "text/csv".to_string(),
+                filename: "export.csv".to_string(),
+            }]
+        };
+        let options = |threshold| ProcessOptions {
+            binary_entropy_threshold: Some(threshold),
+            ..Default::default()
+        };
+
+        let groups = process_files(files(), None).unwrap();
+        assert_eq!(
+            groups[0].files[0].error.as_deref(),
+            Some("Content does not appear to be text")
+        );
+        let groups = process_files(files(), Some(options(8.5))).unwrap();
+        assert_eq!(groups[0].files[0].error, None);
+
+        for threshold in [-1.0, f64::NAN] {
+            let result = process_files(files(), Some(options(threshold)));
+            assert!(
+                result.is_err_and(|err| err.reason.starts_with("Invalid binary_entropy_threshold"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_sequential_matches_parallel() {
+        let files = || {
+            vec![
+                text_file("a.txt", "text/plain", "first file"),
+                text_file("b.csv", "text/csv", "name,city\nAda,London"),
+                text_file("c.txt", "text/plain", "second file"),
+                text_file("d.bin", "application/x-unknown", "opaque"),
+                FileInput {
+                    content: Buffer::from(build_pdf(&[&["Sequential PDF text"]])),
+                    mime_type: "application/pdf".to_string(),
+                    filename: "e.pdf".to_string(),
+                },
+            ]
+        };
+        let summarize = |groups: Vec<GroupedFiles>| {
+            let mut files: Vec<_> = groups
+                .into_iter()
+                .flat_map(|group| {
+                    group.files.into_iter().map(move |f| {
+                        let mime_type = group.mime_type.clone();
+                        (
+                            f.name,
+                            mime_type,
+                            f.text_content,
+                            f.encoding,
+                            f.error,
+                            f.handled,
+                        )
+                    })
+                })
+                .collect();
+            files.sort();
+            files
+        };
+
+        let sequential = process_files_sequential(files(), None).unwrap();
+        // Groups follow the first occurrence of each MIME type, files their input order
+        let order: Vec<_> = sequential
+            .iter()
+            .flat_map(|group| group.files.iter().map(|f| f.name.as_str()))
+            .collect();
+        assert_eq!(order, ["a.txt", "c.txt", "b.csv", "d.bin", "e.pdf"]);
+
+        assert_eq!(
+            summarize(sequential),
+            summarize(process_files(files(), None).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_process_each_emits_every_file() {
+        let files: Vec<FileInput> = (0..20)
+            .map(|i| text_file(&format!("{}.txt", i), "text/plain", &format!("file {}", i)))
+            .collect();
+        let processor = DocumentProcessor::new(ProcessOptions::default());
+
+        let emitted = std::sync::Mutex::new(Vec::new());
+        let count = processor
+            .process_each(&files, |index, metadata| {
+                emitted.lock().unwrap().push((index, metadata.text_content));
+                Ok(())
+            })
+            .unwrap();
+
+        let mut emitted = emitted.into_inner().unwrap();
+        emitted.sort();
+        assert_eq!(count, 20);
+        assert_eq!(
+            emitted,
+            (0..20)
+                .map(|i| (i, format!("file {}", i)))
+                .collect::<Vec<_>>()
+        );
+
+        // With fail_fast, the failing file is never emitted
+        let files = vec![
+            text_file("a.txt", "text/plain", "valid text"),
+            text_file("broken.pdf", "application/pdf", "not a pdf"),
+        ];
+        let processor = DocumentProcessor::new(ProcessOptions {
+            fail_fast: Some(true),
+            ..Default::default()
+        });
+        let emitted = std::sync::Mutex::new(Vec::new());
+        let result = processor.process_each(&files, |index, _| {
+            emitted.lock().unwrap().push(index);
+            Ok(())
+        });
+        assert!(result.is_err_and(|err| err.reason.contains("broken.pdf")));
+        assert!(!emitted.into_inner().unwrap().contains(&1));
+    }
+
+    #[test]
+    fn test_missing_ocr_models_fail_per_file() {
+        let options = ProcessOptions {
+            ocr_detection_model: Some("/nonexistent/text-detection-model.rten".to_string()),
+            ..Default::default()
+        };
+        let files = vec![
+            text_file("notes.txt", "text/plain", "plain text"),
+            text_file("scan.png", "image/png", "not decoded"),
+        ];
+
+        let results = process_files(files, Some(options)).unwrap();
+        let file = |mime_type: &str| {
+            &results
+                .iter()
+                .find(|group| group.mime_type == mime_type)
+                .unwrap()
+                .files[0]
+        };
+
+        assert_eq!(file("text/plain").text_content, "plain text");
+        assert!(
+            file("image/png")
+                .error
+                .as_ref()
+                .is_some_and(|error| error.starts_with("Failed to load detection model"))
+        );
+    }
+
+    #[test]
+    fn test_compute_similarity_matrix() {
+        let files = vec![
+            text_file(
+                "a.txt",
+                "text/plain",
+                "The contract renews every year in March.",
+            ),
+            text_file(
+                "b.txt",
+                "text/plain",
+                "Weekly cafeteria menu: soup and salad.",
+            ),
+            text_file(
+                "c.txt",
+                "text/plain",
+                "The contract renews every year in April.",
+            ),
+        ];
+
+        let pairs = compute_similarity_matrix(files, Some("jaccard".to_string()), Some(60.0));
+
+        assert_eq!(pairs.len(), 1);
+        assert_eq!((pairs[0].i, pairs[0].j), (0, 2));
+        assert!(pairs[0].similarity > 60.0 && pairs[0].similarity < 100.0);
+    }
+
+    #[test]
+    fn test_group_duplicates() {
+        let files = vec![
+            text_file("a.txt", "text/plain", "alpha beta gamma delta epsilon zeta"),
+            text_file("b.txt", "text/plain", "gamma delta epsilon zeta eta theta"),
+            text_file("c.txt", "text/plain", "epsilon zeta eta theta iota kappa"),
+            text_file("d.txt", "text/plain", "weekly cafeteria menu with soup"),
+        ];
+
+        let clusters = group_duplicates(files, Some(50.0), Some("jaccard".to_string()));
+
+        assert_eq!(clusters, vec![vec![0, 1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn test_register_custom_handler() {
+        struct LedgerHandler;
+
+        impl FileHandler for LedgerHandler {
+            fn can_handle(&self, mime_type: &str) -> bool {
+                mime_type == "application/x-ledger"
+            }
+
+            fn extract_text(&self, content: &[u8], _: &str, _: &str) -> Result<String, String> {
+                Ok(String::from_utf8_lossy(content).replace('|', " "))
+            }
+        }
+
+        let files = || {
+            vec![
+                text_file("march.ledger", "application/x-ledger", "rent|1200"),
+                text_file("notes.txt", "text/plain", "plain text"),
+            ]
+        };
+
+        let default_results = process_files(files(), None).unwrap();
+        let ledger = |results: &[GroupedFiles]| {
+            results
+                .iter()
+                .find(|group| group.mime_type == "application/x-ledger")
+                .unwrap()
+                .files[0]
+                .clone()
+        };
+        assert_eq!(
+            ledger(&default_results).encoding,
+            "application/octet-stream"
+        );
+
+        let results = DocumentProcessor::new(ProcessOptions::default())
+            .register_handler(Arc::new(LedgerHandler))
+            .process(&files())
+            .unwrap();
+
+        assert_eq!(ledger(&results).text_content, "rent 1200");
+        let text = results
+            .iter()
+            .find(|group| group.mime_type == "text/plain")
+            .unwrap();
+        assert_eq!(text.files[0].text_content, "plain text");
+    }
+
+    #[test]
+    fn test_zip_entries_use_registered_handlers() {
+        struct ShoutHandler;
+
+        impl FileHandler for ShoutHandler {
+            fn can_handle(&self, mime_type: &str) -> bool {
+                mime_type == "text/plain"
+            }
+
+            fn extract_text(&self, content: &[u8], _: &str, _: &str) -> Result<String, String> {
+                Ok(String::from_utf8_lossy(content).to_uppercase())
+            }
+        }
+
+        let archive = build_zip(&[("notes.txt", b"quiet notes")]);
+        let files = || {
+            vec![FileInput {
+                content: Buffer::from(archive.clone()),
+                mime_type: "application/zip".to_string(),
+                filename: "upload.zip".to_string(),
+            }]
+        };
+
+        let results = process_files(files(), None).unwrap();
+        assert_eq!(
+            results[0].files[0].text_content,
+            "=== entry: notes.txt ===\nquiet notes"
+        );
+
+        let results = DocumentProcessor::new(ProcessOptions::default())
+            .register_handler(Arc::new(ShoutHandler))
+            .process(&files())
+            .unwrap();
+        assert_eq!(
+            results[0].files[0].text_content,
+            "=== entry: notes.txt ===\nQUIET NOTES"
+        );
+    }
+
+    #[test]
+    fn test_later_handlers_take_priority() {
+        struct MarkerHandler(&'static str);
+
+        impl FileHandler for MarkerHandler {
+            fn can_handle(&self, mime_type: &str) -> bool {
+                mime_type == "text/html"
+            }
+
+            fn extract_text(&self, _: &[u8], _: &str, _: &str) -> Result<String, String> {
+                Ok(self.0.to_string())
+            }
+        }
+
+        let file = text_file("page.html", "text/html", "<p>Hello</p>");
+
+        // `HtmlHandler` comes before the catch-all `TextHandler`
+        let metadata = DocumentProcessor::new(ProcessOptions::default()).extract_file(&file);
+        assert_eq!(metadata.text_content, "Hello");
+
+        let metadata = DocumentProcessor::new(ProcessOptions::default())
+            .register_handler(Arc::new(MarkerHandler("first")))
+            .register_handler(Arc::new(MarkerHandler("second")))
+            .extract_file(&file);
+        assert_eq!(metadata.text_content, "second");
+
+        // `process_and_compare_files` uses the same handler order
+        let results = process_and_compare_files(vec![file], vec![], None, None, None).unwrap();
+        assert_eq!(results[0].files[0].text_content, "Hello");
+    }
+
+    #[test]
+    fn test_extraction_timeout() {
+        struct SlowHandler;
+
+        impl FileHandler for SlowHandler {
+            fn can_handle(&self, _mime_type: &str) -> bool {
+                true
+            }
+
+            fn extract_text(&self, _: &[u8], _: &str, _: &str) -> Result<String, String> {
+                thread::sleep(Duration::from_millis(500));
+                Ok("finally".to_string())
+            }
+        }
+
+        let slow =
+            |options| DocumentProcessor::new(options).register_handler(Arc::new(SlowHandler));
+        let file = text_file("slow.pdf", "application/pdf", "%PDF-");
+        let options = ProcessOptions {
+            timeout_ms: Some(20),
+            ..Default::default()
+        };
+
+        let start = Instant::now();
+        let metadata = slow(options).extract_file(&file);
+
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert_eq!(metadata.encoding, "timeout");
+        assert_eq!(metadata.text_content, "");
+        assert_eq!(
+            metadata.error.as_deref(),
+            Some("Extraction timed out after 20 ms")
+        );
+
+        let metadata = slow(ProcessOptions::default()).extract_file(&file);
+        assert_eq!(metadata.text_content, "finally");
+    }
+
+    #[test]
+    fn test_max_bytes_skips_large_files() {
+        struct CountingHandler(AtomicUsize);
+
+        impl FileHandler for CountingHandler {
+            fn can_handle(&self, _mime_type: &str) -> bool {
+                true
+            }
+
+            fn extract_text(&self, content: &[u8], _: &str, _: &str) -> Result<String, String> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Ok(String::from_utf8_lossy(content).into_owned())
+            }
+        }
+
+        let handler = Arc::new(CountingHandler(AtomicUsize::new(0)));
+        let options = ProcessOptions {
+            max_bytes: Some(8.0),
+            ..Default::default()
+        };
+        let processor = DocumentProcessor::new(options).register_handler(handler.clone());
+
+        let metadata = processor.extract_file(&text_file("big.txt", "text/plain", "123456789"));
+        assert_eq!(metadata.encoding, "too_large");
+        assert_eq!(metadata.text_content, "");
+        assert!(metadata.error.is_some());
+        assert_eq!(handler.0.load(Ordering::SeqCst), 0);
+
+        let metadata = processor.extract_file(&text_file("small.txt", "text/plain", "12345678"));
+        assert_eq!(metadata.text_content, "12345678");
+        assert_eq!(handler.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_detected_text_encoding_is_reported() {
+        // "Café crème brûlée à la carte" in windows-1252
+        let content = b"Caf\xe9 cr\xe8me br\xfbl\xe9e \xe0 la carte".to_vec();
+        let file = FileInput {
+            content: Buffer::from(content),
+            mime_type: "text/plain".to_string(),
+            filename: "menu.txt".to_string(),
+        };
+        let ascii = text_file("notes.txt", "text/plain", "plain ascii");
+
+        let results = process_files(vec![file, ascii], None).unwrap();
+
+        let metadata = |name: &str| {
+            results[0]
+                .files
+                .iter()
+                .find(|file| file.name == name)
+                .unwrap()
+        };
+        assert_eq!(metadata("menu.txt").encoding, "windows-1252");
+        assert_eq!(
+            metadata("menu.txt").text_content,
+            "Café crème brûlée à la carte"
+        );
+        assert_eq!(metadata("notes.txt").encoding, "utf-8");
+    }
+
+    #[test]
+    fn test_mime_type_is_sniffed_when_unhandled() {
+        let pdf = build_pdf(&[&["Sniffed PDF text"]]);
+        let file = FileInput {
+            content: Buffer::from(pdf),
+            mime_type: "application/octet-stream".to_string(),
+            filename: "upload.bin".to_string(),
+        };
+
+        let results = process_files(vec![file], None).unwrap();
+
+        assert_eq!(results[0].mime_type, "application/octet-stream");
+        let metadata = &results[0].files[0];
+        assert_eq!(metadata.encoding, "utf-8");
+        assert_eq!(metadata.text_content, "Sniffed PDF text");
+    }
+
+    #[test]
+    fn test_unhandled_type_differs_from_empty_text() {
+        let files = vec![
+            text_file("blob.xyz", "application/x-fictitious", "plain words"),
+            text_file("rule.md", "text/markdown", "---"),
+        ];
+
+        let metadata: Vec<FileMetadata> = process_files(files, None)
+            .unwrap()
+            .into_iter()
+            .flat_map(|group| group.files)
+            .collect();
+
+        let unhandled = metadata.iter().find(|m| m.name == "blob.xyz").unwrap();
+        assert!(!unhandled.handled);
+        assert_eq!(unhandled.encoding, "application/octet-stream");
+        assert_eq!(unhandled.text_content, "");
+
+        let empty = metadata.iter().find(|m| m.name == "rule.md").unwrap();
+        assert!(empty.handled);
+        assert_eq!(empty.encoding, "utf-8");
+        assert_eq!(empty.text_content, "");
+        assert_eq!(empty.error, None);
+    }
+
+    #[test]
+    fn test_include_hash() {
+        let files = [
+            text_file("a.txt", "text/plain", "same content"),
+            text_file("b.txt", "text/plain", "same content"),
+            text_file("c.txt", "text/plain", "other content"),
+        ];
+        let options = ProcessOptions {
+            include_hash: Some(true),
+            ..Default::default()
+        };
+
+        let processor = DocumentProcessor::new(options);
+        let hashes: Vec<String> = files
+            .iter()
+            .map(|file| processor.extract_file(file).content_hash.unwrap())
+            .collect();
+
+        assert_eq!(hashes[0], hashes[1]);
+        assert_ne!(hashes[0], hashes[2]);
+        assert_eq!(
+            hashes[0],
+            "a636bd7cd42060a4d07fa1bfbcc010eb7794c2ba721e1e3e4c20335a15b66eaf"
+        );
+
+        let without = DocumentProcessor::new(ProcessOptions::default()).extract_file(&files[0]);
+        assert_eq!(without.content_hash, None);
+    }
+
+    #[test]
+    fn test_detect_language() {
+        let english = text_file(
+            "en.txt",
+            "text/plain",
+            "The committee reviewed the annual budget and approved the proposal \
+             to expand the public library with a new reading room for children.",
+        );
+        let french = text_file(
+            "fr.txt",
+            "text/plain",
+            "Le comité a examiné le budget annuel et a approuvé la proposition \
+             d'agrandir la bibliothèque publique avec une nouvelle salle de lecture.",
+        );
+        let broken = text_file("broken.pdf", "application/pdf", "not a pdf");
+        let options = ProcessOptions {
+            detect_language: Some(true),
+            ..Default::default()
+        };
+        let processor = DocumentProcessor::new(options);
+
+        let english = processor.extract_file(&english);
+        assert_eq!(english.language.as_deref(), Some("eng"));
+        assert!(english.language_confidence.is_some_and(|c| c > 0.5));
+
+        let french = processor.extract_file(&french);
+        assert_eq!(french.language.as_deref(), Some("fra"));
+
+        // Failed extractions are not analyzed
+        let broken = processor.extract_file(&broken);
+        assert_eq!(broken.language, None);
+        assert_eq!(broken.language_confidence, None);
+    }
+
+    #[test]
+    fn test_empty_content_is_reported_uniformly() {
+        let processor = DocumentProcessor::new(ProcessOptions::default());
+
+        for mime_type in [
+            "text/plain",
+            "application/pdf",
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            "image/png",
+            "application/x-unknown",
+        ] {
+            let metadata = processor.extract_file(&text_file("empty", mime_type, ""));
+
+            assert_eq!(metadata.encoding, "empty", "{}", mime_type);
+            assert_eq!(metadata.text_content, "");
+            assert_eq!(metadata.error, None);
+            assert!(!metadata.handled);
+        }
+    }
+
+    #[test]
+    fn test_report_memory() {
+        let text = text_file("a.txt", "text/plain", "some text");
+        let pdf = text_file("broken.pdf", "application/pdf", "not a pdf");
+        let unknown = text_file("blob", "application/x-unknown", "data");
+
+        let metadata = DocumentProcessor::new(ProcessOptions::default()).extract_file(&text);
+        assert_eq!(metadata.estimated_peak_bytes, None);
+
+        let options = ProcessOptions {
+            report_memory: Some(true),
+            ..Default::default()
+        };
+        let processor = DocumentProcessor::new(options);
+        let estimate = |file| processor.extract_file(file).estimated_peak_bytes;
+        assert_eq!(estimate(&text), Some(9.0));
+        assert_eq!(estimate(&pdf), Some(36.0));
+        assert_eq!(estimate(&unknown), None);
+    }
+
+    #[test]
+    fn test_extraction_error_is_reported_separately() {
+        let file = text_file("broken.pdf", "application/pdf", "not a pdf");
+
+        let metadata = DocumentProcessor::new(ProcessOptions::default()).extract_file(&file);
+
+        assert_eq!(metadata.encoding, "error");
+        assert_eq!(metadata.text_content, "");
+        assert!(
+            metadata
+                .error
+                .as_ref()
+                .is_some_and(|error| error.starts_with("PDF extraction failed"))
+        );
+        // The handler's message is passed through as is, without a prefix
+        let handler_error = PdfHandler::new()
+            .extract_text(b"not a pdf", "broken.pdf", "application/pdf")
+            .unwrap_err();
+        assert_eq!(metadata.error, Some(handler_error));
+    }
+
+    #[test]
+    fn test_process_files_grouped_and_flat() {
+        let files = vec![
+            text_file("a.txt", "text/plain", "first"),
+            text_file("b.csv", "text/csv", "second,row"),
+            text_file("c.txt", "text/plain", "third"),
+        ];
+
+        let results = process_files_grouped_and_flat(files, None).unwrap();
+
+        let flat_names: Vec<&str> = results.flat.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(flat_names, ["a.txt", "b.csv", "c.txt"]);
+        assert_eq!(results.flat[1].text_content, "second\trow");
+
+        assert_eq!(results.grouped.len(), 2);
+        assert_eq!(results.grouped[0].mime_type, "text/plain");
+        let grouped_names: Vec<&str> = results.grouped[0]
+            .files
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(grouped_names, ["a.txt", "c.txt"]);
+        assert_eq!(results.grouped[1].mime_type, "text/csv");
+
+        // Options apply as for `process_files`
+        let files = || {
+            vec![
+                text_file("contact.txt", "text/plain", "Mail ada@example.org"),
+                text_file("broken.pdf", "application/pdf", "not a pdf"),
+            ]
+        };
+        let options = ProcessOptions {
+            redact_patterns: Some(vec![r"\S+@\S+".to_string()]),
+            ..Default::default()
+        };
+        let results = process_files_grouped_and_flat(files(), Some(options)).unwrap();
+        assert_eq!(results.flat[0].text_content, "Mail [REDACTED]");
+        assert_eq!(results.grouped[0].files[0].text_content, "Mail [REDACTED]");
+        assert!(results.flat[1].error.is_some());
+
+        let options = ProcessOptions {
+            fail_fast: Some(true),
+            ..Default::default()
+        };
+        let result = process_files_grouped_and_flat(files(), Some(options));
+        assert!(result.is_err_and(|err| err.reason.starts_with("Failed to process broken.pdf")));
+    }
+
+    #[test]
+    fn test_extract_embedded_documents() {
+        // Each level embeds the next one, one level deeper than the limit
+        let mut docx = build_docx(&["Too deep"], &[]);
+        for level in (0..MAX_EMBEDDED_DEPTH).rev() {
+            docx = build_docx(&[&format!("Level {}", level)], &[("nested.docx", docx)]);
+        }
+        let docx = build_docx(
+            &["Host"],
+            &[
+                ("notes.txt", b"Embedded notes".to_vec()),
+                ("level.docx", docx),
+            ],
+        );
+        let file = FileInput {
+            content: Buffer::from(docx),
+            mime_type: "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+                .to_string(),
+            filename: "host.docx".to_string(),
+        };
+
+        let plain = DocumentProcessor::new(ProcessOptions::default()).extract_file(&file);
+        assert_eq!(plain.text_content, "Host");
+
+        let options = ProcessOptions {
+            extract_embedded: Some(true),
+            ..Default::default()
+        };
+        let metadata = DocumentProcessor::new(options).extract_file(&file);
+        assert_eq!(
+            metadata.text_content,
+            "Host\n\nEmbedded: notes.txt\nEmbedded notes\n\n\
+             Embedded: level.docx\nLevel 0\n\n\
+             Embedded: nested.docx\nLevel 1\n\n\
+             Embedded: nested.docx\nLevel 2"
+        );
+    }
+
+    #[test]
+    fn test_processing_time_is_measured() {
+        let content = "lorem ipsum dolor sit amet ".repeat(50_000);
+        let files = vec![text_file("large.txt", "text/plain", &content)];
+
+        let results = process_and_compare_files(
+            files,
+            vec!["lorem ipsum".to_string()],
+            None,
+            Some("jaccard".to_string()),
+            None,
+        )
+        .unwrap();
+
+        let metadata = &results[0].files[0];
+        assert!(metadata.processing_time_ms > 0.0);
+        assert!(metadata.similarity_time_ms > 0.0);
+
+        let file = text_file("large.txt", "text/plain", &content);
+        let metadata = DocumentProcessor::new(ProcessOptions::default()).extract_file(&file);
+        assert!(metadata.processing_time_ms > 0.0);
+    }
+
+    #[test]
+    fn test_best_only_returns_top_match() {
+        let files = vec![text_file(
+            "notes.txt",
+            "text/plain",
+            "alpha beta gamma delta epsilon",
+        )];
+        let references = vec![
+            "alpha beta gamma".to_string(),
+            "alpha beta gamma delta epsilon zeta".to_string(),
+            "alpha beta gamma delta".to_string(),
+        ];
+        let options = CompareOptions {
+            best_only: Some(true),
+            ..Default::default()
+        };
+
+        let results = process_and_compare_files(
+            files,
+            references,
+            Some(50.0),
+            Some("jaccard".to_string()),
+            Some(options),
+        )
+        .unwrap();
+
+        let matches = &results[0].files[0].similarity_matches;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].reference_index, 1);
+        assert!((matches[0].similarity_percentage - 500.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reference_labels_are_reported() {
+        let files = || vec![text_file("a.txt", "text/plain", "quarterly revenue report")];
+        let references = || {
+            vec![
+                "cafeteria menu".to_string(),
+                "quarterly revenue report".to_string(),
+                "quarterly revenue report draft".to_string(),
+            ]
+        };
+        let options = |labels: &[&str]| CompareOptions {
+            reference_labels: Some(labels.iter().map(|label| label.to_string()).collect()),
+            ..Default::default()
+        };
+
+        let results = process_and_compare_files(
+            files(),
+            references(),
+            Some(50.0),
+            Some("jaccard".to_string()),
+            Some(options(&["menu", "report", "draft"])),
+        )
+        .unwrap();
+
+        let matches = &results[0].files[0].similarity_matches;
+        assert_eq!(matches.len(), 2);
+        for m in matches {
+            let expected = ["menu", "report", "draft"][m.reference_index as usize];
+            assert_eq!(m.reference_label.as_deref(), Some(expected));
+        }
+
+        let result = process_and_compare_files(
+            files(),
+            references(),
+            Some(50.0),
+            None,
+            Some(options(&["menu", "report"])),
+        );
+        assert!(result.is_err_and(|err| err.reason.contains("2 labels but there are 3")));
+    }
+
+    #[test]
+    fn test_per_reference_thresholds() {
+        let compare = |references: &[&str], best_only: bool| {
+            process_and_compare_files(
+                vec![text_file(
+                    "a.txt",
+                    "text/plain",
+                    "quarterly revenue report for march",
+                )],
+                references.iter().map(|text| text.to_string()).collect(),
+                Some(50.0),
+                Some("jaccard".to_string()),
+                Some(CompareOptions {
+                    per_reference_thresholds: Some(vec![90.0, 30.0]),
+                    best_only: Some(best_only),
+                    ..Default::default()
+                }),
+            )
+            .unwrap()
+            .remove(0)
+            .files
+            .remove(0)
+            .similarity_matches
+            .iter()
+            .map(|m| m.reference_index)
+            .collect::<Vec<_>>()
+        };
+
+        // Scoring 67%, 33%, and 100%: the first misses its 90% threshold, the
+        // second meets its 30% one, and the third uses the global 50%
+        let references = [
+            "quarterly revenue report for april",
+            "quarterly revenue summary",
+            "quarterly revenue report for march",
+        ];
+        assert_eq!(compare(&references, false), vec![2, 1]);
+
+        // The best match is the best one meeting its own threshold
+        assert_eq!(compare(&references[..2], true), vec![1]);
+    }
+
+    #[test]
+    fn test_paragraph_granularity_finds_shared_section() {
+        let content = "Cover letter for the annual shareholder meeting in Berlin.\n\n\
+            The supplier shall deliver all goods within thirty days of the order date.\n\n\
+            Appendix with the seating plan, the catering menu, and the parking map.";
+        let reference =
+            "The supplier shall deliver all goods within thirty days of the order date.";
+        let compare = |granularity: &str| {
+            process_and_compare_files(
+                vec![text_file("letter.txt", "text/plain", content)],
+                vec![reference.to_string()],
+                Some(80.0),
+                Some("ngram".to_string()),
+                Some(CompareOptions {
+                    granularity: Some(granularity.to_string()),
+                    ..Default::default()
+                }),
+            )
+            .unwrap()
+            .remove(0)
+            .files
+            .remove(0)
+            .similarity_matches
+        };
+
+        // The whole document shares too little with the reference
+        assert!(compare("document").is_empty());
+
+        let matches = compare("paragraph");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].reference_index, 0);
+        assert_eq!(matches[0].similarity_percentage, 100.0);
+        assert_eq!(matches[0].paragraph_index, Some(1));
+    }
+
+    #[test]
+    fn test_sample_mode_marks_matches_approximate() {
+        let content = "the quick brown fox jumps over the lazy dog ".repeat(100);
+        let compare = |options: Option<CompareOptions>| {
+            let files = vec![text_file("large.txt", "text/plain", &content)];
+            process_and_compare_files(
+                files,
+                vec![content.clone()],
+                None,
+                Some("jaccard".to_string()),
+                options,
+            )
+            .unwrap()
+        };
+
+        let exact = compare(None);
+        assert!(!exact[0].files[0].similarity_matches[0].approximate);
+
+        let options = CompareOptions {
+            sample_mode: Some(true),
+            ..Default::default()
+        };
+        let sampled = compare(Some(options));
+        let similarity_match = &sampled[0].files[0].similarity_matches[0];
+        assert!(similarity_match.approximate);
+        assert_eq!(similarity_match.similarity_percentage, 100.0);
+    }
+
+    fn synthetic_batch(file_count: usize, reference_count: usize) -> (Vec<FileInput>, Vec<String>) {
+        let words = [
+            "invoice", "ledger", "payment", "account", "balance", "report",
+        ];
+        let text = |seed: usize| {
+            (0..40)
+                .map(|i| words[(seed * 7 + i * (seed % 5 + 1)) % words.len()])
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        let files = (0..file_count)
+            .map(|i| text_file(&format!("{i}.txt"), "text/plain", &text(i)))
+            .collect();
+        let references = (0..reference_count).map(|i| text(i + 3)).collect();
+        (files, references)
+    }
+
+    fn compare_batch(
+        file_count: usize,
+        reference_count: usize,
+        parallelism: &str,
+    ) -> Vec<(String, Vec<(u32, f64)>)> {
+        let (files, references) = synthetic_batch(file_count, reference_count);
+        let options = CompareOptions {
+            parallelism: Some(parallelism.to_string()),
+            ..Default::default()
+        };
+        let results = process_and_compare_files(
+            files,
+            references,
+            Some(50.0),
+            Some("ngram".to_string()),
+            Some(options),
+        )
+        .unwrap();
+
+        let mut files: Vec<_> = results
+            .into_iter()
+            .flat_map(|group| group.files)
+            .map(|file| {
+                let matches = file
+                    .similarity_matches
+                    .iter()
+                    .map(|m| (m.reference_index, m.similarity_percentage))
+                    .collect();
+                (file.name, matches)
+            })
+            .collect();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        files
+    }
+
+    #[test]
+    fn test_parallelism_modes_agree() {
+        let by_file = compare_batch(20, 30, "by_file");
+        let by_reference = compare_batch(20, 30, "by_reference");
+        let auto = compare_batch(20, 30, "auto");
+
+        assert_eq!(by_file.len(), 20);
+        assert!(by_file.iter().any(|(_, matches)| !matches.is_empty()));
+        assert_eq!(by_file, by_reference);
+        assert_eq!(by_file, auto);
+    }
+
+    /// Times each parallelism mode on a large synthetic batch.
+    ///
+    /// Run with `cargo test --release -- --ignored --nocapture
+    /// bench_parallelism_modes`. "by_file" wins when files outnumber the
+    /// worker threads, "by_reference" when a handful of files is compared
+    /// with many references.
+    #[test]
+    #[ignore]
+    fn bench_parallelism_modes() {
+        for (file_count, reference_count) in [(500, 100), (4, 5_000)] {
+            for parallelism in ["by_file", "by_reference", "auto"] {
+                let start = Instant::now();
+                compare_batch(file_count, reference_count, parallelism);
+                eprintln!(
+                    "{file_count} files x {reference_count} references, {parallelism}: {:?}",
+                    start.elapsed()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_dedupe_references_matches_every_duplicate() {
+        let references = vec![
+            "alpha beta gamma".to_string(),
+            "delta epsilon".to_string(),
+            "alpha beta gamma".to_string(),
+            "alpha beta delta".to_string(),
+            "alpha beta gamma".to_string(),
+        ];
+        let compare = |dedupe_references: Option<bool>, best_only: Option<bool>| {
+            let options = CompareOptions {
+                dedupe_references,
+                best_only,
+                ..Default::default()
+            };
+            let results = process_and_compare_files(
+                vec![text_file("a.txt", "text/plain", "alpha beta gamma")],
+                references.clone(),
+                Some(40.0),
+                Some("jaccard".to_string()),
+                Some(options),
+            )
+            .unwrap();
+            results[0].files[0]
+                .similarity_matches
+                .iter()
+                .map(|m| (m.reference_index, m.similarity_percentage))
+                .collect::<Vec<_>>()
+        };
+
+        let deduped = compare(Some(true), None);
+        assert_eq!(deduped, vec![(0, 100.0), (2, 100.0), (4, 100.0), (3, 50.0)]);
+        assert_eq!(deduped, compare(None, None));
+        assert_eq!(compare(Some(true), Some(true)), compare(None, Some(true)));
+    }
+
+    #[test]
+    fn test_index_references_keeps_ngram_matches() {
+        let references: Vec<String> = [
+            "invoice 2024-001 for consulting services",
+            "quarterly report on revenue",
+            "invoice 2024-002 for consulting services",
+            "cafeteria menu",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        let compare = |index_references: Option<bool>| {
+            let options = CompareOptions {
+                index_references,
+                ..Default::default()
+            };
+            let results = process_and_compare_files(
+                vec![text_file(
+                    "a.txt",
+                    "text/plain",
+                    "Invoice 2024-003 for consulting services",
+                )],
+                references.clone(),
+                Some(30.0),
+                Some("ngram".to_string()),
+                Some(options),
+            )
+            .unwrap();
+            results[0].files[0]
+                .similarity_matches
+                .iter()
+                .map(|m| (m.reference_index, m.similarity_percentage))
+                .collect::<Vec<_>>()
+        };
+
+        let indexed = compare(Some(true));
+        assert_eq!(indexed.len(), 2);
+        assert_eq!(indexed, compare(None));
+    }
+
+    #[test]
+    fn test_sliding_window_reports_matching_passage() {
+        let reference = "payment is due within thirty days of delivery";
+        let document = format!(
+            "Café supply agreement between the parties. {} Late payments accrue interest at the statutory rate.",
+            reference
+        );
+        let options = CompareOptions {
+            include_match_text: Some(true),
+            ..Default::default()
+        };
+
+        let results = process_and_compare_files(
+            vec![text_file("contract.txt", "text/plain", &document)],
+            vec![reference.to_string()],
+            Some(50.0),
+            Some("sliding_window".to_string()),
+            Some(options),
+        )
+        .unwrap();
+        let found = &results[0].files[0].similarity_matches[0];
+
+        assert_eq!(found.similarity_percentage, 100.0);
+        assert_eq!(found.match_text.as_deref(), Some(reference));
+        let utf16: Vec<u16> = document.encode_utf16().collect();
+        let start = found.match_start.unwrap() as usize;
+        let passage = String::from_utf16(&utf16[start..start + reference.len()]).unwrap();
+        assert_eq!(passage, reference);
+    }
+
+    #[test]
+    fn test_weighted_method_uses_weights() {
+        let compare = |weights: SimilarityWeights| {
+            let options = CompareOptions {
+                weights: Some(weights),
+                ..Default::default()
+            };
+            process_and_compare_files(
+                vec![text_file("a.txt", "text/plain", "the quick brown fox")],
+                vec!["the quick brown cat".to_string()],
+                Some(0.0),
+                Some("weighted".to_string()),
+                Some(options),
+            )
+        };
+
+        let jaccard_only = compare(SimilarityWeights {
+            jaccard: Some(2.0),
+            ..Default::default()
+        })
+        .unwrap();
+        let matches = &jaccard_only[0].files[0].similarity_matches;
+        assert_eq!(matches[0].similarity_percentage, 60.0);
+        assert_eq!(matches[0].method_used.as_deref(), Some("weighted"));
+
+        assert!(
+            compare(SimilarityWeights {
+                ngram: Some(-1.0),
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_compare_texts() {
+        let (source, target) = ("the quick brown fox", "the quick brown cat");
+
+        assert_eq!(
+            compare_texts(
+                source.to_string(),
+                target.to_string(),
+                Some("jaccard".into())
+            ),
+            60.0
+        );
+        assert_eq!(
+            compare_texts(
+                source.to_string(),
+                target.to_string(),
+                Some("unknown".into())
+            ),
+            compare_texts(source.to_string(), target.to_string(), None)
+        );
+        assert_eq!(
+            compare_texts(source.to_string(), target.to_string(), None),
+            calculate_similarity(source, target, SimilarityMethod::Hybrid(DEFAULT_NGRAM_SIZE))
+        );
+    }
+
+    #[test]
+    fn test_levenshtein_alignment_operations() {
+        let ops = levenshtein_alignment("café".to_string(), "cafés".to_string());
+
+        let names: Vec<&str> = ops.iter().map(|op| op.op.as_str()).collect();
+        assert_eq!(names, ["match", "match", "match", "match", "insert"]);
+        // Positions are character indices, so "é" counts once
+        let insert = &ops[4];
+        assert_eq!((insert.source_index, insert.target_index), (4, 4));
+        assert_eq!(insert.source_char, None);
+        assert_eq!(insert.target_char.as_deref(), Some("s"));
+        assert_eq!(ops[3].source_char.as_deref(), Some("é"));
+    }
+
+    #[test]
+    fn test_supported_mime_types() {
+        let mime_types = supported_mime_types();
+
+        assert!(mime_types.contains(&"application/pdf".to_string()));
+        assert!(mime_types.contains(&"text/plain".to_string()));
+        assert!(mime_types.is_sorted());
+    }
+
+    #[test]
+    fn test_which_handler() {
+        assert_eq!(
+            which_handler("application/pdf".to_string()).as_deref(),
+            Some("PdfHandler")
+        );
+        assert_eq!(
+            which_handler("text/csv".to_string()).as_deref(),
+            Some("CsvHandler")
+        );
+        assert_eq!(which_handler("application/x-unknown".to_string()), None);
+    }
+
+    #[test]
+    fn test_mime_type_is_normalized() {
+        for mime_type in ["TEXT/PLAIN", "text/plain; charset=utf-8"] {
+            assert_eq!(
+                which_handler(mime_type.to_string()).as_deref(),
+                Some("TextHandler")
+            );
+
+            let results =
+                process_files(vec![text_file("notes", mime_type, "Plain words")], None).unwrap();
+            assert_eq!(results[0].mime_type, mime_type);
+            assert_eq!(results[0].files[0].text_content, "Plain words");
+            assert!(results[0].files[0].handled);
+        }
+
+        assert_eq!(
+            which_handler("IMAGE/JPG".to_string()).as_deref(),
+            Some("ImageHandler")
+        );
+    }
+
+    #[test]
+    fn test_compare_text_to_many() {
+        let targets = vec![
+            "completely different words".to_string(),
+            "the quick brown cat".to_string(),
+            "the quick brown fox".to_string(),
+        ];
+
+        let matches = compare_text_to_many(
+            "the quick brown fox".to_string(),
+            targets.clone(),
+            Some("jaccard".to_string()),
+            Some(50.0),
+        );
+        let found: Vec<_> = matches
+            .iter()
+            .map(|m| (m.reference_index, m.similarity_percentage))
+            .collect();
+        assert_eq!(found, vec![(2, 100.0), (1, 60.0)]);
+        assert_eq!(matches[0].method_used.as_deref(), Some("jaccard"));
+
+        let fallback = compare_text_to_many(
+            "the quick brown fox".to_string(),
+            targets,
+            Some("unknown".to_string()),
+            None,
+        );
+        assert_eq!(fallback[0].reference_index, 2);
+        assert!(
+            fallback
+                .iter()
+                .all(|m| m.method_used.as_deref() != Some("unknown"))
+        );
+    }
+
+    #[test]
+    fn test_single_thread_pool() {
+        // Setting the process-wide pool would pin every other test to it, so
+        // only the rejected size is tried, and the entry points run inside a
+        // local pool of one thread, as they would inside a configured one
+        assert!(set_thread_pool_size(0).is_err());
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+
+        let files: Vec<FileInput> = (0..8)
+            .map(|i| text_file(&format!("{i}.txt"), "text/plain", &format!("file {i}")))
+            .collect();
+        let results = pool.install(|| process_files(files, None)).unwrap();
+        let mut texts: Vec<_> = results[0]
+            .files
+            .iter()
+            .map(|file| file.text_content.clone())
+            .collect();
+        texts.sort();
+        assert_eq!(
+            texts,
+            (0..8).map(|i| format!("file {i}")).collect::<Vec<_>>()
+        );
+
+        let files = vec![text_file("a.txt", "text/plain", "alpha beta gamma")];
+        let results = pool
+            .install(|| {
+                process_and_compare_files(
+                    files,
+                    vec!["alpha beta gamma".to_string(), "delta".to_string()],
+                    Some(50.0),
+                    Some("jaccard".to_string()),
+                    None,
+                )
+            })
+            .unwrap();
+        let matches = &results[0].files[0].similarity_matches;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].reference_index, 0);
+    }
 }
@@ -0,0 +1,89 @@
+//! Data structures for Levenshtein alignments.
+//!
+//! These structures are returned by `levenshtein_alignment` and describe the
+//! edit operations turning one text into another, for diff visualization.
+
+use crate::core::similarity::EditOp;
+use napi_derive::napi;
+
+/// One step of the alignment of a source text with a target text.
+///
+/// Positions are character indices (Unicode code points, as produced by
+/// `[...text]` in JavaScript), not UTF-16 string indices.
+///
+/// # Fields
+///
+/// * `op` - The operation: "match", "insert", "delete", or "substitute"
+/// * `source_index` - Position in the source. For "insert", the inserted
+///   character goes before this source character
+/// * `target_index` - Position in the target. For "delete", the deleted
+///   character was before this target character
+/// * `source_char` - The source character, for every operation but "insert"
+/// * `target_char` - The target character, for every operation but "delete"
+///
+/// # Example
+///
+/// ```typescript
+/// const op: EditOperation = {
+///   op: 'substitute',
+///   sourceIndex: 0,
+///   targetIndex: 0,
+///   sourceChar: 'k',
+///   targetChar: 's'
+/// };
+/// ```
+#[napi(object)]
+pub struct EditOperation {
+    /// "match", "insert", "delete", or "substitute".
+    pub op: String,
+    /// Character index in the source.
+    pub source_index: u32,
+    /// Character index in the target.
+    pub target_index: u32,
+    /// Character of the source, unless inserting.
+    pub source_char: Option<String>,
+    /// Character of the target, unless deleting.
+    pub target_char: Option<String>,
+}
+
+impl From<EditOp> for EditOperation {
+    fn from(op: EditOp) -> Self {
+        let (name, source_index, target_index, source_char, target_char) = match op {
+            EditOp::Match {
+                source_index,
+                target_index,
+                ch,
+            } => ("match", source_index, target_index, Some(ch), Some(ch)),
+            EditOp::Insert {
+                source_index,
+                target_index,
+                ch,
+            } => ("insert", source_index, target_index, None, Some(ch)),
+            EditOp::Delete {
+                source_index,
+                target_index,
+                ch,
+            } => ("delete", source_index, target_index, Some(ch), None),
+            EditOp::Substitute {
+                source_index,
+                target_index,
+                from,
+                to,
+            } => (
+                "substitute",
+                source_index,
+                target_index,
+                Some(from),
+                Some(to),
+            ),
+        };
+
+        EditOperation {
+            op: name.to_string(),
+            source_index: source_index as u32,
+            target_index: target_index as u32,
+            source_char: source_char.map(String::from),
+            target_char: target_char.map(String::from),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Data structures for DOCX document properties.
+//!
+//! These structures are returned by `extract_docx_properties` and describe a
+//! Word document as recorded in its `docProps/core.xml` and `docProps/app.xml`
+//! package parts.
+
+use napi_derive::napi;
+
+/// Properties of a DOCX document.
+///
+/// The properties are optional parts of the package and often incomplete, so
+/// each field is `None` when the property (or its whole part) is missing or
+/// empty.
+///
+/// # Fields
+///
+/// * `title` - The document title (`dc:title` in `docProps/core.xml`)
+/// * `author` - The person who created the document (`dc:creator`)
+/// * `created` - The creation date (`dcterms:created`), as written: Word
+///   stores it in ISO 8601 (`2024-01-15T10:30:00Z`)
+/// * `modified` - The date of the last save (`dcterms:modified`), as written
+/// * `word_count` - The word count (`Words` in `docProps/app.xml`), as last
+///   computed by the application that saved the document
+///
+/// # Example
+///
+/// ```typescript
+/// const props: DocxProperties = {
+///   title: 'Service Agreement',
+///   author: 'Legal Department',
+///   created: '2024-01-15T10:30:00Z',
+///   modified: '2024-02-01T08:00:00Z',
+///   wordCount: 1834
+/// };
+/// ```
+#[napi(object)]
+pub struct DocxProperties {
+    /// Document title.
+    pub title: Option<String>,
+    /// Document author.
+    pub author: Option<String>,
+    /// Creation date, as written in the document.
+    pub created: Option<String>,
+    /// Last modification date, as written in the document.
+    pub modified: Option<String>,
+    /// Word count recorded by the saving application.
+    pub word_count: Option<u32>,
+}
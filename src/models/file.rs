@@ -46,13 +46,35 @@ pub struct FileInput {
 ///
 /// * `name` - The original filename
 /// * `size` - File size in bytes (as a floating-point number)
-/// * `processing_time_ms` - Time taken to process the file in milliseconds
-///   (currently always 0.0, reserved for future use)
+/// * `processing_time_ms` - Time taken to extract the file's text in milliseconds
 /// * `encoding` - Encoding information:
-///   - "utf-8" for successfully processed files
+///   - The detected source encoding for successfully processed text files
+///     (e.g. "utf-8", "windows-1252"), and "utf-8" for other files
 ///   - "error" for files where extraction failed
+///   - "timeout" for files whose extraction exceeded `timeout_ms`
+///   - "too_large" for files larger than `max_bytes`, which are not extracted
+///   - "empty" for zero-length files, which are not extracted whatever their
+///     type
 ///   - "application/octet-stream" for unhandled file types
-/// * `text_content` - The extracted text content, or an error message if extraction failed
+/// * `text_content` - The extracted text content (empty if extraction failed)
+/// * `error` - The extraction error message, or `None` if extraction succeeded
+///   or no handler matched
+/// * `estimated_peak_bytes` - Heuristic estimate of the peak memory used to
+///   extract the file (file size times a per-handler multiplier), populated
+///   only when `report_memory` is enabled and a handler matched
+/// * `lossy_decoding` - Whether invalid byte sequences in a text file were
+///   replaced with U+FFFD (the replacement character) while decoding it
+/// * `handled` - Whether a handler accepted the file and extraction ran. An
+///   empty `text_content` with `handled` set means the file has no text; with
+///   `handled` unset, the file type is not supported (or the file was
+///   skipped as too large or empty)
+/// * `content_hash` - Lowercase hex SHA-256 of the file content, populated
+///   only when `include_hash` is enabled (including for files that failed or
+///   were skipped)
+/// * `language` - ISO 639-3 code of the language of `text_content` (e.g.
+///   "eng", "fra"), populated only when `detect_language` is enabled, the
+///   extraction succeeded, and the language could be determined
+/// * `language_confidence` - Confidence of `language`, from 0 to 1
 ///
 /// # Example
 ///
@@ -60,23 +82,45 @@ pub struct FileInput {
 /// const metadata: FileMetadata = {
 ///   name: 'document.pdf',
 ///   size: 1024.0,
-///   processingTimeMs: 0.0,
+///   processingTimeMs: 12.4,
 ///   encoding: 'utf-8',
-///   textContent: 'Extracted text from PDF...'
+///   textContent: 'Extracted text from PDF...',
+///   error: null,
+///   estimatedPeakBytes: 4096.0,
+///   lossyDecoding: false,
+///   handled: true,
+///   contentHash: null,
+///   language: 'eng',
+///   languageConfidence: 0.97
 /// };
 /// ```
 #[napi(object)]
+#[derive(Clone)]
 pub struct FileMetadata {
     /// Original filename of the processed file.
     pub name: String,
     /// File size in bytes (floating-point number).
     pub size: f64,
-    /// Processing time in milliseconds (currently always 0.0).
+    /// Time spent extracting text, in milliseconds.
     pub processing_time_ms: f64,
-    /// Encoding information: "utf-8" (success), "error" (failure), or "application/octet-stream" (unhandled).
+    /// Encoding information: the source encoding such as "utf-8" (success), "error" (failure), "timeout", "too_large", "empty", or "application/octet-stream" (unhandled).
     pub encoding: String,
-    /// Extracted text content or error message.
+    /// Extracted text content (empty on failure).
     pub text_content: String,
+    /// Extraction error message, if extraction failed.
+    pub error: Option<String>,
+    /// Estimated peak extraction memory in bytes, if `report_memory` is enabled.
+    pub estimated_peak_bytes: Option<f64>,
+    /// Whether invalid bytes were replaced while decoding text.
+    pub lossy_decoding: bool,
+    /// Whether a handler accepted the file and extraction ran.
+    pub handled: bool,
+    /// Hex-encoded SHA-256 of the file content, if `include_hash` is enabled.
+    pub content_hash: Option<String>,
+    /// ISO 639-3 code of the text's language, if `detect_language` is enabled.
+    pub language: Option<String>,
+    /// Confidence (0-1) of the detected language.
+    pub language_confidence: Option<f64>,
 }
 
 /// Output structure representing files grouped by MIME type.
@@ -108,6 +152,164 @@ pub struct GroupedFiles {
     pub files: Vec<FileMetadata>,
 }
 
+/// Output structure containing the same results in two layouts.
+///
+/// This structure is returned by `process_files_grouped_and_flat`, which runs
+/// extraction once and returns the results both grouped by MIME type and as a
+/// flat, input-ordered list.
+///
+/// # Fields
+///
+/// * `grouped` - Files grouped by MIME type, in order of first appearance
+/// * `flat` - One `FileMetadata` per input file, in input order
+///
+/// # Example
+///
+/// ```typescript
+/// const results: GroupedAndFlatFiles = {
+///   grouped: [{ mimeType: 'text/plain', files: [...] }],
+///   flat: [{ name: 'a.txt', ... }, { name: 'b.pdf', ... }]
+/// };
+/// ```
+#[napi(object)]
+pub struct GroupedAndFlatFiles {
+    /// Files grouped by MIME type.
+    pub grouped: Vec<GroupedFiles>,
+    /// Files in input order.
+    pub flat: Vec<FileMetadata>,
+}
+
+/// Aggregate numbers describing a processed batch.
+///
+/// This structure is returned by `process_files_with_stats` alongside the
+/// grouped results, for monitoring without walking every file.
+///
+/// Every file falls into exactly one of `successes`, `failures`, and
+/// `unhandled`, so they add up to `total_files`.
+///
+/// # Fields
+///
+/// * `total_files` - The number of processed files
+/// * `successes` - Files a handler extracted without error, including files
+///   with no text
+/// * `failures` - Files with an `error`: failed or timed-out extractions and
+///   files over `max_bytes`
+/// * `unhandled` - Files without an error that no handler accepted, including
+///   empty files
+/// * `total_bytes` - The combined size of all files, in bytes
+/// * `total_processing_time_ms` - The sum of the files' `processing_time_ms`.
+///   Files are processed in parallel, so this is usually more than the
+///   wall-clock time of the batch
+///
+/// # Example
+///
+/// ```typescript
+/// const stats: BatchStats = {
+///   totalFiles: 3,
+///   successes: 2,
+///   failures: 0,
+///   unhandled: 1,
+///   totalBytes: 48213,
+///   totalProcessingTimeMs: 35.2
+/// };
+/// ```
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct BatchStats {
+    /// Number of processed files.
+    pub total_files: u32,
+    /// Files extracted without error.
+    pub successes: u32,
+    /// Files with an extraction error.
+    pub failures: u32,
+    /// Files no handler accepted.
+    pub unhandled: u32,
+    /// Combined size of all files, in bytes.
+    pub total_bytes: f64,
+    /// Sum of the files' extraction times, in milliseconds.
+    pub total_processing_time_ms: f64,
+}
+
+impl BatchStats {
+    /// Counts one file's result.
+    pub fn record(&mut self, metadata: &FileMetadata) {
+        self.total_files += 1;
+        if metadata.error.is_some() {
+            self.failures += 1;
+        } else if metadata.handled {
+            self.successes += 1;
+        } else {
+            self.unhandled += 1;
+        }
+        self.total_bytes += metadata.size;
+        self.total_processing_time_ms += metadata.processing_time_ms;
+    }
+}
+
+/// Output structure of `process_files_with_stats`: the grouped results and
+/// their aggregate statistics.
+///
+/// # Fields
+///
+/// * `groups` - Files grouped by MIME type, same as `process_files`
+/// * `stats` - Aggregate numbers over all files
+///
+/// # Example
+///
+/// ```typescript
+/// const result: GroupedFilesWithStats = {
+///   groups: [{ mimeType: 'text/plain', files: [...] }],
+///   stats: { totalFiles: 1, successes: 1, failures: 0, unhandled: 0, ... }
+/// };
+/// ```
+#[napi(object)]
+pub struct GroupedFilesWithStats {
+    /// Files grouped by MIME type.
+    pub groups: Vec<GroupedFiles>,
+    /// Aggregate statistics of the batch.
+    pub stats: BatchStats,
+}
+
+/// Output structure of `extract_text`: the result of extracting one file.
+///
+/// # Fields
+///
+/// * `text` - The extracted text content (empty if extraction failed or no
+///   handler matched)
+/// * `encoding` - Encoding information, with the same values as
+///   `FileMetadata.encoding`
+/// * `error` - The extraction error message, or `None` if extraction succeeded
+///   or no handler matched
+///
+/// # Example
+///
+/// ```typescript
+/// const extracted: ExtractedFile = {
+///   text: 'Extracted text from PDF...',
+///   encoding: 'utf-8',
+///   error: null
+/// };
+/// ```
+#[napi(object)]
+pub struct ExtractedFile {
+    /// Extracted text content (empty on failure).
+    pub text: String,
+    /// Encoding information, as in `FileMetadata`.
+    pub encoding: String,
+    /// Extraction error message, if extraction failed.
+    pub error: Option<String>,
+}
+
+impl From<FileMetadata> for ExtractedFile {
+    fn from(metadata: FileMetadata) -> Self {
+        Self {
+            text: metadata.text_content,
+            encoding: metadata.encoding,
+            error: metadata.error,
+        }
+    }
+}
+
 /// Structure representing a similarity match between extracted text and a reference text.
 ///
 /// This structure is used in similarity comparison results to indicate which
@@ -117,14 +319,39 @@ pub struct GroupedFiles {
 ///
 /// * `reference_index` - The index of the reference text in the input array
 ///   (0-based, corresponds to the position in the `reference_texts` array)
+/// * `reference_label` - The label of the reference text, when labels were
+///   supplied with the `reference_labels` option
 /// * `similarity_percentage` - The similarity score as a percentage (0.0 to 100.0)
+/// * `approximate` - Whether the score is an estimate computed from sampled
+///   windows of the texts (sampling mode) rather than the full texts
+/// * `method_used` - The similarity method that computed the score. For the
+///   "hybrid" method, this is the branch that ran ("jaccard" for the early
+///   exit, "levenshtein" for small texts, or "ngram" for large texts); other
+///   methods report their own name
+/// * `match_start` - With `include_match_text` and the "sliding_window"
+///   method, the offset of the best-matching passage in the compared source
+///   text, in UTF-16 code units (JavaScript string indices). The compared text
+///   is `text_content` unless preprocessing options such as `clean_logs`
+///   changed it
+/// * `match_text` - The best-matching passage itself, set along with
+///   `match_start`
+/// * `paragraph_index` - With the "paragraph" granularity, the index of the
+///   source paragraph that scored best against the reference (0-based, in
+///   order of the blank-line delimited paragraphs of `text_content`).
+///   `match_start` is then an offset in that paragraph
 ///
 /// # Example
 ///
 /// ```typescript
 /// const match: SimilarityMatch = {
 ///   referenceIndex: 0,
-///   similarityPercentage: 85.5
+///   referenceLabel: "contract-2024-017",
+///   similarityPercentage: 85.5,
+///   approximate: false,
+///   methodUsed: "levenshtein",
+///   matchStart: null,
+///   matchText: null,
+///   paragraphIndex: null
 /// };
 /// // Indicates the extracted text is 85.5% similar to reference_texts[0]
 /// ```
@@ -132,8 +359,51 @@ pub struct GroupedFiles {
 pub struct SimilarityMatch {
     /// Index of the reference text in the input array (0-based).
     pub reference_index: u32,
+    /// Label of the reference text, from `reference_labels`.
+    pub reference_label: Option<String>,
     /// Similarity percentage (0.0 to 100.0).
     pub similarity_percentage: f64,
+    /// Whether the score was estimated from sampled text.
+    pub approximate: bool,
+    /// Similarity method that computed the score.
+    pub method_used: Option<String>,
+    /// Offset of the best-matching passage in the source, in UTF-16 code units.
+    pub match_start: Option<u32>,
+    /// Best-matching passage of the source.
+    pub match_text: Option<String>,
+    /// Index of the best-matching source paragraph (0-based).
+    pub paragraph_index: Option<u32>,
+}
+
+/// A pair of files from the same batch whose texts are similar.
+///
+/// Returned by `compute_similarity_matrix`. Each pair of files appears at most
+/// once, with the lower index first.
+///
+/// # Fields
+///
+/// * `i` - Index of the first file in the input array (0-based)
+/// * `j` - Index of the second file in the input array (always greater than `i`)
+/// * `similarity` - Similarity percentage between the two files' texts (0.0 to 100.0)
+///
+/// # Example
+///
+/// ```typescript
+/// const pair: SimilarityPair = {
+///   i: 0,
+///   j: 2,
+///   similarity: 92.3
+/// };
+/// // files[0] and files[2] are 92.3% similar
+/// ```
+#[napi(object)]
+pub struct SimilarityPair {
+    /// Index of the first file (0-based).
+    pub i: u32,
+    /// Index of the second file (greater than `i`).
+    pub j: u32,
+    /// Similarity percentage (0.0 to 100.0).
+    pub similarity: f64,
 }
 
 /// Extended file metadata structure that includes similarity comparison results.
@@ -145,6 +415,8 @@ pub struct SimilarityMatch {
 /// # Fields
 ///
 /// All fields from `FileMetadata` plus:
+/// * `similarity_time_ms` - Time spent comparing the extracted text against the
+///   reference texts in milliseconds (0.0 if no comparison ran)
 /// * `similarity_matches` - Array of `SimilarityMatch` objects representing
 ///   reference texts that matched above the threshold
 ///
@@ -154,9 +426,11 @@ pub struct SimilarityMatch {
 /// const metadata: FileMetadataWithSimilarity = {
 ///   name: 'document.pdf',
 ///   size: 1024.0,
-///   processingTimeMs: 0.0,
+///   processingTimeMs: 12.4,
 ///   encoding: 'utf-8',
 ///   textContent: 'Extracted text...',
+///   error: null,
+///   similarityTimeMs: 3.1,
 ///   similarityMatches: [
 ///     { referenceIndex: 0, similarityPercentage: 85.5 },
 ///     { referenceIndex: 2, similarityPercentage: 72.3 }
@@ -169,12 +443,16 @@ pub struct FileMetadataWithSimilarity {
     pub name: String,
     /// File size in bytes (floating-point number).
     pub size: f64,
-    /// Processing time in milliseconds (currently always 0.0).
+    /// Time spent extracting text, in milliseconds.
     pub processing_time_ms: f64,
-    /// Encoding information: "utf-8" (success), "error" (failure), or "application/octet-stream" (unhandled).
+    /// Encoding information: the source encoding such as "utf-8" (success), "error" (failure), "timeout", "too_large", "empty", or "application/octet-stream" (unhandled).
     pub encoding: String,
-    /// Extracted text content or error message.
+    /// Extracted text content (empty on failure).
     pub text_content: String,
+    /// Extraction error message, if extraction failed.
+    pub error: Option<String>,
+    /// Time spent on similarity comparison, in milliseconds.
+    pub similarity_time_ms: f64,
     /// Array of similarity matches above the threshold.
     pub similarity_matches: Vec<SimilarityMatch>,
 }
@@ -1 +1,8 @@
+pub mod alignment;
+pub mod docx;
 pub mod file;
+pub mod ocr;
+pub mod options;
+pub mod pdf;
+pub mod summary;
+pub mod xlsx;
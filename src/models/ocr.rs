@@ -0,0 +1,49 @@
+//! Data structures for structured OCR results.
+//!
+//! These structures are returned by `extract_image_regions` and describe each
+//! recognized line of text together with its position in the image.
+
+use napi_derive::napi;
+
+/// A line of text recognized by OCR, with its location in the image.
+///
+/// Coordinates are in pixels of the decoded image, with the origin at the
+/// top-left corner.
+///
+/// # Fields
+///
+/// * `text` - The recognized text of the line
+/// * `x` / `y` - Left and top edges of the line's bounding rectangle
+/// * `width` / `height` - Size of the line's bounding rectangle
+/// * `confidence` - Mean probability (0.0 to 1.0) assigned by the text detection
+///   model to the text pixels of the line, if any were found. Low values mark
+///   faint or ambiguous text worth reviewing. The recognition model does not
+///   report per-line scores, so this reflects detection confidence only.
+///
+/// # Example
+///
+/// ```typescript
+/// const line: OcrLine = {
+///   text: 'INVOICE 2024-001',
+///   x: 42,
+///   y: 18,
+///   width: 310,
+///   height: 28,
+///   confidence: 0.93
+/// };
+/// ```
+#[napi(object)]
+pub struct OcrLine {
+    /// Recognized text of the line.
+    pub text: String,
+    /// Left edge of the bounding rectangle, in pixels.
+    pub x: i32,
+    /// Top edge of the bounding rectangle, in pixels.
+    pub y: i32,
+    /// Width of the bounding rectangle, in pixels.
+    pub width: i32,
+    /// Height of the bounding rectangle, in pixels.
+    pub height: i32,
+    /// Mean text detection probability of the line's pixels (0.0 to 1.0).
+    pub confidence: Option<f64>,
+}
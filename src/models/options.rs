@@ -0,0 +1,326 @@
+//! Option structures accepted by the processing functions.
+//!
+//! Options are grouped into NAPI objects so new settings can be added without
+//! changing function signatures. Every field is optional; omitted fields keep
+//! the default behavior.
+
+use napi_derive::napi;
+
+/// Options controlling text extraction in `process_files`.
+///
+/// # Fields
+///
+/// * `fail_fast` - Reject the whole batch as soon as any file fails to extract,
+///   instead of returning per-file errors (default: false)
+/// * `timeout_ms` - Maximum time in milliseconds to spend extracting a single
+///   file. Slower files get the "timeout" encoding and an error instead of
+///   stalling the batch (default: no timeout)
+/// * `max_bytes` - Maximum file size in bytes. Larger files are not extracted
+///   and get the "too_large" encoding, so oversized uploads cannot exhaust
+///   memory (default: no limit)
+/// * `pdf_page_markers` - Extract PDFs page by page, prefixing each page with a
+///   `--- Page N ---` marker (default: false)
+/// * `pdf_password` - Open password for encrypted PDFs. PDFs with only an owner
+///   password are decrypted without it; PDFs that need an open password are
+///   not supported, and the password only makes their error say whether it
+///   was correct (default: none)
+/// * `pdf_collapse_linebreaks` - Join the lines of each PDF paragraph with
+///   spaces, for PDFs that place one word or phrase per line (default: false)
+/// * `pdf_preserve_paragraphs` - Keep a blank line between PDF paragraphs
+///   instead of dropping blank lines (default: false)
+/// * `pdf_ocr_fallback` - Recognize the page images of PDFs without a text
+///   layer (scanned PDFs) with OCR when extraction finds next to no text.
+///   JBIG2 and JPEG 2000 images are not decoded (default: false)
+/// * `extract_embedded` - Also extract documents embedded in DOCX and XLSX files,
+///   appending their text under an `Embedded: name` header (default: false)
+/// * `expand_merged_cells` - Repeat the value of merged XLSX cells and DOCX table
+///   cells across every cell of the merged region (default: false, other cells
+///   stay empty)
+/// * `preserve_empty_cells` - Keep empty XLSX cells and rows as empty
+///   tab-separated fields so values stay in their columns (default: false)
+/// * `sheets` - Names or zero-based indices (as strings, e.g. "0") of the XLSX
+///   sheets to extract; other sheets are skipped (default: all sheets)
+/// * `report_memory` - Populate `estimated_peak_bytes` on each result with a
+///   heuristic estimate of the memory used to extract it (default: false)
+/// * `include_hash` - Populate `content_hash` on each result with the
+///   hex-encoded SHA-256 of the file content, for exact-duplicate detection
+///   and audit trails (default: false)
+/// * `detect_language` - Populate `language` and `language_confidence` on each
+///   successfully extracted result with the detected language of its text
+///   (default: false)
+/// * `normalize_whitespace` - Normalize the whitespace of every extracted
+///   text, whatever its handler: remove zero-width characters, convert line
+///   endings to `\n`, collapse runs of spaces and tabs into one space, and drop
+///   empty lines, for fairer comparisons across formats (default: false)
+/// * `ocr_detection_model` - Path to an alternate `.rten` text detection model
+///   for image OCR (default: the bundled model)
+/// * `ocr_recognition_model` - Path to an alternate `.rten` text recognition
+///   model for image OCR, such as one trained for another language (default:
+///   the bundled English model)
+/// * `ocr_alphabet` - Characters output by the recognition model, in order. A
+///   language-specific recognition model usually changes the alphabet and
+///   needs this set to match (default: the bundled model's alphabet)
+/// * `ocr_grayscale` - Convert images to grayscale before OCR (default: false)
+/// * `ocr_binarize_threshold` - Turn image pixels at least this bright (luma
+///   0-255) white and all others black before OCR, for low-contrast scans
+///   (default: no binarization)
+/// * `ocr_min_width` - Upscale images narrower than this many pixels to this
+///   width before OCR, for small scans (default: no upscaling)
+/// * `docx_link_targets` - Append the URL of each DOCX hyperlink in brackets
+///   after its display text (default: false, only the display text is kept)
+/// * `include_image_alt` - Emit the alt text of DOCX pictures as
+///   `[image: alt text]` at their position in the paragraph, for documents
+///   whose images carry meaningful descriptions (default: false)
+/// * `zip_max_uncompressed_bytes` - Maximum total size in bytes of the
+///   decompressed entries of a ZIP archive, nested archives included. Larger
+///   archives fail to extract (default: 256 MiB)
+/// * `zip_max_entries` - Maximum number of entries in a ZIP archive, nested
+///   archives included (default: 1000)
+/// * `gzip_max_output_bytes` - Maximum decompressed size in bytes of a gzip
+///   file. Larger files fail to extract (default: 256 MiB)
+/// * `gzip_inner_mime_type` - MIME type of the decompressed content of gzip
+///   files (default: inferred from the filename without `.gz`, then from the
+///   content, then `text/plain`)
+/// * `redact_patterns` - Patterns masked in every extracted text, such as
+///   emails, SSNs, or phone numbers, as regular expressions in `regex` crate
+///   syntax. An invalid pattern fails the whole call before any file is
+///   processed (default: none)
+/// * `redact_mask` - Text replacing each match of `redact_patterns` (default:
+///   `[REDACTED]`)
+/// * `binary_entropy_threshold` - Byte entropy in bits per byte (0-8) at or
+///   above which a file labeled as text (including CSV, JSON, Markdown, HTML,
+///   YAML, and TOML) is rejected as binary with an error, instead of being
+///   decoded into garbage. Lower values reject more files; a value above 8
+///   disables the check, and a negative or NaN value fails the call (default:
+///   7.5)
+///
+/// # Example
+///
+/// ```typescript
+/// const options: ProcessOptions = {
+///   failFast: true,
+///   pdfPageMarkers: true,
+///   extractEmbedded: true
+/// };
+/// ```
+#[napi(object)]
+#[derive(Default)]
+pub struct ProcessOptions {
+    /// Abort the batch with an error on the first extraction failure.
+    pub fail_fast: Option<bool>,
+    /// Per-file extraction timeout in milliseconds.
+    pub timeout_ms: Option<u32>,
+    /// Maximum file size in bytes; larger files are skipped.
+    pub max_bytes: Option<f64>,
+    /// Prefix each PDF page's text with a `--- Page N ---` marker.
+    pub pdf_page_markers: Option<bool>,
+    /// Open password for encrypted PDFs.
+    pub pdf_password: Option<String>,
+    /// Join the lines of each PDF paragraph with spaces.
+    pub pdf_collapse_linebreaks: Option<bool>,
+    /// Keep blank lines between PDF paragraphs.
+    pub pdf_preserve_paragraphs: Option<bool>,
+    /// OCR the page images of PDFs without extractable text.
+    pub pdf_ocr_fallback: Option<bool>,
+    /// Extract text from documents embedded in DOCX and XLSX files.
+    pub extract_embedded: Option<bool>,
+    /// Repeat merged cell values across the merged region.
+    pub expand_merged_cells: Option<bool>,
+    /// Keep empty XLSX cells so columns stay aligned.
+    pub preserve_empty_cells: Option<bool>,
+    /// XLSX sheets to extract, by name or zero-based index.
+    pub sheets: Option<Vec<String>>,
+    /// Report a per-file estimate of peak extraction memory.
+    pub report_memory: Option<bool>,
+    /// Report the SHA-256 of each file's content.
+    pub include_hash: Option<bool>,
+    /// Detect the language of each file's text.
+    pub detect_language: Option<bool>,
+    /// Normalize whitespace and line endings of the extracted text.
+    pub normalize_whitespace: Option<bool>,
+    /// Path to an alternate OCR text detection model.
+    pub ocr_detection_model: Option<String>,
+    /// Path to an alternate OCR text recognition model.
+    pub ocr_recognition_model: Option<String>,
+    /// Alphabet of the OCR recognition model.
+    pub ocr_alphabet: Option<String>,
+    /// Convert images to grayscale before OCR.
+    pub ocr_grayscale: Option<bool>,
+    /// Luma threshold for binarizing images before OCR.
+    pub ocr_binarize_threshold: Option<u32>,
+    /// Minimum image width in pixels; narrower images are upscaled before OCR.
+    pub ocr_min_width: Option<u32>,
+    /// Append DOCX hyperlink URLs after their display text.
+    pub docx_link_targets: Option<bool>,
+    /// Emit the alt text of DOCX pictures inline.
+    pub include_image_alt: Option<bool>,
+    /// Maximum total decompressed size of a ZIP archive's entries.
+    pub zip_max_uncompressed_bytes: Option<f64>,
+    /// Maximum number of entries in a ZIP archive.
+    pub zip_max_entries: Option<u32>,
+    /// Maximum decompressed size of a gzip file.
+    pub gzip_max_output_bytes: Option<f64>,
+    /// MIME type of the decompressed content of gzip files.
+    pub gzip_inner_mime_type: Option<String>,
+    /// Patterns masked in every extracted text.
+    pub redact_patterns: Option<Vec<String>>,
+    /// Replacement for matches of `redact_patterns`.
+    pub redact_mask: Option<String>,
+    /// Byte entropy at or above which text files are rejected as binary.
+    pub binary_entropy_threshold: Option<f64>,
+}
+
+/// Options controlling similarity comparison in `process_and_compare_files`.
+///
+/// # Fields
+///
+/// * `clean_logs` - Strip leading ISO timestamps and log-level tokens
+///   (`INFO`, `ERROR`, ...) from each line before comparison (default: false)
+/// * `collapse_whitespace` - Collapse runs of whitespace (spaces, tabs, line
+///   breaks) into a single space and trim, leaving case and punctuation
+///   untouched (default: false)
+/// * `canonicalize_numbers` - Rewrite formatted numbers into a canonical form
+///   before comparison, so "1,000.00", "1000", and "1.000,00" compare equal
+///   (default: false)
+/// * `ngram_size` - N-gram size used by the "ngram" and "word_ngram" methods
+///   (in words for the latter) and the large-text branch of "hybrid"
+///   (default: 3, a value of 0 is clamped to 1)
+/// * `max_results` - Maximum number of matches returned per file, keeping the
+///   highest-scoring ones (default: unlimited)
+/// * `sample_mode` - Estimate similarity from windows sampled from the beginning,
+///   middle, and end of large texts instead of comparing them in full. Matches
+///   computed from samples are marked `approximate` (default: false)
+/// * `sample_fraction` - Fraction of each large text kept in sampling mode
+///   (default: 0.1)
+/// * `remove_stopwords` - Ignore common English words ("the", "a", "are", ...)
+///   in the "jaccard" method (default: false)
+/// * `stem` - Reduce words to their Porter stems in the "jaccard" method, so
+///   "running" and "runs" match (default: false)
+/// * `min_token_len` - Ignore words shorter than this many characters in the
+///   "jaccard" method, so single letters and short numbers don't inflate the
+///   overlap (default: 0, every word counts)
+/// * `timeout_ms` - Maximum time in milliseconds to spend extracting a single
+///   file; slower files get the "timeout" encoding (default: no timeout)
+/// * `best_only` - Return at most one match per file, the highest-scoring
+///   reference (default: false)
+/// * `case_sensitive` - Compare case as written in the "jaccard", "ngram", and
+///   "hybrid" methods instead of lowercasing, so "KB" and "Kb" differ
+///   (default: false)
+/// * `normalize_unicode` - Normalize both texts to Unicode NFC before
+///   comparison, so precomposed ("é") and decomposed ("e" + combining accent)
+///   characters compare equal (default: true)
+/// * `parallelism` - Which dimension runs in parallel: "by_file" compares
+///   files in parallel, "by_reference" compares each file with the reference
+///   texts in parallel, and "auto" picks "by_file" when there are at least as
+///   many files as worker threads (default: "auto")
+/// * `weights` - Relative weights of the "weighted" method, scaled to sum to 1
+///   (default: equal weights)
+/// * `dedupe_references` - Compare each distinct reference text once and
+///   report its matches for every duplicate, which speeds up reference sets
+///   with many exact duplicates without changing the results (default: false)
+/// * `include_match_text` - With the "sliding_window" method, report where in
+///   each file the best-matching passage is (`match_start`) and its text
+///   (`match_text`) on every match, for highlighting (default: false)
+/// * `index_references` - Build a trigram index over the reference texts once
+///   and score each file only against the references sharing enough trigrams
+///   with it, for large reference sets. Exact for the "ngram" method with the
+///   default size of 3; other methods skip references sharing no trigram with
+///   the file (default: false)
+/// * `reference_labels` - Labels of the reference texts, one per reference in
+///   the same order, copied to the `reference_label` of each match. Must have
+///   as many entries as there are reference texts (default: no labels)
+/// * `granularity` - What is compared with each reference: "document" compares
+///   the whole text, "paragraph" compares each blank-line delimited paragraph
+///   and reports the best-scoring one per reference, with its
+///   `paragraph_index` (default: "document")
+/// * `per_reference_thresholds` - Similarity thresholds of the reference texts,
+///   by index, overriding `similarity_threshold` for their matches. References
+///   past the end of the list use `similarity_threshold` (default: none)
+///
+/// # Example
+///
+/// ```typescript
+/// const options: CompareOptions = {
+///   cleanLogs: true,
+///   ngramSize: 2,
+///   maxResults: 5
+/// };
+/// ```
+#[napi(object)]
+#[derive(Default)]
+pub struct CompareOptions {
+    /// Strip leading timestamps and log levels from each line before comparison.
+    pub clean_logs: Option<bool>,
+    /// Collapse whitespace runs into single spaces before comparison.
+    pub collapse_whitespace: Option<bool>,
+    /// Canonicalize number formats (thousands and decimal separators) before comparison.
+    pub canonicalize_numbers: Option<bool>,
+    /// N-gram size for the "ngram", "word_ngram", and "hybrid" methods.
+    pub ngram_size: Option<u32>,
+    /// Maximum number of matches returned per file.
+    pub max_results: Option<u32>,
+    /// Compare sampled windows of large texts for a fast similarity estimate.
+    pub sample_mode: Option<bool>,
+    /// Fraction of each large text kept when sampling.
+    pub sample_fraction: Option<f64>,
+    /// Ignore English stopwords in Jaccard similarity.
+    pub remove_stopwords: Option<bool>,
+    /// Apply Porter stemming in Jaccard similarity.
+    pub stem: Option<bool>,
+    /// Ignore shorter words in Jaccard similarity.
+    pub min_token_len: Option<u32>,
+    /// Per-file extraction timeout in milliseconds.
+    pub timeout_ms: Option<u32>,
+    /// Return only the best match per file.
+    pub best_only: Option<bool>,
+    /// Normalize texts to Unicode NFC before comparison.
+    pub normalize_unicode: Option<bool>,
+    /// Compare case as written instead of lowercasing.
+    pub case_sensitive: Option<bool>,
+    /// Parallelize by "by_file", "by_reference", or "auto".
+    pub parallelism: Option<String>,
+    /// Weights of the "weighted" method.
+    pub weights: Option<SimilarityWeights>,
+    /// Compare duplicate reference texts only once.
+    pub dedupe_references: Option<bool>,
+    /// Report the best-matching passage of "sliding_window" matches.
+    pub include_match_text: Option<bool>,
+    /// Retrieve candidate references from a trigram index.
+    pub index_references: Option<bool>,
+    /// Labels of the reference texts, reported on their matches.
+    pub reference_labels: Option<Vec<String>>,
+    /// Compare the whole "document" or each "paragraph".
+    pub granularity: Option<String>,
+    /// Similarity thresholds overriding the global one, by reference index.
+    pub per_reference_thresholds: Option<Vec<f64>>,
+}
+
+/// Relative weights of the methods blended by the "weighted" similarity method.
+///
+/// Weights must be non-negative, and at least one must be positive. They are
+/// scaled to sum to 1, so `{ jaccard: 1, levenshtein: 3 }` weighs Levenshtein
+/// three times as much as Jaccard.
+///
+/// # Fields
+///
+/// * `jaccard` - Weight of the Jaccard score (default: 0)
+/// * `ngram` - Weight of the n-gram score, using `ngram_size` (default: 0)
+/// * `levenshtein` - Weight of the Levenshtein score (default: 0)
+///
+/// # Example
+///
+/// ```typescript
+/// const options: CompareOptions = {
+///   weights: { jaccard: 0.5, ngram: 0.3, levenshtein: 0.2 }
+/// };
+/// ```
+#[napi(object)]
+#[derive(Default)]
+pub struct SimilarityWeights {
+    /// Weight of the Jaccard score.
+    pub jaccard: Option<f64>,
+    /// Weight of the n-gram score.
+    pub ngram: Option<f64>,
+    /// Weight of the Levenshtein score.
+    pub levenshtein: Option<f64>,
+}
@@ -0,0 +1,48 @@
+//! Data structures for PDF document metadata.
+//!
+//! These structures are returned by `extract_pdf_metadata` and describe a PDF
+//! document as recorded in its document information dictionary.
+
+use napi_derive::napi;
+
+/// Metadata of a PDF document.
+///
+/// The text fields come from the PDF's document information dictionary
+/// (`/Info`), which is optional and often incomplete, so each of them is
+/// `None` when the entry is missing or empty.
+///
+/// # Fields
+///
+/// * `title` - The document title (`/Title`)
+/// * `author` - The person or organization that created the document (`/Author`)
+/// * `subject` - The subject of the document (`/Subject`)
+/// * `page_count` - The number of pages in the document
+/// * `created` - The creation date (`/CreationDate`), converted from the PDF
+///   date format (`D:20240115103000+01'00'`) to ISO 8601
+///   (`2024-01-15T10:30:00+01:00`). Dates that don't follow the PDF format
+///   are returned as written.
+///
+/// # Example
+///
+/// ```typescript
+/// const meta: PdfMetadata = {
+///   title: 'Service Agreement',
+///   author: 'Legal Department',
+///   subject: undefined,
+///   pageCount: 12,
+///   created: '2024-01-15T10:30:00+01:00'
+/// };
+/// ```
+#[napi(object)]
+pub struct PdfMetadata {
+    /// Document title.
+    pub title: Option<String>,
+    /// Document author.
+    pub author: Option<String>,
+    /// Document subject.
+    pub subject: Option<String>,
+    /// Number of pages.
+    pub page_count: u32,
+    /// Creation date, in ISO 8601 when it could be converted.
+    pub created: Option<String>,
+}
@@ -0,0 +1,77 @@
+//! Data structures for aggregate statistics over similarity results.
+//!
+//! These structures are returned by `summarize_similarity` and describe the
+//! overall distribution of matches produced by `process_and_compare_files`.
+
+use napi_derive::napi;
+
+/// A single bucket of the similarity score histogram.
+///
+/// Buckets are 10 percentage points wide. The lower bound is inclusive and the
+/// upper bound is exclusive, except for the last bucket which also includes
+/// scores of exactly 100.0.
+///
+/// # Example
+///
+/// ```typescript
+/// const bucket: SimilarityHistogramBucket = {
+///   rangeStart: 80.0,
+///   rangeEnd: 90.0,
+///   count: 12
+/// };
+/// ```
+#[napi(object)]
+pub struct SimilarityHistogramBucket {
+    /// Lower bound of the bucket (inclusive).
+    pub range_start: f64,
+    /// Upper bound of the bucket (exclusive, except for the last bucket).
+    pub range_end: f64,
+    /// Number of matches whose similarity falls into this bucket.
+    pub count: u32,
+}
+
+/// Aggregate statistics for a batch of similarity comparison results.
+///
+/// # Fields
+///
+/// * `total_files` - Number of files across all MIME type groups
+/// * `files_with_matches` - Number of files with at least one similarity match
+/// * `total_matches` - Total number of similarity matches across all files
+/// * `average_matches_per_file` - `total_matches / total_files` (0.0 for empty input)
+/// * `average_similarity` - Mean similarity percentage over all matches (0.0 if none)
+/// * `min_similarity` / `max_similarity` - Lowest and highest match scores, if any
+/// * `histogram` - Ten buckets covering 0-100 in steps of 10
+///
+/// # Example
+///
+/// ```typescript
+/// const summary: SimilaritySummary = {
+///   totalFiles: 120,
+///   filesWithMatches: 45,
+///   totalMatches: 98,
+///   averageMatchesPerFile: 0.82,
+///   averageSimilarity: 61.4,
+///   minSimilarity: 30.2,
+///   maxSimilarity: 100.0,
+///   histogram: [{ rangeStart: 0, rangeEnd: 10, count: 0 }, ...]
+/// };
+/// ```
+#[napi(object)]
+pub struct SimilaritySummary {
+    /// Number of files across all MIME type groups.
+    pub total_files: u32,
+    /// Number of files with at least one similarity match.
+    pub files_with_matches: u32,
+    /// Total number of similarity matches across all files.
+    pub total_matches: u32,
+    /// Average number of matches per file.
+    pub average_matches_per_file: f64,
+    /// Mean similarity percentage over all matches.
+    pub average_similarity: f64,
+    /// Lowest similarity percentage among all matches.
+    pub min_similarity: Option<f64>,
+    /// Highest similarity percentage among all matches.
+    pub max_similarity: Option<f64>,
+    /// Distribution of match scores in 10-point buckets.
+    pub histogram: Vec<SimilarityHistogramBucket>,
+}
@@ -0,0 +1,70 @@
+//! Data structures for typed spreadsheet cells.
+//!
+//! These structures are returned by `extract_xlsx_cells`, which keeps the type
+//! of each cell that the plain-text extraction flattens into a string.
+
+use napi_derive::napi;
+
+/// A cell of a spreadsheet with its value and type.
+///
+/// # Fields
+///
+/// * `value` - The cell value as text. Numbers use their shortest decimal form
+///   ("12.5"), dates are ISO 8601 ("2024-01-15", or "2024-01-15T10:30:00"
+///   when they have a time), booleans are "true" or "false", and empty cells
+///   are an empty string.
+/// * `kind` - The cell type: "number", "date", "text", "bool", or "empty".
+///   Error cells ("#DIV/0!") are "text", and durations are "number" (in days).
+///
+/// # Example
+///
+/// ```typescript
+/// const cell: XlsxCell = { value: '2024-01-15', kind: 'date' };
+/// ```
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct XlsxCell {
+    /// Cell value as text.
+    pub value: String,
+    /// Cell type: "number", "date", "text", "bool", or "empty".
+    pub kind: String,
+}
+
+/// The cells of one worksheet.
+///
+/// `rows` covers the sheet's used range, the smallest rectangle holding every
+/// non-empty cell, so cells keep their column positions and empty cells
+/// inside the range are included with the "empty" kind.
+///
+/// # Fields
+///
+/// * `name` - The sheet name
+/// * `first_row` - Zero-based index of the first row of the used range
+/// * `first_column` - Zero-based index of the first column of the used range
+/// * `rows` - The rows of the used range, each a list of cells
+///
+/// # Example
+///
+/// ```typescript
+/// const sheet: XlsxSheet = {
+///   name: 'Budget',
+///   firstRow: 0,
+///   firstColumn: 0,
+///   rows: [
+///     [{ value: 'Item', kind: 'text' }, { value: 'Cost', kind: 'text' }],
+///     [{ value: 'Coffee', kind: 'text' }, { value: '12.5', kind: 'number' }]
+///   ]
+/// };
+/// ```
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct XlsxSheet {
+    /// Sheet name.
+    pub name: String,
+    /// Zero-based index of the first row of the used range.
+    pub first_row: u32,
+    /// Zero-based index of the first column of the used range.
+    pub first_column: u32,
+    /// Rows of cells in the used range.
+    pub rows: Vec<Vec<XlsxCell>>,
+}
@@ -0,0 +1,825 @@
+//! Configurable document processing pipeline.
+//!
+//! A `DocumentProcessor` holds the list of file handlers together with the
+//! processing options and extracts text from single files and batches. The
+//! NAPI functions in `lib.rs` are thin wrappers over a processor with the
+//! built-in handlers; Rust callers can register their own handlers on top.
+
+use crate::core::detect::sniff_mime;
+use crate::core::embedded::{MAX_EMBEDDED_DEPTH, embedded_parts};
+use crate::core::handler::{ExtractedText, FileHandler};
+use crate::core::mime::normalize_mime_type;
+use crate::core::preprocess::normalize_whitespace;
+use crate::core::redact::{DEFAULT_REDACTION_MASK, Redactor};
+use crate::handlers::config::ConfigHandler;
+use crate::handlers::csv::CsvHandler;
+use crate::handlers::docx::DocxHandler;
+use crate::handlers::eml::EmlHandler;
+use crate::handlers::epub::EpubHandler;
+use crate::handlers::gzip::GzipHandler;
+use crate::handlers::html::HtmlHandler;
+use crate::handlers::image::{ImageHandler, ImagePreprocessOptions};
+use crate::handlers::json::JsonHandler;
+use crate::handlers::md::MarkdownHandler;
+use crate::handlers::pdf::{PdfCleanOptions, PdfHandler};
+use crate::handlers::pptx::PptxHandler;
+use crate::handlers::text::{DEFAULT_BINARY_ENTROPY_THRESHOLD, TextHandler};
+use crate::handlers::xlsx::XlsxHandler;
+use crate::handlers::zip::ZipHandler;
+use crate::models::file::{BatchStats, FileInput, FileMetadata, GroupedFiles};
+use crate::models::options::ProcessOptions;
+
+use dashmap::DashMap;
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Extracts text from files with a configurable set of handlers.
+///
+/// A processor starts with the built-in handlers, configured from its
+/// `ProcessOptions`. Additional handlers can be registered for formats the
+/// built-in handlers do not support, or to replace a built-in handler for a
+/// MIME type.
+///
+/// # Handler Priority
+///
+/// Each file is extracted by the first handler that accepts its MIME type.
+/// Registered handlers are consulted before the built-in ones, and a handler
+/// registered later is consulted before those registered earlier, so when
+/// MIME types overlap the most recently registered handler wins.
+///
+/// # Redaction
+///
+/// The `redact_patterns` option is compiled once, when the processor is
+/// created, and applied to the text of every file. If a pattern is invalid,
+/// the batch methods fail before processing any file, and `extract_file`
+/// reports the error on the file.
+///
+/// # Example
+///
+/// ```no_run
+/// use dms_toolkit_rs::{DocumentProcessor, FileHandler, ProcessOptions};
+/// use std::sync::Arc;
+///
+/// struct LedgerHandler;
+///
+/// impl FileHandler for LedgerHandler {
+///     fn can_handle(&self, mime_type: &str) -> bool {
+///         mime_type == "application/x-ledger"
+///     }
+///
+///     fn extract_text(&self, content: &[u8], _: &str, _: &str) -> Result<String, String> {
+///         decode_ledger(content)
+///     }
+/// }
+///
+/// let processor = DocumentProcessor::new(ProcessOptions::default())
+///     .register_handler(Arc::new(LedgerHandler));
+/// let results = processor.process(&files)?;
+/// ```
+pub struct DocumentProcessor {
+    /// Handlers in priority order: the first one accepting a MIME type is used.
+    handlers: Vec<Arc<dyn FileHandler>>,
+    options: ProcessOptions,
+    /// The compiled `redact_patterns`, or the error of the first invalid
+    /// option (`binary_entropy_threshold` or a redaction pattern).
+    setup: Result<Option<Redactor>, String>,
+}
+
+impl DocumentProcessor {
+    /// Creates a processor with the built-in handlers.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Processing options, used both to configure the built-in
+    ///   handlers and during processing (`fail_fast`, `timeout_ms`, ...)
+    ///
+    /// # Returns
+    ///
+    /// A new `DocumentProcessor` ready to process files.
+    pub fn new(options: ProcessOptions) -> Self {
+        let setup = check_binary_entropy_threshold(&options).and_then(|()| {
+            match &options.redact_patterns {
+                Some(patterns) if !patterns.is_empty() => {
+                    let mask = options
+                        .redact_mask
+                        .as_deref()
+                        .unwrap_or(DEFAULT_REDACTION_MASK);
+                    Redactor::new(patterns, mask).map(Some)
+                }
+                _ => Ok(None),
+            }
+        });
+
+        Self {
+            handlers: build_handlers(&options),
+            options,
+            setup,
+        }
+    }
+
+    /// Registers an additional file handler.
+    ///
+    /// The handler is consulted before the built-in handlers and any handler
+    /// registered earlier, so it can both add support for new MIME types and
+    /// take over MIME types that are already handled.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler to register
+    ///
+    /// # Returns
+    ///
+    /// The processor with the handler registered.
+    pub fn register_handler(mut self, handler: Arc<dyn FileHandler>) -> Self {
+        // Rebuild the container handlers so their contents can use the new handler
+        self.handlers
+            .truncate(self.handlers.len() - CONTAINER_HANDLER_COUNT);
+        self.handlers.insert(0, handler);
+        push_container_handlers(&mut self.handlers, &self.options);
+        self
+    }
+
+    /// Lists the MIME types accepted by the processor's handlers.
+    ///
+    /// This is the implementation of `supported_mime_types`. Registered
+    /// handlers contribute the types from their `supported_mimes()`.
+    ///
+    /// # Returns
+    ///
+    /// The MIME types, sorted and without duplicates.
+    pub fn supported_mime_types(&self) -> Vec<String> {
+        let mut mime_types: Vec<String> = self
+            .handlers
+            .iter()
+            .flat_map(|handler| handler.supported_mimes())
+            .collect();
+        mime_types.sort();
+        mime_types.dedup();
+        mime_types
+    }
+
+    /// Returns the name of the handler that extracts files of a MIME type.
+    ///
+    /// This is the implementation of `which_handler`. Only the declared MIME
+    /// type is considered, normalized as during extraction; a file whose type
+    /// no handler accepts may still be routed by the type detected from its
+    /// content.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_type` - The MIME type to route
+    ///
+    /// # Returns
+    ///
+    /// The `name()` of the first handler accepting `mime_type`, or `None` if
+    /// no handler accepts it.
+    pub fn handler_name(&self, mime_type: &str) -> Option<&str> {
+        let mime_type = normalize_mime_type(mime_type);
+        self.handlers
+            .iter()
+            .find(|handler| handler.can_handle(&mime_type))
+            .map(|handler| handler.name())
+    }
+
+    /// Extracts text from a batch of files in parallel, grouped by MIME type.
+    ///
+    /// This is the implementation of `process_files`.
+    ///
+    /// # Arguments
+    ///
+    /// * `files` - The files to process
+    ///
+    /// # Returns
+    ///
+    /// The results grouped by MIME type, or an error if `fail_fast` is enabled
+    /// and any file failed to extract.
+    pub fn process(&self, files: &[FileInput]) -> napi::Result<Vec<GroupedFiles>> {
+        let grouped: DashMap<String, Vec<FileMetadata>> = DashMap::new();
+
+        self.process_each(files, |index, metadata| {
+            grouped
+                .entry(files[index].mime_type.clone())
+                .or_default()
+                .push(metadata);
+            Ok(())
+        })?;
+
+        Ok(grouped
+            .into_iter()
+            .map(|(mime_type, files)| GroupedFiles { mime_type, files })
+            .collect())
+    }
+
+    /// Extracts text from a batch of files in parallel, in input order.
+    ///
+    /// This is the implementation of `process_files_grouped_and_flat`, which
+    /// derives its grouped layout from the returned results.
+    ///
+    /// # Arguments
+    ///
+    /// * `files` - The files to process
+    ///
+    /// # Returns
+    ///
+    /// One result per file, in the order of `files`, or an error if
+    /// `fail_fast` is enabled and any file failed to extract.
+    pub fn process_flat(&self, files: &[FileInput]) -> napi::Result<Vec<FileMetadata>> {
+        let results: Vec<OnceLock<FileMetadata>> = files.iter().map(|_| OnceLock::new()).collect();
+
+        self.process_each(files, |index, metadata| {
+            let _ = results[index].set(metadata);
+            Ok(())
+        })?;
+
+        // Without an error, every file has been emitted
+        Ok(results
+            .into_iter()
+            .filter_map(OnceLock::into_inner)
+            .collect())
+    }
+
+    /// Extracts text from a batch of files one at a time on the calling
+    /// thread, grouped by MIME type.
+    ///
+    /// This is the implementation of `process_files_sequential`. No Rayon
+    /// work is started, so it is safe to call from inside another thread pool
+    /// or where only one thread may be used. Groups are listed in the order
+    /// their MIME type first occurs in `files`, and files keep their input
+    /// order within each group.
+    ///
+    /// # Arguments
+    ///
+    /// * `files` - The files to process
+    ///
+    /// # Returns
+    ///
+    /// The results grouped by MIME type, or an error if `fail_fast` is enabled
+    /// and any file failed to extract. Files after a failing one are skipped.
+    pub fn process_sequential(&self, files: &[FileInput]) -> napi::Result<Vec<GroupedFiles>> {
+        self.check_options()?;
+        let fail_fast = self.options.fail_fast.unwrap_or(false);
+        let mut groups: Vec<GroupedFiles> = Vec::new();
+
+        for file in files {
+            let metadata = self.extract_file(file);
+
+            if fail_fast && let Some(error) = &metadata.error {
+                return Err(napi::Error::from_reason(format!(
+                    "Failed to process {}: {}",
+                    file.filename, error
+                )));
+            }
+
+            match groups.iter_mut().find(|g| g.mime_type == file.mime_type) {
+                Some(group) => group.files.push(metadata),
+                None => groups.push(GroupedFiles {
+                    mime_type: file.mime_type.clone(),
+                    files: vec![metadata],
+                }),
+            }
+        }
+
+        Ok(groups)
+    }
+
+    /// Extracts text from a batch of files in parallel, grouped by MIME type,
+    /// and aggregates statistics over the results.
+    ///
+    /// This is the implementation of `process_files_with_stats`. The
+    /// statistics are recorded as each file completes, alongside the grouping.
+    ///
+    /// # Arguments
+    ///
+    /// * `files` - The files to process
+    ///
+    /// # Returns
+    ///
+    /// The results grouped by MIME type and their `BatchStats`, or an error if
+    /// `fail_fast` is enabled and any file failed to extract.
+    pub fn process_with_stats(
+        &self,
+        files: &[FileInput],
+    ) -> napi::Result<(Vec<GroupedFiles>, BatchStats)> {
+        let grouped: DashMap<String, Vec<FileMetadata>> = DashMap::new();
+        let stats = Mutex::new(BatchStats::default());
+
+        self.process_each(files, |index, metadata| {
+            stats.lock().unwrap().record(&metadata);
+            grouped
+                .entry(files[index].mime_type.clone())
+                .or_default()
+                .push(metadata);
+            Ok(())
+        })?;
+
+        let groups = grouped
+            .into_iter()
+            .map(|(mime_type, files)| GroupedFiles { mime_type, files })
+            .collect();
+        let stats = stats.into_inner().unwrap();
+
+        Ok((groups, stats))
+    }
+
+    /// Extracts text from a batch of files in parallel, handing each result to
+    /// `emit` as soon as its file is done.
+    ///
+    /// Results are not collected, so each one can be dropped once `emit`
+    /// returns. `emit` runs on the worker threads, in completion order rather
+    /// than input order.
+    ///
+    /// # Arguments
+    ///
+    /// * `files` - The files to process
+    /// * `emit` - Called once per file with the file's index in `files` and
+    ///   its metadata. Returning an error stops the batch, for example when
+    ///   the results can no longer be delivered
+    ///
+    /// # Returns
+    ///
+    /// The number of results emitted, or an error if `emit` failed or
+    /// `fail_fast` is enabled and any file failed to extract. A failing file
+    /// is not emitted, and files that have not started yet are skipped.
+    pub fn process_each<F>(&self, files: &[FileInput], emit: F) -> napi::Result<u32>
+    where
+        F: Fn(usize, FileMetadata) -> napi::Result<()> + Sync,
+    {
+        self.check_options()?;
+        let fail_fast = self.options.fail_fast.unwrap_or(false);
+        let emitted = AtomicU32::new(0);
+
+        // Returning an error from the closure stops Rayon from starting new files
+        files.par_iter().enumerate().try_for_each(|(index, file)| {
+            let metadata = self.extract_file(file);
+
+            if fail_fast && let Some(error) = &metadata.error {
+                return Err(napi::Error::from_reason(format!(
+                    "Failed to process {}: {}",
+                    file.filename, error
+                )));
+            }
+
+            emit(index, metadata)?;
+            emitted.fetch_add(1, Ordering::Relaxed);
+
+            Ok(())
+        })?;
+
+        Ok(emitted.into_inner())
+    }
+
+    /// Extracts text from a single file with the first handler that accepts it.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The file to extract
+    ///
+    /// # Returns
+    ///
+    /// The file's metadata and text, with `redact_patterns` masked. Failures
+    /// are reported in the metadata (`encoding` and `error`) rather than as an
+    /// `Err`, including an invalid option.
+    pub fn extract_file(&self, file: &FileInput) -> FileMetadata {
+        let mut metadata = extract_file(&self.handlers, file, &self.options);
+
+        match &self.setup {
+            Ok(Some(redactor)) => metadata.text_content = redactor.redact(&metadata.text_content),
+            Ok(None) => {}
+            Err(err) => {
+                metadata.text_content = String::new();
+                metadata.encoding = "error".to_string();
+                metadata.error = Some(err.clone());
+            }
+        }
+
+        metadata
+    }
+
+    /// Fails if an option (`binary_entropy_threshold` or a `redact_patterns`
+    /// pattern) is invalid, so a batch is rejected before any file is
+    /// processed.
+    fn check_options(&self) -> napi::Result<()> {
+        match &self.setup {
+            Err(err) => Err(napi::Error::from_reason(err.clone())),
+            Ok(_) => Ok(()),
+        }
+    }
+}
+
+/// Rejects a `binary_entropy_threshold` that is negative, which would reject
+/// every non-empty file, or not a number, which would disable the check.
+fn check_binary_entropy_threshold(options: &ProcessOptions) -> Result<(), String> {
+    match options.binary_entropy_threshold {
+        Some(threshold) if threshold.is_nan() || threshold < 0.0 => Err(format!(
+            "Invalid binary_entropy_threshold {}: expected bits per byte from 0 to 8",
+            threshold
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Creates the built-in file handlers, configured from `options`.
+///
+/// This is the canonical handler list shared by every entry point. The order
+/// is the priority order: the first handler accepting a MIME type wins, so
+/// handlers for specific text formats (config files, CSV, HTML, JSON, Markdown)
+/// come before the catch-all `TextHandler`, which accepts any `text/*` type.
+/// The remaining handlers accept disjoint MIME types and are listed
+/// alphabetically.
+///
+/// | Priority | Handler | MIME types |
+/// |---|---|---|
+/// | 1 | `ConfigHandler` | YAML and TOML (`text/yaml`, `application/toml`, ...) |
+/// | 2 | `CsvHandler` | `text/csv`, `text/tsv`, `text/tab-separated-values` |
+/// | 3 | `DocxHandler` | Word documents |
+/// | 4 | `EmlHandler` | `message/rfc822` |
+/// | 5 | `EpubHandler` | `application/epub+zip` |
+/// | 6 | `HtmlHandler` | `text/html`, `application/xhtml+xml` |
+/// | 7 | `ImageHandler` | Common image formats (`image/png`, `image/jpeg`, ...) |
+/// | 8 | `JsonHandler` | `application/json`, `application/ld+json` |
+/// | 9 | `MarkdownHandler` | `text/markdown`, `text/x-markdown` |
+/// | 10 | `PdfHandler` | `application/pdf` |
+/// | 11 | `PptxHandler` | PowerPoint presentations |
+/// | 12 | `TextHandler` | Any `text/*` type, XML, and JavaScript |
+/// | 13 | `XlsxHandler` | Excel and OpenDocument spreadsheets |
+/// | 14 | `ZipHandler` | `application/zip`, `application/x-zip-compressed` |
+/// | 15 | `GzipHandler` | `application/gzip`, `application/x-gzip` |
+///
+/// Handlers registered with `DocumentProcessor::register_handler` take
+/// priority over all of these. The `ZipHandler` and `GzipHandler` extract
+/// their contents with the handlers before them, so they are always last;
+/// `register_handler` rebuilds them to include the new handler.
+fn build_handlers(options: &ProcessOptions) -> Vec<Arc<dyn FileHandler>> {
+    let image_handler = Arc::new(build_image_handler(options));
+    let mut pdf_handler = PdfHandler::new()
+        .with_page_markers(options.pdf_page_markers.unwrap_or(false))
+        .with_clean_options(PdfCleanOptions::from(options));
+    if let Some(password) = &options.pdf_password {
+        pdf_handler = pdf_handler.with_password(password.clone());
+    }
+    if options.pdf_ocr_fallback.unwrap_or(false) {
+        // Share the image handler so the OCR models are loaded once
+        pdf_handler = pdf_handler.with_ocr_fallback(image_handler.clone());
+    }
+    let docx_handler = DocxHandler::new()
+        .with_expand_merged_cells(options.expand_merged_cells.unwrap_or(false))
+        .with_link_targets(options.docx_link_targets.unwrap_or(false))
+        .with_image_alt(options.include_image_alt.unwrap_or(false));
+    let xlsx_handler = XlsxHandler::new()
+        .with_expand_merged_cells(options.expand_merged_cells.unwrap_or(false))
+        .with_preserve_empty_cells(options.preserve_empty_cells.unwrap_or(false))
+        .with_sheets(options.sheets.clone().unwrap_or_default());
+    // Every handler decoding text rejects binary content at the same threshold
+    let entropy_threshold = options
+        .binary_entropy_threshold
+        .unwrap_or(DEFAULT_BINARY_ENTROPY_THRESHOLD);
+
+    // Keep in priority order; handlers for specific text formats must come
+    // before `TextHandler`
+    let mut handlers: Vec<Arc<dyn FileHandler>> = vec![
+        Arc::new(ConfigHandler::new().with_binary_entropy_threshold(entropy_threshold)),
+        Arc::new(CsvHandler::new().with_binary_entropy_threshold(entropy_threshold)),
+        Arc::new(docx_handler),
+        Arc::new(EmlHandler::new()),
+        Arc::new(EpubHandler::new()),
+        Arc::new(HtmlHandler::new().with_binary_entropy_threshold(entropy_threshold)),
+        image_handler,
+        Arc::new(JsonHandler::new().with_binary_entropy_threshold(entropy_threshold)),
+        Arc::new(MarkdownHandler::new().with_binary_entropy_threshold(entropy_threshold)),
+        Arc::new(pdf_handler),
+        Arc::new(PptxHandler::new()),
+        Arc::new(TextHandler::new().with_binary_entropy_threshold(entropy_threshold)),
+        Arc::new(xlsx_handler),
+    ];
+    push_container_handlers(&mut handlers, options);
+
+    handlers
+}
+
+/// The number of container handlers that `push_container_handlers` appends.
+const CONTAINER_HANDLER_COUNT: usize = 2;
+
+/// Appends the handlers that extract the files inside other files: the
+/// `ZipHandler` over `handlers`, then the `GzipHandler` over `handlers` and
+/// the `ZipHandler`, so gzipped archives are extracted too.
+fn push_container_handlers(handlers: &mut Vec<Arc<dyn FileHandler>>, options: &ProcessOptions) {
+    let zip_handler = build_zip_handler(handlers, options);
+    handlers.push(Arc::new(zip_handler));
+
+    let mut gzip_handler = GzipHandler::new(handlers.clone());
+    if let Some(max_bytes) = options.gzip_max_output_bytes {
+        gzip_handler = gzip_handler.with_max_output_bytes(max_bytes as u64);
+    }
+    if let Some(mime_type) = &options.gzip_inner_mime_type {
+        gzip_handler = gzip_handler.with_inner_mime_type(mime_type.clone());
+    }
+    handlers.push(Arc::new(gzip_handler));
+}
+
+/// Creates the archive handler, extracting entries with `handlers` and
+/// applying the ZIP limits from `options`.
+fn build_zip_handler(handlers: &[Arc<dyn FileHandler>], options: &ProcessOptions) -> ZipHandler {
+    let mut zip_handler = ZipHandler::new(handlers.to_vec());
+    if let Some(max_bytes) = options.zip_max_uncompressed_bytes {
+        zip_handler = zip_handler.with_max_uncompressed_bytes(max_bytes as u64);
+    }
+    if let Some(max_entries) = options.zip_max_entries {
+        zip_handler = zip_handler.with_max_entries(max_entries as usize);
+    }
+    zip_handler
+}
+
+/// Creates the OCR handler, loading the models selected in `options`.
+///
+/// Models that are not set fall back to the bundled ones. The `ocr_*` image
+/// transforms are applied to every image before recognition.
+pub(crate) fn build_image_handler(options: &ProcessOptions) -> ImageHandler {
+    let preprocessing = ImagePreprocessOptions::from(options);
+    if options.ocr_detection_model.is_none()
+        && options.ocr_recognition_model.is_none()
+        && options.ocr_alphabet.is_none()
+    {
+        return ImageHandler::new().with_preprocessing(preprocessing);
+    }
+
+    let detection_model = options
+        .ocr_detection_model
+        .as_ref()
+        .map_or_else(ImageHandler::default_detection_model, PathBuf::from);
+    let recognition_model = options
+        .ocr_recognition_model
+        .as_ref()
+        .map_or_else(ImageHandler::default_recognition_model, PathBuf::from);
+
+    let handler = match &options.ocr_alphabet {
+        Some(alphabet) => {
+            ImageHandler::with_models_and_alphabet(detection_model, recognition_model, alphabet)
+        }
+        None => ImageHandler::with_models(detection_model, recognition_model),
+    };
+    handler.with_preprocessing(preprocessing)
+}
+
+/// Finds the first handler that accepts a file.
+///
+/// The declared MIME type is normalized (see `normalize_mime_type`) and tried
+/// first. If no handler accepts it, the MIME type is detected from the
+/// content's magic bytes and the handlers are tried again with the detected
+/// type.
+///
+/// # Returns
+///
+/// The matching handler together with the MIME type it accepted, or `None`
+/// if neither the declared nor the detected MIME type is handled.
+pub(crate) fn find_handler<'a, 'm>(
+    handlers: &'a [Arc<dyn FileHandler>],
+    mime_type: &'m str,
+    content: &[u8],
+) -> Option<(&'a Arc<dyn FileHandler>, Cow<'m, str>)> {
+    let mime_type = normalize_mime_type(mime_type);
+    if let Some(handler) = handlers.iter().find(|h| h.can_handle(&mime_type)) {
+        return Some((handler, mime_type));
+    }
+
+    let detected = sniff_mime(content)?;
+    let handler = handlers.iter().find(|h| h.can_handle(&detected))?;
+    Some((handler, Cow::Owned(detected)))
+}
+
+/// Extracts text from a single file using the first handler that accepts it.
+///
+/// Files without a matching handler get an empty `text_content`, the
+/// "application/octet-stream" encoding, and `handled` unset, while a handler
+/// extracting no text still reports `handled`; extraction failures get an empty
+/// `text_content`, the "error" encoding, and the message in `error`. Files
+/// larger than `max_bytes` are rejected with the "too_large" encoding before
+/// any handler runs.
+fn extract_file(
+    handlers: &[Arc<dyn FileHandler>],
+    file: &FileInput,
+    options: &ProcessOptions,
+) -> FileMetadata {
+    let content = file.content.as_ref();
+    let size = content.len() as f64;
+    let content_hash = options
+        .include_hash
+        .unwrap_or(false)
+        .then(|| format!("{:x}", Sha256::digest(content)));
+
+    if let Some(max_bytes) = options.max_bytes
+        && size > max_bytes
+    {
+        return FileMetadata {
+            name: file.filename.clone(),
+            size,
+            processing_time_ms: 0.0,
+            encoding: "too_large".to_string(),
+            text_content: String::new(),
+            error: Some(format!(
+                "File size of {} bytes exceeds the limit of {} bytes",
+                size, max_bytes
+            )),
+            estimated_peak_bytes: None,
+            lossy_decoding: false,
+            handled: false,
+            content_hash,
+            language: None,
+            language_confidence: None,
+        };
+    }
+
+    // Handlers disagree on empty input (empty text vs. parse errors), so skip them
+    if content.is_empty() {
+        return FileMetadata {
+            name: file.filename.clone(),
+            size,
+            processing_time_ms: 0.0,
+            encoding: "empty".to_string(),
+            text_content: String::new(),
+            error: None,
+            estimated_peak_bytes: None,
+            lossy_decoding: false,
+            handled: false,
+            content_hash,
+            language: None,
+            language_confidence: None,
+        };
+    }
+
+    let handler = find_handler(handlers, &file.mime_type, content);
+    let handled = handler.is_some();
+
+    let estimated_peak_bytes = handler
+        .as_ref()
+        .filter(|_| options.report_memory.unwrap_or(false))
+        .map(|(h, _)| size * h.memory_multiplier());
+
+    let extraction_start = Instant::now();
+    let mut lossy_decoding = false;
+    let (text_content, encoding, error) = match handler {
+        Some((h, mime_type)) => {
+            match extract_with_timeout(h, content, &file.filename, &mime_type, options.timeout_ms) {
+                Some(Ok(ExtractedText {
+                    mut text,
+                    encoding,
+                    lossy,
+                })) => {
+                    lossy_decoding = lossy;
+                    if options.extract_embedded.unwrap_or(false) {
+                        append_embedded_text(handlers, content, &mime_type, 1, &mut text);
+                    }
+                    if options.normalize_whitespace.unwrap_or(false) {
+                        text = normalize_whitespace(&text);
+                    }
+                    (text, encoding, None)
+                }
+                Some(Err(err)) => (String::new(), "error".to_string(), Some(err)),
+                None => timed_out(options.timeout_ms),
+            }
+        }
+        None => (String::new(), "application/octet-stream".to_string(), None),
+    };
+    let processing_time_ms = elapsed_ms(extraction_start);
+
+    let (language, language_confidence) =
+        if options.detect_language.unwrap_or(false) && error.is_none() {
+            detect_language(&text_content).unzip()
+        } else {
+            (None, None)
+        };
+
+    FileMetadata {
+        name: file.filename.clone(),
+        size,
+        processing_time_ms,
+        encoding,
+        text_content,
+        error,
+        estimated_peak_bytes,
+        lossy_decoding,
+        handled,
+        content_hash,
+        language,
+        language_confidence,
+    }
+}
+
+/// Detects the language of an extracted text with `whatlang`.
+///
+/// # Returns
+///
+/// The ISO 639-3 code of the language and the detection confidence (0-1), or
+/// `None` if the text is blank or its language could not be determined.
+fn detect_language(text: &str) -> Option<(String, f64)> {
+    if text.trim().is_empty() {
+        return None;
+    }
+
+    whatlang::detect(text).map(|info| (info.lang().code().to_string(), info.confidence()))
+}
+
+/// Runs a handler's `extract_text_with_encoding`, giving up after `timeout_ms`
+/// if it is set.
+///
+/// Without a timeout, the handler runs on the calling thread. With one, it
+/// runs on a dedicated thread with its own copy of the content, so a runaway
+/// extraction (e.g. a malformed PDF) does not hold up the Rayon worker. Threads
+/// cannot be cancelled, so a timed-out extraction keeps running in the
+/// background until it finishes, and its result is discarded.
+///
+/// # Returns
+///
+/// The handler's result, or `None` if it did not finish within the timeout.
+pub(crate) fn extract_with_timeout(
+    handler: &Arc<dyn FileHandler>,
+    content: &[u8],
+    filename: &str,
+    mime_type: &str,
+    timeout_ms: Option<u32>,
+) -> Option<Result<ExtractedText, String>> {
+    let Some(timeout_ms) = timeout_ms else {
+        return Some(handler.extract_text_with_encoding(content, filename, mime_type));
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let handler = Arc::clone(handler);
+    let content = content.to_vec();
+    let filename = filename.to_string();
+    let mime_type = mime_type.to_string();
+    thread::spawn(move || {
+        // The receiver is gone if the extraction already timed out
+        let _ = sender.send(handler.extract_text_with_encoding(&content, &filename, &mime_type));
+    });
+
+    match receiver.recv_timeout(Duration::from_millis(timeout_ms.into())) {
+        Ok(result) => Some(result),
+        Err(RecvTimeoutError::Timeout) => None,
+        Err(RecvTimeoutError::Disconnected) => Some(Err("Extraction panicked".to_string())),
+    }
+}
+
+/// Returns the `(text_content, encoding, error)` result for an extraction
+/// that exceeded `timeout_ms`.
+pub(crate) fn timed_out(timeout_ms: Option<u32>) -> (String, String, Option<String>) {
+    let error = format!(
+        "Extraction timed out after {} ms",
+        timeout_ms.unwrap_or_default()
+    );
+    (String::new(), "timeout".to_string(), Some(error))
+}
+
+/// Returns the time elapsed since `start` in milliseconds.
+pub(crate) fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Appends the text of documents embedded in an Office package to `text`.
+///
+/// Each embedded document is extracted with the first matching handler and
+/// its own embedded documents are appended recursively, as long as `depth`
+/// does not exceed `MAX_EMBEDDED_DEPTH`. Documents without a handler, with
+/// extraction errors, or without text are skipped.
+fn append_embedded_text(
+    handlers: &[Arc<dyn FileHandler>],
+    content: &[u8],
+    mime_type: &str,
+    depth: usize,
+    text: &mut String,
+) {
+    if depth > MAX_EMBEDDED_DEPTH {
+        return;
+    }
+
+    for part in embedded_parts(content, mime_type) {
+        let Some(handler) = handlers.iter().find(|h| h.can_handle(part.mime_type)) else {
+            continue;
+        };
+        let Ok(mut part_text) = handler.extract_text(&part.content, &part.name, part.mime_type)
+        else {
+            continue;
+        };
+
+        append_embedded_text(
+            handlers,
+            &part.content,
+            part.mime_type,
+            depth + 1,
+            &mut part_text,
+        );
+        if part_text.is_empty() {
+            continue;
+        }
+
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+        text.push_str(&format!("Embedded: {}\n{}", part.name, part_text));
+    }
+}